@@ -0,0 +1,110 @@
+//! Finds any of several fixed strings in one pass over a text, however many
+//! there are, with the Aho-Corasick automaton.
+
+use std::{collections::VecDeque, ops::Range};
+
+/// Bytes an automaton state has a transition for: all of them.
+const ALPHABET: usize = 256;
+
+/// Fixed strings to search for together.
+#[derive(Debug, Clone)]
+pub struct Patterns {
+    /// The next state for each state and byte, as one table.
+    transitions: Vec<u32>,
+    /// For each state, the length of the longest pattern that ends there,
+    /// or 0 if none does.
+    longest: Vec<usize>,
+    /// Whether a pattern is empty, which matches every text.
+    matches_empty: bool,
+}
+
+impl Patterns {
+    pub fn new<P: AsRef<[u8]>>(patterns: &[P]) -> Self {
+        // The trie of the patterns, state 0 being its root. Missing
+        // transitions are filled in below.
+        const MISSING: u32 = u32::MAX;
+        let mut transitions = vec![MISSING; ALPHABET];
+        let mut longest = vec![0];
+        for pattern in patterns {
+            let mut state = 0;
+            for &byte in pattern.as_ref() {
+                let index = state * ALPHABET + byte as usize;
+                if transitions[index] == MISSING {
+                    transitions[index] = longest.len() as u32;
+                    transitions.extend([MISSING; ALPHABET]);
+                    longest.push(0);
+                }
+                state = transitions[index] as usize;
+            }
+            longest[state] = longest[state].max(pattern.as_ref().len());
+        }
+
+        // Breadth first, so the state a mismatch falls back to is always
+        // complete before the states that fall back to it.
+        let mut fallback = vec![0; longest.len()];
+        let mut queue = VecDeque::new();
+        for next in &mut transitions[..ALPHABET] {
+            match *next {
+                MISSING => *next = 0,
+                state => queue.push_back(state as usize),
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            longest[state] = longest[state].max(longest[fallback[state]]);
+            for byte in 0..ALPHABET {
+                let index = state * ALPHABET + byte;
+                let fallback_next = transitions[fallback[state] * ALPHABET + byte];
+                match transitions[index] {
+                    MISSING => transitions[index] = fallback_next,
+                    next => {
+                        fallback[next as usize] = fallback_next as usize;
+                        queue.push_back(next as usize);
+                    }
+                }
+            }
+        }
+
+        Self {
+            transitions,
+            longest,
+            matches_empty: patterns.iter().any(|pattern| pattern.as_ref().is_empty()),
+        }
+    }
+
+    /// Whether any pattern occurs in `text`, stopping at the first one.
+    pub fn is_match(&self, text: &[u8]) -> bool {
+        if self.matches_empty {
+            return true;
+        }
+        let mut state = 0;
+        for &byte in text {
+            state = self.transitions[state * ALPHABET + byte as usize] as usize;
+            if self.longest[state] > 0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// The parts of `text` covered by occurrences of the patterns, in order,
+    /// with overlapping and adjacent occurrences joined into one range.
+    pub fn find_ranges(&self, text: &[u8]) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut state = 0;
+        for (index, &byte) in text.iter().enumerate() {
+            state = self.transitions[state * ALPHABET + byte as usize] as usize;
+            let length = self.longest[state];
+            if length == 0 {
+                continue;
+            }
+            // Shorter patterns ending here lie inside the longest one, which
+            // may reach back over several earlier ranges.
+            let mut found = index + 1 - length..index + 1;
+            while let Some(last) = ranges.pop_if(|last| found.start <= last.end) {
+                found.start = found.start.min(last.start);
+            }
+            ranges.push(found);
+        }
+        ranges
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// A file that only replaces its target when [`commit`](AtomicFile::commit)
+/// is called. Data goes to a temporary file in the same directory, which is
+/// synced and renamed over the target, so a crash leaves either the old or
+/// the new content behind, never a mix. Dropping it without committing
+/// removes the temporary file.
+///
+/// The target's permissions carry over to the new content, and a target
+/// that is a symbolic link is followed, so the file it points to is
+/// replaced and the link is kept.
+pub struct AtomicFile {
+    file: File,
+    temp_path: PathBuf,
+    path: PathBuf,
+    committed: bool,
+}
+
+impl AtomicFile {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = resolve_links(path.as_ref())?;
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Not a file path"))?;
+
+        // The process id keeps concurrent instances from sharing a temp file.
+        let temp_path = path.with_file_name(format!(
+            ".{}.{}.tmp",
+            file_name.to_string_lossy(),
+            process::id()
+        ));
+        let file = File::create(&temp_path)?;
+        let atomic = Self {
+            file,
+            temp_path,
+            path,
+            committed: false,
+        };
+
+        // Set before any data is written, so the content of a private file
+        // is never readable under looser permissions.
+        match fs::metadata(&atomic.path) {
+            Ok(metadata) => atomic.file.set_permissions(metadata.permissions())?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+        Ok(atomic)
+    }
+
+    /// Where the data goes until it is committed.
+    pub fn temp_path(&self) -> &Path {
+        &self.temp_path
+    }
+
+    pub fn commit(self) -> io::Result<()> {
+        self.finish(true)
+    }
+
+    /// Like [`commit`](AtomicFile::commit) but without syncing to disk:
+    /// readers still never see a partial file, but the update may be lost on
+    /// power failure. For data written on every command, where a sync per
+    /// write would be too slow.
+    pub fn commit_unsynced(self) -> io::Result<()> {
+        self.finish(false)
+    }
+
+    fn finish(mut self, sync: bool) -> io::Result<()> {
+        self.file.flush()?;
+        if sync {
+            self.file.sync_all()?;
+        }
+        fs::rename(&self.temp_path, &self.path)?;
+        self.committed = true;
+        if !sync {
+            return Ok(());
+        }
+
+        // Persist the rename itself. Directories cannot be opened for syncing
+        // on every platform, so this is best effort.
+        if let Some(dir) = self.path.parent() {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            if let Ok(dir) = File::open(dir) {
+                let _ = dir.sync_all();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Follows `path` through symbolic links to the file they point to, which
+/// need not exist yet. Relative link targets are taken from the link's
+/// directory.
+fn resolve_links(path: &Path) -> io::Result<PathBuf> {
+    // The same limit as Linux, so link loops end in an error.
+    const MAX_LINKS: usize = 40;
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_LINKS {
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let target = fs::read_link(&path)?;
+                path = match path.parent() {
+                    Some(dir) => dir.join(target),
+                    None => target,
+                };
+            }
+            _ => return Ok(path),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{}: Too many levels of symbolic links", path.display()),
+    ))
+}
+
+/// Atomically replaces the content of `path`, like [`fs::write`].
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut file = AtomicFile::create(path)?;
+    file.write_all(contents.as_ref())?;
+    file.commit()
+}
+
+/// An output file that is written either in place or, for `--atomic`,
+/// through an [`AtomicFile`].
+pub enum Output {
+    Direct(File),
+    Atomic(AtomicFile),
+}
+
+impl Output {
+    pub fn create(path: impl AsRef<Path>, atomic: bool) -> io::Result<Self> {
+        if atomic {
+            AtomicFile::create(path).map(Output::Atomic)
+        } else {
+            File::create(path).map(Output::Direct)
+        }
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Output::Direct(mut file) => file.flush(),
+            Output::Atomic(file) => file.commit(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Direct(file) => file.write(buf),
+            Output::Atomic(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Direct(file) => file.flush(),
+            Output::Atomic(file) => file.flush(),
+        }
+    }
+}
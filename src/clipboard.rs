@@ -0,0 +1,53 @@
+//! Copies text to the system clipboard through the platform's command line
+//! tools.
+
+use std::{
+    error::Error,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+/// Tools to try in order, with their arguments.
+#[cfg(target_os = "macos")]
+const TOOLS: &[(&str, &[&str])] = &[("pbcopy", &[])];
+#[cfg(windows)]
+const TOOLS: &[(&str, &[&str])] = &[("clip", &[])];
+#[cfg(not(any(target_os = "macos", windows)))]
+const TOOLS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
+
+pub fn copy(text: &[u8]) -> Result<(), Box<dyn Error>> {
+    for (program, args) in TOOLS {
+        let mut child = match Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(format!("Cannot run {}: {}", program, error).into()),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text)?;
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(format!("{} failed ({})", program, status).into());
+        }
+        return Ok(());
+    }
+
+    let names = TOOLS
+        .iter()
+        .map(|(program, _)| *program)
+        .collect::<Vec<_>>();
+    Err(format!(
+        "No clipboard tool found; install one of: {}",
+        names.join(", ")
+    )
+    .into())
+}
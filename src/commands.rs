@@ -0,0 +1,68 @@
+pub mod archive;
+pub mod audit;
+pub mod backup;
+pub mod cal;
+pub mod capture;
+pub mod cat;
+pub mod cd;
+pub mod chmod;
+pub mod chown;
+pub mod completions;
+pub mod convert_unit;
+pub mod countdown;
+pub mod cp;
+pub mod crypt;
+pub mod cut;
+pub mod date;
+pub mod debuglog;
+pub mod df;
+pub mod dir_stack;
+pub mod docs;
+pub mod du;
+pub mod examples;
+pub mod explain;
+pub mod find;
+pub mod gitinfo;
+pub mod grep;
+pub mod hash;
+pub mod head;
+pub mod help;
+pub mod history;
+pub mod ifconfig;
+pub mod imginfo;
+pub mod lastout;
+pub mod ln;
+pub mod loc;
+pub mod ls;
+pub mod man;
+pub mod mkdir;
+pub mod mounts;
+pub mod mv;
+pub mod myip;
+pub mod note;
+pub mod open;
+pub mod portscan;
+pub mod pwd;
+pub mod qr;
+pub mod remind;
+pub mod replace;
+pub mod rm;
+pub mod run;
+pub mod set;
+pub mod snapshot;
+pub mod sort;
+pub mod stat;
+pub mod stopwatch;
+pub mod tail;
+pub mod tar;
+pub mod todo;
+pub mod todos;
+pub mod tree;
+pub mod tutorial;
+pub mod umask;
+pub mod uniq;
+pub mod usage;
+pub mod vault;
+pub mod wc;
+pub mod wizard;
+pub mod zip;
@@ -0,0 +1,221 @@
+use super::grep;
+use crate::{
+    aho_corasick::Patterns,
+    outln, output,
+    tar::{self, EntryKind, Header},
+    terminal, zip, ExecutableCommand,
+};
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+/// How zip archives start: with a local header, or with the end of the
+/// central directory when empty. Anything else is read as tar.
+const ZIP_SIGNATURES: [&[u8; 4]; 2] = [b"PK\x03\x04", b"PK\x05\x06"];
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let action = command.arguments[0].as_str();
+    match (action, &command.arguments[1..]) {
+        ("grep", [pattern, archive]) => grep(command, pattern, archive),
+        ("cat", [archive, member]) => cat(command, archive, member),
+        _ => Err("Usage: archive grep <pattern> <archive> | archive cat <archive> <member>".into()),
+    }
+}
+
+/// Prints the lines of the files in the archive that contain `pattern`,
+/// prefixed with the member's path and the line number, as grep does.
+fn grep(command: &ExecutableCommand, pattern: &str, archive: &str) -> Result<(), Box<dyn Error>> {
+    let ignore_case = command.has_flag("--ignore-case");
+    let pattern = if ignore_case {
+        pattern.to_lowercase()
+    } else {
+        pattern.to_string()
+    };
+    let patterns = Patterns::new(&[&pattern]);
+    let highlight = terminal::colors_enabled();
+    let cancellation = &command.context.cancellation;
+
+    let archive = command.context.resolve(archive);
+    each_file(&archive, |header, copy_data| {
+        // Members are read as they come, so binary ones are told apart
+        // by their first lines.
+        let (mut number, mut offset, mut binary) = (0, 0, false);
+        let mut lines = Lines::new(|line: &[u8]| {
+            number += 1;
+            if offset < grep::BINARY_CHECK_SIZE && line.contains(&0) {
+                binary = true;
+            }
+            offset += line.len() + 1;
+            if binary {
+                return;
+            }
+            let line = String::from_utf8_lossy(line);
+            let searched = if ignore_case {
+                line.to_lowercase()
+            } else {
+                line.to_string()
+            };
+            if !patterns.is_match(searched.as_bytes()) {
+                return;
+            }
+            let shown = if highlight {
+                grep::highlight(&line, &grep::find_ranges(&patterns, ignore_case, &line))
+            } else {
+                line.into_owned()
+            };
+            outln!("{}:{}:{}", header.path, number, shown);
+        });
+        copy_data(&mut lines)?;
+        lines.finish();
+        Ok(!cancellation.is_cancelled())
+    })?;
+    if cancellation.is_cancelled() {
+        return Err("archive grep cancelled".into());
+    }
+    Ok(())
+}
+
+/// Prints one member of the archive as it is read.
+fn cat(command: &ExecutableCommand, archive: &str, member: &str) -> Result<(), Box<dyn Error>> {
+    let archive = command.context.resolve(archive);
+    let wanted = member_path(member);
+    let mut found = None;
+    each_entry(&archive, |header, copy_data| {
+        if member_path(&header.path) != wanted {
+            return Ok(true);
+        }
+        found = Some(header.kind.clone());
+        if header.kind == EntryKind::File {
+            copy_data(&mut Printed)?;
+        }
+        Ok(false)
+    })?;
+    match found {
+        Some(EntryKind::File) => Ok(()),
+        Some(EntryKind::Directory) => Err(format!("{} is a directory", member).into()),
+        Some(EntryKind::Symlink(target)) => {
+            Err(format!("{} is a link to {}; cat that instead", member, target).into())
+        }
+        None => Err(format!("{} has no member {}", archive.display(), member).into()),
+    }
+}
+
+/// A member's path without a leading `./` or a trailing `/`, as archivers
+/// differ in those.
+fn member_path(path: &str) -> &str {
+    let path = path.strip_suffix('/').unwrap_or(path);
+    path.strip_prefix("./").unwrap_or(path)
+}
+
+/// Copies the data of the current member into a writer.
+type CopyData<'a> = dyn FnMut(&mut dyn Write) -> io::Result<u64> + 'a;
+
+/// Calls `visit` for each entry of a tar or zip archive, in order, with a
+/// way to read its data; it returns whether to go on to the next entry.
+fn each_entry(
+    archive: &Path,
+    mut visit: impl FnMut(&Header, &mut CopyData) -> io::Result<bool>,
+) -> Result<(), Box<dyn Error>> {
+    let in_archive = |error: io::Error| format!("{}: {}", archive.display(), error);
+    let mut file = File::open(archive).map_err(in_archive)?;
+    let mut signature = [0; 4];
+    let is_zip = file.read_exact(&mut signature).is_ok() && ZIP_SIGNATURES.contains(&&signature);
+    file.seek(SeekFrom::Start(0)).map_err(in_archive)?;
+
+    if is_zip {
+        let mut reader = zip::Reader::new(BufReader::new(file)).map_err(in_archive)?;
+        for entry in reader.entries().to_vec() {
+            let go_on = visit(&entry.header, &mut |mut writer: &mut dyn Write| {
+                reader.copy_data(&entry, &mut writer)
+            });
+            if !go_on.map_err(in_archive)? {
+                break;
+            }
+        }
+    } else {
+        let mut reader = tar::Reader::new(BufReader::new(file));
+        while let Some(header) = reader.next_entry().map_err(in_archive)? {
+            let go_on = visit(&header, &mut |mut writer: &mut dyn Write| {
+                reader.copy_data(&mut writer)
+            });
+            if !go_on.map_err(in_archive)? {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// [`each_entry`] for the files only.
+fn each_file(
+    archive: &Path,
+    mut visit: impl FnMut(&Header, &mut CopyData) -> io::Result<bool>,
+) -> Result<(), Box<dyn Error>> {
+    each_entry(archive, |header, copy_data| {
+        if header.kind != EntryKind::File {
+            return Ok(true);
+        }
+        visit(header, copy_data)
+    })
+}
+
+/// Hands each line written to it to a function, without its line ending.
+struct Lines<F: FnMut(&[u8])> {
+    /// The start of a line whose end has not been written yet.
+    pending: Vec<u8>,
+    line: F,
+}
+
+impl<F: FnMut(&[u8])> Lines<F> {
+    fn new(line: F) -> Self {
+        Self {
+            pending: Vec::new(),
+            line,
+        }
+    }
+
+    /// Hands over the last line, if it has no line ending.
+    fn finish(mut self) {
+        if !self.pending.is_empty() {
+            (self.line)(&self.pending);
+        }
+    }
+}
+
+impl<F: FnMut(&[u8])> Write for Lines<F> {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let mut rest = buffer;
+        while let Some(end) = rest.iter().position(|&byte| byte == b'\n') {
+            self.pending.extend_from_slice(&rest[..end]);
+            if self.pending.ends_with(b"\r") {
+                self.pending.pop();
+            }
+            (self.line)(&self.pending);
+            self.pending.clear();
+            rest = &rest[end + 1..];
+        }
+        self.pending.extend_from_slice(rest);
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes to the command's output as is.
+struct Printed;
+
+impl Write for Printed {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        output::write_bytes(buffer);
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{json, options, outln, ExecutableCommand};
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+};
+
+const SENSITIVE_FILE_NAMES: [&str; 5] = ["id_rsa", "id_dsa", "id_ecdsa", "id_ed25519", ".env"];
+const SENSITIVE_EXTENSIONS: [&str; 3] = ["pem", "key", "p12"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Low => f.pad("low"),
+            Severity::Medium => f.pad("medium"),
+            Severity::High => f.pad("high"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Severity {
+    type Error = String;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        match input {
+            "low" => Ok(Severity::Low),
+            "medium" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            _ => Err(format!(
+                "Unknown severity: {} (expected low, medium or high)",
+                input
+            )),
+        }
+    }
+}
+
+struct Finding {
+    severity: Severity,
+    path: PathBuf,
+    message: String,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let root = Path::new(&command.arguments[0]);
+    let default_format = options::get(options::OUTPUT_FORMAT);
+    let format = command
+        .get_flag_value("--format")
+        .unwrap_or(&default_format);
+    if format != "text" && format != "json" {
+        return Err(format!("Unknown format: {} (expected text or json)", format).into());
+    }
+    let min_severity = match command.get_flag_value("--min-severity") {
+        Some(severity) => Severity::try_from(severity)?,
+        None => Severity::Low,
+    };
+
+    let mut findings = Vec::new();
+    audit_directory(root, &mut findings)?;
+    findings.retain(|finding| finding.severity >= min_severity);
+    findings.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    if format == "json" {
+        print_json(&findings);
+    } else {
+        print_text(&findings);
+    }
+
+    // Fails on findings, so scripts and CI can stop on them.
+    if !findings.is_empty() {
+        return Err(format!(
+            "{} {} at or above {} severity",
+            findings.len(),
+            if findings.len() == 1 {
+                "finding"
+            } else {
+                "findings"
+            },
+            min_severity
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn audit_directory(dir: &Path, findings: &mut Vec<Finding>) -> Result<(), Box<dyn Error>> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for path in entries {
+        let metadata = fs::symlink_metadata(&path)?;
+
+        if metadata.file_type().is_symlink() {
+            if fs::metadata(&path).is_err() {
+                findings.push(Finding {
+                    severity: Severity::Low,
+                    message: format!("broken symlink to {}", fs::read_link(&path)?.display()),
+                    path,
+                });
+            }
+            continue;
+        }
+
+        check_permissions(&path, &metadata, findings);
+        if metadata.is_dir() {
+            audit_directory(&path, findings)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path, metadata: &Metadata, findings: &mut Vec<Finding>) {
+    use std::os::unix::fs::PermissionsExt;
+
+    const SETUID: u32 = 0o4000;
+    const SETGID: u32 = 0o2000;
+    const STICKY: u32 = 0o1000;
+    const WORLD_WRITABLE: u32 = 0o002;
+    const GROUP_OR_WORLD_ACCESS: u32 = 0o077;
+    const ANY_EXECUTE: u32 = 0o111;
+
+    let mode = metadata.permissions().mode();
+    let mut report = |severity, message: String| {
+        findings.push(Finding {
+            severity,
+            path: path.to_path_buf(),
+            message: format!("{} (mode {:04o})", message, mode & 0o7777),
+        })
+    };
+
+    if metadata.is_dir() {
+        if mode & WORLD_WRITABLE != 0 && mode & STICKY == 0 {
+            report(
+                Severity::Medium,
+                "world-writable directory without sticky bit".to_string(),
+            );
+        }
+        return;
+    }
+
+    if mode & WORLD_WRITABLE != 0 {
+        report(Severity::High, "world-writable file".to_string());
+    }
+    if mode & SETUID != 0 {
+        report(Severity::High, "setuid binary".to_string());
+    }
+    if mode & SETGID != 0 {
+        report(Severity::Medium, "setgid binary".to_string());
+    }
+    if is_sensitive_file(path) && mode & GROUP_OR_WORLD_ACCESS != 0 {
+        report(
+            Severity::High,
+            "sensitive file accessible by group or others".to_string(),
+        );
+    }
+    if mode & ANY_EXECUTE != 0 && is_document(path) {
+        report(
+            Severity::Low,
+            "executable bit set on a non-program file".to_string(),
+        );
+    }
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path, _metadata: &Metadata, _findings: &mut Vec<Finding>) {}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn is_sensitive_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+
+    SENSITIVE_FILE_NAMES.contains(&name) || SENSITIVE_EXTENSIONS.contains(&extension)
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn is_document(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    matches!(
+        extension,
+        "txt" | "md" | "json" | "toml" | "yaml" | "yml" | "csv" | "log" | "png" | "jpg" | "pdf"
+    )
+}
+
+fn print_text(findings: &[Finding]) {
+    if findings.is_empty() {
+        outln!("No issues found");
+    }
+
+    for finding in findings {
+        outln!(
+            "[{:<6}] {}: {}",
+            finding.severity,
+            finding.path.display(),
+            finding.message
+        );
+    }
+}
+
+fn print_json(findings: &[Finding]) {
+    let findings = findings
+        .iter()
+        .map(|finding| {
+            format!(
+                "{{\"severity\":{},\"path\":{},\"message\":{}}}",
+                json::quote(&finding.severity.to_string()),
+                json::quote(&finding.path.display().to_string()),
+                json::quote(&finding.message)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    outln!("[{}]", findings.join(","));
+}
@@ -0,0 +1,233 @@
+use crate::{atomic, copy, hash, json, options, output, status, verbose, walk, ExecutableCommand};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs, io,
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const STATE_FILE_NAME: &str = ".backup_state.json";
+
+#[derive(Clone, PartialEq)]
+struct FileState {
+    size: u64,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    sha256: String,
+}
+
+#[derive(Default)]
+struct Summary {
+    copied: usize,
+    unchanged: usize,
+    deleted: usize,
+    bytes_copied: u64,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let source = command.context.resolve(&command.arguments[0]);
+    let destination = command.context.resolve(&command.arguments[1]);
+    let copy_options = copy::Options::from_command(command)?;
+    let dry_run = options::dry_run();
+    if !dry_run {
+        fs::create_dir_all(&destination)?;
+    }
+    let nested = nested_destination(&source, &destination)?;
+
+    let state_path = destination.join(STATE_FILE_NAME);
+    let previous = match fs::read_to_string(&state_path) {
+        Ok(content) => state_from_json(&content)?,
+        Err(_) => BTreeMap::new(),
+    };
+
+    let mut current = BTreeMap::new();
+    let mut summary = Summary::default();
+
+    for path in walk::files(&source, false)? {
+        if nested
+            .as_ref()
+            .is_some_and(|nested| path.starts_with(nested))
+        {
+            continue;
+        }
+        let relative = path
+            .strip_prefix(&source)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let target = destination.join(&relative);
+        let metadata = fs::metadata(&path)?;
+        let (mtime_secs, mtime_nanos) = split_time(metadata.modified()?);
+
+        let recorded = previous.get(&relative).filter(|_| target.exists());
+        let quick_match = recorded.is_some_and(|state: &FileState| {
+            state.size == metadata.len()
+                && state.mtime_secs == mtime_secs
+                && state.mtime_nanos == mtime_nanos
+        });
+
+        let state = if quick_match {
+            recorded.unwrap().clone()
+        } else {
+            FileState {
+                size: metadata.len(),
+                mtime_secs,
+                mtime_nanos,
+                sha256: hash::sha256_file(&path)?,
+            }
+        };
+
+        if recorded.is_some_and(|recorded| recorded.sha256 == state.sha256) {
+            summary.unchanged += 1;
+            verbose!("unchanged: {}", relative);
+        } else if dry_run {
+            summary.bytes_copied += metadata.len();
+            summary.copied += 1;
+            status!("would copy: {}", relative);
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            summary.bytes_copied += replace_file(&path, &target, copy_options)?;
+            summary.copied += 1;
+            status!("copied: {}", relative);
+            // Copies are slow enough that progress is worth showing as it happens.
+            output::flush()?;
+        }
+
+        current.insert(relative, state);
+    }
+
+    if command.has_flag("--delete") {
+        for relative in previous.keys().filter(|path| !current.contains_key(*path)) {
+            let target = destination.join(relative);
+            if !target.is_file() {
+                continue;
+            }
+            summary.deleted += 1;
+            if dry_run {
+                status!("would delete: {}", relative);
+            } else {
+                fs::remove_file(&target)?;
+                status!("deleted: {}", relative);
+            }
+        }
+    } else {
+        // Keep tracking files that were removed from the source but still
+        // live in the backup, so a later `--delete` run can clean them up.
+        for (relative, state) in previous {
+            if !current.contains_key(&relative) && destination.join(&relative).is_file() {
+                current.insert(relative, state);
+            }
+        }
+    }
+
+    if dry_run {
+        status!("Dry run, nothing was changed");
+    } else {
+        atomic::write(&state_path, state_to_json(&current))?;
+    }
+
+    status!(
+        "{} copied ({} bytes), {} unchanged, {} deleted",
+        summary.copied,
+        summary.bytes_copied,
+        summary.unchanged,
+        summary.deleted
+    );
+
+    Ok(())
+}
+
+/// Where `destination` lies inside `source`, as a path below `source`, so
+/// the walk can leave the backup out instead of copying it into itself.
+fn nested_destination(source: &Path, destination: &Path) -> io::Result<Option<PathBuf>> {
+    let (Ok(source_real), Ok(destination_real)) =
+        (fs::canonicalize(source), fs::canonicalize(destination))
+    else {
+        return Ok(None);
+    };
+    match destination_real.strip_prefix(&source_real) {
+        Ok(inner) if inner.as_os_str().is_empty() => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "The destination is the source directory",
+        )),
+        Ok(inner) => Ok(Some(source.join(inner))),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Copies `from` over `to`. A read-only copy from an earlier backup cannot
+/// be opened for writing, so it is removed and written again.
+fn replace_file(from: &Path, to: &Path, options: copy::Options) -> io::Result<u64> {
+    match copy::copy_file(from, to, options) {
+        Err(error) if error.kind() == io::ErrorKind::PermissionDenied && to.is_file() => {
+            fs::remove_file(to)?;
+            copy::copy_file(from, to, options)
+        }
+        result => result,
+    }
+}
+
+fn split_time(time: SystemTime) -> (u64, u32) {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    (duration.as_secs(), duration.subsec_nanos())
+}
+
+fn state_to_json(states: &BTreeMap<String, FileState>) -> String {
+    let files = states
+        .iter()
+        .map(|(path, state)| {
+            format!(
+                "    {{\"path\": {}, \"size\": {}, \"mtime_secs\": {}, \"mtime_nanos\": {}, \"sha256\": {}}}",
+                json::quote(path),
+                state.size,
+                state.mtime_secs,
+                state.mtime_nanos,
+                json::quote(&state.sha256)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{\n  \"files\": [\n{}\n  ]\n}}\n", files.join(",\n"))
+}
+
+fn state_from_json(content: &str) -> Result<BTreeMap<String, FileState>, Box<dyn Error>> {
+    let state = json::parse(content)?;
+    let files = state
+        .get("files")
+        .and_then(json::Value::as_array)
+        .ok_or("Backup state has no file list")?;
+
+    let mut states = BTreeMap::new();
+    for file in files {
+        let (Some(path), Some(size), Some(mtime_secs), Some(mtime_nanos), Some(sha256)) = (
+            file.get("path").and_then(json::Value::as_str),
+            file.get("size").and_then(json::Value::as_u64),
+            file.get("mtime_secs").and_then(json::Value::as_u64),
+            file.get("mtime_nanos").and_then(json::Value::as_u64),
+            file.get("sha256").and_then(json::Value::as_str),
+        ) else {
+            return Err("Backup state contains a malformed file entry".into());
+        };
+        // `--delete` removes these paths, so they must stay inside the backup.
+        if !Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!("Backup state contains an unsafe path: {}", path).into());
+        }
+
+        states.insert(
+            path.to_string(),
+            FileState {
+                size,
+                mtime_secs,
+                mtime_nanos: mtime_nanos as u32,
+                sha256: sha256.to_string(),
+            },
+        );
+    }
+
+    Ok(states)
+}
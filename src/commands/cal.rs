@@ -0,0 +1,162 @@
+use crate::{
+    calendar::{self, MONTH_NAMES},
+    options, outln, terminal,
+    timezone::TimeZone,
+    ExecutableCommand,
+};
+use std::error::Error;
+
+/// Width of one month: seven two-character days with spaces between.
+const MONTH_WIDTH: usize = 20;
+/// Rows of weeks, so months side by side line up.
+const WEEK_ROWS: usize = 6;
+const COLUMN_GAP: &str = "  ";
+const YEAR_COLUMNS: usize = 3;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let today = TimeZone::local().local_time(calendar::now()).date_time;
+    let monday_first = if command.has_flag("-m") {
+        true
+    } else if command.has_flag("-s") {
+        false
+    } else {
+        options::get(options::WEEK_START) == "monday"
+    };
+    let calendar = Calendar {
+        monday_first,
+        today: (today.year, today.month, today.day),
+    };
+
+    let (month, year, whole_year) = match &command.arguments[..] {
+        [] => (today.month, today.year, command.has_flag("-y")),
+        [single] if command.has_flag("-y") => (1, parse_year(single)?, true),
+        // A lone number above 12 can only be a year.
+        [single] => match parse_month(single) {
+            Ok(month) => (month, today.year, false),
+            Err(error) => match single.parse::<i32>() {
+                Ok(year) if year > 12 => (1, parse_year(single)?, true),
+                _ => return Err(error),
+            },
+        },
+        [month, year, ..] => (
+            parse_month(month)?,
+            parse_year(year)?,
+            command.has_flag("-y"),
+        ),
+    };
+
+    if whole_year {
+        let width = MONTH_WIDTH * YEAR_COLUMNS + COLUMN_GAP.len() * (YEAR_COLUMNS - 1);
+        outln!("{}", format!("{:^width$}", year).trim_end());
+        outln!();
+        for quarter in 0..4 {
+            let months = (1..=YEAR_COLUMNS as u32)
+                .map(|column| calendar.month(year, quarter * 3 + column, false))
+                .collect::<Vec<_>>();
+            print_side_by_side(&months);
+            if quarter < 3 {
+                outln!();
+            }
+        }
+    } else if command.has_flag("-3") {
+        let months = [-1, 0, 1]
+            .into_iter()
+            .map(|shift| {
+                let (year, month) = shift_month(year, month, shift);
+                calendar.month(year, month, true)
+            })
+            .collect::<Vec<_>>();
+        print_side_by_side(&months);
+    } else {
+        print_side_by_side(&[calendar.month(year, month, true)]);
+    }
+    Ok(())
+}
+
+struct Calendar {
+    monday_first: bool,
+    today: (i32, u32, u32),
+}
+
+impl Calendar {
+    /// Renders a month as lines of exactly [`MONTH_WIDTH`] visible
+    /// characters.
+    fn month(&self, year: i32, month: u32, with_year: bool) -> Vec<String> {
+        let name = MONTH_NAMES[month as usize - 1];
+        let title = if with_year {
+            format!("{} {}", name, year)
+        } else {
+            name.to_string()
+        };
+        let mut lines = vec![
+            format!("{:^MONTH_WIDTH$}", title),
+            if self.monday_first {
+                "Mo Tu We Th Fr Sa Su".to_string()
+            } else {
+                "Su Mo Tu We Th Fr Sa".to_string()
+            },
+        ];
+
+        let first = calendar::days_from_civil(year, month, 1);
+        let first_column = (calendar::weekday(first) + u32::from(!self.monday_first) + 6) % 7;
+        let mut cells = vec![None; first_column as usize];
+        cells.extend((1..=calendar::days_in_month(year, month)).map(Some));
+        cells.resize(WEEK_ROWS * 7, None);
+
+        for week in cells.chunks(7) {
+            let days = week
+                .iter()
+                .map(|day| match day {
+                    Some(day) if (year, month, *day) == self.today => {
+                        terminal::reverse(&format!("{:>2}", day))
+                    }
+                    Some(day) => format!("{:>2}", day),
+                    None => "  ".to_string(),
+                })
+                .collect::<Vec<_>>();
+            lines.push(days.join(" "));
+        }
+        lines
+    }
+}
+
+fn print_side_by_side(months: &[Vec<String>]) {
+    for row in 0..months[0].len() {
+        let line = months
+            .iter()
+            .map(|lines| lines[row].as_str())
+            .collect::<Vec<_>>()
+            .join(COLUMN_GAP);
+        // Week rows that no month needed.
+        if !line.trim().is_empty() {
+            outln!("{}", line.trim_end());
+        }
+    }
+}
+
+fn shift_month(year: i32, month: u32, shift: i32) -> (i32, u32) {
+    let index = year * 12 + month as i32 - 1 + shift;
+    (index.div_euclid(12), index.rem_euclid(12) as u32 + 1)
+}
+
+/// Parses a month number or name, such as `5`, `may` or `September`.
+fn parse_month(text: &str) -> Result<u32, Box<dyn Error>> {
+    if let Ok(month) = text.parse::<u32>() {
+        if (1..=12).contains(&month) {
+            return Ok(month);
+        }
+    }
+    let lowercase = text.to_lowercase();
+    MONTH_NAMES
+        .iter()
+        .position(|name| lowercase.len() >= 3 && name.to_lowercase().starts_with(&lowercase))
+        .map(|index| index as u32 + 1)
+        .ok_or_else(|| format!("Invalid month: {}, expected 1-12 or a name", text).into())
+}
+
+fn parse_year(text: &str) -> Result<i32, Box<dyn Error>> {
+    text.parse::<i32>()
+        .ok()
+        .filter(|year| (1..=9999).contains(year))
+        .ok_or_else(|| format!("Invalid year: {}, expected 1-9999", text).into())
+}
@@ -0,0 +1,36 @@
+use crate::{output, units, variables, Command, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let (name, words) = command
+        .arguments
+        .split_first()
+        .ok_or("Expected a variable name")?;
+    if !variables::is_valid_name(name) {
+        return Err(format!(
+            "Invalid variable name: {} (use letters, digits and _, not starting with a digit)",
+            name
+        )
+        .into());
+    }
+    let nested = Command::from_words(words)?;
+
+    output::start_silent_capture();
+    let result = nested.bind(command.context).execute();
+    let captured = output::finish_capture();
+    result?;
+    if captured.truncated {
+        return Err(format!(
+            "The output is larger than {}, too much for a variable",
+            units::format_size(output::CAPTURE_LIMIT as u64)
+        )
+        .into());
+    }
+
+    let mut value = String::from_utf8_lossy(&captured.bytes).into_owned();
+    // Like `$(...)` in other shells, trailing newlines are dropped; the
+    // lines inside are kept.
+    value.truncate(value.trim_end_matches(['\n', '\r']).len());
+    variables::set(name, value);
+    Ok(())
+}
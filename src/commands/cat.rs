@@ -0,0 +1,39 @@
+use crate::{out, output, ExecutableCommand};
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let number_lines = command.has_flag("-n");
+    // Numbering continues across files, as if they were one stream.
+    let mut line_number = 0;
+
+    for argument in &command.arguments {
+        let path = command.context.resolve(argument);
+        let file = File::open(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let mut reader = BufReader::new(file);
+
+        if !number_lines {
+            let mut buffer = [0; 64 * 1024];
+            loop {
+                let read = reader.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                output::write_bytes(&buffer[..read]);
+            }
+            continue;
+        }
+
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            line_number += 1;
+            out!("{:>6}\t", line_number);
+            output::write_bytes(&line);
+            line.clear();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::{context::ShellContext, env_file, options, outln, ExecutableCommand};
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let context = command.context;
+    let target = match command.arguments.first().map(String::as_str) {
+        None => home_dir().ok_or("Could not locate the home directory")?,
+        Some("-") => {
+            let previous = context.previous_dir().ok_or("No previous directory")?;
+            // Like other shells, show where `cd -` went.
+            outln!("{}", previous.display());
+            previous
+        }
+        Some(path) => locate(context, path)?,
+    };
+
+    change_dir(context, target)
+}
+
+/// Finds the directory `path` refers to: relative to the working directory,
+/// else under one of the `cdpath` directories, else a single subdirectory
+/// of the working directory whose name loosely matches. Found elsewhere, the
+/// directory is printed, as other shells do for CDPATH.
+pub(super) fn locate(context: &ShellContext, path: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let direct = normalize(&context.resolve(path));
+    // Explicitly relative paths mean the working directory, as in bash.
+    let explicit = Path::new(path).is_absolute()
+        || matches!(
+            Path::new(path).components().next(),
+            Some(Component::CurDir | Component::ParentDir)
+        );
+    if direct.is_dir() || explicit {
+        return Ok(direct);
+    }
+
+    for base in cdpath() {
+        let candidate = normalize(&context.resolve(base.join(path)));
+        if candidate.is_dir() {
+            outln!("{}", candidate.display());
+            return Ok(candidate);
+        }
+    }
+
+    if Path::new(path).components().count() == 1 {
+        if let Some(name) = fuzzy_match(&context.cwd(), path)? {
+            let candidate = context.resolve(name);
+            outln!("{}", candidate.display());
+            return Ok(candidate);
+        }
+    }
+    Ok(direct)
+}
+
+/// The `cdpath` option, or `$CDPATH` when the option is empty.
+fn cdpath() -> Vec<PathBuf> {
+    let option = options::get(options::CDPATH);
+    let value = match &*option {
+        "" => env::var_os("CDPATH").unwrap_or_default(),
+        value => value.into(),
+    };
+    env::split_paths(&value)
+        .filter(|base| !base.as_os_str().is_empty())
+        .collect()
+}
+
+/// The one subdirectory of `dir` whose name matches `name` best, ignoring
+/// case: exactly, then as a prefix, then as a substring, then with the
+/// letters of `name` in order. Several equally good matches are an error.
+fn fuzzy_match(dir: &Path, name: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(None);
+    };
+    let mut names = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+
+    let wanted = name.to_lowercase();
+    let tiers: [&dyn Fn(&str) -> bool; 4] = [
+        &|candidate| candidate == wanted,
+        &|candidate| candidate.starts_with(&wanted),
+        &|candidate| candidate.contains(&wanted),
+        &|candidate| is_subsequence(&wanted, candidate),
+    ];
+    for matches in tiers {
+        let found = names
+            .iter()
+            .filter(|candidate| matches(&candidate.to_lowercase()))
+            .collect::<Vec<_>>();
+        match found[..] {
+            [] => continue,
+            [single] => return Ok(Some(single.clone())),
+            _ => {
+                let found = found.iter().map(|name| name.as_str()).collect::<Vec<_>>();
+                return Err(
+                    format!("{} matches several directories: {}", name, found.join(", ")).into(),
+                );
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle
+        .chars()
+        .all(|c| haystack.any(|candidate| candidate == c))
+}
+
+/// Makes `target` the working directory, and applies or reverts the
+/// environment files along the way. Shared with `pushd` and `popd`.
+pub(super) fn change_dir(context: &ShellContext, target: PathBuf) -> Result<(), Box<dyn Error>> {
+    let metadata =
+        fs::metadata(&target).map_err(|error| format!("{}: {}", target.display(), error))?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a directory", target.display()).into());
+    }
+    context
+        .set_cwd(target.clone())
+        .map_err(|error| format!("{}: {}", target.display(), error))?;
+    env_file::update(context)
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// Removes `.` and `..` components without following symlinks, so that
+/// `cd ..` goes back the way it came, as it does in other shells.
+pub(super) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
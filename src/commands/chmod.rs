@@ -0,0 +1,103 @@
+use crate::ExecutableCommand;
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    platform::change_mode(command)
+}
+
+#[cfg(unix)]
+mod platform {
+    use crate::{options, permissions, status, verbose, ExecutableCommand};
+    use std::{
+        error::Error,
+        fs::{self, Permissions},
+        os::unix::fs::PermissionsExt,
+        path::Path,
+    };
+
+    /// The new mode: octal replaces all bits, symbolic clauses change some of
+    /// them, so they depend on each file's current mode.
+    #[derive(Debug, Clone, Copy)]
+    enum Mode<'a> {
+        Octal(u32),
+        Symbolic(&'a str),
+    }
+
+    impl Mode<'_> {
+        fn apply(&self, current: u32) -> Result<u32, Box<dyn Error>> {
+            match self {
+                Mode::Octal(mode) => Ok(*mode),
+                Mode::Symbolic(clauses) => {
+                    Ok(current & !0o777 | permissions::apply_symbolic(current & 0o777, clauses)?)
+                }
+            }
+        }
+    }
+
+    pub fn change_mode(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+        let (spec, paths) = command.arguments.split_first().ok_or("Missing mode")?;
+        let mode = if spec.chars().all(|c| c.is_ascii_digit()) {
+            Mode::Octal(permissions::parse_octal(spec)?)
+        } else {
+            // Checked once up front, so a typo fails before any file changes.
+            permissions::apply_symbolic(0, spec)?;
+            Mode::Symbolic(spec)
+        };
+
+        let recursive = command.has_flag("--recursive");
+        for path in paths {
+            apply(&command.context.resolve(path), mode, recursive)?;
+        }
+        Ok(())
+    }
+
+    /// Changes `path`, and everything below it when `recursive`. Symlinks met
+    /// while recursing are skipped: their own mode means nothing, and their
+    /// targets may lie outside the tree.
+    fn apply(path: &Path, mode: Mode, recursive: bool) -> Result<(), Box<dyn Error>> {
+        let metadata =
+            fs::metadata(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let current = metadata.permissions().mode() & 0o7777;
+        let new = mode.apply(current)?;
+
+        if options::dry_run() {
+            status!(
+                "would change mode of {} to {}",
+                path.display(),
+                permissions::octal(new)
+            );
+        } else if new != current {
+            fs::set_permissions(path, Permissions::from_mode(new))
+                .map_err(|error| format!("{}: {}", path.display(), error))?;
+            verbose!(
+                "changed mode of {} from {} to {}",
+                path.display(),
+                permissions::octal(current),
+                permissions::octal(new)
+            );
+        }
+
+        if recursive && metadata.is_dir() {
+            let mut entries = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.sort();
+            for entry in entries {
+                if !fs::symlink_metadata(&entry)?.is_symlink() {
+                    apply(&entry, mode, true)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use crate::ExecutableCommand;
+    use std::error::Error;
+
+    pub fn change_mode(_command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+        Err("Changing permission bits is only supported on Unix".into())
+    }
+}
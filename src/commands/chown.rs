@@ -0,0 +1,158 @@
+use crate::ExecutableCommand;
+use std::error::Error;
+
+pub fn chown(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    platform::change_ownership(command, false)
+}
+
+pub fn chgrp(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    platform::change_ownership(command, true)
+}
+
+#[cfg(unix)]
+mod platform {
+    use crate::{options, status, users, verbose, ExecutableCommand};
+    use std::{
+        error::Error,
+        fs,
+        os::unix::fs::{chown, lchown, MetadataExt},
+        path::Path,
+    };
+
+    /// The owner and group to set; `None` leaves that part unchanged.
+    #[derive(Debug, Clone, Copy)]
+    struct Ownership {
+        uid: Option<u32>,
+        gid: Option<u32>,
+    }
+
+    pub fn change_ownership(
+        command: &ExecutableCommand,
+        group_only: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let (ownership, paths) = match command.get_flag_value("--reference") {
+            Some(reference) => {
+                let metadata = fs::metadata(command.context.resolve(reference))?;
+                let ownership = Ownership {
+                    uid: (!group_only).then(|| metadata.uid()),
+                    gid: Some(metadata.gid()),
+                };
+                (ownership, &command.arguments[..])
+            }
+            None => {
+                let (spec, paths) = command.arguments.split_first().ok_or("Missing owner")?;
+                if paths.is_empty() {
+                    return Err("Missing file operand".into());
+                }
+                let ownership = if group_only {
+                    Ownership {
+                        uid: None,
+                        gid: Some(parse_group(spec)?),
+                    }
+                } else {
+                    parse_owner(spec)?
+                };
+                (ownership, paths)
+            }
+        };
+        if paths.is_empty() {
+            return Err("Missing file operand".into());
+        }
+
+        let recursive = command.has_flag("--recursive");
+        for path in paths {
+            apply(&command.context.resolve(path), ownership, recursive, false)?;
+        }
+        Ok(())
+    }
+
+    /// Parses `user`, `user:group`, `user:` (the user's login group) or
+    /// `:group`, with names or numeric IDs.
+    fn parse_owner(spec: &str) -> Result<Ownership, Box<dyn Error>> {
+        let (user, group) = match spec.split_once(':') {
+            Some((user, group)) => (user, Some(group)),
+            None => (spec, None),
+        };
+
+        let (uid, login_gid) = match user {
+            "" => (None, None),
+            user => match user.parse() {
+                Ok(uid) => (Some(uid), None),
+                Err(_) => {
+                    let user =
+                        users::find_user(user)?.ok_or_else(|| format!("Unknown user: {}", user))?;
+                    (Some(user.uid), Some(user.gid))
+                }
+            },
+        };
+        let gid = match group {
+            None => None,
+            Some("") => Some(login_gid.ok_or_else(|| {
+                format!("{} names no user whose login group could be used", spec)
+            })?),
+            Some(group) => Some(parse_group(group)?),
+        };
+
+        if uid.is_none() && gid.is_none() {
+            return Err(format!("Invalid owner: {}", spec).into());
+        }
+        Ok(Ownership { uid, gid })
+    }
+
+    fn parse_group(group: &str) -> Result<u32, Box<dyn Error>> {
+        if let Ok(gid) = group.parse() {
+            return Ok(gid);
+        }
+        Ok(users::find_group(group)?
+            .ok_or_else(|| format!("Unknown group: {}", group))?
+            .gid)
+    }
+
+    /// Changes `path`, and everything below it when `recursive`. Symlinks
+    /// met while recursing are changed themselves, never followed.
+    fn apply(
+        path: &Path,
+        ownership: Ownership,
+        recursive: bool,
+        is_nested: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let metadata = fs::symlink_metadata(path)?;
+        let is_link = metadata.file_type().is_symlink();
+
+        if options::dry_run() {
+            status!("would change ownership of {}", path.display());
+        } else {
+            let result = if is_link && is_nested {
+                lchown(path, ownership.uid, ownership.gid)
+            } else {
+                chown(path, ownership.uid, ownership.gid)
+            };
+            result.map_err(|error| format!("{}: {}", path.display(), error))?;
+            verbose!("changed ownership of {}", path.display());
+        }
+
+        if recursive && metadata.is_dir() {
+            let mut entries = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.sort();
+            for entry in entries {
+                apply(&entry, ownership, true, true)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use crate::ExecutableCommand;
+    use std::error::Error;
+
+    pub fn change_ownership(
+        _command: &ExecutableCommand,
+        _group_only: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        Err("Changing file ownership is only supported on Unix".into())
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{docs, out, parse_command::CommandType, ExecutableCommand};
+use std::error::Error;
+
+const BINARY_NAME: &str = env!("CARGO_PKG_NAME");
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let script = match command.arguments[0].as_str() {
+        "bash" => bash(),
+        "zsh" => zsh(),
+        "fish" => fish(),
+        "powershell" => powershell(),
+        other => {
+            return Err(format!(
+                "Unknown shell: {} (expected bash, zsh, fish or powershell)",
+                other
+            )
+            .into())
+        }
+    };
+    out!("{}", script);
+    Ok(())
+}
+
+fn shell_flags() -> Vec<&'static str> {
+    docs::SHELL_FLAGS.iter().map(|(flag, _)| *flag).collect()
+}
+
+fn command_names() -> Vec<&'static str> {
+    CommandType::ALL.iter().map(CommandType::name).collect()
+}
+
+/// Escapes `text` for use inside single quotes in bash, zsh and fish.
+fn single_quoted(text: &str) -> String {
+    text.replace('\'', "'\\''")
+}
+
+fn bash() -> String {
+    let function = format!("_{}", BINARY_NAME);
+    let mut script = format!(
+        "# bash completion for {binary}
+{function}() {{
+    local cur=${{COMP_WORDS[COMP_CWORD]}}
+    local i command_index=0
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        if [[ ${{COMP_WORDS[i]}} == -c ]]; then
+            command_index=$((i + 1))
+            break
+        fi
+    done
+
+    if ((command_index == 0)); then
+        COMPREPLY=($(compgen -W '{shell_flags}' -- \"$cur\"))
+        return
+    fi
+    if ((COMP_CWORD == command_index)); then
+        COMPREPLY=($(compgen -W '{commands}' -- \"$cur\"))
+        return
+    fi
+    if [[ ${{COMP_WORDS[command_index]}} == run && $cur != -* ]]; then
+        COMPREPLY=($(compgen -W \"$({binary} -c run --names 2>/dev/null)\" -- \"$cur\"))
+        return
+    fi
+    if [[ $cur != -* ]]; then
+        COMPREPLY=($(compgen -f -- \"$cur\"))
+        return
+    fi
+
+    local flags=''
+    case ${{COMP_WORDS[command_index]}} in
+",
+        binary = BINARY_NAME,
+        function = function,
+        shell_flags = shell_flags().join(" "),
+        commands = command_names().join(" "),
+    );
+
+    for command_type in CommandType::ALL {
+        let flags = command_type.get_supported_flags();
+        if !flags.is_empty() {
+            script.push_str(&format!(
+                "        {}) flags='{}' ;;\n",
+                command_type.name(),
+                flags.join(" ")
+            ));
+        }
+    }
+
+    script.push_str(&format!(
+        "    esac
+    COMPREPLY=($(compgen -W \"$flags\" -- \"$cur\"))
+}}
+complete -o default -F {function} {binary}
+",
+        function = function,
+        binary = BINARY_NAME,
+    ));
+    script
+}
+
+fn zsh() -> String {
+    let mut script = format!(
+        "#compdef {binary}
+
+_{binary}() {{
+    local command_index=${{words[(i)-c]}}
+    if ((command_index >= CURRENT)); then
+        local -a shell_flags=(
+",
+        binary = BINARY_NAME
+    );
+    for (flag, description) in docs::SHELL_FLAGS {
+        script.push_str(&format!(
+            "            '{}:{}'\n",
+            flag,
+            single_quoted(description)
+        ));
+    }
+    script.push_str(
+        "        )
+        _describe -o 'flag' shell_flags
+        return
+    fi
+    if ((CURRENT == command_index + 1)); then
+        local -a commands=(
+",
+    );
+    for command_type in CommandType::ALL {
+        script.push_str(&format!(
+            "            '{}:{}'\n",
+            command_type.name(),
+            single_quoted(docs::for_command(*command_type).summary)
+        ));
+    }
+    script.push_str(&format!(
+        "        )
+        _describe 'command' commands
+        return
+    fi
+    if [[ ${{words[command_index + 1]}} == run && $PREFIX != -* ]]; then
+        local -a tasks=(${{(f)\"$({binary} -c run --names 2>/dev/null)\"}})
+        compadd -a tasks
+        return
+    fi
+    if [[ $PREFIX != -* ]]; then
+        _files
+        return
+    fi
+
+    local -a flags
+    case ${{words[command_index + 1]}} in
+",
+        binary = BINARY_NAME
+    ));
+
+    for command_type in CommandType::ALL {
+        let flags = command_type.get_supported_flags();
+        if flags.is_empty() {
+            continue;
+        }
+        let doc = docs::for_command(*command_type);
+        let entries = flags
+            .iter()
+            .map(|flag| match doc.flag_description(flag) {
+                // `:` separates the name from the description in _describe.
+                Some(description) => format!(
+                    "'{}:{}'",
+                    flag,
+                    single_quoted(&description.replace(':', "\\:"))
+                ),
+                None => format!("'{}'", flag),
+            })
+            .collect::<Vec<_>>();
+        script.push_str(&format!(
+            "        {}) flags=({}) ;;\n",
+            command_type.name(),
+            entries.join(" ")
+        ));
+    }
+
+    script.push_str(&format!(
+        "    esac
+    _describe -o 'flag' flags
+}}
+
+compdef _{binary} {binary}
+",
+        binary = BINARY_NAME
+    ));
+    script
+}
+
+fn fish() -> String {
+    let mut script = format!(
+        "# fish completion for {binary}
+function __{binary}_command
+    set -l words (commandline -opc)
+    set -l index (contains -i -- -c $words)
+    and set -q words[(math $index + 1)]
+    and echo $words[(math $index + 1)]
+end
+
+complete -c {binary} -f
+",
+        binary = BINARY_NAME
+    );
+
+    for (flag, description) in docs::SHELL_FLAGS {
+        script.push_str(&format!(
+            "complete -c {} -n 'not __{}_command' {} -d '{}'\n",
+            BINARY_NAME,
+            BINARY_NAME,
+            fish_flag(flag),
+            single_quoted(description)
+        ));
+    }
+    for command_type in CommandType::ALL {
+        script.push_str(&format!(
+            "complete -c {} -n 'contains -- -c (commandline -opc); and not __{}_command' -a {} -d '{}'\n",
+            BINARY_NAME,
+            BINARY_NAME,
+            command_type.name(),
+            single_quoted(docs::for_command(*command_type).summary)
+        ));
+    }
+    script.push_str(&format!(
+        "complete -c {} -n '__{}_command' -F\n",
+        BINARY_NAME, BINARY_NAME
+    ));
+    script.push_str(&format!(
+        "complete -c {binary} -n 'test (__{binary}_command) = run' -a '({binary} -c run --names 2>/dev/null)'\n",
+        binary = BINARY_NAME
+    ));
+    for command_type in CommandType::ALL {
+        let doc = docs::for_command(*command_type);
+        for flag in command_type.get_supported_flags() {
+            let mut line = format!(
+                "complete -c {} -n 'test (__{}_command) = {}' {}",
+                BINARY_NAME,
+                BINARY_NAME,
+                command_type.name(),
+                fish_flag(flag)
+            );
+            if let Some(description) = doc.flag_description(flag) {
+                line.push_str(&format!(" -d '{}'", single_quoted(description)));
+            }
+            script.push_str(&line);
+            script.push('\n');
+        }
+    }
+    script
+}
+
+/// Fish spells long, short and old style (`-vv`) options differently.
+fn fish_flag(flag: &str) -> String {
+    if let Some(name) = flag.strip_prefix("--") {
+        format!("-l {}", name)
+    } else if flag.len() == 2 {
+        format!("-s {}", &flag[1..])
+    } else {
+        format!("-o {}", &flag[1..])
+    }
+}
+
+fn powershell() -> String {
+    let mut table = String::new();
+    for command_type in CommandType::ALL {
+        let flags = command_type
+            .get_supported_flags()
+            .iter()
+            .map(|flag| format!("'{}'", flag))
+            .collect::<Vec<_>>();
+        table.push_str(&format!(
+            "        '{}' = @({})\n",
+            command_type.name(),
+            flags.join(", ")
+        ));
+    }
+    let shell_flags = shell_flags()
+        .iter()
+        .map(|flag| format!("'{}'", flag))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "# PowerShell completion for {binary}
+Register-ArgumentCompleter -Native -CommandName {binary} -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commands = @{{
+{table}    }}
+    $words = @($commandAst.CommandElements | ForEach-Object {{ $_.ToString() }})
+    $index = [array]::IndexOf($words, '-c')
+    # The word being completed is only in $words once something was typed.
+    $current = if ($wordToComplete) {{ $words.Count - 1 }} else {{ $words.Count }}
+
+    if ($index -lt 0 -or $current -le $index) {{
+        $candidates = @({shell_flags})
+    }} elseif ($current -eq $index + 1) {{
+        $candidates = $commands.Keys
+    }} elseif ($wordToComplete -like '-*') {{
+        $candidates = $commands[$words[$index + 1]]
+    }} elseif ($words[$index + 1] -eq 'run') {{
+        $candidates = @(& {binary} -c run --names 2>$null)
+    }} else {{
+        return
+    }}
+
+    $candidates | Where-Object {{ $_ -like \"$wordToComplete*\" }} | Sort-Object | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }}
+}}
+",
+        binary = BINARY_NAME,
+        table = table,
+        shell_flags = shell_flags,
+    )
+}
@@ -0,0 +1,68 @@
+use crate::{
+    outln,
+    units::{self, Base, Dimension},
+    ExecutableCommand,
+};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let value = &command.arguments[0];
+    let target = command
+        .get_flag_value("--to")
+        .or(command.arguments.get(1).map(String::as_str));
+
+    // Integers written with a base prefix, or with a base to convert to.
+    let base = target.and_then(|target| Base::try_from(target).ok());
+    if base.is_some() || units::integer_base(value).is_some() {
+        let number = units::parse_integer(value)?;
+        outln!(
+            "{}",
+            units::format_integer(number, base.unwrap_or(Base::Decimal))
+        );
+        return Ok(());
+    }
+
+    let (number, unit_name) = units::split_quantity(value)?;
+    let (unit, base_value) = match units::find_unit(unit_name) {
+        Some(unit) => (unit, unit.base_value(number)),
+        None if unit_name.is_empty() => {
+            return Err(format!("Missing unit in {}, e.g. 10MiB, 90m or 20C", value).into())
+        }
+        // Durations with several parts, such as 1h30m.
+        None => match units::parse_duration(value) {
+            Ok(seconds) => (&units::SECOND, seconds),
+            Err(_) => return Err(format!("Unknown unit: {}", unit_name).into()),
+        },
+    };
+
+    let Some(target) = target else {
+        match unit.dimension {
+            Dimension::Size if base_value >= 0.0 => {
+                outln!("{}", units::format_size(base_value.round() as u64));
+            }
+            Dimension::Duration if base_value >= 0.0 => {
+                outln!("{}", units::format_duration(base_value));
+            }
+            _ => return Err("Missing unit to convert to".into()),
+        }
+        return Ok(());
+    };
+
+    let target = units::find_unit(target).ok_or_else(|| format!("Unknown unit: {}", target))?;
+    if target.dimension != unit.dimension {
+        return Err(format!(
+            "Cannot convert a {} ({}) to a {} ({})",
+            unit.dimension.name(),
+            unit.symbol(),
+            target.dimension.name(),
+            target.symbol()
+        )
+        .into());
+    }
+    outln!(
+        "{} {}",
+        units::format_number(target.unit_value(base_value)),
+        target.symbol()
+    );
+    Ok(())
+}
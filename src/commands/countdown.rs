@@ -0,0 +1,64 @@
+use crate::{options, out, outln, output, units, ExecutableCommand};
+use std::{
+    error::Error,
+    thread,
+    time::{Duration, Instant},
+};
+
+const TICK: Duration = Duration::from_millis(100);
+const BELL: char = '\x07';
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let seconds = units::parse_duration(&command.arguments[0])?;
+    let total = Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("Invalid duration: {}", command.arguments[0]))?;
+    let deadline = Instant::now() + total;
+
+    // Accessible mode prints a line now and then instead of redrawing one.
+    let accessible = options::accessible();
+    let mut shown = None;
+    loop {
+        if command.context.cancellation.is_cancelled() {
+            if !accessible {
+                outln!();
+            }
+            return Err("Countdown cancelled".into());
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        // Rounds up, so the display reads 0:00:01 during the last second.
+        let remaining_seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        if shown != Some(remaining_seconds) {
+            let clock = units::format_clock(Duration::from_secs(remaining_seconds), false);
+            if !accessible {
+                out!("\r{} remaining ", clock);
+            } else if shown.is_none()
+                || remaining_seconds.is_multiple_of(announce_interval(remaining_seconds))
+            {
+                outln!("{} remaining", clock);
+            }
+            output::flush()?;
+            shown = Some(remaining_seconds);
+        }
+        thread::sleep(TICK.min(remaining));
+    }
+
+    if accessible {
+        outln!("Time's up!{}", BELL);
+    } else {
+        outln!("\rTime's up!{:<12}{}", "", BELL);
+    }
+    Ok(())
+}
+
+/// How often accessible mode reports the time left: every minute, and
+/// every 10 seconds in the last minute.
+fn announce_interval(remaining_seconds: u64) -> u64 {
+    if remaining_seconds > 60 {
+        60
+    } else {
+        10
+    }
+}
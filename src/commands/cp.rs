@@ -0,0 +1,195 @@
+use crate::{copy, options, status, verbose, ExecutableCommand};
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let copier = Copier {
+        recursive: command.has_flag("--recursive"),
+        force: command.has_flag("--force"),
+        dry_run: options::dry_run(),
+        options: copy::Options::from_command(command)?,
+    };
+
+    let (destination, sources) = command
+        .arguments
+        .split_last()
+        .ok_or("Missing destination")?;
+    let destination = command.context.resolve(destination);
+    let into_directory = destination.is_dir();
+    if sources.len() > 1 && !into_directory {
+        return Err(format!("{} is not a directory", destination.display()).into());
+    }
+
+    for source in sources {
+        let source = command.context.resolve(source);
+        let target = if into_directory {
+            let name = source
+                .file_name()
+                .ok_or_else(|| format!("Cannot copy {}", source.display()))?;
+            destination.join(name)
+        } else {
+            destination.clone()
+        };
+        copier.copy(&source, &target)?;
+    }
+    Ok(())
+}
+
+/// Copies files and, when recursive, directory trees; `mv` uses it to move
+/// between filesystems.
+pub(super) struct Copier {
+    pub recursive: bool,
+    pub force: bool,
+    pub dry_run: bool,
+    pub options: copy::Options,
+}
+
+impl Copier {
+    pub fn copy(&self, source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+        let metadata = fs::symlink_metadata(source)
+            .map_err(|error| format!("Cannot copy {}: {}", source.display(), error))?;
+        // A top-level symlink is copied as the file it points to, but one to
+        // a directory stays a link, like the links inside a copied tree.
+        let links_to_dir = metadata.is_symlink() && source.is_dir();
+        if !metadata.is_dir() && !links_to_dir {
+            return self.copy_file(source, target);
+        }
+        if !self.recursive {
+            return Err(format!("{} is a directory; use -r to copy it", source.display()).into());
+        }
+        if links_to_dir {
+            return self.copy_symlink(source, target);
+        }
+        if is_inside(target, source) {
+            return Err(format!(
+                "Cannot copy {} into itself ({})",
+                source.display(),
+                target.display()
+            )
+            .into());
+        }
+        self.copy_dir(source, target)
+    }
+
+    fn copy_dir(&self, source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+        if self.dry_run {
+            status!("would create {}", target.display());
+        } else if !target.is_dir() {
+            fs::create_dir(target)
+                .map_err(|error| format!("Cannot create {}: {}", target.display(), error))?;
+        }
+
+        let mut entries = fs::read_dir(source)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let from = entry.path();
+            let to = target.join(entry.file_name());
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                self.copy_dir(&from, &to)?;
+            } else if file_type.is_symlink() {
+                self.copy_symlink(&from, &to)?;
+            } else {
+                self.copy_file(&from, &to)?;
+            }
+        }
+
+        // Set last, so a read-only source directory can still be filled
+        // and its time is not changed by the copies.
+        if !self.dry_run {
+            if self.options.preserve {
+                copy::preserve_metadata(source, target)?;
+            } else {
+                fs::set_permissions(target, fs::metadata(source)?.permissions())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn copy_file(&self, source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+        self.make_room(target)?;
+        if self.dry_run {
+            status!("would copy {} to {}", source.display(), target.display());
+            return Ok(());
+        }
+        copy::copy_file(source, target, self.options).map_err(|error| {
+            format!(
+                "Cannot copy {} to {}: {}",
+                source.display(),
+                target.display(),
+                error
+            )
+        })?;
+        verbose!("copied {} to {}", source.display(), target.display());
+        Ok(())
+    }
+
+    /// Symlinks inside a copied tree stay symlinks.
+    fn copy_symlink(&self, source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+        self.make_room(target)?;
+        if self.dry_run {
+            status!("would link {} like {}", target.display(), source.display());
+            return Ok(());
+        }
+        platform::copy_symlink(source, target)?;
+        verbose!("linked {}", target.display());
+        Ok(())
+    }
+
+    /// Fails if `target` exists, unless forced, in which case it is removed.
+    fn make_room(&self, target: &Path) -> Result<(), Box<dyn Error>> {
+        let Ok(metadata) = fs::symlink_metadata(target) else {
+            return Ok(());
+        };
+        if !self.force {
+            return Err(format!(
+                "{} already exists; use -f to overwrite it",
+                target.display()
+            )
+            .into());
+        }
+        if metadata.is_dir() {
+            return Err(format!("Cannot overwrite directory {}", target.display()).into());
+        }
+        if !self.dry_run {
+            fs::remove_file(target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `path` is `dir` or somewhere below it, comparing real paths.
+fn is_inside(path: &Path, dir: &Path) -> bool {
+    let Ok(dir) = fs::canonicalize(dir) else {
+        return false;
+    };
+    // The target may not exist yet, so its parent is resolved instead.
+    let resolved = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => fs::canonicalize(parent).map(|parent| parent.join(name)),
+        _ => fs::canonicalize(path),
+    };
+    resolved.is_ok_and(|path: PathBuf| path.starts_with(dir))
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::{fs, io, os::unix::fs::symlink, path::Path};
+
+    pub fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+        symlink(fs::read_link(source)?, target)
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use std::{fs, io, path::Path};
+
+    /// Creating symlinks needs extra privileges on Windows, so the file
+    /// they point to is copied instead.
+    pub fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+        fs::copy(source, target).map(|_| ())
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    atomic::{self, AtomicFile},
+    crypto,
+    i18n::Message,
+    options, status, terminal, Command, ExecutableCommand,
+};
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+};
+
+const MAGIC: &[u8; 8] = b"MBCTENC1";
+const SALT_SIZE: usize = 16;
+const NONCE_PREFIX_SIZE: usize = 7;
+const HEADER_SIZE: usize = MAGIC.len() + 4 + SALT_SIZE + NONCE_PREFIX_SIZE + 4;
+const KDF_ITERATIONS: u32 = 200_000;
+/// Keeps a crafted header from making key derivation run for hours.
+const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+const CHUNK_SIZE: u32 = 64 * 1024;
+const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+const ENCRYPTED_EXTENSION: &str = ".enc";
+
+/// Parameters stored unencrypted at the start of the file. The raw header is
+/// passed as associated data to every chunk, so tampering with it is detected.
+struct Header {
+    iterations: u32,
+    salt: [u8; SALT_SIZE],
+    nonce_prefix: [u8; NONCE_PREFIX_SIZE],
+    chunk_size: u32,
+}
+
+impl Header {
+    fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        let mut offset = 0;
+        for part in [
+            &MAGIC[..],
+            &self.iterations.to_be_bytes(),
+            &self.salt,
+            &self.nonce_prefix,
+            &self.chunk_size.to_be_bytes(),
+        ] {
+            bytes[offset..offset + part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_SIZE]) -> Result<Self, Box<dyn Error>> {
+        if &bytes[..MAGIC.len()] != MAGIC {
+            return Err("Not an encrypted file".into());
+        }
+
+        let mut offset = MAGIC.len();
+        let mut take = |length: usize| {
+            let part = &bytes[offset..offset + length];
+            offset += length;
+            part
+        };
+        let iterations = u32::from_be_bytes(take(4).try_into()?);
+        let salt = take(SALT_SIZE).try_into()?;
+        let nonce_prefix = take(NONCE_PREFIX_SIZE).try_into()?;
+        let chunk_size = u32::from_be_bytes(take(4).try_into()?);
+
+        if iterations == 0 || iterations > MAX_KDF_ITERATIONS {
+            return Err(format!("Invalid iteration count in header: {}", iterations).into());
+        }
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(format!("Invalid chunk size in header: {}", chunk_size).into());
+        }
+
+        Ok(Self {
+            iterations,
+            salt,
+            nonce_prefix,
+            chunk_size,
+        })
+    }
+
+    /// Builds the per-chunk nonce: prefix, big-endian chunk counter and a
+    /// final-chunk flag, so chunks cannot be reordered or truncated.
+    fn nonce(&self, counter: u32, is_last: bool) -> [u8; crypto::NONCE_SIZE] {
+        let mut nonce = [0; crypto::NONCE_SIZE];
+        nonce[..NONCE_PREFIX_SIZE].copy_from_slice(&self.nonce_prefix);
+        nonce[NONCE_PREFIX_SIZE..NONCE_PREFIX_SIZE + 4].copy_from_slice(&counter.to_be_bytes());
+        nonce[crypto::NONCE_SIZE - 1] = is_last as u8;
+        nonce
+    }
+}
+
+pub fn encrypt(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let input = &command.arguments[0];
+    let output = output_path(command, || format!("{}{}", input, ENCRYPTED_EXTENSION));
+    if options::dry_run() {
+        File::open(input)?;
+        status!("would encrypt {} to {}", input, output);
+        return Ok(());
+    }
+
+    let passphrase = terminal::read_hidden(Message::Passphrase.text())?;
+    if passphrase.is_empty() {
+        return Err("Passphrase must not be empty".into());
+    }
+    if terminal::read_hidden(Message::ConfirmPassphrase.text())? != passphrase {
+        return Err("Passphrases do not match".into());
+    }
+
+    let header = Header {
+        iterations: KDF_ITERATIONS,
+        salt: crypto::random_bytes()?,
+        nonce_prefix: crypto::random_bytes()?,
+        chunk_size: CHUNK_SIZE,
+    };
+    let header_bytes = header.to_bytes();
+    let key = crypto::derive_key(passphrase.as_bytes(), &header.salt, header.iterations);
+
+    let mut reader = File::open(input)?;
+    let mut writer = BufWriter::new(atomic::Output::create(
+        &output,
+        command.has_flag("--atomic"),
+    )?);
+    writer.write_all(&header_bytes)?;
+
+    let mut current = read_chunk(&mut reader, header.chunk_size as usize)?;
+    let mut counter = 0u32;
+    loop {
+        let next = read_chunk(&mut reader, header.chunk_size as usize)?;
+        let is_last = next.is_empty();
+
+        let nonce = header.nonce(counter, is_last);
+        writer.write_all(&crypto::seal(&key, &nonce, &header_bytes, &current))?;
+
+        if is_last {
+            break;
+        }
+        current = next;
+        counter = counter.checked_add(1).ok_or("File is too large")?;
+    }
+    writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+
+    status!("Encrypted {} to {}", input, output);
+    Ok(())
+}
+
+pub fn decrypt(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let input = &command.arguments[0];
+    let output = output_path(command, || match input.strip_suffix(ENCRYPTED_EXTENSION) {
+        Some(stripped) => stripped.to_string(),
+        None => format!("{}.dec", input),
+    });
+
+    let mut reader = File::open(input)?;
+    let mut header_bytes = [0; HEADER_SIZE];
+    reader
+        .read_exact(&mut header_bytes)
+        .map_err(|_| "Not an encrypted file")?;
+    let header = Header::from_bytes(&header_bytes)?;
+    if options::dry_run() {
+        status!("would decrypt {} to {}", input, output);
+        return Ok(());
+    }
+
+    let passphrase = terminal::read_hidden(Message::Passphrase.text())?;
+    let key = crypto::derive_key(passphrase.as_bytes(), &header.salt, header.iterations);
+
+    decrypt_chunks(&mut reader, &output, &header, &header_bytes, &key)?;
+
+    status!("Decrypted {} to {}", input, output);
+    Ok(())
+}
+
+/// Decrypts into a temporary file that only replaces `output` once every
+/// chunk is authenticated, so a wrong passphrase or a damaged file leaves an
+/// existing `output` untouched and no unauthenticated data behind.
+fn decrypt_chunks(
+    reader: &mut File,
+    output: &str,
+    header: &Header,
+    header_bytes: &[u8],
+    key: &[u8; crypto::KEY_SIZE],
+) -> Result<(), Box<dyn Error>> {
+    let sealed_chunk_size = header.chunk_size as usize + crypto::TAG_SIZE;
+    let mut writer = BufWriter::new(AtomicFile::create(output)?);
+
+    let mut current = read_chunk(reader, sealed_chunk_size)?;
+    let mut counter = 0u32;
+    loop {
+        let next = read_chunk(reader, sealed_chunk_size)?;
+        let is_last = next.is_empty();
+
+        let nonce = header.nonce(counter, is_last);
+        writer.write_all(&crypto::open(key, &nonce, header_bytes, &current)?)?;
+
+        if is_last {
+            break;
+        }
+        current = next;
+        counter = counter.checked_add(1).ok_or("File is too large")?;
+    }
+
+    writer.into_inner().map_err(|e| e.into_error())?.commit()?;
+    Ok(())
+}
+
+fn output_path(command: &Command, default: impl FnOnce() -> String) -> String {
+    command
+        .get_flag_value("--output")
+        .map(str::to_string)
+        .unwrap_or_else(default)
+}
+
+/// Reads up to `size` bytes, only returning less at the end of the input.
+fn read_chunk(reader: &mut impl Read, size: usize) -> io::Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(size);
+    reader.take(size as u64).read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
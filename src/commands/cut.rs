@@ -0,0 +1,66 @@
+use crate::{outln, ExecutableCommand};
+use std::error::Error;
+
+const DEFAULT_DELIMITER: &str = "\t";
+
+/// Fields counted from 1, both ends included; `None` is open ended.
+type FieldRange = (usize, Option<usize>);
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let delimiter = command.get_flag_value("-d").unwrap_or(DEFAULT_DELIMITER);
+    if delimiter.is_empty() {
+        return Err("The delimiter cannot be empty".into());
+    }
+    let ranges = parse_fields(
+        command
+            .get_flag_value("-f")
+            .ok_or("Missing field list: use -f <list>, e.g. -f 1,3-4")?,
+    )?;
+
+    for line in command.read_input()?.lines() {
+        // Lines without the delimiter have a single field and are printed
+        // whole, as in other shells.
+        if !line.contains(delimiter) {
+            outln!("{}", line);
+            continue;
+        }
+        let fields = line
+            .split(delimiter)
+            .enumerate()
+            .filter(|(index, _)| {
+                let field = index + 1;
+                ranges
+                    .iter()
+                    .any(|&(start, end)| field >= start && end.is_none_or(|end| field <= end))
+            })
+            .map(|(_, field)| field)
+            .collect::<Vec<_>>();
+        outln!("{}", fields.join(delimiter));
+    }
+    Ok(())
+}
+
+/// Parses a list such as `1,3-4,6-`, where `-2` means up to field 2.
+fn parse_fields(list: &str) -> Result<Vec<FieldRange>, Box<dyn Error>> {
+    let invalid = || format!("Invalid field list: {}, expected e.g. 1,3-4 or 2-", list);
+    let field = |text: &str| match text.parse::<usize>() {
+        Ok(0) | Err(_) => Err(invalid()),
+        Ok(field) => Ok(field),
+    };
+
+    list.split(',')
+        .map(|item| {
+            let range = match item.split_once('-') {
+                None => (field(item)?, Some(field(item)?)),
+                Some(("", "")) => return Err(invalid().into()),
+                Some(("", end)) => (1, Some(field(end)?)),
+                Some((start, "")) => (field(start)?, None),
+                Some((start, end)) => (field(start)?, Some(field(end)?)),
+            };
+            match range {
+                (start, Some(end)) if end < start => Err(invalid().into()),
+                range => Ok(range),
+            }
+        })
+        .collect()
+}
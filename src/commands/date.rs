@@ -0,0 +1,34 @@
+use crate::{
+    calendar::{self, DateTime},
+    outln,
+    timezone::TimeZone,
+    ExecutableCommand,
+};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let target = match command.get_flag_value("--to") {
+        Some(name) => TimeZone::load(name)?,
+        None => TimeZone::local(),
+    };
+
+    let timestamp = match command.get_flag_value("--convert") {
+        Some(text) => {
+            let source = match command.get_flag_value("--from") {
+                Some(name) => TimeZone::load(name)?,
+                None => TimeZone::local(),
+            };
+            source.timestamp(&DateTime::parse(text)?)
+        }
+        None => calendar::now(),
+    };
+
+    let local = target.local_time(timestamp);
+    outln!(
+        "{} {} ({})",
+        local.date_time,
+        local.abbreviation,
+        local.offset_text()
+    );
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::{
+    log::{self, Level, Target},
+    outln, status, ExecutableCommand,
+};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    match command.arguments.first().map(String::as_str) {
+        None | Some("status") => {
+            let (targets, level, file) = log::status();
+            if targets.is_empty() {
+                outln!("Debug logging is off");
+                return Ok(());
+            }
+            let names = targets.iter().map(Target::name).collect::<Vec<_>>();
+            outln!(
+                "Logging {} at {} level to {}",
+                names.join(","),
+                level.name(),
+                file.map_or("stderr".to_string(), |path| path.display().to_string())
+            );
+        }
+        Some("on") => {
+            let targets = match command.arguments.get(1).map(String::as_str) {
+                None | Some("all") => Target::ALL.to_vec(),
+                Some(list) => list
+                    .split(',')
+                    .map(|name| Target::try_from(name.trim()))
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+            let level = match command.get_flag_value("--level") {
+                Some(level) => Level::try_from(level)?,
+                None => Level::Debug,
+            };
+            let file = command
+                .get_flag_value("--file")
+                .map(|path| command.context.resolve(path));
+
+            log::enable(targets, level, file.as_deref())?;
+            status!("Debug logging enabled");
+        }
+        Some("off") => {
+            log::disable();
+            status!("Debug logging disabled");
+        }
+        Some(other) => {
+            return Err(format!("Unknown debuglog action: {} (expected on or off)", other).into())
+        }
+    }
+
+    Ok(())
+}
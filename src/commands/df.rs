@@ -0,0 +1,113 @@
+use crate::{
+    mounts::{self, Mount, Space},
+    outln, units, walk, ExecutableCommand,
+};
+use std::{error::Error, fs, path::PathBuf};
+
+struct Row {
+    filesystem: String,
+    mount_point: PathBuf,
+    space: Space,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let mut errors = Vec::new();
+    let mut rows = Vec::new();
+
+    if command.arguments.is_empty() {
+        for mount in mounts::list()? {
+            match mounts::space(&mount.mount_point) {
+                // Pseudo filesystems such as proc have no blocks at all.
+                Ok(space) if space.total == 0 => {}
+                Ok(space) => rows.push(row(&mount, space)),
+                Err(error) => errors.push((mount.mount_point, error)),
+            }
+        }
+    } else {
+        // Listing mounts is only needed for names, so a platform without it
+        // still reports the space.
+        let mounts = mounts::list().unwrap_or_default();
+        for argument in &command.arguments {
+            let path = command.context.resolve(argument);
+            let space =
+                mounts::space(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+            let path = fs::canonicalize(&path).unwrap_or(path);
+            rows.push(match mounts::containing(&mounts, &path) {
+                Some(mount) => row(mount, space),
+                None => Row {
+                    filesystem: "-".to_string(),
+                    mount_point: path,
+                    space,
+                },
+            });
+        }
+    }
+
+    print(&rows, command.has_flag("-h"));
+    walk::report_errors(&errors)?;
+    Ok(())
+}
+
+fn row(mount: &Mount, space: Space) -> Row {
+    Row {
+        filesystem: mount.device.clone(),
+        mount_point: mount.mount_point.clone(),
+        space,
+    }
+}
+
+fn print(rows: &[Row], human_readable: bool) {
+    let size = |bytes: u64| {
+        if human_readable {
+            units::format_size(bytes)
+        } else {
+            // Like df, plain sizes count 1 KiB blocks.
+            bytes.div_ceil(1024).to_string()
+        }
+    };
+    let cells = rows
+        .iter()
+        .map(|row| {
+            [
+                size(row.space.total),
+                size(row.space.used()),
+                size(row.space.available),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let size_header = if human_readable { "Size" } else { "1K-blocks" };
+    let filesystem_width = rows
+        .iter()
+        .map(|row| row.filesystem.len())
+        .chain(["Filesystem".len()])
+        .max()
+        .unwrap_or_default();
+    let size_width = cells
+        .iter()
+        .flatten()
+        .map(String::len)
+        .chain([size_header.len(), "Available".len()])
+        .max()
+        .unwrap_or_default();
+
+    outln!(
+        "{:<filesystem_width$} {:>size_width$} {:>size_width$} {:>size_width$} {:>4} Mounted on",
+        "Filesystem",
+        size_header,
+        "Used",
+        "Available",
+        "Use%"
+    );
+    for (row, [total, used, available]) in rows.iter().zip(cells) {
+        outln!(
+            "{:<filesystem_width$} {:>size_width$} {:>size_width$} {:>size_width$} {:>3}% {}",
+            row.filesystem,
+            total,
+            used,
+            available,
+            row.space.used_percent(),
+            row.mount_point.display()
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use super::cd::{change_dir, locate};
+use crate::{outln, ExecutableCommand};
+use std::error::Error;
+
+/// `pushd <dir>` saves the working directory and enters `<dir>`, `pushd`
+/// swaps the top two entries and `pushd +N` rotates entry N to the top.
+pub fn pushd(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let context = command.context;
+    let cwd = context.cwd();
+    let mut saved = context.dir_stack();
+
+    match command.arguments.first() {
+        None => {
+            let target = saved.first().cloned().ok_or("No other directory")?;
+            change_dir(context, target)?;
+            saved[0] = cwd;
+        }
+        Some(argument) if argument.starts_with('+') => {
+            let mut stack = [vec![cwd], saved].concat();
+            let index = parse_index(argument, stack.len())?;
+            stack.rotate_left(index);
+            change_dir(context, stack[0].clone())?;
+            saved = stack.split_off(1);
+        }
+        Some(argument) => {
+            change_dir(context, locate(context, argument)?)?;
+            saved.insert(0, cwd);
+        }
+    }
+    context.set_dir_stack(saved);
+    print_stack(command, false);
+    Ok(())
+}
+
+/// Leaves the working directory for the most recently saved one.
+pub fn popd(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let context = command.context;
+    let mut saved = context.dir_stack();
+    if saved.is_empty() {
+        return Err("Directory stack is empty".into());
+    }
+    change_dir(context, saved.remove(0))?;
+    context.set_dir_stack(saved);
+    print_stack(command, false);
+    Ok(())
+}
+
+pub fn dirs(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    print_stack(command, command.has_flag("-v"));
+    Ok(())
+}
+
+/// Prints the working directory followed by the saved ones, on one line,
+/// or one per line with their positions when `verbose`.
+fn print_stack(command: &ExecutableCommand, verbose: bool) {
+    let stack = [vec![command.context.cwd()], command.context.dir_stack()].concat();
+    if verbose {
+        for (index, dir) in stack.iter().enumerate() {
+            outln!("{:>2}  {}", index, dir.display());
+        }
+    } else {
+        let dirs = stack
+            .iter()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>();
+        outln!("{}", dirs.join(" "));
+    }
+}
+
+fn parse_index(argument: &str, len: usize) -> Result<usize, Box<dyn Error>> {
+    argument[1..]
+        .parse::<usize>()
+        .ok()
+        .filter(|index| *index < len)
+        .ok_or_else(|| {
+            format!(
+                "Invalid stack position: {}, expected +0 to +{}",
+                argument,
+                len - 1
+            )
+            .into()
+        })
+}
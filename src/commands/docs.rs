@@ -0,0 +1,155 @@
+use crate::{
+    atomic,
+    docs::{self, CommandDoc},
+    options,
+    parse_command::CommandType,
+    status, ExecutableCommand,
+};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const BINARY_NAME: &str = env!("CARGO_PKG_NAME");
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    if command.arguments[0] != "generate" {
+        return Err(format!(
+            "Unknown docs action: {} (expected generate)",
+            command.arguments[0]
+        )
+        .into());
+    }
+
+    let format = command.get_flag_value("--format").unwrap_or("md");
+    let render: fn(CommandType, &CommandDoc) -> String = match format {
+        "md" => markdown,
+        "man" => man_page,
+        _ => return Err(format!("Unknown format: {} (expected md or man)", format).into()),
+    };
+    let dir = command
+        .get_flag_value("--output")
+        .map(|dir| command.context.resolve(dir))
+        .ok_or("Missing output directory: use -o <dir>")?;
+    if options::dry_run() {
+        status!("would write the {} docs to {}", format, dir.display());
+        return Ok(());
+    }
+    fs::create_dir_all(&dir)?;
+
+    let mut written = 0;
+    for command_type in CommandType::ALL {
+        let doc = docs::for_command(*command_type);
+        atomic::write(
+            file_path(&dir, *command_type, format),
+            render(*command_type, &doc),
+        )?;
+        written += 1;
+    }
+    if format == "md" {
+        atomic::write(dir.join("index.md"), markdown_index())?;
+        written += 1;
+    }
+
+    status!("Wrote {} files to {}", written, dir.display());
+    Ok(())
+}
+
+fn file_path(dir: &Path, command_type: CommandType, format: &str) -> PathBuf {
+    match format {
+        "man" => dir.join(format!("{}-{}.1", BINARY_NAME, command_type.name())),
+        _ => dir.join(format!("{}.md", command_type.name())),
+    }
+}
+
+fn markdown_index() -> String {
+    let mut page = format!("# {}\n\n", BINARY_NAME);
+    for command_type in CommandType::ALL {
+        page.push_str(&format!(
+            "- [{name}]({name}.md): {}\n",
+            docs::for_command(*command_type).summary,
+            name = command_type.name()
+        ));
+    }
+    page
+}
+
+fn markdown(command_type: CommandType, doc: &CommandDoc) -> String {
+    let mut page = format!("# {}\n\n{}\n\n", command_type.name(), doc.summary);
+    page.push_str(&format!("## Usage\n\n```\n{}\n```\n\n", doc.usage));
+    page.push_str(&format!("## Description\n\n{}\n\n", doc.description));
+
+    if !doc.flags.is_empty() {
+        page.push_str("## Flags\n\n| Flag | Description |\n| --- | --- |\n");
+        for (flag, description) in doc.flags {
+            page.push_str(&format!(
+                "| `{}` | {} |\n",
+                flag.replace('|', "\\|"),
+                description.replace('|', "\\|")
+            ));
+        }
+        page.push('\n');
+    }
+
+    if !doc.examples.is_empty() {
+        page.push_str("## Examples\n\n");
+        for (invocation, description) in doc.examples {
+            page.push_str(&format!("```\n{}\n```\n\n{}\n\n", invocation, description));
+        }
+    }
+
+    page.push_str(&format!("## Exit status\n\n{}\n", docs::EXIT_STATUS));
+    page
+}
+
+fn man_page(command_type: CommandType, doc: &CommandDoc) -> String {
+    let name = command_type.name();
+    let mut page = format!(
+        ".TH \"{}-{}\" 1 \"\" \"{} {}\"\n",
+        BINARY_NAME.to_uppercase(),
+        name.to_uppercase(),
+        BINARY_NAME,
+        env!("CARGO_PKG_VERSION")
+    );
+    page.push_str(&format!(
+        ".SH NAME\n{} \\- {}\n",
+        roff(name),
+        roff(doc.summary)
+    ));
+    page.push_str(&format!(".SH SYNOPSIS\n.B {}\n", roff(doc.usage)));
+    page.push_str(&format!(".SH DESCRIPTION\n{}\n", roff(doc.description)));
+
+    if !doc.flags.is_empty() {
+        page.push_str(".SH OPTIONS\n");
+        for (flag, description) in doc.flags {
+            page.push_str(&format!(".TP\n.B {}\n{}\n", roff(flag), roff(description)));
+        }
+    }
+
+    if !doc.examples.is_empty() {
+        page.push_str(".SH EXAMPLES\n");
+        for (invocation, description) in doc.examples {
+            page.push_str(&format!(
+                ".TP\n.B {}\n{}\n",
+                roff(invocation),
+                roff(description)
+            ));
+        }
+    }
+
+    page.push_str(&format!(".SH EXIT STATUS\n{}\n", roff(docs::EXIT_STATUS)));
+    page
+}
+
+/// Escapes text for roff: backslashes, hyphens (so they render as minus
+/// signs that can be searched and copied) and lines starting with a control
+/// character.
+fn roff(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+    if escaped.starts_with(['.', '\'']) {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    outln, units,
+    walk::{self, Entry, Visitor},
+    ExecutableCommand,
+};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+struct Usage<'a> {
+    human_readable: bool,
+    max_depth: Option<usize>,
+    /// The path walked and how it was given, to show entries below it.
+    root: PathBuf,
+    shown: &'a Path,
+    /// Totals of the directories being walked, the innermost last.
+    totals: Vec<u64>,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let max_depth = command
+        .get_flag_value("-d")
+        .map(|depth| {
+            depth
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid depth: {}", depth))
+        })
+        .transpose()?;
+    let options = walk::Options {
+        follow_links: command.has_flag("-L"),
+        dedupe_hardlinks: true,
+        include_git_dir: true,
+        ..walk::Options::default()
+    };
+
+    let paths = match &command.arguments[..] {
+        [] => vec![".".to_string()],
+        arguments => arguments.to_vec(),
+    };
+    // One walker for all paths, so hard links are counted once overall.
+    let mut walker = walk::Walker::new(options);
+    for argument in &paths {
+        let root = command.context.resolve(argument);
+        let mut usage = Usage {
+            human_readable: command.has_flag("-h"),
+            max_depth,
+            root: root.clone(),
+            shown: Path::new(argument),
+            totals: Vec::new(),
+        };
+        walker
+            .visit(&root, &mut usage)
+            .map_err(|error| format!("{}: {}", root.display(), error))?;
+    }
+
+    walk::report_errors(&walker.errors)?;
+    Ok(())
+}
+
+impl Visitor for Usage<'_> {
+    fn entry(&mut self, entry: &Entry) {
+        if entry.is_dir() {
+            self.totals.push(0);
+            return;
+        }
+        let size = if entry.repeated {
+            0
+        } else {
+            entry.metadata.len()
+        };
+        match self.totals.last_mut() {
+            Some(total) => *total += size,
+            // A file given as the path is shown on its own.
+            None => self.print(size, &entry.path),
+        }
+    }
+
+    /// Adds the directory's total to its parent's and prints it when it is
+    /// within the depth limit or is what was asked about.
+    fn leave(&mut self, dir: &Entry) {
+        let size = self.totals.pop().unwrap_or_default();
+        if let Some(parent) = self.totals.last_mut() {
+            *parent += size;
+        }
+        if dir.depth == 0 || self.max_depth.is_none_or(|max| dir.depth <= max) {
+            self.print(size, &dir.path);
+        }
+    }
+}
+
+impl Usage<'_> {
+    fn print(&self, size: u64, path: &Path) {
+        let shown = match path.strip_prefix(&self.root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => self.shown.join(relative),
+            _ => self.shown.to_path_buf(),
+        };
+        let size = if self.human_readable {
+            units::format_size(size)
+        } else {
+            size.to_string()
+        };
+        outln!("{:>10}  {}", size, shown.display());
+    }
+}
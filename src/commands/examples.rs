@@ -0,0 +1,87 @@
+use crate::{
+    config, context::ShellContext, docs, options, outln, parse_command::CommandType, Command,
+    ExecutableCommand,
+};
+use std::{
+    borrow::Cow,
+    env,
+    error::Error,
+    fs, mem,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const SANDBOX_PREFIX: &str = "my_basic_cli_tools_example";
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let command_type = CommandType::lookup(&command.arguments[0])?;
+    let examples = docs::for_command(command_type).examples;
+
+    let Some(selected) = command.get_flag_value("--run") else {
+        if examples.is_empty() {
+            outln!("No examples for {}", command_type.name());
+        }
+        for (i, (invocation, description)) in examples.iter().enumerate() {
+            outln!("{}. {}", i + 1, invocation);
+            outln!("   {}", description);
+        }
+        return Ok(());
+    };
+
+    let (invocation, _) = selected
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| examples.get(number.checked_sub(1)?))
+        .ok_or_else(|| format!("No example number {} for {}", selected, command_type.name()))?;
+
+    run_in_sandbox(invocation)
+}
+
+/// Runs `invocation` with a fresh temporary directory as the working
+/// directory and the config directory, so history, notes, reminders, the
+/// vault and other saved state are the sandbox's and not the user's.
+fn run_in_sandbox(invocation: &str) -> Result<(), Box<dyn Error>> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+    let sandbox = Sandbox::enter(env::temp_dir().join(format!("{}_{}", SANDBOX_PREFIX, nanos)))?;
+
+    outln!("$ {}", invocation);
+    let context = ShellContext::with_cwd(&sandbox.dir);
+    let command = Command::try_from(invocation)?;
+    command.bind(&context).execute()
+}
+
+/// The directory an example runs in. Dropping it puts back the working
+/// directory, the config directory and options changed with `set`, and
+/// removes the directory, however the example ended.
+struct Sandbox {
+    dir: PathBuf,
+    previous_dir: PathBuf,
+    previous_config_dir: Option<PathBuf>,
+    previous_options: Vec<(&'static str, Cow<'static, str>)>,
+}
+
+impl Sandbox {
+    fn enter(dir: PathBuf) -> Result<Self, Box<dyn Error>> {
+        fs::create_dir_all(&dir)?;
+        let previous_dir = env::current_dir()?;
+        // Created before changing anything, so dropping it undoes what did
+        // change if a step fails.
+        let sandbox = Self {
+            previous_config_dir: config::override_config_dir(Some(dir.join("config"))),
+            previous_options: options::session_values(),
+            dir,
+            previous_dir,
+        };
+        env::set_current_dir(&sandbox.dir)?;
+        Ok(sandbox)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = env::set_current_dir(&self.previous_dir);
+        config::override_config_dir(self.previous_config_dir.take());
+        options::restore(mem::take(&mut self.previous_options));
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
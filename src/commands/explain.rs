@@ -0,0 +1,77 @@
+use crate::{
+    outln,
+    parse_command::{default_flags, tokenize_words, Quoting, Token},
+    variables,
+    vault::SECRET_PREFIX,
+    Command, ExecutableCommand,
+};
+use std::{borrow::Cow, error::Error};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let line = &command.arguments[0];
+    outln!("{:<11}{}", "Line:", line);
+    let line = command.context.expand_alias(line);
+    if let Cow::Owned(aliased) = &line {
+        outln!("{:<11}{}", "Alias:", aliased);
+    }
+
+    // Expanded the way running the line would, but secrets are only named,
+    // so explaining never unlocks the vault.
+    let mut words = Vec::new();
+    outln!("Words:");
+    for (i, token) in tokenize_words(&line)?.into_iter().enumerate() {
+        outln!(
+            "  {:>2}. {:?} ({})",
+            i + 1,
+            token.text,
+            quoting_name(token.quoting)
+        );
+        if !token.allows_expansion() {
+            words.push(token);
+            continue;
+        }
+        let expanded = variables::expand(&token.text)?;
+        if let Cow::Owned(text) = &expanded {
+            outln!("      variables expand to {:?}", text);
+        }
+        if expanded.contains(SECRET_PREFIX) {
+            outln!("      secrets are filled in from the vault when it runs");
+        }
+        words.push(Token {
+            text: Cow::Owned(expanded.into_owned()),
+            ..token
+        });
+    }
+
+    let parsed = Command::from_tokens(&line, words, true)?;
+    let defaults = default_flags(parsed.command_type)?;
+    // Defaults come first when they were applied.
+    let default_count = if parsed.flags.starts_with(&defaults) {
+        defaults.len()
+    } else {
+        0
+    };
+
+    outln!("{:<11}{}", "Command:", parsed.command_type.name());
+    outln!("Arguments:");
+    for argument in &parsed.arguments {
+        outln!("  {:?}", argument);
+    }
+    outln!("Flags:");
+    for (i, flag) in parsed.flags.iter().enumerate() {
+        if i < default_count {
+            outln!("  {} (default from the config file)", flag);
+        } else {
+            outln!("  {}", flag);
+        }
+    }
+    Ok(())
+}
+
+fn quoting_name(quoting: Quoting) -> &'static str {
+    match quoting {
+        Quoting::Unquoted => "unquoted",
+        Quoting::Double => "double quoted, stays one word",
+        Quoting::Raw => "single quoted or raw, not expanded",
+    }
+}
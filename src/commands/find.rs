@@ -0,0 +1,155 @@
+use crate::{
+    filter::Filters,
+    glob, options, out, outln,
+    parse_command::tokenize,
+    status, verbose,
+    walk::{self, Entry, Visitor},
+    Command, ExecutableCommand,
+};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Replaced by each match in the command given to `--exec`.
+const MATCH_PLACEHOLDER: &str = "{}";
+
+struct Search<'a> {
+    pattern: &'a str,
+    files: bool,
+    dirs: bool,
+    filters: Filters,
+    /// The directory walked and how it was given, to show matches below it.
+    root: PathBuf,
+    shown: &'a Path,
+    /// The matches as found and as shown, in walk order.
+    matches: Vec<(PathBuf, PathBuf)>,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let [dir, pattern] = &command.arguments[..] else {
+        return Err("Usage: find <dir> <pattern>".into());
+    };
+    let max_depth = command
+        .get_flag_value("--max-depth")
+        .map(|depth| {
+            depth
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid depth: {}", depth))
+        })
+        .transpose()?;
+    // Neither type flag means both types.
+    let (files, dirs) = match (command.has_flag("-f"), command.has_flag("-d")) {
+        (false, false) => (true, true),
+        types => types,
+    };
+    let exec = command.get_flag_value("--exec").map(tokenize).transpose()?;
+    let delete = command.has_flag("--delete");
+    // Matches are printed unless another action is asked for.
+    let print = command.has_flag("--print0") || (exec.is_none() && !delete);
+
+    let root = command.context.resolve(dir);
+    let mut search = Search {
+        pattern,
+        files,
+        dirs,
+        filters: Filters::from_command(command)?,
+        root: root.clone(),
+        shown: Path::new(dir),
+        matches: Vec::new(),
+    };
+    let options = walk::Options {
+        follow_links: command.has_flag("-L"),
+        max_depth,
+        include_git_dir: true,
+        ..walk::Options::default()
+    };
+    let errors = walk::visit(&root, options, &mut search)
+        .map_err(|error| format!("{}: {}", root.display(), error))?;
+
+    // The walk is over before anything runs, so actions never see their own
+    // changes.
+    if print {
+        for (_, shown) in &search.matches {
+            if command.has_flag("--print0") {
+                out!("{}\0", shown.display());
+            } else {
+                outln!("{}", shown.display());
+            }
+        }
+    }
+    if let Some(words) = &exec {
+        for (_, shown) in &search.matches {
+            if command.context.cancellation.is_cancelled() {
+                return Err("find cancelled".into());
+            }
+            run(command, words, shown)?;
+        }
+    }
+    if delete {
+        // Backwards, so the contents of a directory go before it.
+        for (path, _) in search.matches.iter().rev() {
+            remove(path)?;
+        }
+    }
+
+    walk::report_errors(&errors)?;
+    Ok(())
+}
+
+impl Visitor for Search<'_> {
+    fn entry(&mut self, entry: &Entry) {
+        // The directory searched is not a match itself.
+        if entry.depth == 0 {
+            return;
+        }
+        // Symlinks count as what they are unless -L follows them.
+        let wanted = if entry.is_dir() {
+            self.dirs
+        } else {
+            self.files
+        };
+        let name_matches = entry
+            .path
+            .file_name()
+            .is_some_and(|name| glob::matches(self.pattern, &name.to_string_lossy()));
+        if wanted && name_matches && self.filters.matches(&entry.path) {
+            let shown = match entry.path.strip_prefix(&self.root) {
+                Ok(relative) => self.shown.join(relative),
+                Err(_) => entry.path.clone(),
+            };
+            self.matches.push((entry.path.clone(), shown));
+        }
+    }
+}
+
+/// Runs the `--exec` command for one match, with `{}` standing for it.
+fn run(command: &ExecutableCommand, words: &[String], shown: &Path) -> Result<(), Box<dyn Error>> {
+    let shown = shown.to_string_lossy();
+    let words = words
+        .iter()
+        .map(|word| word.replace(MATCH_PLACEHOLDER, &shown))
+        .collect::<Vec<_>>();
+    let nested = Command::from_words(&words)?;
+    nested.bind(command.context).execute()
+}
+
+/// Removes a match for `--delete`. Like `rmdir`, a directory is only
+/// removed when it is empty by then; symlinks are removed themselves.
+fn remove(path: &Path) -> Result<(), Box<dyn Error>> {
+    let metadata = fs::symlink_metadata(path)
+        .map_err(|error| format!("Cannot remove {}: {}", path.display(), error))?;
+    if options::dry_run() {
+        status!("would remove {}", path.display());
+        return Ok(());
+    }
+    let result = if metadata.is_dir() {
+        fs::remove_dir(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.map_err(|error| format!("Cannot remove {}: {}", path.display(), error))?;
+    verbose!("removed {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{gitinfo::Repository, outln, ExecutableCommand};
+use std::{env, error::Error};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let cwd = env::current_dir()?;
+    let Some(repository) = Repository::discover(&cwd) else {
+        outln!("Not inside a git repository");
+        return Ok(());
+    };
+
+    let modified = repository.modified_files()?;
+
+    outln!("Repository: {}", repository.work_tree.display());
+    outln!("Branch: {}", repository.head()?);
+    outln!(
+        "Status: {}",
+        if modified.is_empty() {
+            "clean"
+        } else {
+            "dirty"
+        }
+    );
+
+    if command.has_flag("--files") {
+        for path in modified {
+            outln!("    modified: {}", path);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,349 @@
+use crate::{aho_corasick::Patterns, out, outln, terminal, walk, ExecutableCommand};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    error::Error,
+    fmt::Write as _,
+    fs::File,
+    io::{self, Read},
+    num::NonZeroUsize,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Instant,
+};
+
+/// Files with a NUL byte this early are taken to be binary and skipped.
+pub(super) const BINARY_CHECK_SIZE: usize = 8192;
+/// Between groups of lines that are not next to each other, with context.
+const GROUP_SEPARATOR: &str = "--";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Lines,
+    /// `-c`: how many lines match in each file.
+    Count,
+    /// `-l`: only the names of files with a match.
+    FilesWithMatches,
+}
+
+struct Search {
+    patterns: Patterns,
+    ignore_case: bool,
+    mode: Mode,
+    before: usize,
+    after: usize,
+    highlight: bool,
+}
+
+/// What searching one file found, to be printed in the order of the files.
+enum Scan {
+    Searched { output: String, matched: bool },
+    Binary,
+    Unreadable,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let started = Instant::now();
+    let ignore_case = command.has_flag("--ignore-case");
+    let recursive = command.has_flag("--recursive");
+    let given = command.get_flag_values("-e").collect::<Vec<_>>();
+    let (patterns, paths) = if given.is_empty() {
+        let (pattern, paths) = command.arguments.split_first().ok_or("Missing pattern")?;
+        (vec![pattern.as_str()], paths)
+    } else {
+        (given, &command.arguments[..])
+    };
+    let paths = match paths {
+        [] if recursive => &[".".to_string()][..],
+        [] => return Err("Missing file to search; use -r to search a directory".into()),
+        paths => paths,
+    };
+    let context = |flag| {
+        command
+            .get_flag_value(flag)
+            .or(command.get_flag_value("-C"))
+            .map_or(Ok(0), |lines| {
+                lines
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid number of lines: {}", lines))
+            })
+    };
+
+    let patterns = patterns
+        .iter()
+        .map(|pattern| {
+            if ignore_case {
+                pattern.to_lowercase()
+            } else {
+                pattern.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    let search = Search {
+        patterns: Patterns::new(&patterns),
+        ignore_case,
+        mode: if command.has_flag("-l") {
+            Mode::FilesWithMatches
+        } else if command.has_flag("-c") {
+            Mode::Count
+        } else {
+            Mode::Lines
+        },
+        before: context("-B")?,
+        after: context("-A")?,
+        highlight: terminal::colors_enabled(),
+    };
+
+    let mut files = Vec::new();
+    for argument in paths {
+        let path = command.context.resolve(argument);
+        files.extend(self::files(&path, Path::new(argument), recursive)?);
+    }
+
+    // Files are searched side by side and printed in order as soon as all
+    // before them are.
+    let (mut matched, mut binary) = (0, 0);
+    let next = AtomicUsize::new(0);
+    let cancellation = &command.context.cancellation;
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(files.len());
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..workers {
+            let (sender, next, files, search) = (sender.clone(), &next, &files, &search);
+            scope.spawn(move || {
+                while !cancellation.is_cancelled() {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((file, shown)) = files.get(index) else {
+                        break;
+                    };
+                    if sender.send((index, search.file(file, shown))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut shown = 0;
+        for (index, scan) in receiver {
+            pending.insert(index, scan);
+            while let Some(scan) = pending.remove(&shown) {
+                match scan {
+                    Scan::Searched {
+                        output,
+                        matched: any,
+                    } => {
+                        out!("{}", output);
+                        matched += usize::from(any);
+                    }
+                    Scan::Binary => binary += 1,
+                    Scan::Unreadable => {
+                        outln!("Skipping {}: cannot read it", files[shown].1.display())
+                    }
+                }
+                shown += 1;
+            }
+        }
+    });
+    if cancellation.is_cancelled() {
+        return Err("grep cancelled".into());
+    }
+
+    if command.has_flag("--stats") {
+        outln!(
+            "{} {} searched, {} with a match, {} binary skipped, in {:.3} ms",
+            files.len(),
+            if files.len() == 1 { "file" } else { "files" },
+            matched,
+            binary,
+            started.elapsed().as_secs_f64() * 1000.0
+        );
+    }
+    Ok(())
+}
+
+impl Search {
+    fn file(&self, file: &Path, shown: &Path) -> Scan {
+        match read_text(file) {
+            Ok(Some(content)) => {
+                let (output, matched) = self.lines(&String::from_utf8_lossy(&content), shown);
+                Scan::Searched { output, matched }
+            }
+            Ok(None) => Scan::Binary,
+            Err(_) => Scan::Unreadable,
+        }
+    }
+
+    /// The output for the lines of one file, and whether any matched.
+    fn lines(&self, content: &str, shown: &Path) -> (String, bool) {
+        let shown = shown.display();
+        let mut output = String::new();
+        let mut count = 0;
+        // Lines kept for -B, and how many more to print for -A.
+        let mut before = VecDeque::with_capacity(self.before);
+        let mut after = 0;
+        let mut last_printed = None;
+
+        for (index, line) in content.lines().enumerate() {
+            if !self.matches(line) {
+                if after > 0 {
+                    let _ = writeln!(output, "{}-{}-{}", shown, index + 1, line);
+                    last_printed = Some(index);
+                    after -= 1;
+                } else if self.before > 0 {
+                    if before.len() == self.before {
+                        before.pop_front();
+                    }
+                    before.push_back((index, line));
+                }
+                continue;
+            }
+
+            count += 1;
+            match self.mode {
+                Mode::FilesWithMatches => break,
+                Mode::Count => continue,
+                Mode::Lines => {}
+            }
+            let first = before.front().map_or(index, |&(first, _)| first);
+            let context = self.before > 0 || self.after > 0;
+            if context && last_printed.is_some_and(|last| first > last + 1) {
+                let _ = writeln!(output, "{}", GROUP_SEPARATOR);
+            }
+            for (index, line) in before.drain(..) {
+                let _ = writeln!(output, "{}-{}-{}", shown, index + 1, line);
+            }
+            let line = if self.highlight {
+                highlight(line, &self.ranges(line))
+            } else {
+                line.to_string()
+            };
+            let _ = writeln!(output, "{}:{}:{}", shown, index + 1, line);
+            last_printed = Some(index);
+            after = self.after;
+        }
+
+        match self.mode {
+            Mode::FilesWithMatches if count > 0 => {
+                let _ = writeln!(output, "{}", shown);
+            }
+            Mode::Count => {
+                let _ = writeln!(output, "{}:{}", shown, count);
+            }
+            _ => {}
+        }
+        (output, count > 0)
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        if self.ignore_case {
+            self.patterns.is_match(line.to_lowercase().as_bytes())
+        } else {
+            self.patterns.is_match(line.as_bytes())
+        }
+    }
+
+    /// Where the patterns occur in `line`, as byte ranges of it.
+    fn ranges(&self, line: &str) -> Vec<Range<usize>> {
+        find_ranges(&self.patterns, self.ignore_case, line)
+    }
+}
+
+/// Where `patterns` occur in `line`, as byte ranges of it. Patterns that
+/// ignore case must be in lowercase.
+pub(super) fn find_ranges(patterns: &Patterns, ignore_case: bool, line: &str) -> Vec<Range<usize>> {
+    if !ignore_case {
+        return patterns.find_ranges(line.as_bytes());
+    }
+    // Lowercasing may change lengths, so each byte of the lowercase
+    // line remembers where its character starts in `line`.
+    let mut lowercase = String::with_capacity(line.len());
+    let mut starts = Vec::with_capacity(line.len() + 1);
+    for (start, character) in line.char_indices() {
+        lowercase.extend(character.to_lowercase());
+        starts.resize(lowercase.len(), start);
+    }
+    starts.push(line.len());
+    patterns
+        .find_ranges(lowercase.as_bytes())
+        .into_iter()
+        .map(|range| starts[range.start]..starts[range.end])
+        .collect()
+}
+
+/// Marks the `ranges` of `line`.
+pub(super) fn highlight(line: &str, ranges: &[Range<usize>]) -> String {
+    let mut highlighted = String::with_capacity(line.len());
+    let mut end = 0;
+    for range in ranges {
+        // A match may start or end inside a character the lowercase line
+        // spelled differently; such a range is left unmarked.
+        let (Some(before), Some(found)) = (line.get(end..range.start), line.get(range.clone()))
+        else {
+            continue;
+        };
+        highlighted.push_str(before);
+        highlighted.push_str(&terminal::bold_red(found));
+        end = range.end;
+    }
+    highlighted.push_str(&line[end..]);
+    highlighted
+}
+
+/// Reads a whole file in one go, or returns `None` if it is binary. That is
+/// decided from its first block, before the rest is read.
+fn read_text(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    let mut file = File::open(path)?;
+    let length = file.metadata()?.len() as usize;
+    let mut content = Vec::with_capacity(length);
+    file.by_ref()
+        .take(BINARY_CHECK_SIZE as u64)
+        .read_to_end(&mut content)?;
+    if content.contains(&0) {
+        return Ok(None);
+    }
+    file.read_to_end(&mut content)?;
+    Ok(Some(content))
+}
+
+/// The files to search under `path`, each with the name to show for it,
+/// which starts with the argument as typed.
+fn files(
+    path: &Path,
+    argument: &Path,
+    recursive: bool,
+) -> Result<Vec<(PathBuf, PathBuf)>, Box<dyn Error>> {
+    if !path.is_dir() {
+        return Ok(vec![(path.to_path_buf(), argument.to_path_buf())]);
+    }
+    if !recursive {
+        return Err(format!("{} is a directory; use -r to search it", path.display()).into());
+    }
+    let walk = walk::walk(
+        path,
+        walk::Options {
+            respect_gitignore: true,
+            ..walk::Options::default()
+        },
+    )?;
+    walk::report_errors(&walk.errors)?;
+    Ok(walk
+        .files
+        .into_iter()
+        .map(|file| {
+            let shown = match file.strip_prefix(path) {
+                Ok(relative) if argument == Path::new(".") => relative.to_path_buf(),
+                Ok(relative) => argument.join(relative),
+                Err(_) => file.clone(),
+            };
+            (file, shown)
+        })
+        .collect())
+}
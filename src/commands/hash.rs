@@ -0,0 +1,110 @@
+use crate::{
+    hash::{self, Algorithm},
+    outln, status, ExecutableCommand,
+};
+use std::{error::Error, fs};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let algorithm = if command.has_flag("--md5") {
+        Some(Algorithm::Md5)
+    } else if command.has_flag("--sha1") {
+        Some(Algorithm::Sha1)
+    } else {
+        None
+    };
+
+    if let Some(list) = command.get_flag_value("--check") {
+        if !command.arguments.is_empty() {
+            return Err("Usage: hash --check <list>; the list names the files".into());
+        }
+        return check(command, list, algorithm);
+    }
+    if command.arguments.is_empty() {
+        return Err("Usage: hash [--md5 | --sha1] <file>...".into());
+    }
+    let algorithm = algorithm.unwrap_or(Algorithm::Sha256);
+    for argument in &command.arguments {
+        let path = command.context.resolve(argument);
+        let digest = hash::hash_file(&path, algorithm)
+            .map_err(|error| format!("{}: {}", argument, error))?;
+        // The layout of sha256sum and friends, so lists can be checked
+        // with either.
+        outln!("{}  {}", digest, argument);
+    }
+    Ok(())
+}
+
+/// Verifies the files in a list of `<digest>  <path>` lines, as `hash`
+/// prints them. Without a flag the algorithm follows from the length of
+/// each digest.
+fn check(
+    command: &ExecutableCommand,
+    list: &str,
+    algorithm: Option<Algorithm>,
+) -> Result<(), Box<dyn Error>> {
+    let list_path = command.context.resolve(list);
+    let contents =
+        fs::read_to_string(&list_path).map_err(|error| format!("{}: {}", list, error))?;
+
+    let (mut checked, mut failed, mut malformed) = (0, 0, 0);
+    for line in contents.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((expected, name, line_algorithm)) = parse_line(line, algorithm) else {
+            malformed += 1;
+            continue;
+        };
+        checked += 1;
+        let path = command.context.resolve(name);
+        match hash::hash_file(&path, line_algorithm) {
+            Ok(actual) if actual.eq_ignore_ascii_case(expected) => status!("{}: OK", name),
+            Ok(_) => {
+                failed += 1;
+                outln!("{}: FAILED", name);
+            }
+            Err(error) => {
+                failed += 1;
+                outln!("{}: FAILED to read: {}", name, error);
+            }
+        }
+    }
+
+    if malformed > 0 {
+        outln!(
+            "Skipped {} {} not checksums",
+            malformed,
+            if malformed == 1 {
+                "line that is"
+            } else {
+                "lines that are"
+            }
+        );
+    }
+    if checked == 0 {
+        return Err(format!("{}: no checksums found", list).into());
+    }
+    if failed > 0 {
+        return Err(format!("{} of {} files did not match", failed, checked).into());
+    }
+    status!("All {} files match", checked);
+    Ok(())
+}
+
+/// Splits a line into the digest, the file name and the algorithm. A `*`
+/// before the name, which marks binary mode in other tools, is dropped.
+fn parse_line(line: &str, algorithm: Option<Algorithm>) -> Option<(&str, &str, Algorithm)> {
+    let (digest, name) = line.split_once(' ')?;
+    let name = name.strip_prefix(' ').or_else(|| name.strip_prefix('*'))?;
+    if name.is_empty() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let algorithm = match algorithm {
+        Some(algorithm) => algorithm,
+        None => *Algorithm::ALL
+            .iter()
+            .find(|algorithm| algorithm.hex_length() == digest.len())?,
+    };
+    (digest.len() == algorithm.hex_length()).then_some((digest, name, algorithm))
+}
@@ -0,0 +1,40 @@
+use crate::{outln, output, ExecutableCommand};
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+const DEFAULT_LINE_COUNT: usize = 10;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let line_count = match command.get_flag_value("-n") {
+        Some(count) => count
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid line count: {}", count))?,
+        None => DEFAULT_LINE_COUNT,
+    };
+    let with_headers = command.arguments.len() > 1;
+
+    for (index, argument) in command.arguments.iter().enumerate() {
+        let path = command.context.resolve(argument);
+        let file = File::open(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let mut reader = BufReader::new(file);
+
+        if with_headers {
+            if index > 0 {
+                outln!();
+            }
+            outln!("{}:", argument);
+        }
+        let mut line = Vec::new();
+        for _ in 0..line_count {
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            output::write_bytes(&line);
+            line.clear();
+        }
+    }
+    Ok(())
+}
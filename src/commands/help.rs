@@ -0,0 +1,48 @@
+use crate::{docs, i18n::Message, outln, parse_command::CommandType, tr, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    match command.arguments.first() {
+        Some(name) => {
+            let command_type = CommandType::lookup(name)?;
+            let doc = docs::for_command(command_type);
+
+            outln!("{}", doc.summary);
+            outln!();
+            outln!("{}", tr!(Message::Usage, doc.usage));
+            if !doc.flags.is_empty() {
+                outln!();
+                outln!("{}", Message::Flags.text());
+                for (flag, description) in doc.flags {
+                    outln!("  {:<26} {}", flag, description);
+                }
+            }
+            if !doc.examples.is_empty() {
+                outln!();
+                outln!("{}", Message::Examples.text());
+                for (invocation, description) in doc.examples {
+                    outln!("  {:<40} {}", invocation, description);
+                }
+            }
+        }
+        None => {
+            outln!("{}", Message::AvailableCommands.text());
+            let name_width = CommandType::ALL
+                .iter()
+                .map(|command_type| command_type.name().len())
+                .max()
+                .unwrap_or_default();
+            for command_type in CommandType::ALL {
+                outln!(
+                    "  {:<name_width$} {}",
+                    command_type.name(),
+                    docs::for_command(*command_type).summary
+                );
+            }
+            outln!();
+            outln!("{}", Message::HelpHint.text());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use crate::{
+    calendar,
+    history::{self, Entry},
+    options, outln, status,
+    timezone::TimeZone,
+    units, ExecutableCommand,
+};
+use std::{cmp::Reverse, collections::HashMap, collections::HashSet, error::Error};
+
+const USAGE: &str =
+    "Usage: history | history stats | history search <term> | history prune [--older-than <age>] [--dedupe]";
+/// How many commands and hours `history stats` shows.
+const TOP_COUNT: usize = 10;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let arguments = command
+        .arguments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    match arguments[..] {
+        [] => print_entries(history::load()?.iter().enumerate()),
+        ["stats"] => stats(&history::load()?),
+        ["search", ref term @ ..] if !term.is_empty() => {
+            let term = term.join(" ").to_lowercase();
+            let entries = history::load()?;
+            // Matches keep their numbers from the full listing.
+            print_entries(
+                entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| entry.line.to_lowercase().contains(&term)),
+            );
+        }
+        ["prune"] => prune(command)?,
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+fn print_entries<'a>(entries: impl Iterator<Item = (usize, &'a Entry)>) {
+    let zone = TimeZone::local();
+    for (index, entry) in entries {
+        outln!(
+            "{:>5}  {}  {}",
+            index + 1,
+            zone.local_time(entry.timestamp).date_time,
+            entry.line
+        );
+    }
+}
+
+fn stats(entries: &[Entry]) {
+    if entries.is_empty() {
+        outln!("No history recorded yet");
+        return;
+    }
+
+    let mut commands = HashMap::<&str, usize>::new();
+    let mut hours = [0usize; 24];
+    let zone = TimeZone::local();
+    for entry in entries {
+        if let Some(name) = entry.line.split_whitespace().next() {
+            *commands.entry(name).or_default() += 1;
+        }
+        hours[zone.local_time(entry.timestamp).date_time.hour as usize] += 1;
+    }
+
+    let mut commands = commands.into_iter().collect::<Vec<_>>();
+    commands.sort_by_key(|&(name, count)| (Reverse(count), name));
+    outln!("Most used commands:");
+    for (name, count) in commands.iter().take(TOP_COUNT) {
+        outln!(
+            "  {:<16} {:>6} {:>5.1}%",
+            name,
+            count,
+            *count as f64 * 100.0 / entries.len() as f64
+        );
+    }
+
+    let mut hours = hours
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .collect::<Vec<_>>();
+    hours.sort_by_key(|&(hour, count)| (Reverse(*count), hour));
+    outln!();
+    outln!("Busiest hours:");
+    for (hour, count) in hours.iter().take(TOP_COUNT) {
+        outln!("  {:02}:00-{:02}:00 {:>6}", hour, (hour + 1) % 24, count);
+    }
+}
+
+fn prune(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let cutoff = match command.get_flag_value("--older-than") {
+        Some(age) => Some(calendar::now() - units::parse_duration(age)?.round() as i64),
+        None => None,
+    };
+    let dedupe = command.has_flag("--dedupe");
+    if cutoff.is_none() && !dedupe {
+        return Err("Nothing to prune; pass --older-than <age>, --dedupe or both".into());
+    }
+
+    let filter = |entries: Vec<Entry>| {
+        let mut entries = entries;
+        if let Some(cutoff) = cutoff {
+            entries.retain(|entry| entry.timestamp >= cutoff);
+        }
+        if dedupe {
+            // Keeps the most recent use of each line.
+            let mut seen = HashSet::new();
+            entries.reverse();
+            entries.retain(|entry| seen.insert(entry.line.clone()));
+            entries.reverse();
+        }
+        entries
+    };
+
+    if options::dry_run() {
+        let entries = history::load()?;
+        let count = entries.len();
+        status!(
+            "would remove {} of {} entries",
+            count - filter(entries).len(),
+            count
+        );
+        return Ok(());
+    }
+    let removed = history::retain(filter)?;
+    outln!(
+        "Removed {} {}",
+        removed,
+        if removed == 1 { "entry" } else { "entries" }
+    );
+    Ok(())
+}
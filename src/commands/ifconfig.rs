@@ -0,0 +1,33 @@
+use crate::{outln, sysinfo, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let mut interfaces = sysinfo::network_interfaces()?;
+    interfaces.retain(|interface| {
+        command.arguments.is_empty() || command.arguments.contains(&interface.name)
+    });
+
+    if interfaces.is_empty() {
+        outln!("No matching network interfaces found");
+    }
+
+    for (i, interface) in interfaces.iter().enumerate() {
+        if i > 0 {
+            outln!();
+        }
+
+        let state = if interface.is_up { "UP" } else { "DOWN" };
+        outln!("{}: {}", interface.name, state);
+        if let Some(mac) = &interface.mac {
+            outln!("    ether {}", mac);
+        }
+        for address in &interface.ipv4 {
+            outln!("    inet  {}", address);
+        }
+        for address in &interface.ipv6 {
+            outln!("    inet6 {}", address);
+        }
+    }
+
+    Ok(())
+}
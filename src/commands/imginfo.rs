@@ -0,0 +1,292 @@
+use crate::{outln, ExecutableCommand};
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+/// What could be read from the headers of an image.
+#[derive(Debug, Default)]
+struct ImageInfo {
+    format: &'static str,
+    width: u32,
+    height: u32,
+    bits_per_pixel: u32,
+    channels: Option<u32>,
+    exif: Exif,
+}
+
+#[derive(Debug, Default)]
+struct Exif {
+    orientation: Option<u16>,
+    /// When the photo was taken, or else when the file was last changed.
+    timestamp: Option<String>,
+    make: Option<String>,
+    model: Option<String>,
+}
+
+const ORIENTATION_TAG: u16 = 0x0112;
+const DATE_TIME_TAG: u16 = 0x0132;
+const DATE_TIME_ORIGINAL_TAG: u16 = 0x9003;
+const MAKE_TAG: u16 = 0x010f;
+const MODEL_TAG: u16 = 0x0110;
+const EXIF_IFD_TAG: u16 = 0x8769;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let path = command.context.resolve(&command.arguments[0]);
+    let mut file = File::open(&path)?;
+
+    let mut magic = [0; 8];
+    let read = file.read(&mut magic)?;
+    file.seek(SeekFrom::Start(0))?;
+    let info = match &magic[..read] {
+        [0x89, b'P', b'N', b'G', ..] => png(&mut file)?,
+        [0xff, 0xd8, ..] => jpeg(&mut file)?,
+        [b'G', b'I', b'F', b'8', ..] => gif(&mut file)?,
+        [b'B', b'M', ..] => bmp(&mut file)?,
+        _ => return Err(format!("{} is not a PNG, JPEG, GIF or BMP image", path.display()).into()),
+    };
+
+    outln!("{:<13}{}", "Format:", info.format);
+    outln!("{:<13}{} x {}", "Dimensions:", info.width, info.height);
+    match info.channels {
+        Some(channels) => outln!(
+            "{:<13}{} bits ({} channels)",
+            "Color depth:",
+            info.bits_per_pixel,
+            channels
+        ),
+        None => outln!("{:<13}{} bits", "Color depth:", info.bits_per_pixel),
+    }
+    if let Some(orientation) = info.exif.orientation {
+        outln!(
+            "{:<13}{} ({})",
+            "Orientation:",
+            orientation,
+            orientation_name(orientation)
+        );
+    }
+    if let Some(timestamp) = &info.exif.timestamp {
+        outln!("{:<13}{}", "Taken:", timestamp);
+    }
+    let camera = [&info.exif.make, &info.exif.model]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if !camera.is_empty() {
+        outln!("{:<13}{}", "Camera:", camera.join(" "));
+    }
+
+    Ok(())
+}
+
+fn read_bytes<const N: usize>(file: &mut File) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    file.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn png(file: &mut File) -> Result<ImageInfo, Box<dyn Error>> {
+    // Signature, then the IHDR chunk's length and type.
+    let header = read_bytes::<29>(file)?;
+    if &header[12..16] != b"IHDR" {
+        return Err("PNG file does not start with an IHDR chunk".into());
+    }
+    let bit_depth = u32::from(header[24]);
+    let channels = match header[25] {
+        0 | 3 => 1,
+        4 => 2,
+        2 => 3,
+        6 => 4,
+        color_type => return Err(format!("Unknown PNG color type {}", color_type).into()),
+    };
+
+    Ok(ImageInfo {
+        format: "PNG",
+        width: u32::from_be_bytes(header[16..20].try_into()?),
+        height: u32::from_be_bytes(header[20..24].try_into()?),
+        bits_per_pixel: bit_depth * channels,
+        channels: Some(channels),
+        ..ImageInfo::default()
+    })
+}
+
+fn gif(file: &mut File) -> Result<ImageInfo, Box<dyn Error>> {
+    let header = read_bytes::<11>(file)?;
+    Ok(ImageInfo {
+        format: "GIF",
+        width: u32::from(u16::from_le_bytes([header[6], header[7]])),
+        height: u32::from(u16::from_le_bytes([header[8], header[9]])),
+        // The global color table has 2^(n + 1) entries.
+        bits_per_pixel: u32::from(header[10] & 0x07) + 1,
+        ..ImageInfo::default()
+    })
+}
+
+fn bmp(file: &mut File) -> Result<ImageInfo, Box<dyn Error>> {
+    let header = read_bytes::<30>(file)?;
+    let dib_size = u32::from_le_bytes(header[14..18].try_into()?);
+    let (width, height, bits_per_pixel) = if dib_size == 12 {
+        // OS/2 BITMAPCOREHEADER with 16-bit dimensions.
+        (
+            u32::from(u16::from_le_bytes([header[18], header[19]])),
+            u32::from(u16::from_le_bytes([header[20], header[21]])),
+            u16::from_le_bytes([header[24], header[25]]),
+        )
+    } else {
+        // A negative height marks a top-down bitmap.
+        (
+            i32::from_le_bytes(header[18..22].try_into()?).unsigned_abs(),
+            i32::from_le_bytes(header[22..26].try_into()?).unsigned_abs(),
+            u16::from_le_bytes([header[28], header[29]]),
+        )
+    };
+
+    Ok(ImageInfo {
+        format: "BMP",
+        width,
+        height,
+        bits_per_pixel: u32::from(bits_per_pixel),
+        ..ImageInfo::default()
+    })
+}
+
+/// Walks the JPEG segments up to the start of the compressed data, reading
+/// the frame header (SOF) and the EXIF block (APP1).
+fn jpeg(file: &mut File) -> Result<ImageInfo, Box<dyn Error>> {
+    file.seek(SeekFrom::Start(2))?;
+    let mut info = ImageInfo {
+        format: "JPEG",
+        ..ImageInfo::default()
+    };
+    let mut found_frame = false;
+
+    loop {
+        let [marker_start, mut marker] = read_bytes::<2>(file)?;
+        if marker_start != 0xff {
+            return Err("Corrupt JPEG segment".into());
+        }
+        // Markers may be padded with extra 0xff bytes.
+        while marker == 0xff {
+            marker = read_bytes::<1>(file)?[0];
+        }
+        // Start of scan: the compressed data follows.
+        if marker == 0xda || marker == 0xd9 {
+            break;
+        }
+        let length = u16::from_be_bytes(read_bytes::<2>(file)?);
+        let body_length = usize::from(length.saturating_sub(2));
+
+        match marker {
+            0xc0..=0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf => {
+                let frame = read_bytes::<6>(file)?;
+                let precision = u32::from(frame[0]);
+                let components = u32::from(frame[5]);
+                info.height = u32::from(u16::from_be_bytes([frame[1], frame[2]]));
+                info.width = u32::from(u16::from_be_bytes([frame[3], frame[4]]));
+                info.bits_per_pixel = precision * components;
+                info.channels = Some(components);
+                found_frame = true;
+                file.seek(SeekFrom::Current(body_length as i64 - 6))?;
+            }
+            0xe1 => {
+                let mut body = vec![0; body_length];
+                file.read_exact(&mut body)?;
+                if let Some(tiff) = body.strip_prefix(b"Exif\0\0") {
+                    info.exif = parse_exif(tiff).unwrap_or_default();
+                }
+            }
+            _ => {
+                file.seek(SeekFrom::Current(body_length as i64))?;
+            }
+        }
+    }
+
+    if !found_frame {
+        return Err("JPEG file has no frame header".into());
+    }
+    Ok(info)
+}
+
+/// Reads a few tags from the TIFF structure inside an EXIF block.
+fn parse_exif(tiff: &[u8]) -> Option<Exif> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| {
+        let bytes = [*tiff.get(offset)?, *tiff.get(offset + 1)?];
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let u32_at = |offset: usize| {
+        let bytes: [u8; 4] = tiff.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+    // ASCII values longer than 4 bytes live at an offset.
+    let string_at = |entry: usize| {
+        let count = u32_at(entry + 4)? as usize;
+        let start = if count <= 4 {
+            entry + 8
+        } else {
+            u32_at(entry + 8)? as usize
+        };
+        let bytes = tiff.get(start..start + count)?;
+        let text = String::from_utf8_lossy(bytes);
+        Some(text.trim_end_matches('\0').trim().to_string())
+    };
+
+    let mut exif = Exif::default();
+    let mut original_time = None;
+    let mut ifds = vec![u32_at(4)? as usize];
+    let mut visited = Vec::new();
+
+    while let Some(ifd) = ifds.pop() {
+        // A corrupt file could point an IFD at itself.
+        if visited.contains(&ifd) {
+            continue;
+        }
+        visited.push(ifd);
+        let count = usize::from(u16_at(ifd)?);
+        for index in 0..count {
+            let entry = ifd + 2 + index * 12;
+            match u16_at(entry)? {
+                ORIENTATION_TAG => exif.orientation = u16_at(entry + 8),
+                DATE_TIME_TAG => exif.timestamp = string_at(entry),
+                DATE_TIME_ORIGINAL_TAG => original_time = string_at(entry),
+                MAKE_TAG => exif.make = string_at(entry),
+                MODEL_TAG => exif.model = string_at(entry),
+                EXIF_IFD_TAG => ifds.push(u32_at(entry + 8)? as usize),
+                _ => {}
+            }
+        }
+    }
+
+    if original_time.is_some() {
+        exif.timestamp = original_time;
+    }
+    Some(exif)
+}
+
+fn orientation_name(orientation: u16) -> &'static str {
+    match orientation {
+        1 => "normal",
+        2 => "mirrored horizontally",
+        3 => "rotated 180°",
+        4 => "mirrored vertically",
+        5 => "mirrored horizontally, rotated 270° clockwise",
+        6 => "rotated 90° clockwise",
+        7 => "mirrored horizontally, rotated 90° clockwise",
+        8 => "rotated 270° clockwise",
+        _ => "unknown",
+    }
+}
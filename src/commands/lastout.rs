@@ -0,0 +1,26 @@
+use crate::{clipboard, output, status, units, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let last_output = command.context.last_output();
+    if last_output.truncated {
+        status!(
+            "Only the first {} of the output was kept",
+            units::format_size(output::CAPTURE_LIMIT as u64)
+        );
+    }
+
+    if command.has_flag("--copy") {
+        if last_output.bytes.is_empty() {
+            return Err("The previous command printed nothing".into());
+        }
+        clipboard::copy(&last_output.bytes)?;
+        status!(
+            "Copied {} to the clipboard",
+            units::format_size(last_output.bytes.len() as u64)
+        );
+    } else {
+        output::write_bytes(&last_output.bytes);
+    }
+    Ok(())
+}
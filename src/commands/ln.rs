@@ -0,0 +1,82 @@
+use crate::{options, status, verbose, ExecutableCommand};
+use std::{error::Error, fs, path::Path};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let symbolic = command.has_flag("-s");
+    let target = &command.arguments[0];
+    let mut link = command.context.resolve(&command.arguments[1]);
+    // Like `cp`, a directory receives a link with the target's name.
+    if link.is_dir() {
+        let name = Path::new(target)
+            .file_name()
+            .ok_or_else(|| format!("Cannot name a link after {}", target))?;
+        link = link.join(name);
+    }
+    if fs::symlink_metadata(&link).is_ok() {
+        return Err(format!("{} already exists", link.display()).into());
+    }
+
+    // A relative symlink target is relative to the link's directory, and
+    // is stored as given so the link keeps working when both move.
+    let resolved_target = if symbolic {
+        link.parent().unwrap_or(Path::new("/")).join(target)
+    } else {
+        command.context.resolve(target)
+    };
+    let metadata = fs::metadata(&resolved_target)
+        .map_err(|error| format!("{}: {}", resolved_target.display(), error))?;
+    if !symbolic && metadata.is_dir() {
+        return Err(format!(
+            "{} is a directory; only symlinks (-s) can point to directories",
+            target
+        )
+        .into());
+    }
+
+    let kind = if symbolic { "symlink" } else { "hard link" };
+    if options::dry_run() {
+        status!("would create {} {} -> {}", kind, link.display(), target);
+        return Ok(());
+    }
+    let result = if symbolic {
+        platform::symlink(Path::new(target), &link, metadata.is_dir())
+    } else {
+        fs::hard_link(&resolved_target, &link)
+    };
+    result.map_err(|error| format!("Cannot create {}: {}", link.display(), error))?;
+    verbose!("created {} {} -> {}", kind, link.display(), target);
+    Ok(())
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::{io, os::unix::fs, path::Path};
+
+    pub fn symlink(target: &Path, link: &Path, _is_dir: bool) -> io::Result<()> {
+        fs::symlink(target, link)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::{io, os::windows::fs, path::Path};
+
+    /// Windows tells file and directory symlinks apart. Creating either
+    /// needs Developer Mode or administrator rights.
+    pub fn symlink(target: &Path, link: &Path, is_dir: bool) -> io::Result<()> {
+        if is_dir {
+            fs::symlink_dir(target, link)
+        } else {
+            fs::symlink_file(target, link)
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::{io, path::Path};
+
+    pub fn symlink(_target: &Path, _link: &Path, _is_dir: bool) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
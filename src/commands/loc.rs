@@ -0,0 +1,311 @@
+use crate::{outln, walk, ExecutableCommand};
+use std::{cmp::Reverse, error::Error, fs, path::Path};
+
+struct Language {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+const C_STYLE_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        name: "Rust",
+        extensions: &["rs"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "C",
+        extensions: &["c", "h"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "C++",
+        extensions: &["cpp", "cc", "cxx", "hpp", "hh"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "C#",
+        extensions: &["cs"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "Go",
+        extensions: &["go"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "Java",
+        extensions: &["java"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "Kotlin",
+        extensions: &["kt", "kts"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "JavaScript",
+        extensions: &["js", "mjs", "cjs", "jsx"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "TypeScript",
+        extensions: &["ts", "tsx"],
+        line_comments: &["//"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "CSS",
+        extensions: &["css"],
+        line_comments: &[],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "Python",
+        extensions: &["py"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "Ruby",
+        extensions: &["rb"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "Shell",
+        extensions: &["sh", "bash", "zsh"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "TOML",
+        extensions: &["toml"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "YAML",
+        extensions: &["yml", "yaml"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "SQL",
+        extensions: &["sql"],
+        line_comments: &["--"],
+        block_comment: C_STYLE_BLOCK,
+    },
+    Language {
+        name: "Lua",
+        extensions: &["lua"],
+        line_comments: &["--"],
+        block_comment: Some(("--[[", "]]")),
+    },
+    Language {
+        name: "Haskell",
+        extensions: &["hs"],
+        line_comments: &["--"],
+        block_comment: Some(("{-", "-}")),
+    },
+    Language {
+        name: "HTML",
+        extensions: &["html", "htm", "xml"],
+        line_comments: &[],
+        block_comment: Some(("<!--", "-->")),
+    },
+    Language {
+        name: "Markdown",
+        extensions: &["md"],
+        line_comments: &[],
+        block_comment: None,
+    },
+    Language {
+        name: "JSON",
+        extensions: &["json"],
+        line_comments: &[],
+        block_comment: None,
+    },
+];
+
+#[derive(Default)]
+struct Stats {
+    files: usize,
+    blank: usize,
+    comment: usize,
+    code: usize,
+}
+
+impl Stats {
+    fn lines(&self) -> usize {
+        self.blank + self.comment + self.code
+    }
+
+    fn add(&mut self, other: &Stats) {
+        self.files += other.files;
+        self.blank += other.blank;
+        self.comment += other.comment;
+        self.code += other.code;
+    }
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let root = command.arguments.first().map(String::as_str).unwrap_or(".");
+    let mut stats = LANGUAGES
+        .iter()
+        .map(|language| (language, Stats::default()))
+        .collect::<Vec<_>>();
+
+    let walk = walk::walk(
+        Path::new(root),
+        walk::Options {
+            respect_gitignore: true,
+            follow_links: command.has_flag("-L"),
+            dedupe_hardlinks: true,
+            ..walk::Options::default()
+        },
+    )?;
+    walk::report_errors(&walk.errors)?;
+
+    for path in walk.files {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+        let extension = extension.to_lowercase();
+        let Some((language, language_stats)) = stats
+            .iter_mut()
+            .find(|(language, _)| language.extensions.contains(&extension.as_str()))
+        else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        language_stats.add(&count_lines(language, &content));
+    }
+
+    stats.retain(|(_, stats)| stats.files > 0);
+    stats.sort_by_key(|(_, stats)| Reverse(stats.lines()));
+
+    if stats.is_empty() {
+        outln!("No source files found");
+        return Ok(());
+    }
+
+    let mut total = Stats::default();
+    outln!(
+        "{:<12} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        "Language",
+        "Files",
+        "Lines",
+        "Blank",
+        "Comment",
+        "Code"
+    );
+    for (language, stats) in &stats {
+        print_row(language.name, stats);
+        total.add(stats);
+    }
+    print_row("Total", &total);
+
+    Ok(())
+}
+
+fn print_row(name: &str, stats: &Stats) {
+    outln!(
+        "{:<12} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        name,
+        stats.files,
+        stats.lines(),
+        stats.blank,
+        stats.comment,
+        stats.code
+    );
+}
+
+/// Classifies each line as blank, comment or code. A line holding both code
+/// and a comment counts as code.
+fn count_lines(language: &Language, content: &str) -> Stats {
+    let mut stats = Stats {
+        files: 1,
+        ..Default::default()
+    };
+    let mut in_block_comment = false;
+
+    for line in content.lines() {
+        let mut rest = line.trim();
+        if rest.is_empty() {
+            if in_block_comment {
+                stats.comment += 1;
+            } else {
+                stats.blank += 1;
+            }
+            continue;
+        }
+
+        let mut has_code = false;
+        let mut has_comment = false;
+        while !rest.is_empty() {
+            if in_block_comment {
+                has_comment = true;
+                let (_, end) = language.block_comment.unwrap_or_default();
+                match rest.find(end) {
+                    Some(index) => {
+                        rest = rest[index + end.len()..].trim_start();
+                        in_block_comment = false;
+                    }
+                    None => rest = "",
+                }
+                continue;
+            }
+
+            if language
+                .line_comments
+                .iter()
+                .any(|prefix| rest.starts_with(prefix))
+            {
+                has_comment = true;
+                break;
+            }
+            if let Some((start, _)) = language.block_comment {
+                if let Some(after) = rest.strip_prefix(start) {
+                    in_block_comment = true;
+                    rest = after;
+                    continue;
+                }
+            }
+
+            has_code = true;
+            let next_comment = language
+                .line_comments
+                .iter()
+                .chain(language.block_comment.iter().map(|(start, _)| start))
+                .filter_map(|marker| rest.find(marker))
+                .min();
+            match next_comment {
+                Some(index) if index > 0 => rest = &rest[index..],
+                _ => break,
+            }
+        }
+
+        if has_code {
+            stats.code += 1;
+        } else if has_comment {
+            stats.comment += 1;
+        }
+    }
+
+    stats
+}
@@ -0,0 +1,70 @@
+use crate::{filter::Filters, glob, listing, outln, ExecutableCommand};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let group_dirs_first = listing::group_dirs_first(command.has_flag("--group-dirs-first"));
+    let pattern = command.get_flag_value("--pattern");
+    let extensions = command.get_flag_value("--ext").map(|extensions| {
+        extensions
+            .split(',')
+            .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+            .filter(|extension| !extension.is_empty())
+            .collect::<Vec<_>>()
+    });
+
+    let filters = Filters::from_command(command)?;
+
+    let dirs = match &command.arguments[..] {
+        [] => vec!["."],
+        arguments => arguments.iter().map(String::as_str).collect(),
+    };
+
+    for dir in &dirs {
+        let dir = Path::new(dir);
+        if !dir.exists() {
+            outln!("Directory {} does not exist", dir.display());
+            continue;
+        }
+        if !dir.is_dir() {
+            outln!("{} is not a directory", dir.display());
+            continue;
+        }
+        let mut entries = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<PathBuf>, _>>()?;
+        entries.retain(|entry| {
+            pattern.is_none_or(|pattern| matches_pattern(entry, pattern))
+                && extensions
+                    .as_ref()
+                    .is_none_or(|extensions| matches_extension(entry, extensions))
+                && filters.matches(entry)
+        });
+        entries.sort_by(|a, b| listing::compare_entries(a, b, group_dirs_first));
+
+        if dirs.len() > 1 {
+            outln!("{}:", dir.display());
+        }
+        for entry in entries {
+            outln!("{}", entry.display());
+        }
+        if dirs.len() > 1 {
+            outln!();
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_pattern(path: &Path, pattern: &str) -> bool {
+    path.file_name()
+        .is_some_and(|name| glob::matches(pattern, &name.to_string_lossy()))
+}
+
+fn matches_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .is_some_and(|extension| extensions.contains(&extension.to_string_lossy().to_lowercase()))
+}
@@ -0,0 +1,80 @@
+use crate::{docs, pager, parse_command::CommandType, terminal, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let command_type = CommandType::lookup(&command.arguments[0])?;
+    pager::page(&render(command_type))?;
+    Ok(())
+}
+
+fn render(command_type: CommandType) -> String {
+    let doc = docs::for_command(command_type);
+    let mut page = String::new();
+
+    let mut section = |title: &str, body: &str| {
+        page.push_str(&terminal::bold(title));
+        page.push('\n');
+        for line in wrap(body, 72) {
+            page.push_str("       ");
+            page.push_str(&line);
+            page.push('\n');
+        }
+        page.push('\n');
+    };
+
+    section(
+        "NAME",
+        &format!("{} - {}", command_type.name(), doc.summary),
+    );
+    section("SYNOPSIS", doc.usage);
+    section("DESCRIPTION", doc.description);
+
+    if !doc.flags.is_empty() {
+        page.push_str(&terminal::bold("OPTIONS"));
+        page.push('\n');
+        for (flag, description) in doc.flags {
+            page.push_str(&format!("       {}\n", terminal::underline(flag)));
+            for line in wrap(description, 65) {
+                page.push_str(&format!("              {}\n", line));
+            }
+            page.push('\n');
+        }
+    }
+
+    if !doc.examples.is_empty() {
+        page.push_str(&terminal::bold("EXAMPLES"));
+        page.push('\n');
+        for (invocation, description) in doc.examples {
+            page.push_str(&format!("       {}\n", invocation));
+            page.push_str(&format!("              {}\n\n", description));
+        }
+    }
+
+    page.push_str(&terminal::bold("EXIT STATUS"));
+    page.push('\n');
+    for line in wrap(docs::EXIT_STATUS, 72) {
+        page.push_str(&format!("       {}\n", line));
+    }
+
+    page
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
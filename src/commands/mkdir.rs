@@ -0,0 +1,26 @@
+use crate::{options, status, verbose, ExecutableCommand};
+use std::{error::Error, fs};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let parents = command.has_flag("-p");
+
+    for argument in &command.arguments {
+        let path = command.context.resolve(argument);
+        if parents && path.is_dir() {
+            continue;
+        }
+        if options::dry_run() {
+            status!("would create {}", path.display());
+            continue;
+        }
+
+        let result = if parents {
+            fs::create_dir_all(&path)
+        } else {
+            fs::create_dir(&path)
+        };
+        result.map_err(|error| format!("Cannot create {}: {}", path.display(), error))?;
+        verbose!("created {}", path.display());
+    }
+    Ok(())
+}
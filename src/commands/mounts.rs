@@ -0,0 +1,69 @@
+use crate::{json, mounts, options, outln, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let default_format = options::get(options::OUTPUT_FORMAT);
+    let format = command
+        .get_flag_value("--format")
+        .unwrap_or(&default_format);
+    if format != "text" && format != "json" {
+        return Err(format!("Unknown format: {} (expected text or json)", format).into());
+    }
+
+    let mut mounts = mounts::list()?;
+    if let Some(fs_type) = command.get_flag_value("--type") {
+        mounts.retain(|mount| mount.fs_type == fs_type);
+    }
+
+    if format == "json" {
+        let entries = mounts
+            .iter()
+            .map(|mount| {
+                let options = mount
+                    .options
+                    .iter()
+                    .map(|option| json::quote(option))
+                    .collect::<Vec<_>>();
+                format!(
+                    "{{\"device\":{},\"mount_point\":{},\"type\":{},\"options\":[{}]}}",
+                    json::quote(&mount.device),
+                    json::quote(&mount.mount_point.display().to_string()),
+                    json::quote(&mount.fs_type),
+                    options.join(",")
+                )
+            })
+            .collect::<Vec<_>>();
+        outln!("[{}]", entries.join(","));
+        return Ok(());
+    }
+
+    let device_width = mounts
+        .iter()
+        .map(|mount| mount.device.len())
+        .chain(["Device".len()])
+        .max()
+        .unwrap_or_default();
+    let mount_point_width = mounts
+        .iter()
+        .map(|mount| mount.mount_point.display().to_string().len())
+        .chain(["Mounted on".len()])
+        .max()
+        .unwrap_or_default();
+
+    outln!(
+        "{:<device_width$} {:<mount_point_width$} {:<10} Options",
+        "Device",
+        "Mounted on",
+        "Type"
+    );
+    for mount in mounts {
+        outln!(
+            "{:<device_width$} {:<mount_point_width$} {:<10} {}",
+            mount.device,
+            mount.mount_point.display(),
+            mount.fs_type,
+            mount.options.join(",")
+        );
+    }
+    Ok(())
+}
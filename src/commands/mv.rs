@@ -0,0 +1,74 @@
+use super::cp::Copier;
+use crate::{copy, i18n::Message, options, status, terminal, tr, verbose, ExecutableCommand};
+use std::{error::Error, fs, io, path::Path};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let interactive = command.has_flag("-i");
+    let (destination, sources) = command
+        .arguments
+        .split_last()
+        .ok_or("Missing destination")?;
+    let destination = command.context.resolve(destination);
+    let into_directory = destination.is_dir();
+    if sources.len() > 1 && !into_directory {
+        return Err(format!("{} is not a directory", destination.display()).into());
+    }
+
+    for source in sources {
+        let source = command.context.resolve(source);
+        fs::symlink_metadata(&source)
+            .map_err(|error| format!("Cannot move {}: {}", source.display(), error))?;
+        let target = if into_directory {
+            let name = source
+                .file_name()
+                .ok_or_else(|| format!("Cannot move {}", source.display()))?;
+            destination.join(name)
+        } else {
+            destination.clone()
+        };
+
+        if interactive
+            && fs::symlink_metadata(&target).is_ok()
+            && !terminal::confirm(&tr!(Message::Overwrite, target.display()))?
+        {
+            continue;
+        }
+        if options::dry_run() {
+            status!("would move {} to {}", source.display(), target.display());
+            continue;
+        }
+        move_path(&source, &target)?;
+        verbose!("moved {} to {}", source.display(), target.display());
+    }
+    Ok(())
+}
+
+/// Renames `source`, or copies and then removes it when `target` is on
+/// another filesystem.
+fn move_path(source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+    match fs::rename(source, target) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices => {
+            let copier = Copier {
+                recursive: true,
+                force: true,
+                dry_run: false,
+                options: copy::Options::default(),
+            };
+            copier.copy(source, target)?;
+            if fs::symlink_metadata(source)?.is_dir() {
+                fs::remove_dir_all(source)?;
+            } else {
+                fs::remove_file(source)?;
+            }
+            Ok(())
+        }
+        Err(error) => Err(format!(
+            "Cannot move {} to {}: {}",
+            source.display(),
+            target.display(),
+            error
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{outln, ExecutableCommand};
+use std::{
+    env,
+    error::Error,
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs, UdpSocket},
+    time::Duration,
+};
+
+const DEFAULT_ENDPOINT: &str = "http://api.ipify.org/";
+const ENDPOINT_ENV_VAR: &str = "MYIP_ENDPOINT";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    if command.has_flag("--public") {
+        let endpoint = match command.get_flag_value("--endpoint") {
+            Some(endpoint) => endpoint.to_string(),
+            None => env::var(ENDPOINT_ENV_VAR).unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string()),
+        };
+        outln!("{}", fetch_public_ip(&endpoint)?);
+    } else {
+        outln!("{}", local_ip()?);
+    }
+
+    Ok(())
+}
+
+/// Picks the address of the interface used for outbound traffic.
+/// Connecting a UDP socket does not send any packets.
+fn local_ip() -> Result<String, Box<dyn Error>> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect("8.8.8.8:80")?;
+    Ok(socket.local_addr()?.ip().to_string())
+}
+
+fn fetch_public_ip(endpoint: &str) -> Result<String, Box<dyn Error>> {
+    let (host, port, path) = parse_http_url(endpoint)?;
+    let address = (host.as_str(), port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| format!("Could not resolve host {}", host))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, REQUEST_TIMEOUT)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: my_basic_cli_tools\r\nConnection: close\r\n\r\n",
+        path, host
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("Malformed HTTP response")?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Request to {} failed: {}", endpoint, status).into());
+    }
+
+    let ip = body.trim();
+    if ip.is_empty() {
+        return Err(format!("Empty response from {}", endpoint).into());
+    }
+
+    Ok(ip.to_string())
+}
+
+/// Splits `http://host[:port][/path]` into its parts. Only plain HTTP is supported.
+fn parse_http_url(url: &str) -> Result<(String, u16, String), Box<dyn Error>> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Only http:// endpoints are supported: {}", url))?;
+
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse()?),
+        None => (authority, 80),
+    };
+
+    Ok((host.to_string(), port, path.to_string()))
+}
@@ -0,0 +1,106 @@
+use crate::{
+    calendar,
+    notes::{self, Note},
+    options, outln, status,
+    timezone::TimeZone,
+    ExecutableCommand,
+};
+use std::{env, error::Error, fs, process::Command};
+
+const USAGE: &str =
+    "Usage: note add <text> | note list | note search <term> | note edit <id> [<text>]";
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let Some((action, rest)) = command.arguments.split_first() else {
+        return Err(USAGE.into());
+    };
+    match (action.as_str(), rest) {
+        ("add", text) if !text.is_empty() && options::dry_run() => {
+            status!("would add note: {}", text.join(" "));
+        }
+        ("add", text) if !text.is_empty() => {
+            let id = notes::add(calendar::now(), &text.join(" "))?;
+            outln!("Added note {}", id);
+        }
+        ("list", []) => print_notes(&notes::list()?, "No notes"),
+        ("search", term) if !term.is_empty() => {
+            print_notes(&notes::search(&term.join(" "))?, "No matching notes")
+        }
+        ("edit", [id, text @ ..]) => {
+            let id = parse_id(id)?;
+            let note = notes::get(id)?.ok_or_else(|| format!("No note with id {}", id))?;
+            let text = if text.is_empty() {
+                edit_in_editor(&note)?
+            } else {
+                text.join(" ")
+            };
+            if text.trim().is_empty() {
+                return Err("The note is empty, so it was not changed".into());
+            }
+            if options::dry_run() {
+                status!("would update note {}", id);
+                return Ok(());
+            }
+            if !notes::update(id, text.trim_end())? {
+                return Err(format!("No note with id {}", id).into());
+            }
+            outln!("Updated note {}", id);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+fn parse_id(id: &str) -> Result<u64, Box<dyn Error>> {
+    id.parse::<u64>()
+        .map_err(|_| format!("Invalid note id: {}", id).into())
+}
+
+fn print_notes(notes: &[Note], empty_message: &str) {
+    if notes.is_empty() {
+        outln!("{}", empty_message);
+        return;
+    }
+    let zone = TimeZone::local();
+    for note in notes {
+        let created = zone.local_time(note.created).date_time;
+        let mut lines = note.text.lines();
+        outln!(
+            "{:>3}  {}  {}",
+            note.id,
+            created,
+            lines.next().unwrap_or_default()
+        );
+        // Continuation lines line up under the first line of text.
+        for line in lines {
+            outln!("{:>26}{}", "", line);
+        }
+    }
+}
+
+/// Opens the note in `$VISUAL` or `$EDITOR` and returns the saved text.
+fn edit_in_editor(note: &Note) -> Result<String, Box<dyn Error>> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
+    // Editors are often set with arguments, like `code --wait`.
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or("$EDITOR is empty")?;
+
+    let path = env::temp_dir().join(format!("note-{}-{}.txt", std::process::id(), note.id));
+    fs::write(&path, format!("{}\n", note.text))?;
+    let status = Command::new(program).args(words).arg(&path).status();
+    let text = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    let status = status.map_err(|error| format!("Cannot run {}: {}", program, error))?;
+    if !status.success() {
+        return Err(format!("{} exited with {}", program, status).into());
+    }
+    Ok(text?)
+}
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
@@ -0,0 +1,99 @@
+use crate::{status, ExecutableCommand};
+use std::{error::Error, ffi::OsString, process::Stdio};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let target = &command.arguments[0];
+    let target = if is_url(target) {
+        OsString::from(target)
+    } else {
+        let path = command.context.resolve(target);
+        if !path.exists() {
+            return Err(format!("{} does not exist", path.display()).into());
+        }
+        path.into_os_string()
+    };
+
+    let mut opener = match command.get_flag_value("--with") {
+        Some(app) => platform::opener_with(app, &target),
+        None => platform::opener(&target),
+    };
+    let program = opener.get_program().to_string_lossy().into_owned();
+    let status = opener
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|error| format!("Could not start {}: {}", program, error))?;
+    if !status.success() {
+        return Err(format!("{} failed with {}", program, status).into());
+    }
+
+    status!("Opened {}", target.to_string_lossy());
+    Ok(())
+}
+
+/// Whether `target` looks like `scheme:...` rather than a path. Windows drive
+/// letters (`C:\`) are single letters, which no scheme is.
+fn is_url(target: &str) -> bool {
+    target.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::{ffi::OsStr, process::Command};
+
+    pub fn opener(target: &OsStr) -> Command {
+        let mut command = Command::new("open");
+        command.arg(target);
+        command
+    }
+
+    pub fn opener_with(app: &str, target: &OsStr) -> Command {
+        let mut command = Command::new("open");
+        command.arg("-a").arg(app).arg(target);
+        command
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::{ffi::OsStr, process::Command};
+
+    pub fn opener(target: &OsStr) -> Command {
+        // The empty argument is the window title `start` expects first.
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]).arg(target);
+        command
+    }
+
+    pub fn opener_with(app: &str, target: &OsStr) -> Command {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", "", app]).arg(target);
+        command
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    use std::{ffi::OsStr, process::Command};
+
+    pub fn opener(target: &OsStr) -> Command {
+        let mut command = Command::new("xdg-open");
+        command.arg(target);
+        command
+    }
+
+    /// Starts `app` in the background through `setsid`, so that waiting
+    /// for it only waits until it has been launched.
+    pub fn opener_with(app: &str, target: &OsStr) -> Command {
+        let mut command = Command::new("setsid");
+        command.arg("--fork").arg(app).arg(target);
+        command
+    }
+}
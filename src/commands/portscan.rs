@@ -0,0 +1,132 @@
+use crate::{outln, ExecutableCommand};
+use std::{
+    error::Error,
+    io::Read,
+    net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+const DEFAULT_PORTS: &str = "1-1024";
+const DEFAULT_TIMEOUT_MS: u64 = 500;
+const DEFAULT_WORKERS: usize = 100;
+const BANNER_BUFFER_SIZE: usize = 1024;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let host = &command.arguments[0];
+    let ports = parse_ports(command.get_flag_value("--ports").unwrap_or(DEFAULT_PORTS))?;
+    let timeout = match command.get_flag_value("--timeout") {
+        Some(timeout) => Duration::from_millis(timeout.parse()?),
+        None => Duration::from_millis(DEFAULT_TIMEOUT_MS),
+    };
+    let workers = match command.get_flag_value("--workers") {
+        Some(workers) => workers.parse::<usize>()?.max(1),
+        None => DEFAULT_WORKERS,
+    };
+    let grab_banner = command.has_flag("--banner");
+
+    let ip = resolve_host(host)?;
+    let ports = Arc::new(ports);
+    let next_port = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+
+    let handles = (0..workers.min(ports.len()))
+        .map(|_| {
+            let ports = Arc::clone(&ports);
+            let next_port = Arc::clone(&next_port);
+            let sender = sender.clone();
+
+            thread::spawn(move || loop {
+                let index = next_port.fetch_add(1, Ordering::Relaxed);
+                let Some(&port) = ports.get(index) else {
+                    break;
+                };
+
+                if let Some(banner) = scan_port(SocketAddr::new(ip, port), timeout, grab_banner) {
+                    let _ = sender.send((port, banner));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let mut open_ports = receiver.iter().collect::<Vec<(u16, Option<String>)>>();
+    for handle in handles {
+        let _ = handle.join();
+    }
+    open_ports.sort_by_key(|(port, _)| *port);
+
+    if open_ports.is_empty() {
+        outln!("No open ports found on {}", host);
+    }
+    for (port, banner) in open_ports {
+        match banner {
+            Some(banner) => outln!("{}/tcp open  {}", port, banner),
+            None => outln!("{}/tcp open", port),
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_host(host: &str) -> Result<IpAddr, Box<dyn Error>> {
+    (host, 0)
+        .to_socket_addrs()?
+        .next()
+        .map(|address| address.ip())
+        .ok_or_else(|| format!("Could not resolve host {}", host).into())
+}
+
+/// Returns `None` when the port is closed, `Some(banner)` otherwise.
+fn scan_port(address: SocketAddr, timeout: Duration, grab_banner: bool) -> Option<Option<String>> {
+    let mut stream = TcpStream::connect_timeout(&address, timeout).ok()?;
+
+    if !grab_banner {
+        return Some(None);
+    }
+
+    let mut buffer = [0; BANNER_BUFFER_SIZE];
+    let _ = stream.set_read_timeout(Some(timeout));
+    let banner = match stream.read(&mut buffer) {
+        Ok(read) if read > 0 => String::from_utf8_lossy(&buffer[..read])
+            .lines()
+            .next()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty()),
+        _ => None,
+    };
+
+    Some(banner)
+}
+
+/// Parses port lists like `22,80,8000-8080`.
+fn parse_ports(spec: &str) -> Result<Vec<u16>, Box<dyn Error>> {
+    let mut ports = Vec::new();
+
+    for part in spec.split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start = start.parse::<u16>()?;
+                let end = end.parse::<u16>()?;
+                if start > end {
+                    return Err(format!("Invalid port range: {}", part).into());
+                }
+                ports.extend(start..=end);
+            }
+            None => ports.push(part.parse()?),
+        }
+    }
+
+    ports.sort_unstable();
+    ports.dedup();
+
+    if ports.is_empty() {
+        return Err(format!("No ports given in: {}", spec).into());
+    }
+
+    Ok(ports)
+}
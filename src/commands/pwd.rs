@@ -0,0 +1,11 @@
+use crate::{outln, ExecutableCommand};
+use std::{error::Error, fs};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    if command.has_flag("-P") {
+        outln!("{}", fs::canonicalize(command.context.cwd())?.display());
+    } else {
+        outln!("{}", command.context.cwd().display());
+    }
+    Ok(())
+}
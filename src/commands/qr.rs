@@ -0,0 +1,68 @@
+use crate::{
+    atomic, options, out, outln, png,
+    qr::{Ecc, QrCode},
+    status, ExecutableCommand,
+};
+use std::error::Error;
+
+/// Light modules around the code that readers need to find it.
+const QUIET_ZONE: usize = 4;
+/// Pixels per module in PNG output.
+const PNG_SCALE: usize = 8;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let text = command.arguments.join(" ");
+    let ecc = match command.get_flag_value("--ecc") {
+        Some(level) => Ecc::try_from(level)?,
+        None => Ecc::Medium,
+    };
+    let code = QrCode::encode(text.as_bytes(), ecc)?;
+
+    let output = command.get_flag_value("--output");
+    match output {
+        Some(output) => {
+            let path = command.context.resolve(output);
+            if options::dry_run() {
+                status!("would write {}", path.display());
+                return Ok(());
+            }
+            let side = ((code.size() + QUIET_ZONE * 2) * PNG_SCALE) as u32;
+            let image = png::encode_bitmap(side, side, |x, y| {
+                is_dark(&code, x as usize / PNG_SCALE, y as usize / PNG_SCALE)
+            });
+            atomic::write(&path, image)?;
+            status!("Wrote {}", path.display());
+        }
+        None => print_blocks(&code, command.has_flag("--invert")),
+    }
+    Ok(())
+}
+
+/// Whether the module at `x`, `y` is dark, counting the quiet zone.
+fn is_dark(code: &QrCode, x: usize, y: usize) -> bool {
+    let range = QUIET_ZONE..QUIET_ZONE + code.size();
+    range.contains(&x) && range.contains(&y) && code.is_dark(x - QUIET_ZONE, y - QUIET_ZONE)
+}
+
+/// Draws two rows of modules per line with half block characters. By default
+/// light modules are drawn as blocks, which reads correctly on the usual
+/// dark terminal background.
+fn print_blocks(code: &QrCode, invert: bool) {
+    let side = code.size() + QUIET_ZONE * 2;
+    for y in (0..side).step_by(2) {
+        for x in 0..side {
+            let top = is_dark(code, x, y) == invert;
+            let bottom = y + 1 < side && is_dark(code, x, y + 1) == invert;
+            out!(
+                "{}",
+                match (top, bottom) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                }
+            );
+        }
+        outln!();
+    }
+}
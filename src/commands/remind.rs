@@ -0,0 +1,76 @@
+use crate::{
+    calendar, options, outln, reminders, status, timezone::TimeZone, units, ExecutableCommand,
+};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let arguments = command
+        .arguments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    match arguments[..] {
+        ["list"] => list(),
+        ["rm", id] => {
+            let id = id
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid reminder id: {}", id))?;
+            if options::dry_run() {
+                if !reminders::list()?.iter().any(|reminder| reminder.id == id) {
+                    return Err(format!("No reminder with id {}", id).into());
+                }
+                status!("would cancel reminder {}", id);
+                return Ok(());
+            }
+            if !reminders::remove(id)? {
+                return Err(format!("No reminder with id {}", id).into());
+            }
+            outln!("Cancelled reminder {}", id);
+            Ok(())
+        }
+        // `remind me in 10m stand up` reads naturally, but `me` is optional.
+        ["me", "in", duration, ref message @ ..] | ["in", duration, ref message @ ..]
+            if !message.is_empty() =>
+        {
+            let seconds = units::parse_duration(duration)?;
+            let message = message.join(" ");
+            if options::dry_run() {
+                status!(
+                    "would set a reminder for {} from now: {}",
+                    units::format_duration(seconds),
+                    message
+                );
+                return Ok(());
+            }
+            let id = reminders::add(calendar::now() + seconds.ceil() as i64, &message)?;
+            outln!(
+                "Reminder {} set for {} from now",
+                id,
+                units::format_duration(seconds)
+            );
+            Ok(())
+        }
+        _ => Err("Usage: remind me in <duration> <message> | remind list | remind rm <id>".into()),
+    }
+}
+
+fn list() -> Result<(), Box<dyn Error>> {
+    let reminders = reminders::list()?;
+    if reminders.is_empty() {
+        outln!("No reminders");
+        return Ok(());
+    }
+    let zone = TimeZone::local();
+    let now = calendar::now();
+    for reminder in reminders {
+        let due = zone.local_time(reminder.due).date_time;
+        outln!(
+            "{:>3}  {}  (in {})  {}",
+            reminder.id,
+            due,
+            units::format_duration((reminder.due - now).max(0) as f64),
+            reminder.message
+        );
+    }
+    Ok(())
+}
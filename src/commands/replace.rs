@@ -0,0 +1,78 @@
+use crate::{atomic, options, out, outln, status, verbose, ExecutableCommand};
+use std::{error::Error, fs};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let [pattern, replacement, paths @ ..] = &command.arguments[..] else {
+        return Err("Usage: replace <pattern> <replacement> <file>...".into());
+    };
+    if pattern.is_empty() {
+        return Err("The pattern cannot be empty".into());
+    }
+    let all = command.has_flag("-g");
+    let in_place = command.has_flag("--in-place");
+    let preview = command.has_flag("--dry-run") || options::dry_run();
+
+    let (mut total, mut changed_files) = (0, 0);
+    for argument in paths {
+        let path = command.context.resolve(argument);
+        let contents = fs::read(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let text = String::from_utf8(contents)
+            .map_err(|_| format!("{}: not a UTF-8 text file", path.display()))?;
+
+        let mut output = String::with_capacity(text.len());
+        let mut count = 0;
+        for (index, line) in text.split_inclusive('\n').enumerate() {
+            let found = line.matches(pattern.as_str()).count();
+            if found == 0 {
+                output.push_str(line);
+                continue;
+            }
+            let new_line = if all {
+                count += found;
+                line.replace(pattern.as_str(), replacement)
+            } else {
+                count += 1;
+                line.replacen(pattern.as_str(), replacement, 1)
+            };
+            if preview {
+                outln!("{}:{}", argument, index + 1);
+                outln!("- {}", line.trim_end_matches(['\n', '\r']));
+                outln!("+ {}", new_line.trim_end_matches(['\n', '\r']));
+            }
+            output.push_str(&new_line);
+        }
+
+        if count > 0 {
+            changed_files += 1;
+            total += count;
+        }
+        if preview {
+            continue;
+        }
+        if !in_place {
+            out!("{}", output);
+        } else if count > 0 {
+            atomic::write(&path, output)?;
+            verbose!("{}: replaced {}", argument, count);
+        }
+    }
+
+    let summary = format!(
+        "{} {} in {} of {} {}",
+        total,
+        if total == 1 {
+            "occurrence"
+        } else {
+            "occurrences"
+        },
+        changed_files,
+        paths.len(),
+        if paths.len() == 1 { "file" } else { "files" }
+    );
+    if preview {
+        status!("Would replace {}", summary);
+    } else if in_place {
+        status!("Replaced {}", summary);
+    }
+    Ok(())
+}
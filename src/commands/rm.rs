@@ -0,0 +1,94 @@
+use crate::{i18n::Message, options, status, terminal, verbose, ExecutableCommand};
+use std::{
+    error::Error,
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let recursive = command.has_flag("--recursive");
+    let force = command.has_flag("--force");
+
+    let mut targets = Vec::new();
+    for argument in &command.arguments {
+        let Some((path, is_dir)) = target(command, argument, force)? else {
+            continue;
+        };
+        if is_dir && !recursive {
+            return Err(format!("{} is a directory; use -r to remove it", path.display()).into());
+        }
+        targets.push((argument, path, is_dir));
+    }
+
+    // With the menu nothing is removed until everything has been checked
+    // and the user has picked what goes.
+    if command.has_flag("--interactive-menu") {
+        let items = targets
+            .iter()
+            .map(|(argument, _, is_dir)| {
+                let slash = if *is_dir && !argument.ends_with('/') {
+                    "/"
+                } else {
+                    ""
+                };
+                format!("{}{}", argument, slash)
+            })
+            .collect::<Vec<_>>();
+        let chosen = terminal::choose_many(Message::RemoveWhich.text(), &items)?;
+        targets = chosen
+            .into_iter()
+            .map(|index| targets[index].clone())
+            .collect();
+    }
+
+    for (_, path, is_dir) in targets {
+        remove(&path, is_dir)?;
+    }
+    Ok(())
+}
+
+/// The path `argument` names and whether it is a directory, or `None` if it
+/// does not exist and that is fine with `-f`.
+fn target(
+    command: &ExecutableCommand,
+    argument: &str,
+    force: bool,
+) -> Result<Option<(PathBuf, bool)>, Box<dyn Error>> {
+    let last = Path::new(argument).components().next_back();
+    if matches!(
+        last,
+        None | Some(
+            Component::CurDir | Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    ) {
+        return Err(format!("Refusing to remove {}", argument).into());
+    }
+    let path = command.context.resolve(argument);
+
+    // Symlinks are removed themselves, never what they point to.
+    match fs::symlink_metadata(&path) {
+        Ok(metadata) => Ok(Some((path, metadata.is_dir()))),
+        Err(error) if force && error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(format!("Cannot remove {}: {}", path.display(), error).into()),
+    }
+}
+
+fn remove(path: &Path, is_dir: bool) -> Result<(), Box<dyn Error>> {
+    if options::dry_run() {
+        if is_dir {
+            status!("would remove {} and its contents", path.display());
+        } else {
+            status!("would remove {}", path.display());
+        }
+        return Ok(());
+    }
+
+    let result = if is_dir {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.map_err(|error| format!("Cannot remove {}: {}", path.display(), error))?;
+    verbose!("removed {}", path.display());
+    Ok(())
+}
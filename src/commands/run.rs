@@ -0,0 +1,55 @@
+use crate::{external, options, outln, project, status, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let dir = command.context.cwd();
+    let tasks = project::tasks(&dir)?;
+
+    // Plain names, one per line, for shell completion scripts.
+    if command.has_flag("--names") {
+        let mut names = tasks
+            .iter()
+            .map(|task| task.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        for name in names {
+            outln!("{}", name);
+        }
+        return Ok(());
+    }
+
+    let Some(name) = command.arguments.first() else {
+        if tasks.is_empty() {
+            outln!(
+                "No Cargo.toml, package.json or Makefile in {}",
+                dir.display()
+            );
+            return Ok(());
+        }
+        let width = tasks
+            .iter()
+            .map(|task| task.qualified_name().len())
+            .max()
+            .unwrap_or_default();
+        for task in &tasks {
+            let line = format!("{:<width$}  {}", task.qualified_name(), task.detail);
+            outln!("{}", line.trim_end());
+        }
+        return Ok(());
+    };
+
+    let task = project::find(&tasks, name).ok_or_else(|| {
+        format!(
+            "No task named {} in {} (run `run` to list them)",
+            name,
+            dir.display()
+        )
+    })?;
+    let args = task.args();
+    if options::dry_run() {
+        status!("would run {} {}", task.program(), args.join(" "));
+        return Ok(());
+    }
+    external::run(command.context, task.program(), &args)
+}
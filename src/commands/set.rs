@@ -0,0 +1,52 @@
+use crate::{
+    options::{self, Kind, OptionSpec},
+    outln, status, ExecutableCommand,
+};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let [name, value] = &command.arguments[..] else {
+        let selected = match command.arguments.first() {
+            Some(name) => {
+                vec![options::find(name).ok_or_else(|| format!("Unknown option: {}", name))?]
+            }
+            None => options::OPTIONS.iter().collect(),
+        };
+        print_options(&selected);
+        return Ok(());
+    };
+
+    let (name, value) = options::set(name, value)?;
+    status!("{} = {}", name, value);
+    Ok(())
+}
+
+fn print_options(selected: &[&OptionSpec]) {
+    outln!(
+        "{:<18} {:<8} {:<8} {}",
+        "Option",
+        "Value",
+        "Source",
+        "Accepts"
+    );
+    for option in selected {
+        let (value, source) = options::value(option);
+        let accepts = match option.kind {
+            Kind::Bool => "true, false".to_string(),
+            Kind::Choice(choices) => choices.join(", "),
+            Kind::Mode => "octal mode, inherit".to_string(),
+            Kind::PathList => format!(
+                "directories separated by {}",
+                if cfg!(windows) { ";" } else { ":" }
+            ),
+        };
+        outln!(
+            "{:<18} {:<8} {:<8} {}",
+            option.name,
+            value,
+            source.name(),
+            accepts
+        );
+        outln!("    {}", option.description);
+    }
+}
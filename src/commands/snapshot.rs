@@ -0,0 +1,155 @@
+use crate::{atomic, hash, json, options, outln, status, walk, ExecutableCommand};
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+
+const SNAPSHOT_VERSION: u64 = 1;
+
+#[derive(PartialEq)]
+struct FileRecord {
+    size: u64,
+    sha256: String,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    match command.arguments[0].as_str() {
+        "create" => {
+            let [_, dir] = &command.arguments[..] else {
+                return Err("Usage: snapshot create <dir> -o <snapshot.json>".into());
+            };
+            let output = command
+                .get_flag_value("--output")
+                .ok_or("Missing output file: use -o <snapshot.json>")?;
+
+            let records = record_directory(Path::new(dir))?;
+            if options::dry_run() {
+                status!("would write {} with {} files", output, records.len());
+                return Ok(());
+            }
+            let content = to_json(dir, &records);
+            if command.has_flag("--atomic") {
+                atomic::write(output, content)?;
+            } else {
+                fs::write(output, content)?;
+            }
+            status!(
+                "Recorded {} files from {} in {}",
+                records.len(),
+                dir,
+                output
+            );
+        }
+        "diff" => {
+            let [_, snapshot, dir] = &command.arguments[..] else {
+                return Err("Usage: snapshot diff <snapshot.json> <dir>".into());
+            };
+
+            let before = from_json(&fs::read_to_string(snapshot)?)?;
+            let after = record_directory(Path::new(dir))?;
+            print_diff(&before, &after);
+        }
+        other => {
+            return Err(format!(
+                "Unknown snapshot action: {} (expected create or diff)",
+                other
+            )
+            .into())
+        }
+    }
+
+    Ok(())
+}
+
+fn record_directory(root: &Path) -> Result<BTreeMap<String, FileRecord>, Box<dyn Error>> {
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()).into());
+    }
+
+    let mut records = BTreeMap::new();
+    for path in walk::files(root, false)? {
+        let relative = path
+            .strip_prefix(root)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let record = FileRecord {
+            size: fs::metadata(&path)?.len(),
+            sha256: hash::sha256_file(&path)?,
+        };
+        records.insert(relative, record);
+    }
+
+    Ok(records)
+}
+
+fn to_json(root: &str, records: &BTreeMap<String, FileRecord>) -> String {
+    let files = records
+        .iter()
+        .map(|(path, record)| {
+            format!(
+                "    {{\"path\": {}, \"size\": {}, \"sha256\": {}}}",
+                json::quote(path),
+                record.size,
+                json::quote(&record.sha256)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "{{\n  \"version\": {},\n  \"root\": {},\n  \"files\": [\n{}\n  ]\n}}\n",
+        SNAPSHOT_VERSION,
+        json::quote(root),
+        files.join(",\n")
+    )
+}
+
+fn from_json(content: &str) -> Result<BTreeMap<String, FileRecord>, Box<dyn Error>> {
+    let snapshot = json::parse(content)?;
+    let files = snapshot
+        .get("files")
+        .and_then(json::Value::as_array)
+        .ok_or("Snapshot has no file list")?;
+
+    let mut records = BTreeMap::new();
+    for file in files {
+        let (Some(path), Some(size), Some(sha256)) = (
+            file.get("path").and_then(json::Value::as_str),
+            file.get("size").and_then(json::Value::as_u64),
+            file.get("sha256").and_then(json::Value::as_str),
+        ) else {
+            return Err("Snapshot contains a malformed file entry".into());
+        };
+
+        records.insert(
+            path.to_string(),
+            FileRecord {
+                size,
+                sha256: sha256.to_string(),
+            },
+        );
+    }
+
+    Ok(records)
+}
+
+fn print_diff(before: &BTreeMap<String, FileRecord>, after: &BTreeMap<String, FileRecord>) {
+    let added = after.keys().filter(|path| !before.contains_key(*path));
+    let removed = before.keys().filter(|path| !after.contains_key(*path));
+    let modified = before
+        .iter()
+        .filter(|(path, record)| after.get(*path).is_some_and(|current| current != *record))
+        .map(|(path, _)| path);
+
+    let mut changes = 0;
+    for (label, paths) in [
+        ("added", added.collect::<Vec<_>>()),
+        ("removed", removed.collect()),
+        ("modified", modified.collect()),
+    ] {
+        for path in paths {
+            outln!("{:<9} {}", format!("{}:", label), path);
+            changes += 1;
+        }
+    }
+
+    if changes == 0 {
+        outln!("No differences found");
+    }
+}
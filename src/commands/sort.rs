@@ -0,0 +1,306 @@
+use crate::{outln, units, ExecutableCommand};
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    env,
+    error::Error,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    mem,
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How much text is sorted in memory before it is written out as a run,
+/// unless `--buffer-size` says otherwise.
+const DEFAULT_BUFFER_SIZE: u64 = 64 << 20;
+/// Most runs merged at once, to stay well below the open files limit.
+const MERGE_WIDTH: usize = 64;
+
+struct Sorter {
+    reverse: bool,
+    numeric: bool,
+    unique: bool,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let sorter = Sorter {
+        reverse: command.has_flag("-r"),
+        numeric: command.has_flag("-n"),
+        unique: command.has_flag("-u"),
+    };
+    let buffer_size = command
+        .get_flag_value("--buffer-size")
+        .map(units::parse_size)
+        .transpose()?
+        .unwrap_or(DEFAULT_BUFFER_SIZE);
+
+    // Lines are read as they come, and whatever does not fit in the buffer
+    // is sorted in runs on disk, then merged.
+    let mut runs = Runs::default();
+    let mut lines = Vec::new();
+    let mut buffered = 0;
+    let mut add = |line: String| -> io::Result<()> {
+        buffered += (line.len() + mem::size_of::<String>()) as u64;
+        lines.push(line);
+        if buffered > buffer_size {
+            runs.write(&sorter, mem::take(&mut lines))?;
+            buffered = 0;
+        }
+        Ok(())
+    };
+    if command.arguments.is_empty() {
+        read_lines(io::stdin().lock(), &mut add)?;
+    }
+    for argument in &command.arguments {
+        let path = command.context.resolve(argument);
+        let file = File::open(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        read_lines(BufReader::new(file), &mut add)
+            .map_err(|error| format!("{}: {}", path.display(), error))?;
+    }
+
+    let mut print = |line: &str| -> io::Result<()> {
+        outln!("{}", line);
+        Ok(())
+    };
+    if runs.paths.is_empty() {
+        sorter.sort(&mut lines);
+        sorter.write(lines.into_iter().map(Ok), &mut print)?;
+    } else {
+        if !lines.is_empty() {
+            runs.write(&sorter, lines)?;
+        }
+        runs.merge(&sorter, &mut print)?;
+    }
+    Ok(())
+}
+
+/// Hands each line to `add`, without its line ending. Invalid UTF-8 is
+/// replaced.
+fn read_lines(
+    mut reader: impl BufRead,
+    add: &mut impl FnMut(String) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        if line.ends_with(b"\n") {
+            line.pop();
+            if line.ends_with(b"\r") {
+                line.pop();
+            }
+        }
+        add(String::from_utf8_lossy(&line).into_owned())?;
+        line.clear();
+    }
+    Ok(())
+}
+
+impl Sorter {
+    /// Lines with equal keys keep a fixed order, so runs merge the same
+    /// way the whole input would sort.
+    fn order(&self, a: &str, b: &str) -> Ordering {
+        let order = self.compare_keys(a, b).then_with(|| a.cmp(b));
+        if self.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+
+    fn compare_keys(&self, a: &str, b: &str) -> Ordering {
+        if self.numeric {
+            leading_number(a).total_cmp(&leading_number(b))
+        } else {
+            a.cmp(b)
+        }
+    }
+
+    fn sort(&self, lines: &mut [String]) {
+        lines.sort_by(|a, b| self.order(a, b));
+    }
+
+    /// Writes sorted lines. With `-u` only one of the lines with equal keys
+    /// is written, always the same one: the first in plain order.
+    fn write(
+        &self,
+        lines: impl Iterator<Item = io::Result<String>>,
+        write: &mut impl FnMut(&str) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut kept: Option<String> = None;
+        for line in lines {
+            let line = line?;
+            if !self.unique {
+                write(&line)?;
+                continue;
+            }
+            match &kept {
+                Some(previous) if self.compare_keys(previous, &line) == Ordering::Equal => {
+                    if line < *previous {
+                        kept = Some(line);
+                    }
+                }
+                _ => {
+                    if let Some(previous) = kept.replace(line) {
+                        write(&previous)?;
+                    }
+                }
+            }
+        }
+        if let Some(last) = kept {
+            write(&last)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sorted runs of lines in a temporary directory, removed when dropped.
+#[derive(Default)]
+struct Runs {
+    dir: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+    /// For naming runs, including merged ones.
+    created: usize,
+}
+
+impl Runs {
+    fn write(&mut self, sorter: &Sorter, mut lines: Vec<String>) -> io::Result<()> {
+        sorter.sort(&mut lines);
+        let (path, mut writer) = self.create()?;
+        sorter.write(lines.into_iter().map(Ok), &mut |line| {
+            writeln!(writer, "{}", line)
+        })?;
+        writer.flush()?;
+        self.paths.push(path);
+        Ok(())
+    }
+
+    fn create(&mut self) -> io::Result<(PathBuf, BufWriter<File>)> {
+        let dir = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => {
+                let nanos = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.subsec_nanos());
+                let dir = env::temp_dir().join(format!("sort-{}-{}", process::id(), nanos));
+                fs::create_dir(&dir)?;
+                self.dir.insert(dir).clone()
+            }
+        };
+        let path = dir.join(format!("run-{}", self.created));
+        self.created += 1;
+        let file = File::create(&path)?;
+        Ok((path, BufWriter::new(file)))
+    }
+
+    /// Merges all runs into `write`, first into wider runs while there are
+    /// too many to open at once.
+    fn merge(
+        &mut self,
+        sorter: &Sorter,
+        write: &mut impl FnMut(&str) -> io::Result<()>,
+    ) -> io::Result<()> {
+        while self.paths.len() > MERGE_WIDTH {
+            let batch = self.paths.drain(..MERGE_WIDTH).collect::<Vec<_>>();
+            let (path, mut writer) = self.create()?;
+            merge(sorter, &batch, &mut |line| writeln!(writer, "{}", line))?;
+            writer.flush()?;
+            for merged in batch {
+                fs::remove_file(merged)?;
+            }
+            self.paths.push(path);
+        }
+        merge(sorter, &self.paths, write)
+    }
+}
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// The next line of one run, ordered so the heap yields the smallest first.
+struct Head<'a> {
+    line: String,
+    run: usize,
+    sorter: &'a Sorter,
+}
+
+impl Ord for Head<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sorter
+            .order(&other.line, &self.line)
+            .then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+impl PartialOrd for Head<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Head<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head<'_> {}
+
+fn merge(
+    sorter: &Sorter,
+    paths: &[PathBuf],
+    write: &mut impl FnMut(&str) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut readers = paths
+        .iter()
+        .map(|path| Ok(BufReader::new(File::open(path)?).lines()))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut heap = BinaryHeap::new();
+    for (run, reader) in readers.iter_mut().enumerate() {
+        if let Some(line) = reader.next() {
+            heap.push(Head {
+                line: line?,
+                run,
+                sorter,
+            });
+        }
+    }
+
+    let merged = std::iter::from_fn(|| {
+        let Head { line, run, .. } = heap.pop()?;
+        match readers[run].next() {
+            Some(Ok(next)) => heap.push(Head {
+                line: next,
+                run,
+                sorter,
+            }),
+            Some(Err(error)) => return Some(Err(error)),
+            None => {}
+        }
+        Some(Ok(line))
+    });
+    sorter.write(merged, write)
+}
+
+/// The number a line starts with, after any leading whitespace. Lines that
+/// do not start with one count as zero, as in other shells.
+fn leading_number(line: &str) -> f64 {
+    let line = line.trim_start();
+    let mut end = 0;
+    let mut seen_point = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '-' | '+' if index == 0 => {}
+            '.' if !seen_point => seen_point = true,
+            c if c.is_ascii_digit() => {}
+            _ => break,
+        }
+        end = index + c.len_utf8();
+    }
+    line[..end].parse().unwrap_or(0.0)
+}
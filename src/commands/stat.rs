@@ -0,0 +1,153 @@
+use crate::{outln, timezone::TimeZone, units, ExecutableCommand};
+use std::{
+    error::Error,
+    fs::{self, Metadata},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let zone = TimeZone::local();
+    for (i, argument) in command.arguments.iter().enumerate() {
+        let path = command.context.resolve(argument);
+        let metadata = fs::symlink_metadata(&path)
+            .map_err(|error| format!("{}: {}", path.display(), error))?;
+        if i > 0 {
+            outln!();
+        }
+
+        match fs::read_link(&path) {
+            Ok(target) if metadata.is_symlink() => {
+                outln!("{:<10}{} -> {}", "File:", argument, target.display())
+            }
+            _ => outln!("{:<10}{}", "File:", argument),
+        }
+        outln!("{:<10}{}", "Type:", file_type(&metadata));
+        outln!(
+            "{:<10}{} ({})",
+            "Size:",
+            metadata.len(),
+            units::format_size(metadata.len())
+        );
+        for (label, value) in platform::details(&metadata) {
+            outln!("{:<10}{}", label, value);
+        }
+
+        let times = [
+            ("Modified:", metadata.modified()),
+            ("Accessed:", metadata.accessed()),
+            ("Created:", metadata.created()),
+        ];
+        for (label, time) in times {
+            // Not every platform and filesystem records every time.
+            if let Some(timestamp) = time.ok().and_then(unix_time) {
+                let local = zone.local_time(timestamp);
+                outln!("{:<10}{} {}", label, local.date_time, local.offset_text());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn unix_time(time: SystemTime) -> Option<i64> {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => i64::try_from(duration.as_secs()).ok(),
+        Err(error) => i64::try_from(error.duration().as_secs())
+            .ok()
+            .map(|secs| -secs),
+    }
+}
+
+fn file_type(metadata: &Metadata) -> &'static str {
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        "symbolic link"
+    } else if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_file() {
+        "regular file"
+    } else {
+        platform::special_file_type(metadata)
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use crate::{permissions, users};
+    use std::{
+        fs::Metadata,
+        os::unix::fs::{FileTypeExt, MetadataExt},
+    };
+
+    pub fn special_file_type(metadata: &Metadata) -> &'static str {
+        let file_type = metadata.file_type();
+        if file_type.is_fifo() {
+            "named pipe"
+        } else if file_type.is_socket() {
+            "socket"
+        } else if file_type.is_block_device() {
+            "block device"
+        } else if file_type.is_char_device() {
+            "character device"
+        } else {
+            "unknown"
+        }
+    }
+
+    pub fn details(metadata: &Metadata) -> Vec<(&'static str, String)> {
+        let mode = metadata.mode() & 0o7777;
+        // Unknown IDs, e.g. from another machine, are shown as numbers only.
+        let user = users::users()
+            .ok()
+            .and_then(|users| users.into_iter().find(|user| user.uid == metadata.uid()));
+        let group = users::groups()
+            .ok()
+            .and_then(|groups| groups.into_iter().find(|group| group.gid == metadata.gid()));
+        vec![
+            (
+                "Mode:",
+                format!(
+                    "{} ({})",
+                    permissions::octal(mode),
+                    permissions::symbolic(mode)
+                ),
+            ),
+            (
+                "Owner:",
+                match user {
+                    Some(user) => format!("{} ({})", user.name, user.uid),
+                    None => metadata.uid().to_string(),
+                },
+            ),
+            (
+                "Group:",
+                match group {
+                    Some(group) => format!("{} ({})", group.name, group.gid),
+                    None => metadata.gid().to_string(),
+                },
+            ),
+            ("Links:", metadata.nlink().to_string()),
+            (
+                "Inode:",
+                format!("{} on device {}", metadata.ino(), metadata.dev()),
+            ),
+        ]
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use std::fs::Metadata;
+
+    pub fn special_file_type(_metadata: &Metadata) -> &'static str {
+        "unknown"
+    }
+
+    pub fn details(metadata: &Metadata) -> Vec<(&'static str, String)> {
+        let access = if metadata.permissions().readonly() {
+            "read-only"
+        } else {
+            "read-write"
+        };
+        vec![("Access:", access.to_string())]
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{atomic, config, outln, units, ExecutableCommand};
+use std::{
+    error::Error,
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const STATE_FILE_NAME: &str = "stopwatch";
+
+/// The start and lap times, in milliseconds since the Unix epoch. Kept in a
+/// file so the stopwatch keeps running across sessions.
+#[derive(Debug, Default)]
+struct State {
+    started: u128,
+    laps: Vec<u128>,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let action = command.arguments.first().map_or("status", String::as_str);
+    let now = now_millis();
+    let state = load_state()?;
+
+    match (action, state) {
+        ("start", Some(state)) => Err(format!(
+            "The stopwatch is already running ({}); use `stopwatch stop` first",
+            clock(now - state.started)
+        )
+        .into()),
+        ("start", None) => {
+            save_state(&State {
+                started: now,
+                laps: Vec::new(),
+            })?;
+            outln!("Stopwatch started");
+            Ok(())
+        }
+        ("lap", Some(mut state)) => {
+            let previous = state.laps.last().copied().unwrap_or(state.started);
+            state.laps.push(now);
+            save_state(&state)?;
+            outln!(
+                "Lap {}: {} (total {})",
+                state.laps.len(),
+                clock(now - previous),
+                clock(now - state.started)
+            );
+            Ok(())
+        }
+        ("stop", Some(state)) => {
+            fs::remove_file(state_path()?)?;
+            outln!("Stopped at {}", clock(now - state.started));
+            Ok(())
+        }
+        ("status", Some(state)) => {
+            let laps = match state.laps.len() {
+                1 => "1 lap".to_string(),
+                count => format!("{} laps", count),
+            };
+            outln!("Running for {} ({})", clock(now - state.started), laps);
+            Ok(())
+        }
+        ("status", None) => {
+            outln!("The stopwatch is not running");
+            Ok(())
+        }
+        ("lap" | "stop", None) => Err("The stopwatch is not running; use `stopwatch start`".into()),
+        (action, _) => Err(format!(
+            "Unknown stopwatch action: {} (expected start, lap, stop or status)",
+            action
+        )
+        .into()),
+    }
+}
+
+fn clock(millis: u128) -> String {
+    units::format_clock(Duration::from_millis(millis as u64), true)
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn state_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(STATE_FILE_NAME))
+}
+
+/// Reads the state file: the start time on the first line, then one line
+/// per lap.
+fn load_state() -> Result<Option<State>, Box<dyn Error>> {
+    let content = match fs::read_to_string(state_path()?) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let mut times = content
+        .lines()
+        .map(|line| line.trim().parse::<u128>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "Corrupt stopwatch state file")?
+        .into_iter();
+    let started = times.next().ok_or("Corrupt stopwatch state file")?;
+    Ok(Some(State {
+        started,
+        laps: times.collect(),
+    }))
+}
+
+fn save_state(state: &State) -> Result<(), Box<dyn Error>> {
+    let content = std::iter::once(state.started)
+        .chain(state.laps.iter().copied())
+        .map(|time| format!("{}\n", time))
+        .collect::<String>();
+    atomic::write(state_path()?, content)?;
+    Ok(())
+}
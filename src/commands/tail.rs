@@ -0,0 +1,123 @@
+use crate::{outln, output, status, ExecutableCommand};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+const DEFAULT_LINE_COUNT: usize = 10;
+const BLOCK_SIZE: u64 = 8 * 1024;
+/// How often `-f` checks the file for new data.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let line_count = match command.get_flag_value("-n") {
+        Some(count) => count
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid line count: {}", count))?,
+        None => DEFAULT_LINE_COUNT,
+    };
+    let follow = command.has_flag("-f");
+    if follow && command.arguments.len() > 1 {
+        return Err("-f follows a single file".into());
+    }
+    let with_headers = command.arguments.len() > 1;
+
+    for (index, argument) in command.arguments.iter().enumerate() {
+        let path = command.context.resolve(argument);
+        let mut file =
+            File::open(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+
+        if with_headers {
+            if index > 0 {
+                outln!();
+            }
+            outln!("{}:", argument);
+        }
+        let start = last_lines_start(&mut file, line_count)?;
+        let end = copy_from(&mut file, start)?;
+        if follow {
+            output::flush()?;
+            follow_file(command, &path, file, end)?;
+        }
+    }
+    Ok(())
+}
+
+/// The offset at which the last `count` lines of the file begin, reading
+/// backwards from the end a block at a time.
+fn last_lines_start(file: &mut File, count: usize) -> io::Result<u64> {
+    let length = file.metadata()?.len();
+    if count == 0 {
+        return Ok(length);
+    }
+    let mut position = length;
+    let mut newlines = 0;
+    let mut buffer = vec![0; BLOCK_SIZE as usize];
+    while position > 0 {
+        let block_start = position.saturating_sub(BLOCK_SIZE);
+        let block = &mut buffer[..(position - block_start) as usize];
+        file.seek(SeekFrom::Start(block_start))?;
+        file.read_exact(block)?;
+        for (offset, byte) in block.iter().enumerate().rev() {
+            let at = block_start + offset as u64;
+            // A newline ending the file does not start another line.
+            if *byte != b'\n' || at + 1 == length {
+                continue;
+            }
+            newlines += 1;
+            if newlines == count {
+                return Ok(at + 1);
+            }
+        }
+        position = block_start;
+    }
+    Ok(0)
+}
+
+/// Prints the file from `start` to its current end, returning that end.
+fn copy_from(file: &mut File, start: u64) -> io::Result<u64> {
+    file.seek(SeekFrom::Start(start))?;
+    let mut buffer = vec![0; BLOCK_SIZE as usize];
+    let mut position = start;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(position);
+        }
+        output::write_bytes(&buffer[..read]);
+        position += read as u64;
+    }
+}
+
+/// Prints data appended to the file until the command is cancelled. A file
+/// that shrinks was truncated, so it is printed again from the start.
+fn follow_file(
+    command: &ExecutableCommand,
+    path: &Path,
+    mut file: File,
+    mut position: u64,
+) -> Result<(), Box<dyn Error>> {
+    while !command.context.cancellation.is_cancelled() {
+        thread::sleep(POLL_INTERVAL);
+        let length = match fs::metadata(path) {
+            Ok(metadata) => metadata.len(),
+            // Rotated away; keep watching in case it comes back.
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error.into()),
+        };
+        if length < position {
+            status!("{}: file truncated", path.display());
+            file = File::open(path)?;
+            position = 0;
+        }
+        if length > position {
+            position = copy_from(&mut file, position)?;
+            output::flush()?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,397 @@
+use crate::{
+    atomic::AtomicFile,
+    options, outln, status,
+    tar::{EntryKind, Header, Reader, Writer},
+    verbose, walk, ExecutableCommand,
+};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Component, Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let (archive, rest) = command
+        .arguments
+        .split_first()
+        .ok_or("Missing archive path")?;
+    let archive = command.context.resolve(archive);
+    if command.has_flag("-c") {
+        create(command, &archive, rest)
+    } else if command.has_flag("-x") {
+        let target = match rest {
+            [] => command.context.cwd().to_path_buf(),
+            [dir] => command.context.resolve(dir),
+            _ => return Err("Usage: tar -x <archive.tar> [<dir>]".into()),
+        };
+        extract(&archive, &target)
+    } else if command.has_flag("-t") {
+        if !rest.is_empty() {
+            return Err("Usage: tar -t <archive.tar>".into());
+        }
+        list(&archive)
+    } else {
+        Err("Say what to do: -c to create, -x to extract or -t to list".into())
+    }
+}
+
+fn open_archive(archive: &Path) -> Result<Reader<BufReader<File>>, Box<dyn Error>> {
+    let file = File::open(archive).map_err(|error| format!("{}: {}", archive.display(), error))?;
+    Ok(Reader::new(BufReader::new(file)))
+}
+
+fn list(archive: &Path) -> Result<(), Box<dyn Error>> {
+    let mut reader = open_archive(archive)?;
+    while let Some(header) = reader.next_entry()? {
+        match header.kind {
+            EntryKind::Symlink(target) => outln!("{} -> {}", header.path, target),
+            _ => outln!("{}", header.path),
+        }
+    }
+    Ok(())
+}
+
+/// A file, directory or link to add to an archive, and where it is read from.
+pub(super) struct Member {
+    pub header: Header,
+    pub path: PathBuf,
+}
+
+/// Walks the paths to archive, collecting their members in order.
+struct Collector<'a> {
+    /// The archive and its temporary file, left out when they are below one
+    /// of the paths being archived.
+    skipped: &'a [PathBuf],
+    members: Vec<Member>,
+    /// Entries that could not be read, reported together at the end.
+    errors: Vec<walk::Error>,
+}
+
+/// The members for the `paths` given to a command, with everything below
+/// directories, and the entries that could not be read.
+pub(super) fn collect(
+    command: &ExecutableCommand,
+    paths: &[String],
+    skipped: &[PathBuf],
+) -> Result<(Vec<Member>, Vec<walk::Error>), Box<dyn Error>> {
+    if paths.is_empty() {
+        return Err("Nothing to archive: give the files and directories to add".into());
+    }
+    let mut collector = Collector {
+        skipped,
+        members: Vec::new(),
+        errors: Vec::new(),
+    };
+    for argument in paths {
+        let path = command.context.resolve(argument);
+        let name = entry_name(Path::new(argument));
+        let metadata =
+            fs::symlink_metadata(&path).map_err(|error| format!("{}: {}", argument, error))?;
+        collector.add(&path, name, &metadata);
+    }
+    Ok((collector.members, collector.errors))
+}
+
+/// Opens each member and hands it to `append`, with the file for regular
+/// files. Files that cannot be opened go to `errors`. Returns how many
+/// members were added.
+pub(super) fn write_members(
+    members: &[Member],
+    errors: &mut Vec<walk::Error>,
+    mut append: impl FnMut(&Header, Option<File>) -> io::Result<()>,
+) -> io::Result<usize> {
+    let mut count = 0;
+    for member in members {
+        let file = match member.header.kind {
+            EntryKind::File => match File::open(&member.path) {
+                Ok(file) => Some(file),
+                Err(error) => {
+                    errors.push((member.path.clone(), error));
+                    continue;
+                }
+            },
+            _ => None,
+        };
+        verbose!("{}", member.header.path);
+        append(&member.header, file)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn create(
+    command: &ExecutableCommand,
+    archive: &Path,
+    paths: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let file = if options::dry_run() {
+        None
+    } else {
+        Some(AtomicFile::create(archive)?)
+    };
+    let mut skipped = vec![archive.to_path_buf()];
+    skipped.extend(file.as_ref().map(|file| file.temp_path().to_path_buf()));
+    let (members, mut errors) = collect(command, paths, &skipped)?;
+
+    let mut writer = file.map(|file| Writer::new(BufWriter::new(file)));
+    let count = write_members(&members, &mut errors, |header, file| match &mut writer {
+        Some(writer) => match file {
+            Some(file) => writer.append(header, file),
+            None => writer.append(header, io::empty()),
+        },
+        None => Ok(()),
+    })?;
+
+    // Skipped entries are reported first, so that with `strict` on an
+    // incomplete archive never replaces the old one.
+    walk::report_errors(&errors)?;
+    match writer {
+        Some(writer) => {
+            let file = writer.finish()?.into_inner().map_err(|e| e.into_error())?;
+            file.commit()?;
+            status!("Archived {} entries in {}", count, archive.display());
+        }
+        None => status!("Would archive {} entries in {}", count, archive.display()),
+    }
+    Ok(())
+}
+
+impl Collector<'_> {
+    fn add(&mut self, path: &Path, name: String, metadata: &fs::Metadata) {
+        if self.skipped.iter().any(|skipped| skipped == path) {
+            return;
+        }
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs());
+        let mut header = Header {
+            path: name,
+            kind: EntryKind::File,
+            mode: platform::mode(metadata),
+            size: 0,
+            modified,
+        };
+
+        if metadata.is_dir() {
+            // Directories such as `.` have no name of their own, so only
+            // what they hold is added.
+            if !header.path.is_empty() {
+                header.path.push('/');
+                header.kind = EntryKind::Directory;
+                self.push(path, header.clone());
+            }
+            let entries = match fs::read_dir(path)
+                .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+            {
+                Ok(entries) => entries,
+                Err(error) => {
+                    self.errors.push((path.to_path_buf(), error));
+                    return;
+                }
+            };
+            let mut entries = entries
+                .into_iter()
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>();
+            entries.sort();
+            for entry in entries {
+                let Some(file_name) = entry.file_name() else {
+                    continue;
+                };
+                let name = format!("{}{}", header.path, file_name.to_string_lossy());
+                match fs::symlink_metadata(&entry) {
+                    Ok(metadata) => self.add(&entry, name, &metadata),
+                    Err(error) => self.errors.push((entry, error)),
+                }
+            }
+        } else if metadata.is_symlink() {
+            match fs::read_link(path) {
+                Ok(target) => {
+                    header.kind = EntryKind::Symlink(target.to_string_lossy().replace('\\', "/"));
+                    self.push(path, header);
+                }
+                Err(error) => self.errors.push((path.to_path_buf(), error)),
+            }
+        } else if metadata.is_file() {
+            header.size = metadata.len();
+            self.push(path, header);
+        } else {
+            self.errors.push((
+                path.to_path_buf(),
+                io::Error::new(io::ErrorKind::Unsupported, "not a file, directory or link"),
+            ));
+        }
+    }
+
+    fn push(&mut self, path: &Path, header: Header) {
+        self.members.push(Member {
+            header,
+            path: path.to_path_buf(),
+        });
+    }
+}
+
+/// The name a path is stored under: its plain components joined with `/`,
+/// so absolute paths and `..` never end up in the archive. Empty for `.`.
+fn entry_name(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn extract(archive: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+    let mut reader = open_archive(archive)?;
+    let dry_run = options::dry_run();
+    if !dry_run {
+        fs::create_dir_all(target)?;
+    }
+
+    let (mut count, mut errors) = (0, Vec::new());
+    while let Some(header) = reader.next_entry()? {
+        let Some(path) = safe_path(target, &header.path) else {
+            errors.push((
+                PathBuf::from(&header.path),
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "outside the directory being extracted to",
+                ),
+            ));
+            continue;
+        };
+        verbose!("{}", header.path);
+        count += 1;
+        if dry_run {
+            continue;
+        }
+        if let Err(error) = extract_entry(&header, target, &path, |file| reader.copy_data(file)) {
+            errors.push((path, error));
+        }
+    }
+
+    walk::report_errors(&errors)?;
+    if dry_run {
+        status!("Would extract {} entries to {}", count, target.display());
+    } else {
+        status!("Extracted {} entries to {}", count, target.display());
+    }
+    Ok(())
+}
+
+/// Where an entry goes below `target`, unless its path is absolute or
+/// climbs out with `..`.
+pub(super) fn safe_path(target: &Path, name: &str) -> Option<PathBuf> {
+    let mut path = target.to_path_buf();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (path != target).then_some(path)
+}
+
+/// Creates the entry described by `header` at `path`, below `target`;
+/// `copy_data` writes the contents of files.
+pub(super) fn extract_entry(
+    header: &Header,
+    target: &Path,
+    path: &Path,
+    copy_data: impl FnOnce(&mut File) -> io::Result<u64>,
+) -> io::Result<()> {
+    // A link extracted earlier must not lead later entries out of `target`.
+    let mut parent = target.to_path_buf();
+    if let Ok(relative) = path.strip_prefix(target) {
+        for component in relative.parent().into_iter().flat_map(Path::components) {
+            parent.push(component);
+            if parent.is_symlink() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "inside a symbolic link from the archive",
+                ));
+            }
+        }
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Replace rather than write through an existing link.
+    if path.is_symlink() {
+        fs::remove_file(path)?;
+    }
+
+    match &header.kind {
+        EntryKind::Directory => fs::create_dir_all(path),
+        EntryKind::Symlink(link_target) => platform::symlink(Path::new(link_target), path),
+        EntryKind::File => {
+            let mut file = platform::create_file(path, header.mode)?;
+            copy_data(&mut file)?;
+            file.set_modified(UNIX_EPOCH + Duration::from_secs(header.modified))
+        }
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::{
+        fs::{self, File, OpenOptions},
+        io,
+        os::unix::fs::{OpenOptionsExt, PermissionsExt},
+        path::Path,
+    };
+
+    pub fn mode(metadata: &fs::Metadata) -> u32 {
+        metadata.permissions().mode() & 0o7777
+    }
+
+    /// Creates the file with the archived permissions, less the umask.
+    pub fn create_file(path: &Path, mode: u32) -> io::Result<File> {
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(mode & 0o777)
+            .open(path)
+    }
+
+    pub fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+        std::os::unix::fs::symlink(target, link)
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use std::{
+        fs::{self, File},
+        io,
+        path::Path,
+    };
+
+    pub fn mode(metadata: &fs::Metadata) -> u32 {
+        match (metadata.is_dir(), metadata.permissions().readonly()) {
+            (true, _) => 0o755,
+            (false, true) => 0o444,
+            (false, false) => 0o644,
+        }
+    }
+
+    pub fn create_file(path: &Path, _mode: u32) -> io::Result<File> {
+        File::create(path)
+    }
+
+    /// Links are left out: making them needs extra rights on Windows.
+    pub fn symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "symbolic links can only be extracted on Unix",
+        ))
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    calendar, json, options, outln, status,
+    tasks::{self, Date, Priority, Task},
+    terminal,
+    timezone::TimeZone,
+    ExecutableCommand,
+};
+use std::error::Error;
+
+const USAGE: &str =
+    "Usage: todo add <text> | todo done <id> | todo rm <id> | todo list | todo export";
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let Some((action, rest)) = command.arguments.split_first() else {
+        return Err(USAGE.into());
+    };
+    match (action.as_str(), rest) {
+        ("add", text) if !text.is_empty() => add(command, text.join(" ")),
+        ("done", [id]) => {
+            let id = parse_id(id)?;
+            if options::dry_run() {
+                return dry_run(id, "complete");
+            }
+            if !tasks::complete(id)? {
+                return Err(format!("No task with id {}", id).into());
+            }
+            outln!("Completed task {}", id);
+            Ok(())
+        }
+        ("rm", [id]) => {
+            let id = parse_id(id)?;
+            if options::dry_run() {
+                return dry_run(id, "remove");
+            }
+            if !tasks::remove(id)? {
+                return Err(format!("No task with id {}", id).into());
+            }
+            outln!("Removed task {}", id);
+            Ok(())
+        }
+        ("list", []) => list(command),
+        ("export", []) => export(command),
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn add(command: &ExecutableCommand, text: String) -> Result<(), Box<dyn Error>> {
+    let priority = match command.get_flag_value("--priority") {
+        Some(priority) => Priority::try_from(priority)?,
+        None => Priority::Medium,
+    };
+    let due = command
+        .get_flag_value("--due")
+        .map(Date::parse)
+        .transpose()?;
+    let tags = command
+        .get_flag_value("--tags")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if options::dry_run() {
+        status!("would add task: {}", text);
+        return Ok(());
+    }
+    let id = tasks::add(Task {
+        id: 0,
+        text,
+        done: false,
+        priority,
+        due,
+        tags,
+    })?;
+    outln!("Added task {}", id);
+    Ok(())
+}
+
+fn parse_id(id: &str) -> Result<u64, Box<dyn Error>> {
+    id.parse::<u64>()
+        .map_err(|_| format!("Invalid task id: {}", id).into())
+}
+
+/// Reports what `done` or `rm` would do without saving the task list.
+fn dry_run(id: u64, action: &str) -> Result<(), Box<dyn Error>> {
+    if !tasks::list()?.iter().any(|task| task.id == id) {
+        return Err(format!("No task with id {}", id).into());
+    }
+    status!("would {} task {}", action, id);
+    Ok(())
+}
+
+fn list(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let mut tasks = tasks::list()?;
+    if !command.has_flag("--all") {
+        tasks.retain(|task| !task.done);
+    }
+    if let Some(tag) = command.get_flag_value("--tags") {
+        tasks.retain(|task| task.tags.iter().any(|task_tag| task_tag == tag));
+    }
+    if tasks.is_empty() {
+        outln!("Nothing to do");
+        return Ok(());
+    }
+
+    let today = Date::from(TimeZone::local().local_time(calendar::now()).date_time);
+    let tags = tasks
+        .iter()
+        .map(|task| task.tags.join(","))
+        .collect::<Vec<_>>();
+    let tags_width = tags
+        .iter()
+        .map(|tags| tags.chars().count())
+        .chain(["Tags".len()])
+        .max()
+        .unwrap_or_default();
+
+    outln!(
+        "{:>3}  {:<4}  {:<8}  {:<10}  {:<tags_width$}  Task",
+        "ID",
+        "Done",
+        "Priority",
+        "Due",
+        "Tags"
+    );
+    for (task, tags) in tasks.iter().zip(tags) {
+        let due = task.due.map(|due| due.to_string()).unwrap_or_default();
+        // Padding first, so the escape codes do not throw off the columns.
+        let row = format!(
+            "{:>3}  {:<4}  {:<8}  {:<10}  {:<tags_width$}  {}",
+            task.id,
+            if task.done { "x" } else { "" },
+            task.priority.name(),
+            due,
+            tags,
+            task.text
+        );
+        let overdue = !task.done && task.due.is_some_and(|due| due < today);
+        if overdue {
+            outln!("{}", terminal::red(&row));
+        } else {
+            outln!("{}", row);
+        }
+    }
+    Ok(())
+}
+
+fn export(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let tasks = tasks::list()?;
+    match command.get_flag_value("--format").unwrap_or("md") {
+        "md" | "markdown" => {
+            for task in tasks {
+                let mut line = format!(
+                    "- [{}] {} (priority: {}",
+                    if task.done { "x" } else { " " },
+                    task.text,
+                    task.priority.name()
+                );
+                if let Some(due) = task.due {
+                    line.push_str(&format!(", due: {}", due));
+                }
+                line.push(')');
+                for tag in &task.tags {
+                    line.push_str(&format!(" #{}", tag));
+                }
+                outln!("{}", line);
+            }
+        }
+        "json" => {
+            let entries = tasks
+                .iter()
+                .map(|task| {
+                    let tags = task
+                        .tags
+                        .iter()
+                        .map(|tag| json::quote(tag))
+                        .collect::<Vec<_>>();
+                    format!(
+                        "{{\"id\":{},\"text\":{},\"done\":{},\"priority\":{},\"due\":{},\"tags\":[{}]}}",
+                        task.id,
+                        json::quote(&task.text),
+                        task.done,
+                        json::quote(task.priority.name()),
+                        task.due
+                            .map_or("null".to_string(), |due| json::quote(&due.to_string())),
+                        tags.join(",")
+                    )
+                })
+                .collect::<Vec<_>>();
+            outln!("[{}]", entries.join(","));
+        }
+        format => {
+            return Err(format!("Unknown format: {} (expected md or json)", format).into());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use crate::{json, options, outln, walk, ExecutableCommand};
+use std::{error::Error, fs, path::Path};
+
+const MARKERS: [&str; 3] = ["TODO", "FIXME", "HACK"];
+const BINARY_CHECK_SIZE: usize = 8192;
+
+struct Todo {
+    line_number: usize,
+    marker: &'static str,
+    text: String,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let root = command.arguments.first().map(String::as_str).unwrap_or(".");
+    let default_format = options::get(options::OUTPUT_FORMAT);
+    let format = command
+        .get_flag_value("--format")
+        .unwrap_or(&default_format);
+    if format != "text" && format != "json" {
+        return Err(format!("Unknown format: {} (expected text or json)", format).into());
+    }
+
+    let mut results = Vec::new();
+    let walk = walk::walk(
+        Path::new(root),
+        walk::Options {
+            respect_gitignore: true,
+            follow_links: command.has_flag("-L"),
+            ..walk::Options::default()
+        },
+    )?;
+    walk::report_errors(&walk.errors)?;
+
+    for path in walk.files {
+        let Ok(content) = fs::read(&path) else {
+            continue;
+        };
+        if content.iter().take(BINARY_CHECK_SIZE).any(|&b| b == 0) {
+            continue;
+        }
+
+        let todos = find_todos(&String::from_utf8_lossy(&content));
+        if !todos.is_empty() {
+            results.push((path.display().to_string(), todos));
+        }
+    }
+
+    if format == "json" {
+        print_json(&results);
+    } else {
+        print_text(&results);
+    }
+
+    Ok(())
+}
+
+fn find_todos(content: &str) -> Vec<Todo> {
+    let mut todos = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        for marker in MARKERS {
+            if let Some(position) = find_marker(line, marker) {
+                todos.push(Todo {
+                    line_number: index + 1,
+                    marker,
+                    text: line[position..].trim().to_string(),
+                });
+                break;
+            }
+        }
+    }
+
+    todos
+}
+
+/// Finds `marker` as a whole word, so `TODOS` or `mytodo` do not count.
+fn find_marker(line: &str, marker: &str) -> Option<usize> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    line.match_indices(marker)
+        .find(|(position, _)| {
+            let before = line[..*position].chars().next_back();
+            let after = line[position + marker.len()..].chars().next();
+            !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+        })
+        .map(|(position, _)| position)
+}
+
+fn print_text(results: &[(String, Vec<Todo>)]) {
+    if results.is_empty() {
+        outln!("No TODOs found");
+    }
+
+    for (i, (path, todos)) in results.iter().enumerate() {
+        if i > 0 {
+            outln!();
+        }
+        outln!("{}:", path);
+        for todo in todos {
+            outln!("{:>6}: {}", todo.line_number, todo.text);
+        }
+    }
+}
+
+fn print_json(results: &[(String, Vec<Todo>)]) {
+    let files = results
+        .iter()
+        .map(|(path, todos)| {
+            let todos = todos
+                .iter()
+                .map(|todo| {
+                    format!(
+                        "{{\"line\":{},\"marker\":{},\"text\":{}}}",
+                        todo.line_number,
+                        json::quote(todo.marker),
+                        json::quote(&todo.text)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "{{\"file\":{},\"todos\":[{}]}}",
+                json::quote(path),
+                todos.join(",")
+            )
+        })
+        .collect::<Vec<_>>();
+
+    outln!("[{}]", files.join(","));
+}
@@ -0,0 +1,121 @@
+use crate::{
+    listing, options, outln,
+    walk::{self, Entry, Visitor},
+    ExecutableCommand,
+};
+use std::{cmp::Ordering, error::Error, fs, io, path::Path};
+
+#[derive(Debug, Default)]
+struct Tree<'a> {
+    group_dirs_first: bool,
+    /// Indents levels without drawing branches, for accessible mode.
+    plain: bool,
+    /// The directory as given, shown for the root.
+    shown: &'a str,
+    /// What continues the branches of each directory being walked, the
+    /// innermost last.
+    prefixes: Vec<String>,
+    dirs: usize,
+    files: usize,
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let max_depth = command
+        .get_flag_value("--max-depth")
+        .map(|depth| {
+            depth
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid depth: {}", depth))
+        })
+        .transpose()?;
+    let argument = command.arguments.first().map_or(".", String::as_str);
+    let mut tree = Tree {
+        group_dirs_first: listing::group_dirs_first(command.has_flag("--group-dirs-first")),
+        plain: options::accessible(),
+        shown: argument,
+        ..Tree::default()
+    };
+
+    let root = command.context.resolve(argument);
+    let options = walk::Options {
+        max_depth,
+        include_git_dir: true,
+        ..walk::Options::default()
+    };
+    // Unreadable directories are shown in place, so the errors need no
+    // report of their own.
+    walk::visit(&root, options, &mut tree)
+        .map_err(|error| format!("{}: {}", root.display(), error))?;
+
+    outln!();
+    outln!(
+        "{} {}, {} {}",
+        tree.dirs,
+        if tree.dirs == 1 {
+            "directory"
+        } else {
+            "directories"
+        },
+        tree.files,
+        if tree.files == 1 { "file" } else { "files" }
+    );
+    Ok(())
+}
+
+impl Visitor for Tree<'_> {
+    fn entry(&mut self, entry: &Entry) {
+        if entry.depth == 0 {
+            outln!("{}", self.shown);
+            self.prefixes.push(String::new());
+            return;
+        }
+
+        let prefix = self.prefixes.last().cloned().unwrap_or_default();
+        let (branch, continuation) = self.branch(entry.is_last);
+        let mut name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+        // Without branches, a trailing `/` is what tells directories apart.
+        if self.plain && entry.is_dir() {
+            name.to_mut().push('/');
+        }
+        // Symlinks are shown with their target and never followed.
+        match fs::read_link(&entry.path) {
+            Ok(target) => outln!("{}{}{} -> {}", prefix, branch, name, target.display()),
+            Err(_) => outln!("{}{}{}", prefix, branch, name),
+        }
+
+        if entry.path.is_dir() {
+            self.dirs += 1;
+        } else {
+            self.files += 1;
+        }
+        if entry.is_dir() {
+            self.prefixes.push(format!("{}{}", prefix, continuation));
+        }
+    }
+
+    fn leave(&mut self, _dir: &Entry) {
+        self.prefixes.pop();
+    }
+
+    fn unreadable(&mut self, _dir: &Entry, error: &io::Error) {
+        let prefix = self.prefixes.last().cloned().unwrap_or_default();
+        let (branch, _) = self.branch(true);
+        outln!("{}{}[{}]", prefix, branch, error);
+    }
+
+    fn order(&self, a: &Path, b: &Path) -> Ordering {
+        listing::compare_entries(a, b, self.group_dirs_first)
+    }
+}
+
+impl Tree<'_> {
+    /// The branch drawn before an entry, and what continues it on the lines
+    /// of the entries below.
+    fn branch(&self, is_last: bool) -> (&'static str, &'static str) {
+        match (self.plain, is_last) {
+            (true, _) => ("", "  "),
+            (false, true) => ("└── ", "    "),
+            (false, false) => ("├── ", "│   "),
+        }
+    }
+}
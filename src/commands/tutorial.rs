@@ -0,0 +1,169 @@
+use crate::{
+    atomic, config, i18n::Message, out, outln, output, parse_command::CommandType, tr, Command,
+    ExecutableCommand, ParsedLine,
+};
+use std::{error::Error, fs, io, path::PathBuf};
+
+const PROGRESS_FILE_NAME: &str = "tutorial_progress";
+const QUIT_INPUT: &str = "quit";
+
+struct Step {
+    instruction: &'static str,
+    suggestion: &'static str,
+    is_expected: fn(&Command) -> bool,
+}
+
+const STEPS: &[Step] = &[
+    Step {
+        instruction:
+            "Commands are typed at the prompt and run with Enter. Start by printing some text.",
+        suggestion: "echo hello",
+        is_expected: |command| command.command_type == CommandType::Echo,
+    },
+    Step {
+        instruction: "Quotes keep words together. Print a single line containing a space.",
+        suggestion: "echo \"hello world\"",
+        is_expected: |command| {
+            command.command_type == CommandType::Echo
+                && command
+                    .arguments
+                    .iter()
+                    .any(|argument| argument.contains(' '))
+        },
+    },
+    Step {
+        instruction: "`help` lists every available command with a short summary.",
+        suggestion: "help",
+        is_expected: |command| {
+            command.command_type == CommandType::Help && command.arguments.is_empty()
+        },
+    },
+    Step {
+        instruction: "Pass a command name to `help` to see its usage, flags and examples.",
+        suggestion: "help ls",
+        is_expected: |command| {
+            command.command_type == CommandType::Help && command.arguments.len() == 1
+        },
+    },
+    Step {
+        instruction: "List the files in the current directory.",
+        suggestion: "ls",
+        is_expected: |command| command.command_type == CommandType::Ls,
+    },
+    Step {
+        instruction: "Flags start with `--` and some take a value. Ask `todos` for JSON output.",
+        suggestion: "todos --format json",
+        is_expected: |command| {
+            command.command_type == CommandType::Todos
+                && command.get_flag_value("--format") == Some("json")
+        },
+    },
+    Step {
+        instruction: "`man` shows the full manual page of a command.",
+        suggestion: "man echo",
+        is_expected: |command| command.command_type == CommandType::Man,
+    },
+    Step {
+        instruction: "`examples` runs an example invocation safely in a temporary directory.",
+        suggestion: "examples echo --run 1",
+        is_expected: |command| {
+            command.command_type == CommandType::Examples
+                && command.get_flag_value("--run").is_some()
+        },
+    },
+];
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    if command.has_flag("--reset") {
+        save_progress(0)?;
+        outln!("Tutorial progress reset");
+        return Ok(());
+    }
+
+    let mut step_index = load_progress()?;
+    if step_index >= STEPS.len() {
+        outln!("You already finished the tutorial. Use `tutorial --reset` to start over.");
+        return Ok(());
+    }
+
+    outln!(
+        "Welcome to the tutorial! Type `{}` at any time to stop, your progress is saved.",
+        QUIT_INPUT
+    );
+
+    while let Some(step) = STEPS.get(step_index) {
+        outln!();
+        outln!(
+            "Step {}/{}: {}",
+            step_index + 1,
+            STEPS.len(),
+            step.instruction
+        );
+        outln!("Try: {}", step.suggestion);
+
+        loop {
+            out!("tutorial> ");
+            output::flush()?;
+
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input)? == 0 || input.trim() == QUIT_INPUT {
+                outln!(
+                    "Stopped at step {}. Run `tutorial` to continue.",
+                    step_index + 1
+                );
+                return Ok(());
+            }
+
+            let attempt = match ParsedLine::try_from(input.trim()) {
+                Ok(ParsedLine::Command(attempt)) => attempt,
+                Ok(ParsedLine::Nothing) => continue,
+                Err(error) => {
+                    outln!("{}", error);
+                    continue;
+                }
+            };
+            if attempt.command_type == CommandType::Exit
+                || attempt.command_type == CommandType::Tutorial
+            {
+                outln!("Type `{}` to leave the tutorial.", QUIT_INPUT);
+                continue;
+            }
+
+            let is_expected = (step.is_expected)(&attempt);
+            if let Err(error) = attempt.bind(command.context).execute() {
+                outln!("{}", tr!(Message::CommandFailed, error));
+                continue;
+            }
+
+            if is_expected {
+                outln!("Well done!");
+                break;
+            }
+            outln!("Not quite, try `{}`.", step.suggestion);
+        }
+
+        step_index += 1;
+        save_progress(step_index)?;
+    }
+
+    outln!();
+    outln!("Tutorial complete! Run `help` whenever you need a reminder.");
+    Ok(())
+}
+
+fn progress_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(PROGRESS_FILE_NAME))
+}
+
+fn load_progress() -> Result<usize, Box<dyn Error>> {
+    match fs::read_to_string(progress_path()?) {
+        Ok(content) => Ok(content.trim().parse().unwrap_or(0)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn save_progress(step_index: usize) -> Result<(), Box<dyn Error>> {
+    atomic::write(progress_path()?, step_index.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::{options, outln, permissions, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    if !cfg!(unix) {
+        return Err("umask is only supported on Unix".into());
+    }
+
+    let current = permissions::current_umask();
+    let Some(mode) = command.arguments.first() else {
+        if command.has_flag("-S") {
+            outln!("{}", permissions::symbolic(!current & 0o777));
+        } else {
+            outln!("{}", permissions::octal(current));
+        }
+        return Ok(());
+    };
+
+    // Symbolic modes name the permissions to keep, like chmod does.
+    let mask = if mode.starts_with(|c: char| c.is_ascii_digit()) {
+        permissions::parse_octal(mode)?
+    } else {
+        !permissions::apply_symbolic(!current & 0o777, mode)? & 0o777
+    };
+    options::set(options::UMASK, &permissions::octal(mask))?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::{outln, ExecutableCommand};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let with_counts = command.has_flag("-c");
+    let only_duplicates = command.has_flag("-d");
+
+    let text = command.read_input()?;
+
+    // Each run of equal adjacent lines, with its length.
+    let mut runs: Vec<(&str, usize)> = Vec::new();
+    for line in text.lines() {
+        match runs.last_mut() {
+            Some((last, count)) if *last == line => *count += 1,
+            _ => runs.push((line, 1)),
+        }
+    }
+
+    for (line, count) in runs {
+        if only_duplicates && count < 2 {
+            continue;
+        }
+        if with_counts {
+            outln!("{:>7} {}", count, line);
+        } else {
+            outln!("{}", line);
+        }
+    }
+    Ok(())
+}
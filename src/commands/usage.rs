@@ -0,0 +1,44 @@
+use crate::{options, outln, status, usage, ExecutableCommand};
+use std::{cmp::Reverse, error::Error};
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    if command.has_flag("--reset") {
+        if options::dry_run() {
+            status!("would clear the usage data");
+            return Ok(());
+        }
+        usage::clear()?;
+        outln!("Usage data cleared");
+        return Ok(());
+    }
+
+    if !usage::is_enabled() {
+        outln!("Usage tracking is disabled (usage_tracking = false in the config file)");
+    }
+
+    let mut records = usage::load()?.into_iter().collect::<Vec<_>>();
+    if records.is_empty() {
+        outln!("No usage recorded yet");
+        return Ok(());
+    }
+    records.sort_by_key(|(_, record)| Reverse(record.runs));
+
+    outln!(
+        "{:<12} {:>8} {:>10} {:>10}",
+        "Command",
+        "Runs",
+        "Failures",
+        "Avg (ms)"
+    );
+    for (name, record) in records {
+        outln!(
+            "{:<12} {:>8} {:>9.1}% {:>10}",
+            name,
+            record.runs,
+            record.failure_rate() * 100.0,
+            record.average_duration_ms()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::{
+    i18n::Message,
+    options, outln, status, terminal, tr,
+    vault::{self, Vault},
+    ExecutableCommand,
+};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let action = command.arguments[0].as_str();
+    let name = command.arguments.get(1);
+
+    if action != "set" && !Vault::exists()? {
+        outln!("The vault is empty");
+        return Ok(());
+    }
+
+    match (action, name) {
+        ("set", Some(name)) => {
+            if !vault::is_valid_name(name) {
+                return Err(format!(
+                    "Invalid secret name: {} (use letters, digits, '_' and '-')",
+                    name
+                )
+                .into());
+            }
+            if options::dry_run() {
+                status!("would store {}", name);
+                return Ok(());
+            }
+            // Unlock (or create) the vault before asking for the value.
+            Vault::open()?;
+            let value = terminal::read_hidden(&tr!(Message::SecretValue, name))?;
+            Vault::update(|entries| entries.insert(name.to_owned(), value))?;
+            status!("Stored {}", name);
+        }
+        ("get", Some(name)) => {
+            let vault = Vault::open()?;
+            match vault.entries.get(name) {
+                Some(value) => outln!("{}", value),
+                None => return Err(format!("Unknown secret: {}", name).into()),
+            }
+        }
+        ("rm", Some(name)) if options::dry_run() => {
+            if !Vault::open()?.entries.contains_key(name) {
+                return Err(format!("Unknown secret: {}", name).into());
+            }
+            status!("would remove {}", name);
+        }
+        ("rm", Some(name)) => {
+            if Vault::update(|entries| entries.remove(name))?.is_none() {
+                return Err(format!("Unknown secret: {}", name).into());
+            }
+            status!("Removed {}", name);
+        }
+        ("list", None) => {
+            let vault = Vault::open()?;
+            if vault.entries.is_empty() {
+                outln!("The vault is empty");
+            }
+            for name in vault.entries.keys() {
+                outln!("{}", name);
+            }
+        }
+        _ => {
+            return Err(
+                "Usage: vault set <name> | vault get <name> | vault rm <name> | vault list".into(),
+            )
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::{outln, ExecutableCommand};
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufReader, Read},
+    ops::AddAssign,
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    lines: u64,
+    words: u64,
+    bytes: u64,
+}
+
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Self) {
+        self.lines += other.lines;
+        self.words += other.words;
+        self.bytes += other.bytes;
+    }
+}
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let mut show_lines = command.has_flag("-l");
+    let mut show_words = command.has_flag("-w");
+    let mut show_bytes = command.has_flag("-c");
+    // No flags means all three, as in other shells.
+    if !(show_lines || show_words || show_bytes) {
+        (show_lines, show_words, show_bytes) = (true, true, true);
+    }
+
+    let mut rows = Vec::new();
+    let mut total = Counts::default();
+    for argument in &command.arguments {
+        let path = command.context.resolve(argument);
+        let file = File::open(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let counts = count(file)?;
+        total += counts;
+        rows.push((counts, argument.as_str()));
+    }
+    if rows.len() > 1 {
+        rows.push((total, "total"));
+    }
+
+    // Every column is as wide as the largest number in the output.
+    let width = [total.lines, total.words, total.bytes]
+        .iter()
+        .map(|count| count.to_string().len())
+        .max()
+        .unwrap_or_default();
+    for (counts, name) in rows {
+        let mut columns = Vec::new();
+        if show_lines {
+            columns.push(format!("{:>width$}", counts.lines));
+        }
+        if show_words {
+            columns.push(format!("{:>width$}", counts.words));
+        }
+        if show_bytes {
+            columns.push(format!("{:>width$}", counts.bytes));
+        }
+        outln!("{} {}", columns.join(" "), name);
+    }
+    Ok(())
+}
+
+/// Counts newlines, whitespace separated words and bytes.
+fn count(file: File) -> std::io::Result<Counts> {
+    let mut reader = BufReader::new(file);
+    let mut counts = Counts::default();
+    let mut in_word = false;
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(counts);
+        }
+        counts.bytes += read as u64;
+        for byte in &buffer[..read] {
+            if *byte == b'\n' {
+                counts.lines += 1;
+            }
+            if byte.is_ascii_whitespace() {
+                in_word = false;
+            } else if !in_word {
+                in_word = true;
+                counts.words += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    docs, outln,
+    parse_command::{ArgumentCount, CommandType, FlagConstraint, END_OF_FLAGS},
+    status, terminal, Command, ExecutableCommand,
+};
+use std::error::Error;
+
+pub fn execute(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let command_type = CommandType::lookup(&command.arguments[0])?;
+    if command_type == CommandType::Wizard {
+        return Err("The wizard cannot build a wizard command".into());
+    }
+    let doc = docs::for_command(command_type);
+    outln!("{}: {}", command_type.name(), doc.summary);
+    outln!("Usage: {}", doc.usage);
+    outln!();
+
+    let flags = ask_flags(command_type)?;
+    let arguments = ask_arguments(command, command_type)?;
+
+    let mut words = vec![command_type.name().to_string()];
+    words.extend(flags);
+    // Arguments that look like flags go after `--`, so they stay arguments.
+    if arguments.iter().any(|argument| argument.starts_with('-')) {
+        words.push(END_OF_FLAGS.to_string());
+    }
+    words.extend(arguments);
+
+    // The same checks as a typed line, including the configured defaults.
+    let built = Command::from_words(&words)?;
+    let line = crate::parse_command::join_quoted(&words);
+    outln!();
+    outln!("Command: {}", line);
+    if !terminal::confirm("Run it?")? {
+        status!("Not run; the line is in the history");
+        command.context.add_history(&line);
+        return Ok(());
+    }
+    command.context.add_history(&line);
+    built.bind(command.context).execute()
+}
+
+/// Asks about each flag, in the order `help` lists them. Flags that only
+/// work with another one are asked about last, and only if it was chosen;
+/// flags that conflict with a chosen one are skipped.
+fn ask_flags(command_type: CommandType) -> Result<Vec<String>, Box<dyn Error>> {
+    let doc = docs::for_command(command_type);
+    let constraints = command_type.get_flag_constraints();
+    let required_by = |flag: &str| {
+        constraints.iter().find_map(|constraint| match constraint {
+            FlagConstraint::Requires(dependent, required) if *dependent == flag => Some(*required),
+            _ => None,
+        })
+    };
+    let mut order = command_type.canonical_flags();
+    order.sort_by_key(|flag| required_by(flag).is_some());
+
+    let mut flags: Vec<String> = Vec::new();
+    for flag in order {
+        if command_type.conflicts_with_any(flag, &flags) {
+            continue;
+        }
+        if let Some(required) = required_by(flag) {
+            if !flags.iter().any(|chosen| chosen == required) {
+                continue;
+            }
+        }
+        let description = doc.flag_description(flag).unwrap_or("no description");
+        if command_type.is_value_flag(flag) {
+            let value = terminal::ask(&format!(
+                "{} ({}), value or empty to leave out: ",
+                flag, description
+            ))?;
+            if !value.is_empty() {
+                flags.push(flag.to_string());
+                flags.push(value);
+            }
+        } else if terminal::confirm(&format!("{} ({})?", flag, description))? {
+            flags.push(flag.to_string());
+        }
+    }
+    Ok(flags)
+}
+
+/// Asks for the arguments one at a time, checking each against the type the
+/// command declares for it, until the count allows no more or an optional
+/// one is left empty.
+fn ask_arguments(
+    command: &ExecutableCommand,
+    command_type: CommandType,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let (min, max) = match command_type.get_expected_argument_count() {
+        Some(ArgumentCount::Exact(count)) => (count, Some(count)),
+        Some(ArgumentCount::AtLeast(min)) => (min, None),
+        Some(ArgumentCount::AtMost(max)) => (0, Some(max)),
+        Some(ArgumentCount::Range(min, max)) => (min, Some(max)),
+        None => (0, None),
+    };
+
+    let mut arguments = Vec::new();
+    while max.is_none_or(|max| arguments.len() < max) {
+        let index = arguments.len();
+        let expected = command_type.argument_type(index);
+        let optional = if index >= min {
+            ", empty to finish"
+        } else {
+            ""
+        };
+        let argument = terminal::ask(&format!(
+            "Argument {} ({}{}): ",
+            index + 1,
+            expected.description(),
+            optional
+        ))?;
+        if argument.is_empty() {
+            if index >= min {
+                break;
+            }
+            outln!("This argument is required");
+            continue;
+        }
+        if !expected.accepts(&argument, &command.context.resolve(&argument)) {
+            outln!("Argument {} must be {}", index + 1, expected.description());
+            continue;
+        }
+        arguments.push(argument);
+    }
+    Ok(arguments)
+}
@@ -0,0 +1,141 @@
+use super::tar;
+use crate::{
+    atomic::AtomicFile,
+    options, outln, status,
+    tar::EntryKind,
+    timezone::TimeZone,
+    verbose, walk,
+    zip::{Reader, Writer},
+    ExecutableCommand,
+};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+/// `zip <archive.zip> <path>...` packs files and directories into a new
+/// archive, replacing it once it is complete.
+pub fn zip(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let (archive, paths) = command
+        .arguments
+        .split_first()
+        .ok_or("Missing archive path")?;
+    let archive = command.context.resolve(archive);
+    let file = if options::dry_run() {
+        None
+    } else {
+        Some(AtomicFile::create(&archive)?)
+    };
+    let mut skipped = vec![archive.clone()];
+    skipped.extend(file.as_ref().map(|file| file.temp_path().to_path_buf()));
+    let (members, mut errors) = tar::collect(command, paths, &skipped)?;
+
+    let mut writer = file.map(|file| Writer::new(BufWriter::new(file)));
+    let count = tar::write_members(&members, &mut errors, |header, file| match &mut writer {
+        Some(writer) => match file {
+            Some(file) => writer.append(header, file),
+            None => writer.append(header, io::empty()),
+        },
+        None => Ok(()),
+    })?;
+
+    // As with tar, an incomplete archive never replaces the old one.
+    walk::report_errors(&errors)?;
+    match writer {
+        Some(writer) => {
+            let file = writer.finish()?.into_inner().map_err(|e| e.into_error())?;
+            file.commit()?;
+            status!("Archived {} entries in {}", count, archive.display());
+        }
+        None => status!("Would archive {} entries in {}", count, archive.display()),
+    }
+    Ok(())
+}
+
+/// `unzip <archive.zip> [<dir>]` extracts an archive; with `-l` it lists
+/// what the archive holds instead.
+pub fn unzip(command: &ExecutableCommand) -> Result<(), Box<dyn Error>> {
+    let archive = command.context.resolve(&command.arguments[0]);
+    let file = File::open(&archive).map_err(|error| format!("{}: {}", archive.display(), error))?;
+    let mut reader = Reader::new(BufReader::new(file))
+        .map_err(|error| format!("{}: {}", archive.display(), error))?;
+
+    if command.has_flag("-l") {
+        if command.arguments.len() > 1 {
+            return Err("Usage: unzip -l <archive.zip>".into());
+        }
+        list(&reader);
+        return Ok(());
+    }
+    let target = match command.arguments.get(1) {
+        Some(dir) => command.context.resolve(dir),
+        None => command.context.cwd().to_path_buf(),
+    };
+    extract(&mut reader, &target)
+}
+
+fn list(reader: &Reader<BufReader<File>>) {
+    let zone = TimeZone::local();
+    outln!("{:>10}  {:<19}  Name", "Length", "Modified");
+    let mut total = 0;
+    for entry in reader.entries() {
+        let header = &entry.header;
+        let modified = zone.local_time(header.modified as i64).date_time;
+        let name = match &header.kind {
+            EntryKind::Symlink(target) => format!("{} -> {}", header.path, target),
+            _ => header.path.clone(),
+        };
+        outln!("{:>10}  {}  {}", header.size, modified, name);
+        total += header.size;
+    }
+    let count = reader.entries().len();
+    outln!(
+        "{:>10}  {:<19}  {} {}",
+        total,
+        "",
+        count,
+        if count == 1 { "entry" } else { "entries" }
+    );
+}
+
+fn extract(reader: &mut Reader<BufReader<File>>, target: &Path) -> Result<(), Box<dyn Error>> {
+    let dry_run = options::dry_run();
+    if !dry_run {
+        fs::create_dir_all(target)?;
+    }
+
+    let (mut count, mut errors): (usize, Vec<walk::Error>) = (0, Vec::new());
+    for entry in reader.entries().to_vec() {
+        let header = &entry.header;
+        let Some(path) = tar::safe_path(target, &header.path) else {
+            errors.push((
+                PathBuf::from(&header.path),
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "outside the directory being extracted to",
+                ),
+            ));
+            continue;
+        };
+        verbose!("{}", header.path);
+        count += 1;
+        if dry_run {
+            continue;
+        }
+        if let Err(error) =
+            tar::extract_entry(header, target, &path, |file| reader.copy_data(&entry, file))
+        {
+            errors.push((path, error));
+        }
+    }
+
+    walk::report_errors(&errors)?;
+    if dry_run {
+        status!("Would extract {} entries to {}", count, target.display());
+    } else {
+        status!("Extracted {} entries to {}", count, target.display());
+    }
+    Ok(())
+}
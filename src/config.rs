@@ -0,0 +1,97 @@
+use crate::{log, options, parse_command::CommandType};
+use std::{
+    env, io,
+    path::PathBuf,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+const APP_DIR_NAME: &str = "my_basic_cli_tools";
+const CONFIG_FILE_NAME: &str = "config";
+
+/// Settings named `defaults.<command>` hold flags added to every run of the
+/// command, such as `defaults.ls = -a --group-dirs-first`.
+pub const DEFAULT_FLAGS_PREFIX: &str = "defaults.";
+
+/// Settings from the config file, read on first use.
+static SETTINGS: OnceLock<Vec<(String, String)>> = OnceLock::new();
+/// Where the config directory is instead, while examples run in a sandbox.
+static DIR_OVERRIDE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Returns the per-user configuration directory, creating it if needed.
+pub fn config_dir() -> io::Result<PathBuf> {
+    let overridden = DIR_OVERRIDE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(dir) = overridden {
+        std::fs::create_dir_all(&dir)?;
+        return Ok(dir);
+    }
+
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "Could not locate a config directory",
+            )
+        })?;
+
+    let dir = base.join(APP_DIR_NAME);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Makes `config_dir` return `dir` instead, or the usual directory again
+/// for `None`, and returns what it returned before. Settings already read
+/// from the config file are kept.
+pub fn override_config_dir(dir: Option<PathBuf>) -> Option<PathBuf> {
+    let mut overridden = DIR_OVERRIDE.lock().unwrap_or_else(PoisonError::into_inner);
+    std::mem::replace(&mut *overridden, dir)
+}
+
+/// Reads `key` from the `config` file in the config directory. The file holds
+/// one `key = value` pair per line; lines starting with `#` are ignored. It is
+/// read once, the first time any setting is needed.
+pub fn get(key: &str) -> Option<String> {
+    let value = settings()
+        .iter()
+        .rev()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.clone());
+    log!(Config, Trace, "{} = {:?}", key, value);
+    value
+}
+
+/// Loads the config file if it was not loaded yet.
+pub fn settings() -> &'static [(String, String)] {
+    SETTINGS.get_or_init(|| {
+        let Some(content) = config_dir()
+            .ok()
+            .and_then(|dir| std::fs::read_to_string(dir.join(CONFIG_FILE_NAME)).ok())
+        else {
+            log!(Config, Info, "no config file, using defaults");
+            return Vec::new();
+        };
+
+        let settings = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect::<Vec<_>>();
+        log!(Config, Info, "loaded {} settings", settings.len());
+        for (name, _) in &settings {
+            let is_default_flags = name
+                .strip_prefix(DEFAULT_FLAGS_PREFIX)
+                .is_some_and(|command| CommandType::try_from(command).is_ok());
+            if options::find(name).is_none() && !is_default_flags {
+                log!(Config, Warn, "unknown setting {}", name);
+            }
+        }
+        settings
+    })
+}
@@ -0,0 +1,364 @@
+use crate::output::Captured;
+use std::{
+    borrow::Cow,
+    env,
+    ffi::OsString,
+    fs, io, mem,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
+};
+
+/// Environment variable holding the output of the previous command.
+pub const LAST_OUTPUT_VARIABLE: &str = "LAST_OUTPUT";
+
+/// Runtime state commands execute against. Kept apart from the parsed
+/// [`Command`](crate::Command), so parsing and validation stay pure and
+/// commands can be run against a context set up by a test.
+#[derive(Debug)]
+pub struct ShellContext {
+    /// State that builtins such as `cd` change for the commands after them.
+    state: RwLock<ShellState>,
+    pub cancellation: CancellationToken,
+}
+
+#[derive(Debug)]
+struct ShellState {
+    /// Directory that relative paths are resolved against.
+    cwd: PathBuf,
+    /// Where `cd -` goes back to.
+    previous_dir: Option<PathBuf>,
+    /// Directories saved by `pushd`, the most recent first. The working
+    /// directory is not part of it.
+    dir_stack: Vec<PathBuf>,
+    /// Lines entered this session, the oldest first.
+    history: Vec<String>,
+    /// Environment layers in effect, the outermost directory first.
+    env_layers: Vec<ActiveEnvLayer>,
+    /// What the previous command printed, for `lastout`.
+    last_output: Captured,
+}
+
+/// Variables and aliases that apply while the working directory is inside
+/// `dir`.
+#[derive(Debug, Clone, Default)]
+pub struct EnvLayer {
+    pub dir: PathBuf,
+    pub variables: Vec<(String, String)>,
+    pub aliases: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+struct ActiveEnvLayer {
+    layer: EnvLayer,
+    /// Values the layer's variables had before it, to put back on leaving.
+    saved: Vec<(String, Option<OsString>)>,
+}
+
+impl ShellContext {
+    /// A context for the current process and working directory.
+    pub fn new() -> io::Result<Self> {
+        Ok(Self::with_cwd(logical_current_dir()?))
+    }
+
+    pub fn with_cwd(cwd: impl Into<PathBuf>) -> Self {
+        Self {
+            state: RwLock::new(ShellState {
+                cwd: cwd.into(),
+                previous_dir: None,
+                dir_stack: Vec::new(),
+                history: Vec::new(),
+                env_layers: Vec::new(),
+                last_output: Captured::default(),
+            }),
+            cancellation: CancellationToken::default(),
+        }
+    }
+
+    pub fn cwd(&self) -> PathBuf {
+        self.state().cwd.clone()
+    }
+
+    pub fn previous_dir(&self) -> Option<PathBuf> {
+        self.state().previous_dir.clone()
+    }
+
+    pub fn dir_stack(&self) -> Vec<PathBuf> {
+        self.state().dir_stack.clone()
+    }
+
+    pub fn set_dir_stack(&self, dir_stack: Vec<PathBuf>) {
+        self.state_mut().dir_stack = dir_stack;
+    }
+
+    pub fn history(&self) -> Vec<String> {
+        self.state().history.clone()
+    }
+
+    pub fn add_history(&self, line: &str) {
+        self.state_mut().history.push(line.to_string());
+    }
+
+    pub fn last_output(&self) -> Captured {
+        self.state().last_output.clone()
+    }
+
+    /// Keeps the output of a command for `lastout` and for programs the
+    /// shell starts, see [`ShellContext::export_last_output`].
+    pub fn set_last_output(&self, output: Captured) {
+        self.state_mut().last_output = output;
+    }
+
+    /// Passes the output of the previous command to a program about to be
+    /// started, as `LAST_OUTPUT`. Only that program gets it, not the shell's
+    /// own environment.
+    pub fn export_last_output(&self, command: &mut process::Command) {
+        let text = String::from_utf8_lossy(&self.state().last_output.bytes).replace('\0', "");
+        command.env(LAST_OUTPUT_VARIABLE, text);
+    }
+
+    /// Changes the working directory, remembering the old one for `cd -`.
+    /// The process directory follows along, for commands that take paths
+    /// as given and for the programs they start.
+    pub fn set_cwd(&self, cwd: PathBuf) -> io::Result<()> {
+        env::set_current_dir(&cwd)?;
+        let mut state = self.state_mut();
+        let previous = mem::replace(&mut state.cwd, cwd);
+        state.previous_dir = Some(previous);
+        Ok(())
+    }
+
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.state().cwd.join(path)
+    }
+
+    /// Applies a layer's variables on top of the current environment.
+    pub fn push_env_layer(&self, layer: EnvLayer) {
+        let saved = layer
+            .variables
+            .iter()
+            .map(|(name, value)| {
+                let previous = env::var_os(name);
+                env::set_var(name, value);
+                (name.clone(), previous)
+            })
+            .collect();
+        self.state_mut()
+            .env_layers
+            .push(ActiveEnvLayer { layer, saved });
+    }
+
+    /// Reverts the layers of directories that do not contain `dir`,
+    /// returning those directories.
+    pub fn pop_env_layers_outside(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut state = self.state_mut();
+        let mut left = Vec::new();
+        // Layers are nested, so the innermost ones are left first.
+        while let Some(active) = state
+            .env_layers
+            .pop_if(|active| !dir.starts_with(&active.layer.dir))
+        {
+            for (name, value) in active.saved.into_iter().rev() {
+                match value {
+                    Some(value) => env::set_var(name, value),
+                    None => env::remove_var(name),
+                }
+            }
+            left.push(active.layer.dir);
+        }
+        left
+    }
+
+    /// Directories whose environment layers are in effect.
+    pub fn env_layer_dirs(&self) -> Vec<PathBuf> {
+        self.state()
+            .env_layers
+            .iter()
+            .map(|active| active.layer.dir.clone())
+            .collect()
+    }
+
+    /// Replaces the first word of `line` if it is an alias. The innermost
+    /// layer wins, and the result is not expanded again.
+    pub fn expand_alias<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let state = self.state();
+        let alias = state
+            .env_layers
+            .iter()
+            .rev()
+            .flat_map(|active| &active.layer.aliases)
+            .find(|(alias, _)| alias == name);
+        match alias {
+            Some((_, value)) if rest.is_empty() => Cow::Owned(value.clone()),
+            Some((_, value)) => Cow::Owned(format!("{} {}", value, rest)),
+            None => Cow::Borrowed(line),
+        }
+    }
+
+    fn state(&self) -> RwLockReadGuard<'_, ShellState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn state_mut(&self) -> RwLockWriteGuard<'_, ShellState> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The working directory as the parent shell spelled it in `$PWD`, keeping
+/// any symlinks along the way, or the physical directory if `$PWD` is stale.
+fn logical_current_dir() -> io::Result<PathBuf> {
+    let physical = env::current_dir()?;
+    let logical = env::var_os("PWD").map(PathBuf::from).filter(|pwd| {
+        pwd.is_absolute() && fs::canonicalize(pwd).ok() == fs::canonicalize(&physical).ok()
+    });
+    Ok(logical.unwrap_or(physical))
+}
+
+/// Shared flag that long-running commands poll to stop early.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Makes Ctrl-C cancel this token while a command runs, see
+    /// [`CancellationToken::interruptible`], instead of ending the shell.
+    /// Only the first token registered this way is cancelled.
+    pub fn cancel_on_interrupt(&self) -> io::Result<()> {
+        if INTERRUPT_TARGET.set(self.clone()).is_err() {
+            return Ok(());
+        }
+        interrupt::install()
+    }
+
+    /// Lets Ctrl-C cancel the token until the guard is dropped, starting
+    /// from a token that is not cancelled. Ctrl-C pressed again after that
+    /// ends the process, for commands that never check the token.
+    pub fn interruptible(&self) -> Interruptible<'_> {
+        self.0.store(false, Ordering::Relaxed);
+        INTERRUPT_ARMED.store(true, Ordering::SeqCst);
+        Interruptible { token: self }
+    }
+}
+
+/// Returned by [`CancellationToken::interruptible`]; Ctrl-C is ignored again,
+/// and the token reset, once it is dropped.
+pub struct Interruptible<'a> {
+    token: &'a CancellationToken,
+}
+
+impl Drop for Interruptible<'_> {
+    fn drop(&mut self) {
+        INTERRUPT_ARMED.store(false, Ordering::SeqCst);
+        self.token.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// The token Ctrl-C cancels. Read by the signal handler, so it is set once
+/// and never locked.
+static INTERRUPT_TARGET: OnceLock<CancellationToken> = OnceLock::new();
+
+/// Whether a command is running. At the prompt Ctrl-C only clears the line,
+/// as the terminal does on its own.
+static INTERRUPT_ARMED: AtomicBool = AtomicBool::new(false);
+
+/// Called from the signal handler, so it only touches atomics.
+fn on_interrupt() {
+    if !INTERRUPT_ARMED.load(Ordering::SeqCst) {
+        return;
+    }
+    if let Some(token) = INTERRUPT_TARGET.get() {
+        if token.is_cancelled() {
+            interrupt::exit();
+        }
+        token.cancel();
+    }
+}
+
+#[cfg(unix)]
+mod interrupt {
+    use std::{ffi::c_int, io};
+
+    const SIGINT: c_int = 2;
+    /// What `signal` returns on failure, `SIG_ERR`.
+    const SIG_ERR: usize = usize::MAX;
+
+    extern "C" {
+        fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+        fn _exit(status: c_int) -> !;
+    }
+
+    extern "C" fn handle(_signum: c_int) {
+        super::on_interrupt();
+    }
+
+    pub fn install() -> io::Result<()> {
+        // SAFETY: the handler only touches atomics, which is signal safe.
+        if unsafe { signal(SIGINT, handle) } == SIG_ERR {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Ends the process with the status shells report for Ctrl-C.
+    pub fn exit() -> ! {
+        // SAFETY: _exit may be called from a signal handler, unlike exit.
+        unsafe { _exit(130) }
+    }
+}
+
+#[cfg(windows)]
+mod interrupt {
+    use std::io;
+
+    const CTRL_C_EVENT: u32 = 0;
+
+    extern "system" {
+        fn SetConsoleCtrlHandler(handler: extern "system" fn(u32) -> i32, add: i32) -> i32;
+    }
+
+    /// Runs on a thread of its own; returning nonzero keeps the process.
+    extern "system" fn handle(event: u32) -> i32 {
+        if event != CTRL_C_EVENT {
+            return 0;
+        }
+        super::on_interrupt();
+        1
+    }
+
+    pub fn install() -> io::Result<()> {
+        // SAFETY: the handler is a plain function that lives forever.
+        if unsafe { SetConsoleCtrlHandler(handle, 1) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub fn exit() -> ! {
+        std::process::exit(130)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod interrupt {
+    use std::io;
+
+    /// Without signals Ctrl-C keeps ending the process.
+    pub fn install() -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn exit() -> ! {
+        std::process::exit(130)
+    }
+}
@@ -0,0 +1,317 @@
+use crate::Command;
+use std::{
+    error::Error,
+    fs::{self, File, FileTimes},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+const BLOCK_SIZE: usize = 4096;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Reflink {
+    /// Copy the data.
+    #[default]
+    Never,
+    /// Clone the file when the filesystem supports it, copy otherwise.
+    Auto,
+}
+
+impl TryFrom<&str> for Reflink {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "never" => Ok(Reflink::Never),
+            "auto" => Ok(Reflink::Auto),
+            _ => Err(format!("Unknown reflink mode: {} (expected auto or never)", value).into()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Sparse {
+    /// Write every byte, filling holes with zeros.
+    Never,
+    /// Keep the holes of sparse source files.
+    #[default]
+    Auto,
+    /// Also turn runs of zero blocks into holes.
+    Always,
+}
+
+impl TryFrom<&str> for Sparse {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "never" => Ok(Sparse::Never),
+            "auto" => Ok(Sparse::Auto),
+            "always" => Ok(Sparse::Always),
+            _ => Err(format!(
+                "Unknown sparse mode: {} (expected never, auto or always)",
+                value
+            )
+            .into()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Keep timestamps and permissions, and ownership when privileged.
+    pub preserve: bool,
+    pub reflink: Reflink,
+    pub sparse: Sparse,
+}
+
+impl Options {
+    /// Reads `--preserve`, `--reflink` and `--sparse`, for the commands
+    /// that copy files.
+    pub fn from_command(command: &Command) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            preserve: command.has_flag("--preserve"),
+            reflink: command
+                .get_flag_value("--reflink")
+                .map(Reflink::try_from)
+                .transpose()?
+                .unwrap_or_default(),
+            sparse: command
+                .get_flag_value("--sparse")
+                .map(Sparse::try_from)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Copies the file `from` to `to`, returning the number of bytes copied.
+pub fn copy_file(from: &Path, to: &Path, options: Options) -> io::Result<u64> {
+    let copied = if options.reflink == Reflink::Auto && platform::clone_file(from, to).is_ok() {
+        fs::metadata(to)?.len()
+    } else if options.sparse == Sparse::Always
+        || (options.sparse == Sparse::Auto && is_sparse(&fs::metadata(from)?))
+    {
+        copy_sparse(from, to, options.sparse)?
+    } else {
+        fs::copy(from, to)?
+    };
+
+    if options.preserve {
+        preserve_metadata(from, to)?;
+    }
+
+    Ok(copied)
+}
+
+/// Whether the file occupies fewer blocks on disk than its length needs.
+#[cfg(unix)]
+fn is_sparse(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.blocks() * 512 < metadata.len()
+}
+
+#[cfg(not(unix))]
+fn is_sparse(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Copies only the data of `from`, leaving holes in `to` where the source has
+/// holes or, with [`Sparse::Always`], where whole blocks are zero.
+fn copy_sparse(from: &Path, to: &Path, mode: Sparse) -> io::Result<u64> {
+    let mut source = File::open(from)?;
+    let metadata = source.metadata()?;
+    let mut target = File::create(to)?;
+    target.set_permissions(metadata.permissions())?;
+
+    let ranges = match mode {
+        Sparse::Always => None,
+        _ => holes::data_ranges(&source, metadata.len()).ok(),
+    };
+    // Data the filesystem reports is copied as it is, zeros included.
+    let skip_zeros = ranges.is_none();
+    let ranges = ranges.unwrap_or_else(|| vec![(0, metadata.len())]);
+
+    let mut block = [0; BLOCK_SIZE];
+    for (start, end) in ranges {
+        source.seek(SeekFrom::Start(start))?;
+        let mut offset = start;
+        while offset < end {
+            let length = (end - offset).min(BLOCK_SIZE as u64) as usize;
+            source.read_exact(&mut block[..length])?;
+            // Without hole information, zero blocks are the holes.
+            if !skip_zeros || !block[..length].iter().all(|&b| b == 0) {
+                target.seek(SeekFrom::Start(offset))?;
+                target.write_all(&block[..length])?;
+            }
+            offset += length as u64;
+        }
+    }
+
+    // Extends the file over a trailing hole.
+    target.set_len(metadata.len())?;
+    Ok(metadata.len())
+}
+
+/// Gives `to` the times and permissions of `from`, and its owner when
+/// privileged.
+pub fn preserve_metadata(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = fs::metadata(from)?;
+
+    // Setting times takes owning the file rather than write access, so
+    // read-only copies and directories can be opened for it as they are.
+    #[cfg(unix)]
+    let file = File::open(to)?;
+    #[cfg(not(unix))]
+    let file = File::options().write(true).open(to)?;
+    file.set_times(
+        FileTimes::new()
+            .set_accessed(metadata.accessed()?)
+            .set_modified(metadata.modified()?),
+    )?;
+
+    // Changing the owner clears the setuid and setgid bits, so it comes
+    // before the permissions are set.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        // Only root may give files away, so losing ownership is not an error.
+        match std::os::unix::fs::chown(to, Some(metadata.uid()), Some(metadata.gid())) {
+            Err(error) if error.kind() != io::ErrorKind::PermissionDenied => return Err(error),
+            _ => {}
+        }
+    }
+    fs::set_permissions(to, metadata.permissions())?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::{
+        ffi::{c_int, c_ulong},
+        fs::{self, File},
+        io,
+        os::fd::AsRawFd,
+        path::Path,
+    };
+
+    // _IOW(0x94, 9, int)
+    const FICLONE: c_ulong = 0x40049409;
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    /// Shares the data blocks of `from` with a new file `to` (btrfs, XFS, ...).
+    pub fn clone_file(from: &Path, to: &Path) -> io::Result<()> {
+        let source = File::open(from)?;
+        let target = File::create(to)?;
+
+        // SAFETY: both descriptors are open for the duration of the call.
+        if unsafe { ioctl(target.as_raw_fd(), FICLONE, source.as_raw_fd()) } != 0 {
+            let error = io::Error::last_os_error();
+            drop(target);
+            let _ = fs::remove_file(to);
+            return Err(error);
+        }
+
+        target.set_permissions(source.metadata()?.permissions())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::{
+        ffi::{c_char, c_int, CString},
+        io,
+        os::unix::ffi::OsStrExt,
+        path::Path,
+    };
+
+    extern "C" {
+        fn clonefile(src: *const c_char, dst: *const c_char, flags: c_int) -> c_int;
+    }
+
+    pub fn clone_file(from: &Path, to: &Path) -> io::Result<()> {
+        let from = CString::new(from.as_os_str().as_bytes())?;
+        let to_path = CString::new(to.as_os_str().as_bytes())?;
+
+        // clonefile refuses to replace an existing file.
+        if to.exists() {
+            std::fs::remove_file(to)?;
+        }
+        // SAFETY: both pointers are valid NUL-terminated strings.
+        if unsafe { clonefile(from.as_ptr(), to_path.as_ptr(), 0) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use std::{io, path::Path};
+
+    pub fn clone_file(_from: &Path, _to: &Path) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod holes {
+    use std::{ffi::c_int, fs::File, io, os::fd::AsRawFd};
+
+    #[cfg(target_os = "linux")]
+    const SEEK_DATA: c_int = 3;
+    #[cfg(target_os = "linux")]
+    const SEEK_HOLE: c_int = 4;
+    #[cfg(target_os = "macos")]
+    const SEEK_HOLE: c_int = 3;
+    #[cfg(target_os = "macos")]
+    const SEEK_DATA: c_int = 4;
+    const ENXIO: i32 = 6;
+
+    extern "C" {
+        fn lseek(fd: c_int, offset: i64, whence: c_int) -> i64;
+    }
+
+    /// Lists the `(start, end)` data ranges of `file` with SEEK_DATA/SEEK_HOLE.
+    pub fn data_ranges(file: &File, length: u64) -> io::Result<Vec<(u64, u64)>> {
+        let fd = file.as_raw_fd();
+        let mut ranges = Vec::new();
+        let mut offset = 0;
+
+        while offset < length {
+            // SAFETY: `fd` stays open while `file` is borrowed.
+            let start = unsafe { lseek(fd, offset as i64, SEEK_DATA) };
+            if start < 0 {
+                let error = io::Error::last_os_error();
+                // No data after `offset`: the rest is a hole.
+                if error.raw_os_error() == Some(ENXIO) {
+                    break;
+                }
+                return Err(error);
+            }
+            let end = unsafe { lseek(fd, start, SEEK_HOLE) };
+            if end < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            ranges.push((start as u64, (end as u64).min(length)));
+            offset = end as u64;
+        }
+
+        Ok(ranges)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod holes {
+    use std::{fs::File, io};
+
+    pub fn data_ranges(_file: &File, _length: u64) -> io::Result<Vec<(u64, u64)>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
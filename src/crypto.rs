@@ -0,0 +1,281 @@
+use crate::hash::Sha256;
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::File,
+    io::{self, Read},
+};
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 12;
+pub const TAG_SIZE: usize = 16;
+
+const SHA256_BLOCK_SIZE: usize = 64;
+const RANDOM_SOURCE: &str = "/dev/urandom";
+
+#[derive(Debug)]
+pub struct AuthenticationError;
+
+impl Error for AuthenticationError {}
+
+impl Display for AuthenticationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Authentication failed: wrong passphrase or corrupted data"
+        )
+    }
+}
+
+pub fn random_bytes<const N: usize>() -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    File::open(RANDOM_SOURCE)?.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Encrypts `plaintext` with ChaCha20-Poly1305 (RFC 8439), returning the
+/// ciphertext followed by the authentication tag.
+pub fn seal(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Vec<u8> {
+    let mut output = plaintext.to_vec();
+    chacha20_xor(key, nonce, 1, &mut output);
+
+    let tag = poly1305_aead_tag(key, nonce, aad, &output);
+    output.extend_from_slice(&tag);
+    output
+}
+
+/// Verifies and decrypts data produced by [`seal`].
+pub fn open(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    aad: &[u8],
+    sealed: &[u8],
+) -> Result<Vec<u8>, AuthenticationError> {
+    let split = sealed
+        .len()
+        .checked_sub(TAG_SIZE)
+        .ok_or(AuthenticationError)?;
+    let (ciphertext, tag) = sealed.split_at(split);
+
+    let expected = poly1305_aead_tag(key, nonce, aad, ciphertext);
+    // Compare in constant time so the tag cannot be guessed byte by byte.
+    if expected
+        .iter()
+        .zip(tag)
+        .fold(0, |acc, (a, b)| acc | (a ^ b))
+        != 0
+    {
+        return Err(AuthenticationError);
+    }
+
+    let mut output = ciphertext.to_vec();
+    chacha20_xor(key, nonce, 1, &mut output);
+    Ok(output)
+}
+
+/// Derives a key from a passphrase with PBKDF2-HMAC-SHA256.
+pub fn derive_key(passphrase: &[u8], salt: &[u8], iterations: u32) -> [u8; KEY_SIZE] {
+    let mut first_input = salt.to_vec();
+    first_input.extend_from_slice(&1u32.to_be_bytes());
+
+    let mut block = hmac_sha256(passphrase, &first_input);
+    let mut key = block;
+    for _ in 1..iterations {
+        block = hmac_sha256(passphrase, &block);
+        for (k, b) in key.iter_mut().zip(block) {
+            *k ^= b;
+        }
+    }
+
+    key
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut padded_key = [0; SHA256_BLOCK_SIZE];
+    if key.len() > SHA256_BLOCK_SIZE {
+        let mut hasher = Sha256::new();
+        hasher.update(key);
+        padded_key[..32].copy_from_slice(&hasher.finalize());
+    } else {
+        padded_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(&padded_key.map(|b| b ^ 0x36));
+    inner.update(message);
+
+    let mut outer = Sha256::new();
+    outer.update(&padded_key.map(|b| b ^ 0x5c));
+    outer.update(&inner.finalize());
+    outer.finalize()
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn chacha20_block(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], counter: u32) -> [u8; 64] {
+    let mut initial = [0u32; 16];
+    initial[..4].copy_from_slice(&[0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]);
+    for (word, bytes) in initial[4..12].iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    initial[12] = counter;
+    for (word, bytes) in initial[13..].iter_mut().zip(nonce.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let mut state = initial;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut output = [0; 64];
+    for (i, bytes) in output.chunks_exact_mut(4).enumerate() {
+        bytes.copy_from_slice(&state[i].wrapping_add(initial[i]).to_le_bytes());
+    }
+    output
+}
+
+fn chacha20_xor(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], counter: u32, data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(64).enumerate() {
+        let keystream = chacha20_block(key, nonce, counter.wrapping_add(i as u32));
+        for (byte, key_byte) in chunk.iter_mut().zip(keystream) {
+            *byte ^= key_byte;
+        }
+    }
+}
+
+fn poly1305_aead_tag(
+    key: &[u8; KEY_SIZE],
+    nonce: &[u8; NONCE_SIZE],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> [u8; TAG_SIZE] {
+    let one_time_key = chacha20_block(key, nonce, 0);
+
+    let mut message = Vec::with_capacity(aad.len() + ciphertext.len() + 48);
+    for part in [aad, ciphertext] {
+        message.extend_from_slice(part);
+        message.resize(message.len().next_multiple_of(16), 0);
+    }
+    message.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+    message.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+    poly1305(one_time_key[..32].try_into().unwrap(), &message)
+}
+
+/// Poly1305 one-time authenticator using 26-bit limbs.
+pub fn poly1305(key: &[u8; 32], message: &[u8]) -> [u8; TAG_SIZE] {
+    let read = |bytes: &[u8], offset: usize| {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    };
+
+    let r0 = read(key, 0) & 0x3ffffff;
+    let r1 = (read(key, 3) >> 2) & 0x3ffff03;
+    let r2 = (read(key, 6) >> 4) & 0x3ffc0ff;
+    let r3 = (read(key, 9) >> 6) & 0x3f03fff;
+    let r4 = (read(key, 12) >> 8) & 0x00fffff;
+    let [s1, s2, s3, s4] = [r1 * 5, r2 * 5, r3 * 5, r4 * 5];
+
+    let mut h = [0u32; 5];
+
+    for chunk in message.chunks(16) {
+        let mut block = [0u8; 17];
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()] = 1;
+
+        h[0] += read(&block, 0) & 0x3ffffff;
+        h[1] += (read(&block, 3) >> 2) & 0x3ffffff;
+        h[2] += (read(&block, 6) >> 4) & 0x3ffffff;
+        h[3] += (read(&block, 9) >> 6) & 0x3ffffff;
+        h[4] += (read(&block, 12) >> 8) | ((block[16] as u32) << 24);
+
+        let m = |a: u32, b: u32| a as u64 * b as u64;
+        let d0 = m(h[0], r0) + m(h[1], s4) + m(h[2], s3) + m(h[3], s2) + m(h[4], s1);
+        let mut d1 = m(h[0], r1) + m(h[1], r0) + m(h[2], s4) + m(h[3], s3) + m(h[4], s2);
+        let mut d2 = m(h[0], r2) + m(h[1], r1) + m(h[2], r0) + m(h[3], s4) + m(h[4], s3);
+        let mut d3 = m(h[0], r3) + m(h[1], r2) + m(h[2], r1) + m(h[3], r0) + m(h[4], s4);
+        let mut d4 = m(h[0], r4) + m(h[1], r3) + m(h[2], r2) + m(h[3], r1) + m(h[4], r0);
+
+        let mut carry = d0 >> 26;
+        h[0] = d0 as u32 & 0x3ffffff;
+        d1 += carry;
+        carry = d1 >> 26;
+        h[1] = d1 as u32 & 0x3ffffff;
+        d2 += carry;
+        carry = d2 >> 26;
+        h[2] = d2 as u32 & 0x3ffffff;
+        d3 += carry;
+        carry = d3 >> 26;
+        h[3] = d3 as u32 & 0x3ffffff;
+        d4 += carry;
+        carry = d4 >> 26;
+        h[4] = d4 as u32 & 0x3ffffff;
+        h[0] += carry as u32 * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= 0x3ffffff;
+    }
+
+    // Fully carry h.
+    let mut carry = h[1] >> 26;
+    h[1] &= 0x3ffffff;
+    for limb in &mut h[2..] {
+        *limb += carry;
+        carry = *limb >> 26;
+        *limb &= 0x3ffffff;
+    }
+    h[0] += carry * 5;
+    carry = h[0] >> 26;
+    h[0] &= 0x3ffffff;
+    h[1] += carry;
+
+    // Compute h - p and select it if h >= p.
+    let mut g = [0u32; 5];
+    carry = 5;
+    for (g_limb, h_limb) in g[..4].iter_mut().zip(&h[..4]) {
+        *g_limb = h_limb + carry;
+        carry = *g_limb >> 26;
+        *g_limb &= 0x3ffffff;
+    }
+    g[4] = h[4].wrapping_add(carry).wrapping_sub(1 << 26);
+
+    let mask = (g[4] >> 31).wrapping_sub(1);
+    for (h_limb, g_limb) in h.iter_mut().zip(g) {
+        *h_limb = (*h_limb & !mask) | (g_limb & mask);
+    }
+
+    // Pack into 128 bits and add the second half of the key.
+    let h0 = h[0] | (h[1] << 26);
+    let h1 = (h[1] >> 6) | (h[2] << 20);
+    let h2 = (h[2] >> 12) | (h[3] << 14);
+    let h3 = (h[3] >> 18) | (h[4] << 8);
+
+    let mut tag = [0; TAG_SIZE];
+    let mut accumulator = 0u64;
+    for (i, word) in [h0, h1, h2, h3].into_iter().enumerate() {
+        accumulator += word as u64 + read(key, 16 + i * 4) as u64;
+        tag[i * 4..i * 4 + 4].copy_from_slice(&(accumulator as u32).to_le_bytes());
+        accumulator >>= 32;
+    }
+    tag
+}
@@ -0,0 +1,1324 @@
+use crate::parse_command::CommandType;
+
+pub struct CommandDoc {
+    pub summary: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub flags: &'static [(&'static str, &'static str)],
+    /// Example invocations with a short description of what they do.
+    pub examples: &'static [(&'static str, &'static str)],
+}
+
+pub const EXIT_STATUS: &str =
+    "Commands report failures as \"An error occured: ...\" and leave the \
+shell running. Only `exit` terminates the shell, with status 0. A command run with -c \
+exits with status 0 on success and 1 on failure.";
+
+/// Flags of the binary itself, as opposed to those of its commands.
+pub const SHELL_FLAGS: &[(&str, &str)] = &[
+    (
+        "-c",
+        "Run the command given by the remaining arguments and exit",
+    ),
+    ("-q", "Only print results and errors"),
+    ("-v", "Also print per-file actions and command timings"),
+    ("-vv", "Also print how each line was parsed"),
+    (
+        "--accessible",
+        "Plain output for screen readers, like `set accessible true`",
+    ),
+    (
+        "--profile-startup",
+        "Report how long each subsystem takes to start",
+    ),
+];
+
+impl CommandDoc {
+    /// The description of `flag`, found by its name in the flag list, which
+    /// may spell it with a value placeholder or next to its short form.
+    pub fn flag_description(&self, flag: &str) -> Option<&'static str> {
+        self.flags
+            .iter()
+            .find(|(label, _)| label.split([',', ' ', '=']).any(|part| part == flag))
+            .map(|(_, description)| *description)
+    }
+}
+
+pub fn for_command(command_type: CommandType) -> CommandDoc {
+    match command_type {
+        CommandType::Archive => CommandDoc {
+            summary: "Search or print the files in an archive without extracting it",
+            usage: "archive grep [-i] <pattern> <archive> | archive cat <archive> <member>",
+            description: "Reads tar and zip archives, telling them apart by their contents, \
+and goes through their members as it reads them, so nothing is written to disk. `grep` prints \
+the lines of every file in the archive that contain <pattern> as plain text, prefixed with the \
+member's path and the line number; binary members are skipped. `cat` prints one member, named \
+by its path in the archive as `tar -t` or `unzip -l` lists it.",
+            flags: &[("-i, --ignore-case", "Ignore case when searching")],
+            examples: &[
+                ("archive grep TODO project.tar", "Find TODOs in an archived project"),
+                ("archive grep -i error logs.zip", "Search archived logs, ignoring case"),
+                ("archive cat project.tar project/README.md", "Read one file of an archive"),
+            ],
+        },
+        CommandType::Audit => CommandDoc {
+            summary: "Report risky file permissions",
+            usage: "audit <dir> [--format text|json] [--min-severity low|medium|high]",
+            description: "Walks <dir> and reports world-writable files and directories, setuid \
+and setgid binaries, broken symlinks, sensitive files readable by others and executable \
+documents, each with a severity level. The command fails, so `-c` exits with status 1, when \
+anything at or above --min-severity is found, and succeeds when nothing is.",
+            flags: &[
+                (
+                    "--format <text|json>",
+                    "Output format, json is meant for CI tooling",
+                ),
+                (
+                    "--min-severity <level>",
+                    "Hide findings below the given severity; only the others make it fail",
+                ),
+            ],
+            examples: &[
+                ("audit .", "Audit the current directory"),
+                (
+                    "audit . --format json --min-severity medium",
+                    "Report medium and high findings as JSON",
+                ),
+            ],
+        },
+        CommandType::Backup => CommandDoc {
+            summary: "Incrementally copy a directory",
+            usage: "backup <src> <dest> [--delete] [-p] [--reflink=auto|never] \
+[--sparse=never|auto|always]",
+            description: "Copies the files of <src> into <dest>, skipping files whose size, \
+modification time and hash did not change since the last run. The state is kept in \
+.backup_state.json inside <dest>. A <dest> inside <src> is left out of the copy.",
+            flags: &[
+                (
+                    "--delete",
+                    "Remove files from <dest> that were deleted from <src>",
+                ),
+                (
+                    "-p, --preserve",
+                    "Keep timestamps and permissions, and ownership when run as root",
+                ),
+                (
+                    "--reflink=<auto|never>",
+                    "auto clones files on filesystems that support it (default never)",
+                ),
+                (
+                    "--sparse=<never|auto|always>",
+                    "Keep holes of sparse files (auto, the default), or also make holes \
+of zero blocks (always)",
+                ),
+            ],
+            examples: &[
+                (
+                    "backup . ../backup",
+                    "Copy changed files of the current directory",
+                ),
+                (
+                    "backup . ../backup --delete",
+                    "Mirror the current directory, removing deleted files",
+                ),
+            ],
+        },
+        CommandType::Cal => CommandDoc {
+            summary: "Show a calendar",
+            usage: "cal [-3|-y] [-m|-s] [<month>] [<year>]",
+            description: "Prints a month as a grid, with today highlighted. The month can be a \
+number or a name; a single number above 12 is taken as a year and shows the whole year. Weeks \
+start on the day set by the week_start option (Monday by default).",
+            flags: &[
+                ("-3", "Show the previous, current and next month"),
+                ("-y", "Show the whole year"),
+                ("-m", "Start weeks on Monday"),
+                ("-s", "Start weeks on Sunday"),
+            ],
+            examples: &[
+                ("cal", "Show this month"),
+                ("cal -3", "Show three months around today"),
+                ("cal may 2025", "Show May 2025"),
+                ("cal -y 2026", "Show a whole year"),
+            ],
+        },
+        CommandType::Capture => CommandDoc {
+            summary: "Store the output of a command in a variable",
+            usage: "capture <name> <command> [<argument>...]",
+            description: "Runs the command without showing its output and stores the output in \
+the shell variable <name>, for $name or ${name} to expand to in later lines. Everything after \
+the command's name belongs to it, flags included. Trailing newlines are dropped and the lines \
+inside are kept; a value always expands to a single argument, however many lines or spaces it \
+holds. Output over 100 KiB is refused, and output of external programs is not captured. \
+Names that are not shell variables expand to environment variables.",
+            flags: &[],
+            examples: &[
+                ("capture dir pwd", "Remember the current directory as $dir"),
+                ("capture lines wc -l Cargo.toml", "Store the line count of a file, flags and all"),
+                ("cd ${dir}", "Go back to the remembered directory"),
+            ],
+        },
+        CommandType::Cat => CommandDoc {
+            summary: "Print the contents of files",
+            usage: "cat [-n] <file>...",
+            description: "Prints each file to stdout in order, byte for byte.",
+            flags: &[("-n", "Number the lines, continuing across files")],
+            examples: &[
+                ("cat notes.txt", "Show a file"),
+                ("cat -n a.rs b.rs", "Show two files with line numbers"),
+            ],
+        },
+        CommandType::Cd => CommandDoc {
+            summary: "Change the current directory",
+            usage: "cd [<dir> | -]",
+            description: "Changes the directory that later commands resolve relative paths \
+against. Without an argument it goes to the home directory; `cd -` goes back to the previous \
+directory and prints it. `..` steps back along the path as typed, even through symlinks. \
+A relative path that is not found is looked up in the directories of the cdpath option (or \
+$CDPATH), then matched loosely against the names of subdirectories, so `cd doc` can find \
+Documents. Entering a directory with a .mbct-env file applies it until you leave the directory again. \
+Each line is `export NAME=value`, `NAME=value` or `alias name=command`; lines starting with # \
+are comments. You are asked once whether to trust a file, and again whenever it changes.",
+            flags: &[],
+            examples: &[
+                ("cd src", "Enter a subdirectory"),
+                ("cd -", "Go back to the previous directory"),
+                ("cd", "Go to the home directory"),
+                ("set cdpath /home/me/src", "Let cd find projects under /home/me/src from anywhere"),
+            ],
+        },
+        CommandType::Chgrp => CommandDoc {
+            summary: "Change the group of files",
+            usage: "chgrp [-R] <group> <path>... | chgrp [-R] --reference <file> <path>...",
+            description: "Sets the group of each <path> to <group>, given by name or numeric ID, or \
+to the group of <file> with --reference. Names are looked up in /etc/group. Only available on \
+Unix.",
+            flags: &[
+                ("-R, --recursive", "Also change everything below directories, without following symlinks"),
+                ("--reference <file>", "Use the group of <file>"),
+            ],
+            examples: &[
+                ("chgrp staff notes.txt", "Give notes.txt to the staff group"),
+                ("chgrp -R 100 shared", "Change shared/ and its contents to group 100"),
+            ],
+        },
+        CommandType::Chmod => CommandDoc {
+            summary: "Change the permissions of files",
+            usage: "chmod [-R] <mode> <path>...",
+            description: "Sets the permission bits of each <path>. An octal <mode> such as 644 or \
+0755 replaces them all; symbolic clauses such as u+x or go=r change only the bits named, for \
+the user (u), group (g), others (o) or all (a). Only available on Unix.",
+            flags: &[("-R, --recursive", "Also change everything below directories, skipping symlinks")],
+            examples: &[
+                ("chmod 644 notes.txt", "Owner can write, everyone can read"),
+                ("chmod u+x build.sh", "Make a script executable for its owner"),
+                ("chmod -R go=r docs", "Let others only read a whole tree"),
+            ],
+        },
+        CommandType::Chown => CommandDoc {
+            summary: "Change the owner and group of files",
+            usage: "chown [-R] <user>[:<group>] <path>... | chown [-R] --reference <file> <path>...",
+            description: "Sets the owner of each <path>, and its group when given after a colon. \
+`user:` also sets the login group of the user and `:group` only the group. Users and groups \
+are given by name, looked up in /etc/passwd and /etc/group, or by numeric ID. With --reference \
+the owner and group of <file> are copied. Only available on Unix, and giving files away \
+usually requires root.",
+            flags: &[
+                ("-R, --recursive", "Also change everything below directories, without following symlinks"),
+                ("--reference <file>", "Use the owner and group of <file>"),
+            ],
+            examples: &[
+                ("chown alice notes.txt", "Make alice the owner of notes.txt"),
+                ("chown -R alice:staff project", "Change owner and group of a whole tree"),
+                ("chown --reference a.txt b.txt", "Give b.txt the ownership of a.txt"),
+            ],
+        },
+        CommandType::Completions => CommandDoc {
+            summary: "Print a completion script for bash, zsh, fish or PowerShell",
+            usage: "completions bash|zsh|fish|powershell",
+            description: "Prints a script that completes the shell flags of the binary and, after \
+-c, the names and flags of its commands, generated from the same metadata as `help`. Load it \
+from the startup file of your shell.",
+            flags: &[],
+            examples: &[
+                (
+                    "completions bash",
+                    "Print the bash script, e.g. for ~/.bashrc: source <(my_basic_cli_tools -c completions bash)",
+                ),
+                (
+                    "completions fish",
+                    "Print the fish script, e.g. to save in ~/.config/fish/completions",
+                ),
+            ],
+        },
+        CommandType::ConvertUnit => CommandDoc {
+            summary: "Convert sizes, durations, temperatures and number bases",
+            usage: "convert-unit <value> [<unit>] | convert-unit <number> --to hex|bin|oct|dec",
+            description: "Converts a value with a unit to another unit of the same kind. Sizes \
+use B, KB, MB, GB, TB (powers of 1000) and KiB, MiB, GiB, TiB (powers of 1024); durations use \
+ms, s, min, h, d and w; temperatures use C, F and K. Without a target unit, sizes and durations \
+are shown in the most readable form. Integers with a 0x, 0b or 0o prefix are shown in decimal, \
+or in the base given with --to. Negative values such as -40C are read as values, not as flags.",
+            flags: &[("--to <unit|base>", "Unit or base to convert to, instead of a second argument")],
+            examples: &[
+                ("convert-unit 1.5GiB MB", "Convert between binary and decimal sizes"),
+                ("convert-unit 5400s", "Show a duration as 1h 30m"),
+                ("convert-unit 98.6F C", "Convert a temperature"),
+                ("convert-unit 0xff", "Show a hexadecimal number in decimal"),
+                ("convert-unit 255 --to bin", "Show a number in binary"),
+            ],
+        },
+        CommandType::Countdown => CommandDoc {
+            summary: "Count down and ring the terminal bell",
+            usage: "countdown <duration>",
+            description: "Shows the remaining time, updated every second, and rings the terminal \
+bell when it reaches zero. Durations are written like 90s, 5m or 1h30m.",
+            flags: &[],
+            examples: &[
+                ("countdown 5m", "Time a five minute break"),
+                ("countdown 1h30m", "Count down an hour and a half"),
+            ],
+        },
+        CommandType::Cp => CommandDoc {
+            summary: "Copy files and directories",
+            usage: "cp [-r] [-f] [-p] [--reflink=auto|never] [--sparse=never|auto|always] \
+<source>... <destination>",
+            description: "Copies a file to a new name, or any number of sources into an existing \
+directory. Permissions are kept. Existing files are never overwritten without -f. With -r \
+directories are copied with everything inside them; symlinks inside, and symlinks to directories, \
+are copied as symlinks.",
+            flags: &[
+                ("-r, --recursive", "Copy directories recursively"),
+                ("-f, --force", "Overwrite existing files"),
+                (
+                    "-p, --preserve",
+                    "Keep timestamps too, and ownership when run as root",
+                ),
+                (
+                    "--reflink=<auto|never>",
+                    "auto clones files on filesystems that support it (default never)",
+                ),
+                (
+                    "--sparse=<never|auto|always>",
+                    "Keep holes of sparse files (auto, the default), or also make holes \
+of zero blocks (always)",
+                ),
+            ],
+            examples: &[
+                ("cp notes.txt notes.bak", "Copy a file"),
+                ("cp -r src backup/src", "Copy a directory tree"),
+                ("cp -f a.txt b.txt docs", "Copy two files into docs, replacing old copies"),
+            ],
+        },
+        CommandType::Cut => CommandDoc {
+            summary: "Print selected fields of each line",
+            usage: "cut -f <list> [-d <delimiter>] [file]...",
+            description: "Splits each line of the given files at the delimiter, a tab unless -d is \
+given, and prints the selected fields joined by it. Lines without the delimiter are printed \
+whole. Without files the lines are read from standard input until end of file (Ctrl+D).",
+            flags: &[
+                (
+                    "-f <list>",
+                    "Fields to print, counted from 1, e.g. 2, 1,3, 2-4, -3 or 3-",
+                ),
+                ("-d <delimiter>", "Text that separates the fields"),
+            ],
+            examples: &[
+                ("cut -d , -f 1,3 people.csv", "Print the first and third columns of a CSV file"),
+                ("cut -d : -f 1 /etc/passwd", "List user names"),
+            ],
+        },
+        CommandType::Date => CommandDoc {
+            summary: "Show the time, or convert a time between time zones",
+            usage: "date [--convert <time> [--from <zone>]] [--to <zone>]",
+            description: "Prints the current date and time in the local time zone, or in the zone \
+given with --to. With --convert the given time (YYYY-MM-DD HH:MM) is read in the --from zone and \
+shown in the --to zone; both default to the local zone. Zones are tz database names such as \
+Europe/Warsaw, UTC, or offsets such as +05:30.",
+            flags: &[
+                ("--convert <time>", "Time to convert, as YYYY-MM-DD [HH:MM[:SS]]"),
+                ("--from <zone>", "Zone the time to convert is in"),
+                ("--to <zone>", "Zone to show the time in"),
+            ],
+            examples: &[
+                ("date --to Asia/Tokyo", "Show the time in Tokyo"),
+                (
+                    "date --convert \"2024-05-01 12:00\" --from UTC --to Europe/Warsaw",
+                    "Convert a UTC time to Warsaw time",
+                ),
+            ],
+        },
+        CommandType::Debuglog => CommandDoc {
+            summary: "Capture diagnostics from the shell's subsystems",
+            usage: "debuglog [on [<targets>] [--level <level>] [--file <path>] | off | status]",
+            description: "Turns on internal logging for a comma separated list of subsystems \
+(config, executor, parser, vault, walk; all by default). Records go to stderr, or are appended \
+to the file given with --file, which is handy to attach to a bug report. Without arguments it \
+shows what is being logged.",
+            flags: &[
+                (
+                    "--level <level>",
+                    "Most detailed level to record: error, warn, info, debug (default) or trace",
+                ),
+                (
+                    "--file <path>",
+                    "Append records to <path> instead of stderr",
+                ),
+            ],
+            examples: &[
+                (
+                    "debuglog on parser,executor",
+                    "Log parsing and command execution",
+                ),
+                (
+                    "debuglog on all --level trace --file debug.log",
+                    "Record everything to debug.log",
+                ),
+                ("debuglog off", "Stop logging"),
+            ],
+        },
+        CommandType::Decrypt => CommandDoc {
+            summary: "Decrypt a file created by encrypt",
+            usage: "decrypt <file> [-o <out>]",
+            description: "Prompts for the passphrase and decrypts <file>, verifying every \
+chunk. The plaintext goes to a temporary file that only replaces <out> once the whole file \
+is verified, so a wrong passphrase or a damaged file leaves an existing <out> as it was.",
+            flags: &[(
+                "-o, --output <out>",
+                "Output path, defaults to <file> without its .enc extension",
+            )],
+            examples: &[("decrypt notes.txt.enc", "Decrypt to notes.txt")],
+        },
+        CommandType::Df => CommandDoc {
+            summary: "Show free space on mounted filesystems",
+            usage: "df [-h] [<path>...]",
+            description: "Lists the size, used and available space of every mounted filesystem, \
+or of the filesystems that hold the given paths. Available space leaves out blocks reserved \
+for the administrator, so used and available may not add up to the size. Sizes are in 1 KiB \
+blocks unless -h is given.",
+            flags: &[("-h", "Human readable sizes, like 12.5 GiB")],
+            examples: &[
+                ("df -h", "Space on every filesystem"),
+                ("df -h .", "Space left where the current directory lives"),
+            ],
+        },
+        CommandType::Dirs => CommandDoc {
+            summary: "Show the directory stack",
+            usage: "dirs [-v]",
+            description: "Prints the current directory followed by the directories saved by \
+`pushd`, most recent first. The prompt can show how many are saved with {dirs} or {dir_depth} \
+in MY_BASIC_CLI_PROMPT.",
+            flags: &[("-v", "One directory per line, numbered for pushd +n")],
+            examples: &[("dirs -v", "List the stack with positions")],
+        },
+        CommandType::Docs => CommandDoc {
+            summary: "Generate documentation files for every command",
+            usage: "docs generate [--format md|man] -o <dir>",
+            description: "Renders the usage, flags and examples of every command into <dir>, one \
+file per command, from the same metadata as `help` and `man`. Markdown output also gets an \
+index.md linking all pages; man output produces section 1 pages named \
+my_basic_cli_tools-<command>.1.",
+            flags: &[
+                ("--format <md|man>", "Markdown (default) or roff manual pages"),
+                ("-o, --output <dir>", "Directory to write the files to"),
+            ],
+            examples: &[
+                ("docs generate -o docs", "Write Markdown pages to docs/"),
+                (
+                    "docs generate --format man -o man",
+                    "Write manual pages to man/",
+                ),
+            ],
+        },
+        CommandType::Du => CommandDoc {
+            summary: "Show how much space files and directories take",
+            usage: "du [-h] [-d <depth>] [-L] [<path>...]",
+            description: "Adds up the sizes of the files below each path, the current directory \
+by default, and prints the total of every directory inside, deepest first, before the path \
+itself. Sizes are the lengths of the files in bytes. Files with several hard links count once. \
+Symlinks count as the links themselves unless -L is given; a directory reached through several \
+links is counted once. Entries that cannot be read are skipped, or fail the command when the \
+strict option is on.",
+            flags: &[
+                ("-h", "Human readable sizes, like 1.5 MiB"),
+                ("-d <depth>", "Only list directories this many levels down; 0 shows just the totals"),
+                ("-L", "Follow symbolic links, counting what they point to"),
+            ],
+            examples: &[
+                ("du -h", "Sizes of the current directory and everything in it"),
+                ("du -h -d 1 src target", "Totals of two directories and their subdirectories"),
+            ],
+        },
+        CommandType::Echo => CommandDoc {
+            summary: "Print the arguments",
+            usage: "echo <text>...",
+            description: "Prints each argument on its own line. Use quotes to keep spaces.",
+            flags: &[],
+            examples: &[
+                ("echo hello world", "Print two lines"),
+                ("echo \"hello world\"", "Print one line"),
+            ],
+        },
+        CommandType::Encrypt => CommandDoc {
+            summary: "Encrypt a file with a passphrase",
+            usage: "encrypt <file> [-o <out>] [--atomic]",
+            description: "Prompts for a passphrase without echoing it and encrypts <file> \
+with ChaCha20-Poly1305, using a key derived with PBKDF2-HMAC-SHA256. Large files are \
+processed in chunks.",
+            flags: &[
+                ("-o, --output <out>", "Output path, defaults to <file>.enc"),
+                (
+                    "--atomic",
+                    "Write to a temporary file and rename it over <out> when done",
+                ),
+            ],
+            examples: &[
+                ("encrypt notes.txt", "Encrypt to notes.txt.enc"),
+                (
+                    "encrypt notes.txt -o secret.bin",
+                    "Encrypt to a chosen file",
+                ),
+            ],
+        },
+        CommandType::Examples => CommandDoc {
+            summary: "Show or run example invocations",
+            usage: "examples <command> [--run <n>]",
+            description: "Lists numbered example invocations of a command. With --run the \
+selected example is executed inside a temporary directory, which is also its config \
+directory, so it cannot change your history, notes, reminders or vault. The directory is \
+removed afterwards, and options the example sets are put back.",
+            flags: &[(
+                "--run <n>",
+                "Execute example number <n> in a sandbox directory",
+            )],
+            examples: &[
+                ("examples ls", "List the examples of ls"),
+                ("examples loc --run 1", "Run the first example of loc"),
+            ],
+        },
+        CommandType::Exit => CommandDoc {
+            summary: "Leave the shell",
+            usage: "exit",
+            description: "Terminates the shell.",
+            flags: &[],
+            examples: &[],
+        },
+        CommandType::Explain => CommandDoc {
+            summary: "Show how a line would be parsed, without running it",
+            usage: "explain <line>",
+            description: "Takes the rest of the line exactly as typed and shows the alias it \
+resolves to, every word with how it was quoted and what its variables expand to, then the \
+command, arguments and flags it would run with, marking the flags that come from defaults in \
+the config file. History references such as !! are expanded before explain sees the line. \
+Secrets are not filled in, so the vault stays locked. Useful for finding out why quotes or \
+flags do not do what you expect.",
+            flags: &[],
+            examples: &[
+                ("explain cat \"my notes.txt\" -n", "See that the name stays one argument"),
+                ("explain ls --pattern '*.rs' $dir", "Check quoting and variable expansion"),
+            ],
+        },
+        CommandType::Find => CommandDoc {
+            summary: "Find files and directories by name",
+            usage: "find <dir> <pattern> [-f | -d] [-L] [--max-depth <n>] [filters] [actions]",
+            description: "Walks <dir> recursively and prints every path whose name matches \
+<pattern>, a glob where * matches any text, ? any one character and [abc] one of a set. Quote \
+the pattern so it stays one argument. Symlinks are listed but not followed unless -L is given. \
+The size and date filters work as for ls. Actions run once the walk is done, on every match in \
+order: --exec runs a command of this shell with {} replaced by the match, everything up to a \
+lone ; belonging to it, and stops at the first one that fails. Matches are only printed when \
+no action or --print0 is given.",
+            flags: &[
+                ("-f", "Only files"),
+                ("-d", "Only directories"),
+                ("-L", "Follow symlinks, descending into linked directories once each"),
+                ("--max-depth <n>", "Descend at most n levels; 1 lists only entries of <dir>"),
+                (
+                    "--larger-than <size>",
+                    "Only files larger than the size, e.g. 10M (units K, M, G, T)",
+                ),
+                ("--smaller-than <size>", "Only files smaller than the size"),
+                (
+                    "--newer-than <age|date>",
+                    "Only entries modified after, e.g. 2d or 2024-01-01",
+                ),
+                (
+                    "--older-than <age|date>",
+                    "Only entries modified before (ages use s, m, h, d, w)",
+                ),
+                ("--exec <command> {} ;", "Run the command for each match"),
+                (
+                    "--delete",
+                    "Remove the matches, contents first; directories only when empty by then",
+                ),
+                ("--print0", "Print matches ending in a NUL byte instead of a newline"),
+            ],
+            examples: &[
+                ("find . \"*.rs\"", "Find Rust sources below the current directory"),
+                ("find src \"*test*\" -d", "Find test directories"),
+                ("find . \"*.md\" --max-depth 1", "Only look in the current directory"),
+                (
+                    "find . \"*.log\" --older-than 30d --delete",
+                    "Remove logs not changed in a month",
+                ),
+                ("find src \"*.rs\" --exec wc -l {} ;", "Count the lines of each Rust file"),
+            ],
+        },
+        CommandType::Gitinfo => CommandDoc {
+            summary: "Show the git branch and working tree state",
+            usage: "gitinfo [--files]",
+            description: "Detects the git repository containing the current directory and \
+prints its branch and whether tracked files were modified, without running git. Files whose \
+modification time changed but whose size did not are hashed and compared with the index, so \
+files that were only touched do not count as modified.",
+            flags: &[("--files", "List the modified files")],
+            examples: &[("gitinfo --files", "Show the branch and modified files")],
+        },
+        CommandType::Grep => CommandDoc {
+            summary: "Search files for lines containing text",
+            usage: "grep [-i] [-r] [-A <n>] [-B <n>] [-C <n>] [-c | -l] [--stats] \
+(<pattern> | -e <pattern>...) <path>...",
+            description: "Prints every line that contains <pattern> as plain text, prefixed with \
+the file name and line number. Several patterns can be given with repeated -e; a line matches \
+when it contains any of them, and with -e every argument is a path. Matches are highlighted \
+when colors are on. With -r directories are searched recursively, skipping .git, files \
+excluded by .gitignore and binary files; without a path -r searches the current directory. \
+Files are searched in parallel and printed in order. Context lines are marked with - instead \
+of :, and -- separates groups of lines that are not next to each other.",
+            flags: &[
+                ("-i, --ignore-case", "Ignore case"),
+                ("-r, --recursive", "Search directories recursively"),
+                ("-e <pattern>", "A pattern to search for; repeat it for more"),
+                (
+                    "-F",
+                    "Treat patterns as fixed strings, which they always are (for other greps' sake)",
+                ),
+                ("-A <n>", "Also print n lines after each match"),
+                ("-B <n>", "Also print n lines before each match"),
+                ("-C <n>", "Also print n lines around each match"),
+                ("-c", "Print how many lines match in each file instead"),
+                ("-l", "Print only the names of files with a match"),
+                (
+                    "--stats",
+                    "End with the number of files searched, matched and skipped, and the time",
+                ),
+            ],
+            examples: &[
+                ("grep TODO notes.txt", "Find lines mentioning TODO"),
+                ("grep -i -r \"connection refused\" logs", "Search a directory, ignoring case"),
+                ("grep -C 2 -e panic -e error app.log", "Show two lines around each error"),
+                ("grep -r -l unsafe src", "List the files that use unsafe"),
+            ],
+        },
+        CommandType::Hash => CommandDoc {
+            summary: "Print or verify file checksums",
+            usage: "hash [--md5 | --sha1] <file>... | hash [--md5 | --sha1] --check <list>",
+            description: "Prints the SHA-256 digest of each file, or its MD5 or SHA-1 digest with \
+--md5 or --sha1, in the same layout as sha256sum. --check reads such a list and verifies each \
+file in it, relative to the current directory, reporting OK or FAILED; it fails if any file \
+does not match. Without a flag the algorithm of each line follows from the length of its \
+digest. MD5 and SHA-1 are only fit for comparing with published checksums, not for security.",
+            flags: &[
+                ("--md5", "Use MD5 instead of SHA-256"),
+                ("--sha1", "Use SHA-1 instead of SHA-256"),
+                ("-c, --check <list>", "Verify the files in a checksum list"),
+            ],
+            examples: &[
+                ("hash release.tar", "Print the SHA-256 digest of a file"),
+                ("hash --md5 *.iso", "Print MD5 digests"),
+                ("hash -c SHA256SUMS", "Check files against a downloaded list"),
+            ],
+        },
+        CommandType::Head => CommandDoc {
+            summary: "Print the first lines of files",
+            usage: "head [-n <count>] <file>...",
+            description: "Prints the first 10 lines of each file, or as many as -n asks for. When \
+more than one file is given, each is introduced by its name, as `ls` does for directories.",
+            flags: &[(
+                "-n <count>, -<count>",
+                "Number of lines to print (default 10)",
+            )],
+            examples: &[
+                ("head notes.txt", "Print the first 10 lines"),
+                ("head -5 notes.txt", "Print the first 5 lines"),
+                ("head -n 3 a.txt b.txt", "Print the first 3 lines of two files"),
+            ],
+        },
+        CommandType::Help => CommandDoc {
+            summary: "List commands or show a command's usage",
+            usage: "help [command]",
+            description: "Without arguments lists every command with a short summary. With a \
+command name shows its usage and flags.",
+            flags: &[],
+            examples: &[
+                ("help", "List all commands"),
+                ("help ls", "Show the usage of ls"),
+            ],
+        },
+        CommandType::History => CommandDoc {
+            summary: "Show, search and prune the command history",
+            usage: "history | history stats | history search <term> | history prune [--older-than <age>] [--dedupe]",
+            description: "Every line entered in the interactive shell is saved with its time in \
+the config directory. `history` lists them, `history search` finds those containing a term, \
+ignoring case, and `history stats` shows the most used commands and the busiest hours. \
+`history prune` removes old entries, repeated lines, or both, rewriting the file atomically.",
+            flags: &[
+                ("--older-than <age>", "Prune entries older than this, e.g. 90d"),
+                ("--dedupe", "Prune all but the latest use of each line"),
+            ],
+            examples: &[
+                ("history stats", "See which commands you use most"),
+                ("history search cargo", "Find earlier cargo invocations"),
+                ("history prune --older-than 90d --dedupe", "Trim the history"),
+            ],
+        },
+        CommandType::Ifconfig => CommandDoc {
+            summary: "List network interfaces",
+            usage: "ifconfig [interface]...",
+            description: "Lists network interfaces with their state, MAC address and IPv4 \
+and IPv6 addresses. Also available as `ip`.",
+            flags: &[],
+            examples: &[
+                ("ifconfig", "List all interfaces"),
+                ("ip lo", "Show the loopback interface"),
+            ],
+        },
+        CommandType::Imginfo => CommandDoc {
+            summary: "Show the dimensions and metadata of an image",
+            usage: "imginfo <file>",
+            description: "Reads the headers of a PNG, JPEG, GIF or BMP file and reports its \
+dimensions and color depth. For JPEG photos it also shows the EXIF orientation, the time the \
+photo was taken and the camera. Pixel data is never decoded, so this is fast even for large \
+images.",
+            flags: &[],
+            examples: &[("imginfo photo.jpg", "Show the size and EXIF data of a photo")],
+        },
+        CommandType::Lastout => CommandDoc {
+            summary: "Show or copy what the previous command printed",
+            usage: "lastout [-c]",
+            description: "Every command's output is kept until the next command runs, so it can \
+be shown again or copied without running the command twice or selecting it with the mouse. \
+The same text is in the LAST_OUTPUT environment variable of programs started by run. Colors \
+are left out, and only the first 100 KiB are kept. Output of external programs is not \
+captured. Running lastout does not replace the kept output, and nothing is kept from vault or \
+from commands that use a $SECRET: reference, so secrets never end up there.",
+            flags: &[("-c, --copy", "Copy it to the clipboard instead of printing it")],
+            examples: &[
+                ("lastout", "Print the previous output again"),
+                ("lastout -c", "Copy the previous output to the clipboard"),
+            ],
+        },
+        CommandType::Ln => CommandDoc {
+            summary: "Create hard links and symlinks",
+            usage: "ln [-s] <target> <link>",
+            description: "Creates <link> as a hard link to <target>, another name for the same \
+file, or with -s as a symlink pointing to it. When <link> is a directory, the link is created \
+inside it with the name of <target>. The target must exist and the link must not, so nothing \
+is ever replaced. A relative symlink target is taken from the link's directory and stored as \
+given. Hard links cannot point to directories or cross filesystems.",
+            flags: &[("-s", "Create a symlink instead of a hard link")],
+            examples: &[
+                ("ln notes.txt backup/notes.txt", "Give a file a second name"),
+                ("ln -s ../shared/config.toml config.toml", "Point to a file elsewhere"),
+                ("ln -s target/release/tool bin", "Create bin/tool when bin is a directory"),
+            ],
+        },
+        CommandType::Loc => CommandDoc {
+            summary: "Count lines of code per language",
+            usage: "loc [dir] [-L]",
+            description: "Counts files and blank, comment and code lines per language, \
+detected by file extension. Files excluded by .gitignore are skipped, and hard-linked \
+files are counted once.",
+            flags: &[("-L", "Follow symbolic links to directories")],
+            examples: &[
+                ("loc", "Count lines in the current directory"),
+                ("loc src", "Count lines below src"),
+            ],
+        },
+        CommandType::Ls => CommandDoc {
+            summary: "List directory contents",
+            usage: "ls [dir]... [--group-dirs-first] [--pattern <glob>] [--ext <list>] [filters]",
+            description: "Lists the entries of each directory, or of the current directory \
+when none is given.",
+            flags: &[
+                (
+                    "--group-dirs-first",
+                    "List directories before files (default: group_dirs_first in the config file)",
+                ),
+                (
+                    "--pattern <glob>",
+                    "Only list entries whose name matches, e.g. '*.rs'",
+                ),
+                (
+                    "--ext <list>",
+                    "Only list files with one of the extensions, e.g. rs,toml",
+                ),
+                (
+                    "--larger-than <size>",
+                    "Only list files larger than the size, e.g. 10M (units K, M, G, T)",
+                ),
+                (
+                    "--smaller-than <size>",
+                    "Only list files smaller than the size",
+                ),
+                (
+                    "--newer-than <age|date>",
+                    "Only list entries modified after, e.g. 2d or 2024-01-01",
+                ),
+                (
+                    "--older-than <age|date>",
+                    "Only list entries modified before (ages use s, m, h, d, w)",
+                ),
+            ],
+            examples: &[
+                ("ls", "List the current directory"),
+                ("ls src target", "List two directories"),
+                ("ls src --pattern '*.rs'", "List the Rust files in src"),
+                (
+                    "ls --larger-than 1M --newer-than 2d",
+                    "List files over 1 MiB changed in the last two days",
+                ),
+            ],
+        },
+        CommandType::Man => CommandDoc {
+            summary: "Show the manual page of a command",
+            usage: "man <command>",
+            description: "Shows the description, usage, flags and exit status of a command \
+through the built-in pager.",
+            flags: &[],
+            examples: &[("man backup", "Show the manual page of backup")],
+        },
+        CommandType::Mkdir => CommandDoc {
+            summary: "Create directories",
+            usage: "mkdir [-p] <dir>...",
+            description: "Creates each directory. Without -p the parent must exist and the \
+directory must not.",
+            flags: &[("-p", "Create missing parents, and accept directories that already exist")],
+            examples: &[
+                ("mkdir notes", "Create a directory"),
+                ("mkdir -p src/commands/new", "Create a nested path"),
+            ],
+        },
+        CommandType::Mounts => CommandDoc {
+            summary: "List mounted filesystems",
+            usage: "mounts [--type <fstype>] [--format text|json]",
+            description: "Lists every mounted filesystem with its device, mount point, filesystem \
+type and mount options, read from /proc/self/mountinfo on Linux and from the kernel on macOS.",
+            flags: &[
+                ("--type <fstype>", "Only list filesystems of this type, e.g. ext4"),
+                (
+                    "--format <text|json>",
+                    "Output format, defaults to the output_format option",
+                ),
+            ],
+            examples: &[
+                ("mounts", "List all mounts"),
+                ("mounts --type tmpfs", "List tmpfs mounts"),
+            ],
+        },
+        CommandType::Mv => CommandDoc {
+            summary: "Move or rename files and directories",
+            usage: "mv [-i] <source>... <destination>",
+            description: "Renames a file or directory, or moves any number of sources into an \
+existing directory. Moving to another filesystem copies the files and then removes the \
+originals. Existing files are replaced unless -i is given, which asks first.",
+            flags: &[("-i", "Ask before overwriting an existing file")],
+            examples: &[
+                ("mv draft.txt final.txt", "Rename a file"),
+                ("mv -i a.log b.log archive", "Move two logs into archive, asking before replacing"),
+            ],
+        },
+        CommandType::Myip => CommandDoc {
+            summary: "Show the local or public IP address",
+            usage: "myip [--public [--endpoint <url>]]",
+            description: "Prints the address of the interface used for outbound traffic. \
+With --public queries an HTTP endpoint instead, taken from --endpoint, the MYIP_ENDPOINT \
+environment variable or http://api.ipify.org/.",
+            flags: &[
+                ("--public", "Query the public address"),
+                (
+                    "--endpoint <url>",
+                    "Plain HTTP endpoint returning the address",
+                ),
+            ],
+            examples: &[
+                ("myip", "Show the local address"),
+                ("myip --public", "Show the public address"),
+            ],
+        },
+        CommandType::Note => CommandDoc {
+            summary: "Keep quick timestamped notes",
+            usage: "note add <text> | note list | note search <term> | note edit <id> [<text>]",
+            description: "A scratchpad for thoughts during a terminal session. Notes are saved \
+with the time they were added in the config directory. `note edit` replaces the text when one \
+is given, and otherwise opens the note in $VISUAL or $EDITOR. Search ignores case.",
+            flags: &[],
+            examples: &[
+                ("note add \"check the backup logs\"", "Add a note"),
+                ("note search backup", "Find notes mentioning backup"),
+                ("note edit 3", "Edit note 3 in your editor"),
+            ],
+        },
+        CommandType::Open => CommandDoc {
+            summary: "Open a file or URL with the default application",
+            usage: "open <path|url> [--with <app>]",
+            description: "Hands <path> or <url> to the platform opener: xdg-open on Linux, open on \
+macOS and start on Windows. Paths are checked to exist first, so a typo is reported here \
+instead of by the desktop.",
+            flags: &[("--with <app>", "Open with <app> instead of the default application")],
+            examples: &[
+                ("open report.pdf", "Open a document in the default viewer"),
+                ("open https://example.com", "Open a URL in the default browser"),
+                ("open notes.txt --with gedit", "Open a file with a specific editor"),
+            ],
+        },
+        CommandType::Popd => CommandDoc {
+            summary: "Return to the directory on top of the stack",
+            usage: "popd",
+            description: "Removes the most recently saved directory from the directory stack and \
+changes to it, then prints the stack.",
+            flags: &[],
+            examples: &[("popd", "Go back to where the last pushd came from")],
+        },
+        CommandType::Portscan => CommandDoc {
+            summary: "Scan a host for open TCP ports",
+            usage: "portscan <host> [--ports <list>] [--timeout <ms>] [--workers <n>] [--banner]",
+            description: "Tries to connect to each port concurrently and prints the open ones.",
+            flags: &[
+                (
+                    "--ports <list>",
+                    "Ports to scan, e.g. 22,80,8000-8080 (default 1-1024)",
+                ),
+                (
+                    "--timeout <ms>",
+                    "Connect timeout in milliseconds (default 500)",
+                ),
+                (
+                    "--workers <n>",
+                    "Number of concurrent connections (default 100)",
+                ),
+                ("--banner", "Print the first line sent by each open port"),
+            ],
+            examples: &[
+                (
+                    "portscan 127.0.0.1 --ports 1-1024",
+                    "Scan the well-known ports of this machine",
+                ),
+                (
+                    "portscan example.com --ports 22,80,443 --banner",
+                    "Scan a few ports and show banners",
+                ),
+            ],
+        },
+        CommandType::Pushd => CommandDoc {
+            summary: "Save the current directory on the stack and change to another",
+            usage: "pushd [<dir> | +<n>]",
+            description: "Changes to <dir> like `cd`, saving the current directory on the \
+directory stack so `popd` can return to it. Without an argument it swaps the current directory \
+with the top of the stack. `pushd +n` rotates the stack so entry n, as numbered by `dirs -v`, \
+becomes the current directory. The stack is printed afterwards.",
+            flags: &[],
+            examples: &[
+                ("pushd /var/log", "Go to /var/log, remembering where you were"),
+                ("pushd", "Swap back and forth between two directories"),
+                ("pushd +2", "Rotate to the third entry of the stack"),
+            ],
+        },
+        CommandType::Pwd => CommandDoc {
+            summary: "Print the current working directory",
+            usage: "pwd [-P]",
+            description: "Prints the directory that relative paths are resolved against. By \
+default the path is shown as it was entered, including symlinks.",
+            flags: &[("-P", "Resolve symlinks and print the physical path")],
+            examples: &[("pwd", "Show where you are"), ("pwd -P", "Show the real path")],
+        },
+        CommandType::Qr => CommandDoc {
+            summary: "Show text as a QR code",
+            usage: "qr <text>... [-o <file.png>] [--ecc L|M|Q|H] [--invert]",
+            description: "Encodes the text (several arguments are joined with spaces) as a QR \
+code and draws it in the terminal with block characters, ready to scan with a phone. With -o the \
+code is written as a PNG image instead. For Wi-Fi credentials use the text \
+WIFI:T:WPA;S:<network>;P:<password>;;.",
+            flags: &[
+                ("-o, --output <file>", "Write a PNG image instead of drawing the code"),
+                (
+                    "--ecc <L|M|Q|H>",
+                    "Error correction level, from 7% to 30% of the code (default M)",
+                ),
+                ("--invert", "Draw dark modules as blocks, for light terminal backgrounds"),
+            ],
+            examples: &[
+                ("qr https://example.com", "Show a link to scan with a phone"),
+                (
+                    "qr \"WIFI:T:WPA;S:home;P:secret;;\" -o wifi.png",
+                    "Save Wi-Fi credentials as an image",
+                ),
+            ],
+        },
+        CommandType::Remind => CommandDoc {
+            summary: "Set reminders that the shell shows when they are due",
+            usage: "remind me in <duration> <message> | remind list | remind rm <id>",
+            description: "Stores a reminder that the interactive shell prints, with a bell, at \
+the first prompt after it is due. Reminders are saved in the config directory, so they survive \
+restarts and are shown by whichever shell session sees them first. Durations are written like \
+10m, 1h or 1h30m.",
+            flags: &[],
+            examples: &[
+                ("remind me in 10m \"stand up\"", "Get a reminder in ten minutes"),
+                ("remind list", "Show pending reminders"),
+                ("remind rm 2", "Cancel reminder 2"),
+            ],
+        },
+        CommandType::Replace => CommandDoc {
+            summary: "Replace text in files",
+            usage: "replace <pattern> <replacement> <file>... [-g] [-i] [--dry-run]",
+            description: "Replaces the first occurrence of the pattern on each line, or every \
+occurrence with -g, and prints the result. The pattern is plain text, not a regular expression. \
+With -i the files are changed instead, each replaced as a whole so an interrupted run leaves it \
+untouched. Like sed -i, this keeps a file's permissions and changes the file a symlink points to \
+rather than the link. --dry-run, like the dry_run option, only shows the lines that would change.",
+            flags: &[
+                ("-g", "Replace every occurrence on a line, not only the first"),
+                ("-i, --in-place", "Change the files instead of printing the result"),
+                ("--dry-run", "Show each line that would change, before and after"),
+            ],
+            examples: &[
+                ("replace http: https: links.md", "Print a file with the first http: on each line replaced"),
+                ("replace -g -i foo bar src/a.rs src/b.rs", "Rename foo to bar in two files"),
+                ("replace -g --dry-run foo bar notes.txt", "Preview the changes"),
+            ],
+        },
+        CommandType::Rm => CommandDoc {
+            summary: "Remove files and directories",
+            usage: "rm [-r] [-f] [--interactive-menu] <path>...",
+            description: "Removes each path. Directories need -r, which removes everything \
+inside them. Symlinks are removed, not the files they point to. `.`, `..` and the root \
+directory are never removed. With --interactive-menu the paths are listed first and only the \
+ones picked are removed: in a terminal with arrow keys or j and k to move, space to pick, a to \
+pick all and enter to confirm; otherwise, and in accessible mode, by typing their numbers.",
+            flags: &[
+                ("-r, --recursive", "Remove directories and their contents"),
+                ("-f, --force", "Ignore paths that do not exist"),
+                ("--interactive-menu", "Pick which of the paths to remove"),
+            ],
+            examples: &[
+                ("rm notes.txt", "Remove a file"),
+                ("rm -r -f build", "Remove a directory if it exists"),
+                ("rm -r --interactive-menu build dist out", "Choose which of them to remove"),
+            ],
+        },
+        CommandType::Run => CommandDoc {
+            summary: "List and run the tasks of the project in the current directory",
+            usage: "run [<task>]",
+            description: "Finds the projects in the current directory by their Cargo.toml, \
+package.json or Makefile. Without a task it lists the tasks they define: the usual cargo \
+commands, npm scripts and make targets. With a task it runs it with the project's tool. When \
+two projects share a task name the first one listed wins; write e.g. make:build to pick one.",
+            flags: &[("--names", "Print task names only, for shell completion")],
+            examples: &[
+                ("run", "List the tasks"),
+                ("run test", "Run the test task"),
+                ("run make:build", "Run the build target of the Makefile"),
+            ],
+        },
+        CommandType::Set => CommandDoc {
+            summary: "Show or change shell options for this session",
+            usage: "set [<option> [<value>]]",
+            description: "Without arguments lists every option with its current value and where \
+it comes from: the default, the config file or an earlier `set`. With a value it changes the \
+option until the shell exits. The same names can be used as `name = value` lines in the config \
+file to change the defaults: color (auto, always, never), pager, dry_run, strict, \
+output_format (text, json), group_dirs_first, usage_tracking and accessible.",
+            flags: &[],
+            examples: &[
+                ("set", "List all options"),
+                (
+                    "set dry_run true",
+                    "Make file changing commands only report what they would do",
+                ),
+                ("set output_format json", "Default to JSON output"),
+                (
+                    "set command_matching prefix",
+                    "Accept hist for history, and other unambiguous abbreviations",
+                ),
+            ],
+        },
+        CommandType::Snapshot => CommandDoc {
+            summary: "Record and compare directory contents",
+            usage:
+                "snapshot create <dir> -o <snap.json> [--atomic] | snapshot diff <snap.json> <dir>",
+            description: "`create` records the path, size and SHA-256 of every file below \
+<dir>. `diff` compares a recorded snapshot against <dir> and reports added, removed and \
+modified files.",
+            flags: &[
+                ("-o, --output <file>", "Where `create` writes the snapshot"),
+                (
+                    "--atomic",
+                    "Write to a temporary file and rename it over the output when done",
+                ),
+            ],
+            examples: &[
+                (
+                    "snapshot create . -o ../snap.json",
+                    "Record the current directory",
+                ),
+                (
+                    "snapshot diff ../snap.json .",
+                    "Compare the current directory with a snapshot",
+                ),
+            ],
+        },
+        CommandType::Sort => CommandDoc {
+            summary: "Sort lines of text",
+            usage: "sort [-r] [-n] [-u] [--buffer-size <size>] [file]...",
+            description: "Prints the lines of the given files in sorted order. Without files the \
+lines are read from standard input until end of file (Ctrl+D). Lines are compared \
+character by character unless -n is given, which compares the number each line starts with; \
+lines without one count as zero. Input larger than the buffer is sorted in parts written to \
+temporary files, which are merged and removed, so files larger than memory can be sorted.",
+            flags: &[
+                ("-r", "Reverse the order"),
+                ("-n", "Compare the leading numbers"),
+                ("-u", "Print only the first of lines that compare equal"),
+                (
+                    "--buffer-size <size>",
+                    "Memory to sort in before using temporary files, e.g. 512M (default 64M)",
+                ),
+            ],
+            examples: &[
+                ("sort names.txt", "Sort a file alphabetically"),
+                ("sort -n -r scores.txt", "Sort by number, largest first"),
+                ("sort -u a.txt b.txt", "Merge two files without duplicates"),
+            ],
+        },
+        CommandType::Stat => CommandDoc {
+            summary: "Show the metadata of files",
+            usage: "stat <path>...",
+            description: "Prints the type, size, permissions, owner and times of each path. \
+Symlinks are described themselves, with their target, rather than the file they point to. \
+Times are in the local time zone; the creation time is left out where the filesystem does \
+not record it.",
+            flags: &[],
+            examples: &[
+                ("stat Cargo.toml", "Details of one file"),
+                ("stat src target", "Details of two directories"),
+            ],
+        },
+        CommandType::Stopwatch => CommandDoc {
+            summary: "Measure elapsed time with laps",
+            usage: "stopwatch [start|lap|stop|status]",
+            description: "Starts a stopwatch that keeps running in the background, even across \
+sessions. `lap` prints the time since the previous lap and the total, `stop` prints the total \
+and resets the stopwatch, and `status` (the default) shows how long it has been running.",
+            flags: &[],
+            examples: &[
+                ("stopwatch start", "Start timing"),
+                ("stopwatch lap", "Record a lap"),
+                ("stopwatch stop", "Stop and show the total"),
+            ],
+        },
+        CommandType::Tail => CommandDoc {
+            summary: "Print the last lines of files",
+            usage: "tail [-n <count>] [-f] <file>...",
+            description: "Prints the last 10 lines of each file, or as many as -n asks for, with \
+the name of each file before it when there are several. With -f it then keeps watching a \
+single file and prints lines as they are appended, until Ctrl-C stops it. A truncated file is \
+printed again from the start.",
+            flags: &[
+                ("-n <count>, -<count>", "Number of lines to print (default 10)"),
+                ("-f", "Keep printing lines appended to the file"),
+            ],
+            examples: &[
+                ("tail -n 20 app.log", "Print the last 20 lines"),
+                ("tail -f app.log", "Follow a growing log file"),
+            ],
+        },
+        CommandType::Tar => CommandDoc {
+            summary: "Create, extract and list tar archives",
+            usage: "tar -c <archive.tar> <path>... | tar -x <archive.tar> [<dir>] | tar -t <archive.tar>",
+            description: "-c packs files and directories, with everything below them, into a \
+new uncompressed archive, replacing it only once it is complete. -x unpacks an archive into the \
+current directory or <dir>, skipping entries that would end up outside it. -t lists the paths \
+in an archive. Archives use the POSIX ustar format, which other tar programs read and write.",
+            flags: &[
+                ("-c", "Create an archive"),
+                ("-x", "Extract an archive"),
+                ("-t", "List the contents of an archive"),
+            ],
+            examples: &[
+                ("tar -c notes.tar notes todo.md", "Archive a directory and a file"),
+                ("tar -x notes.tar restored", "Extract into a directory named restored"),
+                ("tar -t notes.tar", "See what an archive holds"),
+            ],
+        },
+        CommandType::Todo => CommandDoc {
+            summary: "Keep a persistent task list",
+            usage: "todo add <text> [-p <priority>] [--due <date>] [--tags <tags>] | todo done <id> | todo rm <id> | todo list [--all] [--tags <tag>] | todo export [--format md|json]",
+            description: "Tasks are saved in the config directory with a priority, an optional \
+due date and tags. `todo list` shows open tasks, the most important first, with overdue ones \
+in red. `todo export` prints every task as a Markdown checklist or as JSON. Not to be confused \
+with `todos`, which finds TODO comments in code.",
+            flags: &[
+                ("-p, --priority <priority>", "high, medium (the default) or low"),
+                ("--due <date>", "Due date as YYYY-MM-DD"),
+                ("--tags <tags>", "Comma separated tags when adding, or a tag to list"),
+                ("--all", "Also list tasks that are done"),
+                ("--format <format>", "Export as md (the default) or json"),
+            ],
+            examples: &[
+                ("todo add renew passport -p high --due 2026-11-01", "Add an urgent task"),
+                ("todo add buy milk --tags home,errands", "Add a tagged task"),
+                ("todo done 2", "Complete task 2"),
+                ("todo export --format json", "Print every task as JSON"),
+            ],
+        },
+        CommandType::Todos => CommandDoc {
+            summary: "Find TODO, FIXME and HACK markers",
+            usage: "todos [dir] [--format text|json] [-L]",
+            description: "Searches text files below [dir] for TODO, FIXME and HACK markers, \
+skipping files excluded by .gitignore, and groups them by file.",
+            flags: &[
+                ("--format <text|json>", "Output format"),
+                ("-L", "Follow symbolic links to directories"),
+            ],
+            examples: &[
+                ("todos", "List markers in the current directory"),
+                ("todos src --format json", "List markers below src as JSON"),
+            ],
+        },
+        CommandType::Tree => CommandDoc {
+            summary: "Show a directory and everything below it as a tree",
+            usage: "tree [--max-depth <n>] [--group-dirs-first] [<dir>]",
+            description: "Prints the entries of <dir>, the current directory by default, with \
+branch lines showing how they nest, and ends with the number of directories and files shown. \
+Symlinks are listed with their target and not followed. Entries are sorted like ls sorts them.",
+            flags: &[
+                ("--max-depth <n>", "Only go this many levels down; 1 shows just the entries of <dir>"),
+                ("--group-dirs-first", "List directories before files"),
+            ],
+            examples: &[
+                ("tree src", "The whole source tree"),
+                ("tree --max-depth 2", "The top two levels of the current directory"),
+            ],
+        },
+        CommandType::Tutorial => CommandDoc {
+            summary: "Learn the shell step by step",
+            usage: "tutorial [--reset]",
+            description: "Guides you through the basics of the shell by asking you to run \
+commands and checking what you typed. Progress is saved between sessions, so running \
+`tutorial` again continues where you stopped.",
+            flags: &[("--reset", "Start the tutorial over")],
+            examples: &[("tutorial", "Start or continue the tutorial")],
+        },
+        CommandType::Umask => CommandDoc {
+            summary: "Show or set the permissions removed from new files",
+            usage: "umask [-S] [<mode>]",
+            description: "Without a mode prints the current umask in octal, or with -S the \
+permissions new files may get, e.g. u=rwx,g=rx,o=rx. A mode is either octal (022) or symbolic \
+(u=rwx,g=rx,o= or g-w) and applies to everything the shell creates from then on. The same \
+value is the `umask` option, so it can be given a default in the config file. Only available \
+on Unix.",
+            flags: &[("-S", "Show the permissions that are kept, symbolically")],
+            examples: &[
+                ("umask", "Show the umask, e.g. 0022"),
+                ("umask 077", "Make new files private"),
+                ("umask g-w", "Stop giving the group write access"),
+            ],
+        },
+        CommandType::Uniq => CommandDoc {
+            summary: "Collapse repeated adjacent lines",
+            usage: "uniq [-c] [-d] [file]",
+            description: "Prints the lines of a file, printing a line that repeats on the lines \
+right after it only once. Lines that repeat further apart are kept, so sort the input first to \
+collapse all duplicates. Without a file the lines are read from standard input until end of \
+file (Ctrl+D).",
+            flags: &[
+                ("-c", "Prefix each line with the number of times it repeats"),
+                ("-d", "Print only lines that repeat"),
+            ],
+            examples: &[
+                ("uniq log.txt", "Collapse repeated lines in a file"),
+                ("uniq -c -d log.txt", "Count the lines that repeat"),
+            ],
+        },
+        CommandType::Unzip => CommandDoc {
+            summary: "Extract or list a zip archive",
+            usage: "unzip [-l] <archive.zip> [<dir>]",
+            description: "Extracts an archive into the current directory or <dir>, skipping \
+entries that would end up outside it. Reads archives made by other zip programs, stored or \
+compressed with deflate; encrypted entries and zip64 archives over 4 GiB are not supported.",
+            flags: &[("-l", "List the contents with sizes and times instead of extracting")],
+            examples: &[
+                ("unzip notes.zip", "Extract into the current directory"),
+                ("unzip notes.zip restored", "Extract into a directory named restored"),
+                ("unzip -l notes.zip", "See what an archive holds"),
+            ],
+        },
+        CommandType::Usage => CommandDoc {
+            summary: "Report how often commands are used",
+            usage: "usage [--reset]",
+            description: "Shows per-command invocation counts, failure rates and average \
+durations, recorded locally in the config directory. Set `usage_tracking = false` in the \
+config file to disable tracking.",
+            flags: &[("--reset", "Delete the recorded usage data")],
+            examples: &[("usage", "Show the usage report")],
+        },
+        CommandType::Vault => CommandDoc {
+            summary: "Store secrets in an encrypted vault",
+            usage: "vault set <name> | vault get <name> | vault rm <name> | vault list",
+            description: "Keeps secrets in a file in the config directory, encrypted with a \
+master passphrase. Arguments containing $SECRET:<name> are replaced with the stored value.",
+            flags: &[],
+            examples: &[
+                ("vault set API_TOKEN", "Store a secret"),
+                ("vault list", "List stored secret names"),
+                (
+                    "echo \"token: $SECRET:API_TOKEN\"",
+                    "Use a secret in a command",
+                ),
+            ],
+        },
+        CommandType::Wc => CommandDoc {
+            summary: "Count lines, words and bytes in files",
+            usage: "wc [-l] [-w] [-c] <file>...",
+            description: "Prints the counts for each file, followed by a total when more than \
+one file is given. Without flags all three counts are shown, in the order lines, words, bytes. \
+Words are separated by whitespace.",
+            flags: &[
+                ("-l", "Count lines"),
+                ("-w", "Count words"),
+                ("-c", "Count bytes"),
+            ],
+            examples: &[
+                ("wc notes.txt", "Count everything in a file"),
+                ("wc -l src/main.rs src/lib.rs", "Count lines in two files, with a total"),
+            ],
+        },
+        CommandType::Wizard => CommandDoc {
+            summary: "Build a command line step by step",
+            usage: "wizard <command>",
+            description: "Asks about each flag of <command>, with its description from the \
+manual, and then for each argument, checking it against what the command expects before going \
+on. Flags that conflict with one already chosen are skipped. The finished line is shown and, \
+if confirmed, run; either way it is added to the history, so it can be recalled and changed.",
+            flags: &[],
+            examples: &[
+                ("wizard tar", "Put together a tar command"),
+                ("wizard hash", "Learn the flags of hash while using it"),
+            ],
+        },
+        CommandType::Zip => CommandDoc {
+            summary: "Pack files and directories into a zip archive",
+            usage: "zip <archive.zip> <path>...",
+            description: "Adds the files and directories, with everything below them, to a new \
+archive, replacing <archive.zip> only once it is complete. Files are stored uncompressed, with \
+their permissions and modification times; links are stored as links. Use unzip to extract or \
+list an archive.",
+            flags: &[],
+            examples: &[
+                ("zip notes.zip notes todo.md", "Archive a directory and a file"),
+                ("zip site.zip .", "Archive everything in the current directory"),
+            ],
+        },
+    }
+}
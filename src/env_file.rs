@@ -0,0 +1,153 @@
+//! Per-directory environments: a `.mbct-env` file sets variables and
+//! aliases while the working directory is inside its directory.
+
+use crate::{
+    atomic, config,
+    context::{EnvLayer, ShellContext},
+    hash::{self, Sha256},
+    i18n::Message,
+    lock::FileLock,
+    outln, status, terminal, tr,
+};
+use std::{
+    error::Error,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+
+pub const ENV_FILE_NAME: &str = ".mbct-env";
+const TRUSTED_FILE_NAME: &str = "trusted_env_files.tsv";
+
+/// Files the user chose not to trust, so they are only asked once per
+/// session.
+static DECLINED: Mutex<Vec<(PathBuf, String)>> = Mutex::new(Vec::new());
+
+/// Brings the environment in line with the working directory: reverts the
+/// layers of directories that were left, then applies the env files of the
+/// directories around the new one, outermost first.
+pub fn update(context: &ShellContext) -> Result<(), Box<dyn Error>> {
+    let cwd = context.cwd();
+    for dir in context.pop_env_layers_outside(&cwd) {
+        status!("Unloaded {}", dir.join(ENV_FILE_NAME).display());
+    }
+
+    let active = context.env_layer_dirs();
+    let mut dirs = cwd.ancestors().collect::<Vec<_>>();
+    dirs.reverse();
+    for dir in dirs {
+        let path = dir.join(ENV_FILE_NAME);
+        if active.iter().any(|active| active == dir) || !path.is_file() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        if !is_trusted(&path, &content)? {
+            continue;
+        }
+        let (variables, aliases) =
+            parse(&content).map_err(|error| format!("{}: {}", path.display(), error))?;
+        context.push_env_layer(EnvLayer {
+            dir: dir.to_path_buf(),
+            variables,
+            aliases,
+        });
+        status!("Loaded {}", path.display());
+    }
+    Ok(())
+}
+
+/// Whether the file may be loaded, asking the first time it is seen and
+/// again whenever its content changes.
+fn is_trusted(path: &Path, content: &str) -> Result<bool, Box<dyn Error>> {
+    let digest = digest(content);
+    let trusted_path = config::config_dir()?.join(TRUSTED_FILE_NAME);
+    let _lock = FileLock::acquire(&trusted_path)?;
+    let mut trusted = load_trusted(&trusted_path)?;
+    if trusted.contains(&(path.to_path_buf(), digest.clone())) {
+        return Ok(true);
+    }
+
+    let mut declined = DECLINED.lock().unwrap_or_else(PoisonError::into_inner);
+    let entry = (path.to_path_buf(), digest);
+    if declined.contains(&entry) {
+        return Ok(false);
+    }
+    // Reading a pipe would swallow the next command as the answer.
+    if !io::stdin().is_terminal() {
+        outln!("Not loading {}, which is not trusted yet", path.display());
+        declined.push(entry);
+        return Ok(false);
+    }
+    if !terminal::confirm(&tr!(Message::TrustEnvFile, path.display()))? {
+        declined.push(entry);
+        return Ok(false);
+    }
+
+    trusted.retain(|(trusted_path, _)| trusted_path != path);
+    trusted.push(entry);
+    let content = trusted
+        .iter()
+        .map(|(path, digest)| format!("{}\t{}\n", digest, path.display()))
+        .collect::<String>();
+    atomic::write(&trusted_path, content)?;
+    Ok(true)
+}
+
+fn load_trusted(path: &Path) -> io::Result<Vec<(PathBuf, String)>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(digest, path)| (PathBuf::from(path), digest.to_string()))
+        .collect())
+}
+
+fn digest(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    hash::to_hex(&hasher.finalize())
+}
+
+type Assignments = Vec<(String, String)>;
+
+/// Parses `NAME=value` or `export NAME=value` lines into variables and
+/// `alias name=command` lines into aliases. Values may be quoted; `#`
+/// starts a comment line.
+fn parse(content: &str) -> Result<(Assignments, Assignments), String> {
+    let mut variables = Vec::new();
+    let mut aliases = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (assignment, target) = match line.strip_prefix("alias ") {
+            Some(alias) => (alias, &mut aliases),
+            None => (line.strip_prefix("export ").unwrap_or(line), &mut variables),
+        };
+        let invalid = || format!("line {}: expected NAME=value", index + 1);
+        let (name, value) = assignment.trim().split_once('=').ok_or_else(invalid)?;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(invalid());
+        }
+        target.push((name.to_string(), unquote(value).to_string()));
+    }
+    Ok((variables, aliases))
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
@@ -0,0 +1,22 @@
+//! Runs programs outside the shell, such as build tools.
+
+use crate::{context::ShellContext, output, parse_command::join_quoted, verbose};
+use std::{error::Error, process::Command};
+
+/// Runs `program` in the working directory with the terminal handed over to
+/// it, failing if it exits unsuccessfully.
+pub fn run(context: &ShellContext, program: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+    verbose!("running {} {}", program, join_quoted(args));
+    // Anything already printed must come before the program's output.
+    output::flush()?;
+    let mut command = Command::new(program);
+    command.args(args).current_dir(context.cwd());
+    context.export_last_output(&mut command);
+    let status = command
+        .status()
+        .map_err(|error| format!("Cannot run {}: {}", program, error))?;
+    if !status.success() {
+        return Err(format!("{} {} failed ({})", program, join_quoted(args), status).into());
+    }
+    Ok(())
+}
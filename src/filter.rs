@@ -0,0 +1,121 @@
+use crate::{
+    calendar,
+    units::{parse_duration, parse_size},
+    Command,
+};
+use std::{
+    error::Error,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Size and modification time filters shared by the listing commands.
+#[derive(Debug, Default)]
+pub struct Filters {
+    larger_than: Option<u64>,
+    smaller_than: Option<u64>,
+    newer_than: Option<SystemTime>,
+    older_than: Option<SystemTime>,
+}
+
+impl Filters {
+    pub fn from_command(command: &Command) -> Result<Self, Box<dyn Error>> {
+        let now = SystemTime::now();
+        let size = |flag| command.get_flag_value(flag).map(parse_size).transpose();
+        let time = |flag| {
+            command
+                .get_flag_value(flag)
+                .map(|value| parse_time(value, now))
+                .transpose()
+        };
+
+        Ok(Self {
+            larger_than: size("--larger-than")?,
+            smaller_than: size("--smaller-than")?,
+            newer_than: time("--newer-than")?,
+            older_than: time("--older-than")?,
+        })
+    }
+
+    /// Whether `path` passes every filter. Size filters only match regular
+    /// files; entries whose metadata cannot be read never match.
+    pub fn matches(&self, path: &Path) -> bool {
+        if self.larger_than.is_none()
+            && self.smaller_than.is_none()
+            && self.newer_than.is_none()
+            && self.older_than.is_none()
+        {
+            return true;
+        }
+
+        let Ok(metadata) = fs::metadata(path) else {
+            return false;
+        };
+
+        if self.larger_than.is_some() || self.smaller_than.is_some() {
+            if !metadata.is_file() {
+                return false;
+            }
+            let size = metadata.len();
+            if self.larger_than.is_some_and(|limit| size <= limit)
+                || self.smaller_than.is_some_and(|limit| size >= limit)
+            {
+                return false;
+            }
+        }
+
+        if self.newer_than.is_some() || self.older_than.is_some() {
+            let Ok(modified) = metadata.modified() else {
+                return false;
+            };
+            if self.newer_than.is_some_and(|limit| modified <= limit)
+                || self.older_than.is_some_and(|limit| modified >= limit)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Parses either an age relative to `now` (`30s`, `15m`, `12h`, `2d`, `1w`,
+/// or combinations such as `1d12h`)
+/// or a date (`2024-01-01`, midnight UTC).
+pub fn parse_time(text: &str, now: SystemTime) -> Result<SystemTime, Box<dyn Error>> {
+    let text = text.trim();
+    if text.contains('-') {
+        return parse_date(text);
+    }
+
+    let seconds = parse_duration(text).map_err(|_| {
+        format!(
+            "Invalid age or date: {}, expected e.g. 12h, 2d or 2024-01-01",
+            text
+        )
+    })?;
+    Duration::try_from_secs_f64(seconds)
+        .ok()
+        .and_then(|age| now.checked_sub(age))
+        .ok_or_else(|| format!("Age is too large: {}", text).into())
+}
+
+fn parse_date(text: &str) -> Result<SystemTime, Box<dyn Error>> {
+    let invalid = || format!("Invalid date: {}, expected YYYY-MM-DD", text);
+    let mut parts = text.splitn(3, '-').map(str::parse::<u32>);
+    let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid().into());
+    };
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || day == 0 {
+        return Err(invalid().into());
+    }
+    if day > calendar::days_in_month(year as i32, month) {
+        return Err(invalid().into());
+    }
+
+    let days = calendar::days_from_civil(year as i32, month, day);
+    Ok(UNIX_EPOCH + Duration::from_secs((days * calendar::SECONDS_PER_DAY) as u64))
+}
@@ -0,0 +1,268 @@
+use crate::hash::Sha1;
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+const INDEX_SIGNATURE: &[u8] = b"DIRC";
+const INDEX_HEADER_SIZE: usize = 12;
+const ENTRY_FIXED_SIZE: usize = 62;
+const EXTENDED_FLAG: u16 = 0x4000;
+const OBJECT_ID_SIZE: usize = 20;
+
+const FILE_TYPE_MASK: u32 = 0o170000;
+/// A submodule, whose commit is recorded instead of any content.
+const GITLINK: u32 = 0o160000;
+
+#[derive(Debug)]
+pub struct Repository {
+    pub work_tree: PathBuf,
+    pub git_dir: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum Head {
+    Branch(String),
+    Detached(String),
+}
+
+impl Repository {
+    /// Walks up from `start` looking for a `.git` directory (or a `.git` file
+    /// pointing at one, as used by worktrees and submodules).
+    pub fn discover(start: &Path) -> Option<Self> {
+        for dir in start.ancestors() {
+            let dot_git = dir.join(".git");
+
+            if dot_git.is_dir() {
+                return Some(Self {
+                    work_tree: dir.to_path_buf(),
+                    git_dir: dot_git,
+                });
+            }
+
+            if dot_git.is_file() {
+                let content = fs::read_to_string(&dot_git).ok()?;
+                let git_dir = Path::new(content.strip_prefix("gitdir:")?.trim());
+                return Some(Self {
+                    work_tree: dir.to_path_buf(),
+                    git_dir: dir.join(git_dir),
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn head(&self) -> Result<Head, Box<dyn Error>> {
+        let head = fs::read_to_string(self.git_dir.join("HEAD"))?;
+        let head = head.trim();
+
+        match head.strip_prefix("ref:") {
+            Some(reference) => {
+                let reference = reference.trim();
+                let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+                Ok(Head::Branch(branch.to_string()))
+            }
+            None => Ok(Head::Detached(head.chars().take(7).collect())),
+        }
+    }
+
+    /// Lists tracked files whose content no longer matches the index,
+    /// including deleted ones. Untracked files are not reported. As with git,
+    /// files whose size and modification time still match are taken to be
+    /// unchanged; files where only the time differs are hashed to be sure.
+    pub fn modified_files(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let index = match fs::read(self.git_dir.join("index")) {
+            Ok(index) => index,
+            // A fresh repository has no index until something is staged.
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut modified = Vec::new();
+        for entry in parse_index(&index)? {
+            let path = self.work_tree.join(&entry.path);
+            let is_modified = match fs::symlink_metadata(&path) {
+                Ok(_) if entry.mode & FILE_TYPE_MASK == GITLINK => false,
+                Ok(metadata) => {
+                    let mtime = metadata
+                        .modified()?
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_secs() as u32)
+                        .unwrap_or_default();
+                    if metadata.len() as u32 != entry.size {
+                        true
+                    } else if mtime != entry.mtime {
+                        // Touched, or saved with the same size: only the
+                        // content can tell.
+                        blob_id(&path, &metadata)? != entry.id
+                    } else {
+                        false
+                    }
+                }
+                Err(_) => true,
+            };
+
+            if is_modified {
+                modified.push(entry.path);
+            }
+        }
+
+        Ok(modified)
+    }
+
+    pub fn is_dirty(&self) -> Result<bool, Box<dyn Error>> {
+        Ok(!self.modified_files()?.is_empty())
+    }
+}
+
+impl std::fmt::Display for Head {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Head::Branch(branch) => write!(f, "{}", branch),
+            Head::Detached(commit) => write!(f, "({})", commit),
+        }
+    }
+}
+
+struct IndexEntry {
+    path: String,
+    mtime: u32,
+    size: u32,
+    mode: u32,
+    /// The SHA-1 of the staged blob.
+    id: [u8; OBJECT_ID_SIZE],
+}
+
+/// The id git gives the content at `path` as a blob: the SHA-1 of a
+/// `blob <size>` header and the content, which for symlinks is the target.
+fn blob_id(path: &Path, metadata: &fs::Metadata) -> io::Result<[u8; OBJECT_ID_SIZE]> {
+    let mut hasher = Sha1::new();
+    if metadata.file_type().is_symlink() {
+        let target = fs::read_link(path)?;
+        let target = target.as_os_str().as_encoded_bytes();
+        hasher.update(format!("blob {}\0", target.len()).as_bytes());
+        hasher.update(target);
+        return Ok(hasher.finalize());
+    }
+    if !metadata.is_file() {
+        // Something else now stands where the file was.
+        return Ok([0; OBJECT_ID_SIZE]);
+    }
+
+    hasher.update(format!("blob {}\0", metadata.len()).as_bytes());
+    let mut file = File::open(path)?.take(metadata.len());
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Parses the entries of a version 2, 3 or 4 git index file.
+fn parse_index(data: &[u8]) -> Result<Vec<IndexEntry>, Box<dyn Error>> {
+    if data.len() < INDEX_HEADER_SIZE || &data[..4] != INDEX_SIGNATURE {
+        return Err("Not a git index file".into());
+    }
+
+    let version = read_u32(data, 4)?;
+    if !(2..=4).contains(&version) {
+        return Err(format!("Unsupported git index version: {}", version).into());
+    }
+
+    let entry_count = read_u32(data, 8)?;
+    let mut entries = Vec::with_capacity(entry_count as usize);
+    let mut offset = INDEX_HEADER_SIZE;
+    let mut previous_path = Vec::new();
+
+    for _ in 0..entry_count {
+        let start = offset;
+        let mtime = read_u32(data, offset + 8)?;
+        let mode = read_u32(data, offset + 24)?;
+        let size = read_u32(data, offset + 36)?;
+        let id = data
+            .get(offset + 40..offset + 40 + OBJECT_ID_SIZE)
+            .ok_or("Corrupt git index")?
+            .try_into()?;
+        let flags = read_u16(data, offset + 60)?;
+        offset += ENTRY_FIXED_SIZE;
+        if version >= 3 && flags & EXTENDED_FLAG != 0 {
+            offset += 2;
+        }
+
+        let path = if version == 4 {
+            // Paths are prefix-compressed against the previous entry.
+            let (strip, read) = read_varint(data, offset)?;
+            offset += read;
+            let keep = previous_path
+                .len()
+                .checked_sub(strip)
+                .ok_or("Corrupt git index")?;
+            let suffix = read_c_string(data, offset)?;
+            offset += suffix.len() + 1;
+
+            let mut path = previous_path[..keep].to_vec();
+            path.extend_from_slice(suffix);
+            path
+        } else {
+            let path = read_c_string(data, offset)?.to_vec();
+            // Entries are NUL-padded to a multiple of eight bytes.
+            let entry_length = offset - start + path.len();
+            offset = start + (entry_length / 8 + 1) * 8;
+            path
+        };
+
+        entries.push(IndexEntry {
+            path: String::from_utf8_lossy(&path).into_owned(),
+            mtime,
+            size,
+            mode,
+            id,
+        });
+        previous_path = path;
+    }
+
+    Ok(entries)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Box<dyn Error>> {
+    let bytes = data.get(offset..offset + 4).ok_or("Corrupt git index")?;
+    Ok(u32::from_be_bytes(bytes.try_into()?))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Box<dyn Error>> {
+    let bytes = data.get(offset..offset + 2).ok_or("Corrupt git index")?;
+    Ok(u16::from_be_bytes(bytes.try_into()?))
+}
+
+fn read_c_string(data: &[u8], offset: usize) -> Result<&[u8], Box<dyn Error>> {
+    let rest = data.get(offset..).ok_or("Corrupt git index")?;
+    let end = rest
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or("Corrupt git index")?;
+    Ok(&rest[..end])
+}
+
+/// Reads git's offset-encoded varint, returning the value and the bytes consumed.
+fn read_varint(data: &[u8], offset: usize) -> Result<(usize, usize), Box<dyn Error>> {
+    let mut read = 0;
+    let mut byte = *data.get(offset).ok_or("Corrupt git index")?;
+    let mut value = (byte & 0x7f) as usize;
+    read += 1;
+
+    while byte & 0x80 != 0 {
+        byte = *data.get(offset + read).ok_or("Corrupt git index")?;
+        value = ((value + 1) << 7) | (byte & 0x7f) as usize;
+        read += 1;
+    }
+
+    Ok((value, read))
+}
@@ -0,0 +1,84 @@
+/// Matches `text` against a shell-style glob pattern.
+///
+/// `*` and `?` never match `/`, `**` matches across directories and
+/// `[abc]`, `[a-z]` and `[!abc]` match character classes.
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let text = text.chars().collect::<Vec<char>>();
+    matches_from(&pattern, &text)
+}
+
+fn matches_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            // `**/` also matches zero directories.
+            if rest.first() == Some(&'/') && matches_from(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|skip| matches_from(rest, &text[skip..]))
+        }
+        Some('*') => {
+            for skip in 0..=text.len() {
+                if matches_from(&pattern[1..], &text[skip..]) {
+                    return true;
+                }
+                if text.get(skip) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => match text.first() {
+            Some(&c) if c != '/' => matches_from(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        Some('[') => match (parse_class(pattern), text.first()) {
+            (Some((class_matches, length)), Some(&c)) => {
+                c != '/' && class_matches(c) && matches_from(&pattern[length..], &text[1..])
+            }
+            // An unterminated class is matched literally.
+            (None, Some('[')) => matches_from(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && matches_from(&pattern[2..], &text[1..])
+        }
+        Some(&p) => text.first() == Some(&p) && matches_from(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Parses a `[...]` class at the start of `pattern`, returning a predicate and
+/// the number of pattern characters consumed.
+fn parse_class(pattern: &[char]) -> Option<(impl Fn(char) -> bool, usize)> {
+    let mut index = 1;
+    let negated = matches!(pattern.get(index), Some('!') | Some('^'));
+    if negated {
+        index += 1;
+    }
+
+    let mut ranges = Vec::new();
+    let mut first = true;
+    loop {
+        let c = *pattern.get(index)?;
+        if c == ']' && !first {
+            break;
+        }
+        first = false;
+
+        if pattern.get(index + 1) == Some(&'-')
+            && pattern.get(index + 2).is_some_and(|&end| end != ']')
+        {
+            ranges.push((c, pattern[index + 2]));
+            index += 3;
+        } else {
+            ranges.push((c, c));
+            index += 1;
+        }
+    }
+
+    let predicate =
+        move |c: char| ranges.iter().any(|&(start, end)| start <= c && c <= end) != negated;
+    Some((predicate, index + 1))
+}
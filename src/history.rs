@@ -0,0 +1,157 @@
+use crate::{atomic::AtomicFile, calendar, config, lock::FileLock, options};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+const HISTORY_FILE_NAME: &str = "history.tsv";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Unix time the line was entered at.
+    pub timestamp: i64,
+    pub line: String,
+}
+
+/// The history file as one interactive session sees it. Other sessions
+/// append to the same file, so their lines can be picked up while this one
+/// runs unless the `shared_history` option is off.
+#[derive(Debug, Default)]
+pub struct Session {
+    /// How far into the file this session has read.
+    offset: u64,
+    /// Lines this session wrote that have not been read back yet, so they
+    /// are not taken for another session's.
+    written: VecDeque<String>,
+}
+
+impl Session {
+    /// Starts a session, returning the lines entered before it, the oldest
+    /// first. With `shared_history` off that is nothing.
+    pub fn start() -> (Self, Vec<String>) {
+        let mut session = Self::default();
+        let lines = session.sync();
+        if options::get_bool(options::SHARED_HISTORY) {
+            (session, lines)
+        } else {
+            (session, Vec::new())
+        }
+    }
+
+    /// Appends a line to the persistent history, stamped with the current
+    /// time. Errors are ignored, like in usage tracking, so a broken history
+    /// file never stops a command.
+    pub fn append(&mut self, line: &str) {
+        if try_append(calendar::now(), line).is_ok() {
+            self.written.push_back(clean(line));
+        }
+    }
+
+    /// Lines other sessions appended since the last call, the oldest first.
+    /// Empty when `shared_history` is off, though the file is still read so
+    /// that turning the option on does not bring back old lines.
+    pub fn sync(&mut self) -> Vec<String> {
+        let lines = self.read_new().unwrap_or_default();
+        let mut others = Vec::new();
+        for line in lines {
+            if self.written.front() == Some(&line) {
+                self.written.pop_front();
+            } else {
+                others.push(line);
+            }
+        }
+        if options::get_bool(options::SHARED_HISTORY) {
+            others
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn read_new(&mut self) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut file = match File::open(history_path()?) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+        if file.metadata()?.len() < self.offset {
+            // Pruned since the last read; what is left was seen already.
+            self.offset = file.metadata()?.len();
+            self.written.clear();
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        // A line still being written is left for the next read.
+        let complete = content.rfind('\n').map_or(0, |end| end + 1);
+        self.offset += complete as u64;
+        Ok(parse(&content[..complete])
+            .map(|entry| entry.line)
+            .collect())
+    }
+}
+
+fn clean(line: &str) -> String {
+    // Keeps the file one entry per line.
+    line.replace(['\t', '\n', '\r'], " ")
+}
+
+fn try_append(timestamp: i64, line: &str) -> Result<(), Box<dyn Error>> {
+    let path = history_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut file = File::options().create(true).append(true).open(&path)?;
+    file.write_all(format!("{}\t{}\n", timestamp, clean(line)).as_bytes())?;
+    Ok(())
+}
+
+/// Every recorded entry, the oldest first.
+pub fn load() -> Result<Vec<Entry>, Box<dyn Error>> {
+    let content = match fs::read_to_string(history_path()?) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+    Ok(parse(&content).collect())
+}
+
+fn parse(content: &str) -> impl Iterator<Item = Entry> + '_ {
+    content.lines().filter_map(|line| {
+        let (timestamp, line) = line.split_once('\t')?;
+        Some(Entry {
+            timestamp: timestamp.parse().ok()?,
+            line: line.to_string(),
+        })
+    })
+}
+
+/// Rewrites the history with only the entries `keep` returns, atomically
+/// and under the lock, so lines appended by other sessions are not lost.
+/// Returns how many entries were removed.
+pub fn retain(keep: impl FnOnce(Vec<Entry>) -> Vec<Entry>) -> Result<usize, Box<dyn Error>> {
+    let path = history_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let entries = load()?;
+    let count = entries.len();
+    let kept = keep(entries);
+    let removed = count - kept.len();
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    let content = kept
+        .iter()
+        .map(|entry| format!("{}\t{}\n", entry.timestamp, entry.line))
+        .collect::<String>();
+    let mut file = AtomicFile::create(&path)?;
+    file.write_all(content.as_bytes())?;
+    file.commit()?;
+    Ok(removed)
+}
+
+fn history_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(HISTORY_FILE_NAME))
+}
@@ -0,0 +1,233 @@
+//! Translations of the shell's own messages: parse errors, the frame of
+//! `help` and prompts. Each locale has a catalog; messages missing from one
+//! fall back to English. Command output and documentation stay in English.
+
+use crate::options;
+use std::{env, fmt::Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    English,
+    German,
+}
+
+impl Locale {
+    /// Reads a language code such as `de`, or a POSIX locale name such as
+    /// `de_DE.UTF-8`.
+    fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['_', '.', '@', '-']).next()?;
+        match language.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Locale::English),
+            "de" => Some(Locale::German),
+            _ => None,
+        }
+    }
+}
+
+/// The locale set with the `language` option or, when that is `auto`, the
+/// first of `LC_ALL`, `LC_MESSAGES` and `LANG` that is set. Unknown
+/// languages get English.
+pub fn locale() -> Locale {
+    let language = options::get(options::LANGUAGE);
+    if language != "auto" {
+        return Locale::from_tag(&language).unwrap_or(Locale::English);
+    }
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Locale::from_tag(&value))
+        .unwrap_or(Locale::English)
+}
+
+/// A translatable message. `{}` in its text stands for an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    // Parse errors.
+    NoCommandGiven,
+    UnterminatedQuote,
+    UnknownCommand,
+    AmbiguousCommand,
+    UnsupportedFlag,
+    MissingFlagValue,
+    SecretExpansion,
+    EventNotFound,
+    InvalidDefaults,
+    ConflictingFlags,
+    MissingRequiredFlag,
+    WrongArgumentsCount,
+    InvalidArgument,
+    InvalidFlagValue,
+    ErrorLocation,
+    ExactCount,
+    AtLeastCount,
+    AtMostCount,
+    // Argument types.
+    Text,
+    ExistingPath,
+    ExistingFile,
+    Directory,
+    Integer,
+    Duration,
+    Url,
+    // Running commands.
+    CommandFailed,
+    // Help.
+    Usage,
+    Flags,
+    Examples,
+    AvailableCommands,
+    HelpHint,
+    // Prompts.
+    YesNo,
+    More,
+    Passphrase,
+    ConfirmPassphrase,
+    VaultPassphrase,
+    NewVaultPassphrase,
+    SecretValue,
+    Overwrite,
+    TrustEnvFile,
+    ChooseKeys,
+    ChooseNumbers,
+    RemoveWhich,
+}
+
+impl Message {
+    pub fn text(self) -> &'static str {
+        let translated = match locale() {
+            Locale::English => None,
+            Locale::German => german(self),
+        };
+        translated.unwrap_or_else(|| english(self))
+    }
+}
+
+/// Fills the `{}` in the text of `message` with `arguments`, in order.
+pub fn format(message: Message, arguments: &[&dyn Display]) -> String {
+    let mut parts = message.text().split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    let mut arguments = arguments.iter();
+    for part in parts {
+        if let Some(argument) = arguments.next() {
+            text.push_str(&argument.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+/// Formats a [`Message`] in the current locale, like `format!`.
+#[macro_export]
+macro_rules! tr {
+    ($message:expr $(, $argument:expr)* $(,)?) => {
+        $crate::i18n::format($message, &[$(&$argument),*])
+    };
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::NoCommandGiven => "No command given",
+        Message::UnterminatedQuote => "Unterminated quote",
+        Message::UnknownCommand => "Unknown command: {}",
+        Message::AmbiguousCommand => "Ambiguous command: {} could be {}",
+        Message::UnsupportedFlag => "Unsupported flag: {}",
+        Message::MissingFlagValue => "Missing value for flag: {}",
+        Message::SecretExpansion => "Could not expand secret: {}",
+        Message::EventNotFound => "Event not found: {}",
+        Message::InvalidDefaults => "{} in {} in the config file",
+        Message::ConflictingFlags => "{} cannot be used together with {}",
+        Message::MissingRequiredFlag => "{} can only be used together with {}",
+        Message::WrongArgumentsCount => "Wrong number of arguments: expected {}, got {}",
+        Message::InvalidArgument => "Argument {} must be {}: {}",
+        Message::InvalidFlagValue => "The value of {} must be {}: {}",
+        Message::ErrorLocation => "at bytes {}..{}",
+        Message::ExactCount => "exactly {}",
+        Message::AtLeastCount => "at least {}",
+        Message::AtMostCount => "up to {}",
+        Message::Text => "text",
+        Message::ExistingPath => "an existing path",
+        Message::ExistingFile => "an existing file",
+        Message::Directory => "a directory",
+        Message::Integer => "an integer",
+        Message::Duration => "a duration such as 30s or 1h30m",
+        Message::Url => "an http or https URL",
+        Message::CommandFailed => "An error occured: {}",
+        Message::Usage => "Usage: {}",
+        Message::Flags => "Flags:",
+        Message::Examples => "Examples:",
+        Message::AvailableCommands => "Available commands:",
+        Message::HelpHint => "Run `help <command>` for usage or `man <command>` for details.",
+        Message::YesNo => "[y/N]",
+        Message::More => "--More-- ({}%)",
+        Message::Passphrase => "Passphrase: ",
+        Message::ConfirmPassphrase => "Confirm passphrase: ",
+        Message::VaultPassphrase => "Vault passphrase: ",
+        Message::NewVaultPassphrase => "New vault passphrase: ",
+        Message::SecretValue => "Value for {}: ",
+        Message::Overwrite => "Overwrite {}?",
+        Message::TrustEnvFile => "Trust and load {}?",
+        Message::ChooseKeys => "space selects, a selects all, enter confirms, q cancels",
+        Message::ChooseNumbers => "Numbers such as 1 3-5, or all; nothing cancels: ",
+        Message::RemoveWhich => "Remove which of these?",
+    }
+}
+
+fn german(message: Message) -> Option<&'static str> {
+    let text = match message {
+        Message::NoCommandGiven => "Kein Befehl angegeben",
+        Message::UnterminatedQuote => "Nicht geschlossenes Anführungszeichen",
+        Message::UnknownCommand => "Unbekannter Befehl: {}",
+        Message::AmbiguousCommand => "Mehrdeutiger Befehl: {} kann {} sein",
+        Message::UnsupportedFlag => "Nicht unterstützte Option: {}",
+        Message::MissingFlagValue => "Fehlender Wert für Option: {}",
+        Message::SecretExpansion => "Geheimnis konnte nicht eingesetzt werden: {}",
+        Message::EventNotFound => "Eintrag nicht im Verlauf: {}",
+        Message::InvalidDefaults => "{} bei {} in der Konfigurationsdatei",
+        Message::ConflictingFlags => "{} kann nicht zusammen mit {} verwendet werden",
+        Message::MissingRequiredFlag => "{} kann nur zusammen mit {} verwendet werden",
+        Message::WrongArgumentsCount => "Falsche Anzahl von Argumenten: erwartet {}, erhalten {}",
+        Message::InvalidArgument => "Argument {} muss {} sein: {}",
+        Message::InvalidFlagValue => "Der Wert von {} muss {} sein: {}",
+        Message::ErrorLocation => "bei Bytes {}..{}",
+        Message::ExactCount => "genau {}",
+        Message::AtLeastCount => "mindestens {}",
+        Message::AtMostCount => "höchstens {}",
+        Message::Text => "Text",
+        Message::ExistingPath => "ein vorhandener Pfad",
+        Message::ExistingFile => "eine vorhandene Datei",
+        Message::Directory => "ein Verzeichnis",
+        Message::Integer => "eine ganze Zahl",
+        Message::Duration => "eine Dauer wie 30s oder 1h30m",
+        Message::Url => "eine http- oder https-URL",
+        Message::CommandFailed => "Ein Fehler ist aufgetreten: {}",
+        Message::Usage => "Aufruf: {}",
+        Message::Flags => "Optionen:",
+        Message::Examples => "Beispiele:",
+        Message::AvailableCommands => "Verfügbare Befehle:",
+        Message::HelpHint => "`help <Befehl>` zeigt den Aufruf, `man <Befehl>` die Einzelheiten.",
+        Message::YesNo => "[j/N]",
+        Message::More => "--Mehr-- ({}%)",
+        Message::Passphrase => "Passphrase: ",
+        Message::ConfirmPassphrase => "Passphrase bestätigen: ",
+        Message::VaultPassphrase => "Passphrase des Tresors: ",
+        Message::NewVaultPassphrase => "Neue Passphrase des Tresors: ",
+        Message::SecretValue => "Wert für {}: ",
+        Message::Overwrite => "{} überschreiben?",
+        Message::TrustEnvFile => "{} vertrauen und laden?",
+        Message::ChooseKeys => "Leertaste wählt, a wählt alle, Enter bestätigt, q bricht ab",
+        Message::ChooseNumbers => "Nummern wie 1 3-5 oder all; nichts bricht ab: ",
+        Message::RemoveWhich => "Welche davon entfernen?",
+    };
+    Some(text)
+}
+
+/// Whether `answer` to a yes or no question means yes.
+pub fn is_yes(answer: &str) -> bool {
+    let answer = answer.trim().to_lowercase();
+    let localized: &[&str] = match locale() {
+        Locale::English => &[],
+        Locale::German => &["j", "ja"],
+    };
+    matches!(answer.as_str(), "y" | "yes") || localized.contains(&answer.as_str())
+}
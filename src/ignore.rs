@@ -0,0 +1,91 @@
+use crate::glob;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const IGNORE_FILE_NAME: &str = ".gitignore";
+
+#[derive(Debug, Clone)]
+struct Rule {
+    base: PathBuf,
+    pattern: String,
+    negated: bool,
+    directory_only: bool,
+    anchored: bool,
+}
+
+/// The `.gitignore` rules in effect for a directory, including the ones
+/// inherited from its parents.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    /// Returns the rules for `dir`, adding its `.gitignore` (if any) to the
+    /// ones inherited from the parent directory.
+    pub fn for_directory(&self, dir: &Path) -> Self {
+        let mut rules = self.clone();
+        if let Ok(content) = fs::read_to_string(dir.join(IGNORE_FILE_NAME)) {
+            rules.add_rules(dir, &content);
+        }
+        rules
+    }
+
+    fn add_rules(&mut self, base: &Path, content: &str) {
+        for line in content.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (negated, line) = match line.strip_prefix('!') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            let (directory_only, line) = match line.strip_suffix('/') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+            let anchored = line.contains('/');
+
+            self.rules.push(Rule {
+                base: base.to_path_buf(),
+                pattern: line.trim_start_matches('/').to_string(),
+                negated,
+                directory_only,
+                anchored,
+            });
+        }
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+
+        for rule in &self.rules {
+            if rule.directory_only && !is_dir {
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(&rule.base) else {
+                continue;
+            };
+
+            let is_match = if rule.anchored {
+                glob::matches(
+                    &rule.pattern,
+                    &relative.to_string_lossy().replace('\\', "/"),
+                )
+            } else {
+                path.file_name()
+                    .is_some_and(|name| glob::matches(&rule.pattern, &name.to_string_lossy()))
+            };
+
+            if is_match {
+                ignored = !rule.negated;
+            }
+        }
+
+        ignored
+    }
+}
@@ -1,3 +1,53 @@
+pub mod aho_corasick;
+pub mod atomic;
+mod calendar;
+mod clipboard;
+mod commands;
+mod config;
+pub mod context;
+mod copy;
+pub mod crypto;
+mod docs;
+pub mod env_file;
+mod external;
+mod filter;
+mod gitinfo;
+mod glob;
+pub mod hash;
+pub mod history;
+pub mod i18n;
+mod ignore;
+pub mod inflate;
+mod json;
+mod listing;
+mod lock;
+pub mod log;
+mod mounts;
+mod notes;
+pub mod options;
+pub mod output;
+mod pager;
 pub mod parse_command;
+pub mod permissions;
+pub mod png;
+mod project;
+pub mod prompt;
+pub mod qr;
+pub mod reminders;
+pub mod report;
+pub mod startup;
+mod sysinfo;
+pub mod tar;
+mod tasks;
+mod terminal;
+mod timezone;
+mod units;
+pub mod usage;
+#[cfg(unix)]
+mod users;
+mod variables;
+pub mod vault;
+mod walk;
+pub mod zip;
 
-pub use self::parse_command::Command;
+pub use self::parse_command::{Command, ExecutableCommand, ParsedLine};
@@ -0,0 +1,21 @@
+use crate::options;
+use std::{cmp::Ordering, path::Path};
+
+/// Ordering shared by all listing commands: by path, optionally with
+/// directories before files.
+pub fn compare_entries(a: &Path, b: &Path, group_dirs_first: bool) -> Ordering {
+    if group_dirs_first {
+        let by_kind = b.is_dir().cmp(&a.is_dir());
+        if by_kind != Ordering::Equal {
+            return by_kind;
+        }
+    }
+
+    a.cmp(b)
+}
+
+/// Whether directories should be listed first: set by `--group-dirs-first`,
+/// or by the `group_dirs_first` option.
+pub fn group_dirs_first(flag_given: bool) -> bool {
+    flag_given || options::get_bool(options::GROUP_DIRS_FIRST)
+}
@@ -0,0 +1,30 @@
+use std::{
+    ffi::OsString,
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// An exclusive advisory lock guarding a file shared between running
+/// instances. The lock lives on a separate `<file>.lock` file, because atomic
+/// writes replace the data file itself. It is released when dropped.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Blocks until no other instance holds the lock for `path`.
+    pub fn acquire(path: &Path) -> io::Result<Self> {
+        let mut lock_path = OsString::from(path.as_os_str());
+        lock_path.push(".lock");
+
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(PathBuf::from(lock_path))?;
+        file.lock()?;
+
+        Ok(Self { _file: file })
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{output, vault};
+use std::{
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+impl TryFrom<&str> for Level {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: &str) -> Result<Self, Box<dyn Error>> {
+        match value {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!(
+                "Unknown log level: {} (expected error, warn, info, debug or trace)",
+                value
+            )
+            .into()),
+        }
+    }
+}
+
+/// The subsystem a log record comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Config,
+    Executor,
+    Parser,
+    Vault,
+    Walk,
+}
+
+impl Target {
+    pub const ALL: &'static [Target] = &[
+        Target::Config,
+        Target::Executor,
+        Target::Parser,
+        Target::Vault,
+        Target::Walk,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Target::Config => "config",
+            Target::Executor => "executor",
+            Target::Parser => "parser",
+            Target::Vault => "vault",
+            Target::Walk => "walk",
+        }
+    }
+}
+
+impl TryFrom<&str> for Target {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Target::ALL
+            .iter()
+            .find(|target| target.name() == value)
+            .copied()
+            .ok_or_else(|| format!("Unknown log target: {}", value).into())
+    }
+}
+
+struct Logger {
+    targets: Vec<Target>,
+    level: Level,
+    file: Option<(PathBuf, File)>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+    targets: Vec::new(),
+    level: Level::Debug,
+    file: None,
+});
+
+/// Lets [`enabled`] skip the lock while logging is off, which is almost always.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Starts logging records of `targets` up to `level`, appending them to
+/// `file` when given and printing them to stderr otherwise.
+pub fn enable(targets: Vec<Target>, level: Level, file: Option<&Path>) -> io::Result<()> {
+    let file = match file {
+        Some(path) => Some((
+            path.to_path_buf(),
+            File::options().create(true).append(true).open(path)?,
+        )),
+        None => None,
+    };
+
+    let mut logger = LOGGER.lock().unwrap_or_else(PoisonError::into_inner);
+    *logger = Logger {
+        targets,
+        level,
+        file,
+    };
+    ACTIVE.store(!logger.targets.is_empty(), Ordering::Relaxed);
+    Ok(())
+}
+
+pub fn disable() {
+    let mut logger = LOGGER.lock().unwrap_or_else(PoisonError::into_inner);
+    logger.targets.clear();
+    logger.file = None;
+    ACTIVE.store(false, Ordering::Relaxed);
+}
+
+/// The enabled targets, the level and the log file, if any.
+pub fn status() -> (Vec<Target>, Level, Option<PathBuf>) {
+    let logger = LOGGER.lock().unwrap_or_else(PoisonError::into_inner);
+    (
+        logger.targets.clone(),
+        logger.level,
+        logger.file.as_ref().map(|(path, _)| path.clone()),
+    )
+}
+
+pub fn enabled(target: Target, level: Level) -> bool {
+    if !ACTIVE.load(Ordering::Relaxed) {
+        return false;
+    }
+    let logger = LOGGER.lock().unwrap_or_else(PoisonError::into_inner);
+    level <= logger.level && logger.targets.contains(&target)
+}
+
+/// Writes one record as `<unix time> <level> <target>: <message>`. Secrets
+/// filled in from the vault are replaced by their `$SECRET:NAME` references,
+/// since log files end up attached to bug reports.
+pub fn write(target: Target, level: Level, args: fmt::Arguments) {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let line = format!(
+        "{}.{:03} {:<5} {}: {}\n",
+        time.as_secs(),
+        time.subsec_millis(),
+        level.name().to_uppercase(),
+        target.name(),
+        vault::redact(&args.to_string())
+    );
+
+    let mut logger = LOGGER.lock().unwrap_or_else(PoisonError::into_inner);
+    // Diagnostics must never make a command fail.
+    let _ = match &mut logger.file {
+        Some((_, file)) => file.write_all(line.as_bytes()),
+        None => {
+            // Keeps records in order with the buffered command output.
+            let _ = output::flush();
+            io::stderr().write_all(line.as_bytes())
+        }
+    };
+}
+
+/// Logs a record for a subsystem, e.g. `log!(Parser, Debug, "{} tokens", n)`.
+/// The message is only formatted when `debuglog` enabled the target.
+#[macro_export]
+macro_rules! log {
+    ($target:ident, $level:ident, $($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Target::$target, $crate::log::Level::$level) {
+            $crate::log::write(
+                $crate::log::Target::$target,
+                $crate::log::Level::$level,
+                format_args!($($arg)*),
+            );
+        }
+    };
+}
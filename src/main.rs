@@ -1,31 +1,153 @@
-use my_basic_cli_tools::Command;
-use std::{
-    error::Error,
-    io::{self, Write},
+use my_basic_cli_tools::{
+    context::ShellContext,
+    env_file, history,
+    i18n::Message,
+    options, out, outln,
+    output::{self, Captured},
+    parse_command::{expand_history, join_quoted, CommandType},
+    permissions, prompt, reminders,
+    report::{self, Verbosity},
+    startup, tr, usage, vault, verbose, ParsedLine,
 };
+use std::{env, error::Error, io, process, time::Instant};
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    // Everything after `-c` belongs to the command to run.
+    let command_line = args.iter().position(|arg| arg == "-c").map(|index| {
+        let words = args.split_off(index)[1..].to_vec();
+        match &words[..] {
+            [line] => line.clone(),
+            _ => join_quoted(&words),
+        }
+    });
+
+    report::set_verbosity(Verbosity::from_args(args.iter().cloned()));
+    if args.iter().any(|arg| arg == "--accessible") {
+        options::set(options::ACCESSIBLE, "true")?;
+    }
+
+    if args.iter().any(|arg| arg == "--profile-startup") {
+        for (subsystem, duration) in startup::profile() {
+            outln!(
+                "{:<10}{:>10.3} ms",
+                subsystem,
+                duration.as_secs_f64() * 1000.0
+            );
+        }
+    }
+
+    permissions::apply_configured_umask();
+    let context = ShellContext::new()?;
+    context.cancellation.cancel_on_interrupt()?;
+
+    if let Some(line) = command_line {
+        let succeeded = run_line(&context, &line, None);
+        output::flush()?;
+        process::exit(if succeeded { 0 } else { 1 });
+    }
+
+    // Starting inside a directory counts as entering it.
+    if let Err(error) = env_file::update(&context) {
+        outln!("{}", error);
+    }
+
+    let (mut history, earlier_lines) = history::Session::start();
+    for line in earlier_lines {
+        context.add_history(&line);
+    }
+
     let mut input = String::new();
 
     loop {
-        print!("> ");
+        reminders::announce_due();
+        for line in history.sync() {
+            context.add_history(&line);
+        }
+        out!("{}", prompt::render(&context));
 
         // cleanup
-        io::stdout().flush()?;
+        output::flush()?;
         input.clear();
 
-        io::stdin().read_line(&mut input)?;
+        if io::stdin().read_line(&mut input)? == 0 {
+            // End of input, e.g. Ctrl-D or a closed pipe.
+            outln!();
+            return Ok(output::flush()?);
+        }
 
-        let command = match Command::try_from(input.trim().to_owned()) {
-            Ok(command) => command,
-            Err(error) => {
-                println!("{}", error);
-                continue;
+        run_line(&context, &input, Some(&mut history));
+    }
+}
+
+/// Parses and runs one line of input, returning whether it succeeded.
+/// Interactive sessions pass their history, which the line is saved to.
+fn run_line(context: &ShellContext, input: &str, history: Option<&mut history::Session>) -> bool {
+    let input = input.trim();
+    let input = match expand_history(input, &context.history()) {
+        Ok(expanded) => {
+            if expanded != input {
+                // Show what is about to run, as other shells do.
+                outln!("{}", expanded);
             }
-        };
+            expanded
+        }
+        Err(error) => {
+            outln!("{}", error);
+            return false;
+        }
+    };
+    if !input.is_empty() {
+        context.add_history(&input);
+        if let Some(history) = history {
+            history.append(&input);
+        }
+    }
 
-        if let Err(e) = command.execute() {
-            println!("An error occured: {}", e);
+    let input = context.expand_alias(&input);
+    let command = match ParsedLine::try_from(&*input) {
+        Ok(ParsedLine::Command(command)) => command,
+        Ok(ParsedLine::Nothing) => return true,
+        Err(error) => {
+            outln!("{}", vault::redact(&error.to_string()));
+            return false;
         }
+    };
+
+    if report::verbosity() >= Verbosity::Debug {
+        outln!("parsed: {}", command.describe());
+    }
+
+    let command_type = command.command_type;
+    // Output of commands that handle secrets is not kept, so `lastout` and
+    // `LAST_OUTPUT` never hold one.
+    let handles_secrets = command_type == CommandType::Vault || command.uses_secrets();
+    let started = Instant::now();
+    output::start_capture();
+    let interruptible = context.cancellation.interruptible();
+    let result = command.bind(context).execute();
+    drop(interruptible);
+    let captured = output::finish_capture();
+    let elapsed = started.elapsed();
+    // Showing the last output keeps it around, so it can be copied after.
+    if handles_secrets {
+        context.set_last_output(Captured::default());
+    } else if command_type != CommandType::Lastout {
+        context.set_last_output(captured);
+    }
+    usage::record(command_type, elapsed, result.is_ok());
+
+    if let Err(e) = &result {
+        // Errors may quote an argument, and with it a secret.
+        outln!(
+            "{}",
+            tr!(Message::CommandFailed, vault::redact(&e.to_string()))
+        );
     }
+    verbose!(
+        "{} took {:.3} ms",
+        command_type.name(),
+        elapsed.as_secs_f64() * 1000.0
+    );
+    result.is_ok()
 }
@@ -0,0 +1,301 @@
+use std::{
+    error::Error,
+    io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone)]
+pub struct Mount {
+    /// What is mounted: a device path, or a name such as `tmpfs`.
+    pub device: String,
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    pub options: Vec<String>,
+}
+
+/// Sizes in bytes of a filesystem.
+#[derive(Debug, Clone, Copy)]
+pub struct Space {
+    pub total: u64,
+    pub free: u64,
+    /// Free space that unprivileged users may use; less than `free` when
+    /// blocks are reserved for root.
+    pub available: u64,
+}
+
+impl Space {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+
+    /// Percentage of the space users may have that is used, rounded up the
+    /// way `df` does.
+    pub fn used_percent(&self) -> u64 {
+        let usable = self.used() + self.available;
+        if usable == 0 {
+            return 0;
+        }
+        (self.used() * 100).div_ceil(usable)
+    }
+}
+
+/// Lists the mounted filesystems in mount order.
+pub fn list() -> Result<Vec<Mount>, Box<dyn Error>> {
+    platform::list()
+}
+
+/// The size and free space of the filesystem that contains `path`.
+pub fn space(path: &Path) -> io::Result<Space> {
+    platform::space(path)
+}
+
+/// The mount that `path`, which should be canonical, lives on: the one with
+/// the longest mount point above it. A later mount over the same point
+/// hides earlier ones.
+pub fn containing<'a>(mounts: &'a [Mount], path: &Path) -> Option<&'a Mount> {
+    mounts
+        .iter()
+        .filter(|mount| path.starts_with(&mount.mount_point))
+        .max_by_key(|mount| mount.mount_point.components().count())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Mount, Space};
+    use std::{
+        error::Error,
+        ffi::{c_char, c_int, c_ulong, CString},
+        fs, io,
+        os::unix::ffi::OsStrExt,
+        path::{Path, PathBuf},
+    };
+
+    const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+    /// `struct statvfs`; the block counts are `unsigned long` wide.
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct StatVfs {
+        f_bsize: c_ulong,
+        f_frsize: c_ulong,
+        f_blocks: c_ulong,
+        f_bfree: c_ulong,
+        f_bavail: c_ulong,
+        f_files: c_ulong,
+        f_ffree: c_ulong,
+        f_favail: c_ulong,
+        f_fsid: c_ulong,
+        #[cfg(target_pointer_width = "32")]
+        f_unused: c_int,
+        f_flag: c_ulong,
+        f_namemax: c_ulong,
+        f_spare: [c_int; 6],
+    }
+
+    extern "C" {
+        fn statvfs(path: *const c_char, buf: *mut StatVfs) -> c_int;
+    }
+
+    // `c_ulong` is only 32 bits on 32-bit targets.
+    #[allow(clippy::useless_conversion)]
+    pub fn space(path: &Path) -> io::Result<Space> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        let mut stat = std::mem::MaybeUninit::<StatVfs>::uninit();
+        // SAFETY: `path` is NUL-terminated and `stat` is large enough.
+        if unsafe { statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: statvfs filled in `stat` when it succeeded.
+        let stat = unsafe { stat.assume_init() };
+        let block = u64::from(stat.f_frsize);
+        Ok(Space {
+            total: u64::from(stat.f_blocks) * block,
+            free: u64::from(stat.f_bfree) * block,
+            available: u64::from(stat.f_bavail) * block,
+        })
+    }
+
+    pub fn list() -> Result<Vec<Mount>, Box<dyn Error>> {
+        fs::read_to_string(MOUNTINFO_PATH)?
+            .lines()
+            .map(|line| {
+                parse_line(line)
+                    .ok_or_else(|| format!("Unexpected mountinfo line: {}", line).into())
+            })
+            .collect()
+    }
+
+    /// Parses a line such as
+    /// `36 35 98:0 / /mnt rw,noatime master:1 - ext4 /dev/sda1 rw,errors=continue`.
+    /// A variable number of optional fields precedes the `-` separator.
+    fn parse_line(line: &str) -> Option<Mount> {
+        let (mount_fields, source_fields) = line.split_once(" - ")?;
+        let mount_fields = mount_fields.split(' ').collect::<Vec<_>>();
+        let mut source_fields = source_fields.split(' ');
+
+        let fs_type = source_fields.next()?;
+        let device = source_fields.next()?;
+        Some(Mount {
+            device: unescape(device),
+            mount_point: PathBuf::from(unescape(mount_fields.get(4)?)),
+            fs_type: unescape(fs_type),
+            options: mount_fields
+                .get(5)?
+                .split(',')
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+
+    /// Decodes the `\ooo` octal escapes the kernel uses for spaces, tabs,
+    /// newlines and backslashes.
+    fn unescape(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let escape = bytes.get(i + 1..i + 4).filter(|digits| {
+                bytes[i] == b'\\' && digits.iter().all(|digit| (b'0'..=b'7').contains(digit))
+            });
+            match escape {
+                Some(digits) => {
+                    decoded.push(digits.iter().fold(0u8, |value, digit| {
+                        value.wrapping_mul(8).wrapping_add(digit - b'0')
+                    }));
+                    i += 4;
+                }
+                None => {
+                    decoded.push(bytes[i]);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{Mount, Space};
+    use std::{
+        error::Error,
+        ffi::{c_char, c_int, CStr, CString},
+        io,
+        os::unix::ffi::OsStrExt,
+        path::{Path, PathBuf},
+        slice,
+    };
+
+    const MNT_NOWAIT: c_int = 2;
+    const MFSTYPENAMELEN: usize = 16;
+    const MAXPATHLEN: usize = 1024;
+
+    /// The flags `mount` reports, with the names it uses.
+    const FLAGS: &[(u32, &str)] = &[
+        (0x0000_0001, "read-only"),
+        (0x0000_0002, "synchronous"),
+        (0x0000_0004, "noexec"),
+        (0x0000_0008, "nosuid"),
+        (0x0000_0010, "nodev"),
+        (0x0000_1000, "local"),
+        (0x0000_4000, "root"),
+        (0x0010_0000, "journaled"),
+        (0x1000_0000, "nobrowse"),
+    ];
+
+    /// `struct statfs` with 64-bit inodes.
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct StatFs {
+        f_bsize: u32,
+        f_iosize: i32,
+        f_blocks: u64,
+        f_bfree: u64,
+        f_bavail: u64,
+        f_files: u64,
+        f_ffree: u64,
+        f_fsid: [i32; 2],
+        f_owner: u32,
+        f_type: u32,
+        f_flags: u32,
+        f_fssubtype: u32,
+        f_fstypename: [c_char; MFSTYPENAMELEN],
+        f_mntonname: [c_char; MAXPATHLEN],
+        f_mntfromname: [c_char; MAXPATHLEN],
+        f_flags_ext: u32,
+        f_reserved: [u32; 7],
+    }
+
+    extern "C" {
+        #[cfg_attr(target_arch = "x86_64", link_name = "getmntinfo$INODE64")]
+        fn getmntinfo(mntbufp: *mut *mut StatFs, flags: c_int) -> c_int;
+        #[cfg_attr(target_arch = "x86_64", link_name = "statfs$INODE64")]
+        fn statfs(path: *const c_char, buf: *mut StatFs) -> c_int;
+    }
+
+    pub fn space(path: &Path) -> io::Result<Space> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        let mut stat = std::mem::MaybeUninit::<StatFs>::uninit();
+        // SAFETY: `path` is NUL-terminated and `stat` is large enough.
+        if unsafe { statfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: statfs filled in `stat` when it succeeded.
+        let stat = unsafe { stat.assume_init() };
+        let block = u64::from(stat.f_bsize);
+        Ok(Space {
+            total: stat.f_blocks * block,
+            free: stat.f_bfree * block,
+            available: stat.f_bavail * block,
+        })
+    }
+
+    pub fn list() -> Result<Vec<Mount>, Box<dyn Error>> {
+        let mut buffer = std::ptr::null_mut();
+        // SAFETY: getmntinfo points `buffer` at `count` entries it owns.
+        let count = unsafe { getmntinfo(&mut buffer, MNT_NOWAIT) };
+        if count <= 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        // SAFETY: the entries stay valid until the next getmntinfo call.
+        let entries = unsafe { slice::from_raw_parts(buffer, count as usize) };
+
+        Ok(entries
+            .iter()
+            .map(|entry| Mount {
+                device: text(&entry.f_mntfromname),
+                mount_point: PathBuf::from(text(&entry.f_mntonname)),
+                fs_type: text(&entry.f_fstypename),
+                options: FLAGS
+                    .iter()
+                    .filter(|(flag, _)| entry.f_flags & flag != 0)
+                    .map(|(_, name)| name.to_string())
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn text(field: &[c_char]) -> String {
+        // SAFETY: the kernel NUL-terminates every name field.
+        unsafe { CStr::from_ptr(field.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use super::{Mount, Space};
+    use std::{error::Error, io, path::Path};
+
+    pub fn list() -> Result<Vec<Mount>, Box<dyn Error>> {
+        Err("Listing mounted filesystems is not supported on this platform".into())
+    }
+
+    pub fn space(_path: &Path) -> io::Result<Space> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Reading free space is not supported on this platform",
+        ))
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{atomic::AtomicFile, config, lock::FileLock};
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+const NOTES_FILE_NAME: &str = "notes.tsv";
+
+#[derive(Debug, Clone)]
+pub struct Note {
+    pub id: u64,
+    /// Unix time the note was added at.
+    pub created: i64,
+    pub text: String,
+}
+
+/// Adds a note, returning its id.
+pub fn add(created: i64, text: &str) -> Result<u64, Box<dyn Error>> {
+    let path = notes_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut notes = load()?;
+    let id = notes.iter().map(|note| note.id).max().unwrap_or(0) + 1;
+    notes.push(Note {
+        id,
+        created,
+        text: text.to_string(),
+    });
+    save(&notes)?;
+    Ok(id)
+}
+
+/// Replaces the text of a note, returning whether it existed.
+pub fn update(id: u64, text: &str) -> Result<bool, Box<dyn Error>> {
+    let path = notes_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut notes = load()?;
+    let Some(note) = notes.iter_mut().find(|note| note.id == id) else {
+        return Ok(false);
+    };
+    note.text = text.to_string();
+    save(&notes)?;
+    Ok(true)
+}
+
+pub fn get(id: u64) -> Result<Option<Note>, Box<dyn Error>> {
+    Ok(load()?.into_iter().find(|note| note.id == id))
+}
+
+/// All notes, the oldest first.
+pub fn list() -> Result<Vec<Note>, Box<dyn Error>> {
+    let mut notes = load()?;
+    notes.sort_by_key(|note| (note.created, note.id));
+    Ok(notes)
+}
+
+/// Notes containing `term`, ignoring case.
+pub fn search(term: &str) -> Result<Vec<Note>, Box<dyn Error>> {
+    let term = term.to_lowercase();
+    let mut notes = list()?;
+    notes.retain(|note| note.text.to_lowercase().contains(&term));
+    Ok(notes)
+}
+
+fn load() -> Result<Vec<Note>, Box<dyn Error>> {
+    let content = match fs::read_to_string(notes_path()?) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut notes = Vec::new();
+    for line in content.lines() {
+        let [id, created, text] = line.splitn(3, '\t').collect::<Vec<_>>()[..] else {
+            continue;
+        };
+        notes.push(Note {
+            id: id.parse()?,
+            created: created.parse()?,
+            text: unescape(text),
+        });
+    }
+    Ok(notes)
+}
+
+fn save(notes: &[Note]) -> Result<(), Box<dyn Error>> {
+    let content = notes
+        .iter()
+        .map(|note| format!("{}\t{}\t{}\n", note.id, note.created, escape(&note.text)))
+        .collect::<String>();
+
+    let mut file = AtomicFile::create(notes_path()?)?;
+    file.write_all(content.as_bytes())?;
+    file.commit_unsynced()?;
+    Ok(())
+}
+
+/// Notes may span lines, but the file keeps one note per line.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+fn notes_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(NOTES_FILE_NAME))
+}
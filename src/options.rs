@@ -0,0 +1,268 @@
+use crate::{config, log, permissions};
+use std::{
+    borrow::Cow,
+    error::Error,
+    sync::{Mutex, PoisonError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Bool,
+    /// One of a fixed set of words.
+    Choice(&'static [&'static str]),
+    /// Octal permission bits such as `022`, or `inherit`.
+    Mode,
+    /// Directories separated like `PATH`, with `:`, or `;` on Windows.
+    PathList,
+}
+
+/// An option that can be set in the config file and changed at runtime with
+/// `set`. Both go through this registry, so they share names and defaults.
+#[derive(Debug)]
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub kind: Kind,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+pub const COLOR: &str = "color";
+pub const PAGER: &str = "pager";
+pub const DRY_RUN: &str = "dry_run";
+pub const STRICT: &str = "strict";
+pub const OUTPUT_FORMAT: &str = "output_format";
+pub const GROUP_DIRS_FIRST: &str = "group_dirs_first";
+pub const USAGE_TRACKING: &str = "usage_tracking";
+pub const UMASK: &str = "umask";
+pub const WEEK_START: &str = "week_start";
+pub const CDPATH: &str = "cdpath";
+pub const SHARED_HISTORY: &str = "shared_history";
+pub const COMMAND_MATCHING: &str = "command_matching";
+pub const LANGUAGE: &str = "language";
+pub const ACCESSIBLE: &str = "accessible";
+
+pub const OPTIONS: &[OptionSpec] = &[
+    OptionSpec {
+        name: COLOR,
+        kind: Kind::Choice(&["auto", "always", "never"]),
+        default: "auto",
+        description: "Styled output; auto styles terminals unless NO_COLOR is set",
+    },
+    OptionSpec {
+        name: PAGER,
+        kind: Kind::Bool,
+        default: "true",
+        description: "Show long output such as manual pages one screen at a time",
+    },
+    OptionSpec {
+        name: DRY_RUN,
+        kind: Kind::Bool,
+        default: "false",
+        description: "Report what commands would change without changing anything",
+    },
+    OptionSpec {
+        name: STRICT,
+        kind: Kind::Bool,
+        default: "false",
+        description: "Fail on unreadable entries instead of skipping them",
+    },
+    OptionSpec {
+        name: OUTPUT_FORMAT,
+        kind: Kind::Choice(&["text", "json"]),
+        default: "text",
+        description: "Default for --format in commands that support it",
+    },
+    OptionSpec {
+        name: GROUP_DIRS_FIRST,
+        kind: Kind::Bool,
+        default: "false",
+        description: "List directories before files",
+    },
+    OptionSpec {
+        name: USAGE_TRACKING,
+        kind: Kind::Bool,
+        default: "true",
+        description: "Record local command usage statistics",
+    },
+    OptionSpec {
+        name: UMASK,
+        kind: Kind::Mode,
+        default: permissions::INHERIT,
+        description: "Permission bits removed from the files and directories the shell creates",
+    },
+    OptionSpec {
+        name: WEEK_START,
+        kind: Kind::Choice(&["monday", "sunday"]),
+        default: "monday",
+        description: "First day of the week in calendars",
+    },
+    OptionSpec {
+        name: CDPATH,
+        kind: Kind::PathList,
+        default: "",
+        description: "Directories cd searches for relative paths; empty uses $CDPATH",
+    },
+    OptionSpec {
+        name: SHARED_HISTORY,
+        kind: Kind::Bool,
+        default: "true",
+        description: "Pick up lines entered in other running sessions; false keeps sessions apart",
+    },
+    OptionSpec {
+        name: COMMAND_MATCHING,
+        kind: Kind::Choice(&["exact", "ignore_case", "prefix"]),
+        default: "exact",
+        description: "How command names are matched; prefix also ignores case and takes any \
+unambiguous start of a name",
+    },
+    OptionSpec {
+        name: LANGUAGE,
+        kind: Kind::Choice(&["auto", "en", "de"]),
+        default: "auto",
+        description: "Language of messages, help and prompts; auto follows LC_ALL, LC_MESSAGES \
+and LANG",
+    },
+    OptionSpec {
+        name: ACCESSIBLE,
+        kind: Kind::Bool,
+        default: "false",
+        description: "Output for screen readers: no colors, box drawing or lines that redraw \
+themselves",
+    },
+];
+
+/// Where the current value of an option comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    Config,
+    Set,
+}
+
+impl Source {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Source::Default => "default",
+            Source::Config => "config",
+            Source::Set => "set",
+        }
+    }
+}
+
+/// Values changed with `set` during this session.
+static OVERRIDES: Mutex<Vec<(&'static str, Cow<'static, str>)>> = Mutex::new(Vec::new());
+
+/// Looks up an option; `-` and `_` are interchangeable in names.
+pub fn find(name: &str) -> Option<&'static OptionSpec> {
+    let name = name.replace('-', "_");
+    OPTIONS.iter().find(|option| option.name == name)
+}
+
+impl OptionSpec {
+    /// Checks `value` against the option's type, returning its canonical form.
+    pub fn parse(&self, value: &str) -> Result<Cow<'static, str>, Box<dyn Error>> {
+        match self.kind {
+            Kind::Bool => match value {
+                "true" | "yes" | "on" | "1" => Ok(Cow::Borrowed("true")),
+                "false" | "no" | "off" | "0" => Ok(Cow::Borrowed("false")),
+                _ => Err(format!("{} expects true or false, got {}", self.name, value).into()),
+            },
+            Kind::Choice(choices) => choices
+                .iter()
+                .find(|choice| **choice == value)
+                .map(|choice| Cow::Borrowed(*choice))
+                .ok_or_else(|| {
+                    format!(
+                        "{} expects one of {}, got {}",
+                        self.name,
+                        choices.join(", "),
+                        value
+                    )
+                    .into()
+                }),
+            Kind::Mode if value == permissions::INHERIT => Ok(Cow::Borrowed(permissions::INHERIT)),
+            Kind::Mode => Ok(Cow::Owned(permissions::octal(permissions::parse_octal(
+                value,
+            )?))),
+            Kind::PathList => Ok(Cow::Owned(value.to_string())),
+        }
+    }
+}
+
+/// The current value of a registered option and where it was set. Invalid
+/// values in the config file are logged and ignored.
+pub fn value(option: &OptionSpec) -> (Cow<'static, str>, Source) {
+    let overridden = OVERRIDES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(name, _)| *name == option.name)
+        .map(|(_, value)| value.clone());
+    if let Some(value) = overridden {
+        return (value, Source::Set);
+    }
+
+    if let Some(value) = config::get(option.name) {
+        match option.parse(&value) {
+            Ok(value) => return (value, Source::Config),
+            Err(error) => log!(Config, Warn, "ignoring config value: {}", error),
+        }
+    }
+    (Cow::Borrowed(option.default), Source::Default)
+}
+
+/// The current value of the option called `name`.
+///
+/// Panics if `name` is not registered, which is a programming error.
+pub fn get(name: &str) -> Cow<'static, str> {
+    let option = find(name).unwrap_or_else(|| panic!("Unregistered option: {}", name));
+    value(option).0
+}
+
+pub fn get_bool(name: &str) -> bool {
+    get(name) == "true"
+}
+
+/// Changes an option for the rest of the session, returning its canonical
+/// name and value.
+pub fn set(name: &str, value: &str) -> Result<(&'static str, Cow<'static, str>), Box<dyn Error>> {
+    let option = find(name).ok_or_else(|| format!("Unknown option: {}", name))?;
+    let value = option.parse(value)?;
+
+    let mut overrides = OVERRIDES.lock().unwrap_or_else(PoisonError::into_inner);
+    overrides.retain(|(name, _)| *name != option.name);
+    overrides.push((option.name, value.clone()));
+    drop(overrides);
+
+    // The umask is process state, so a new value takes effect right away.
+    if option.name == UMASK {
+        permissions::apply_configured_umask();
+    }
+    Ok((option.name, value))
+}
+
+/// The values changed with `set` so far, to put back with `restore`.
+pub fn session_values() -> Vec<(&'static str, Cow<'static, str>)> {
+    OVERRIDES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Puts back values saved with `session_values`, undoing any `set` since.
+pub fn restore(values: Vec<(&'static str, Cow<'static, str>)>) {
+    *OVERRIDES.lock().unwrap_or_else(PoisonError::into_inner) = values;
+    permissions::apply_configured_umask();
+}
+
+/// Whether `dry_run` is on: commands that change files only report what
+/// they would do.
+pub fn dry_run() -> bool {
+    get_bool(DRY_RUN)
+}
+
+/// Whether `accessible` is on: output avoids styles, drawing characters and
+/// redrawn lines, which screen readers read poorly.
+pub fn accessible() -> bool {
+    get_bool(ACCESSIBLE)
+}
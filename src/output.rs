@@ -0,0 +1,135 @@
+use crate::terminal;
+use std::{
+    fmt,
+    io::{self, BufWriter, Stdout, Write},
+    sync::{Mutex, PoisonError},
+};
+
+/// Stdout shared by all commands. Output is buffered and flushed at the end
+/// of every command and before reading interactive input, rather than once
+/// per line as `println!` does.
+static STDOUT: Mutex<Option<BufWriter<Stdout>>> = Mutex::new(None);
+
+/// Captures in progress, the innermost last.
+static CAPTURES: Mutex<Vec<Capture>> = Mutex::new(Vec::new());
+
+/// Most output kept by a capture. It stays under the length Linux allows
+/// for one environment variable, so it fits in `$LAST_OUTPUT`.
+pub const CAPTURE_LIMIT: usize = 100 * 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct Captured {
+    pub bytes: Vec<u8>,
+    /// Whether output past `CAPTURE_LIMIT` was left out.
+    pub truncated: bool,
+}
+
+#[derive(Debug)]
+struct Capture {
+    captured: Captured,
+    /// Whether the output is kept from the terminal, as for `capture`.
+    silent: bool,
+}
+
+/// Prints to the shared stdout buffer, like `print!`.
+#[macro_export]
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write_fmt(format_args!($($arg)*))
+    };
+}
+
+/// Prints a line to the shared stdout buffer, like `println!`.
+#[macro_export]
+macro_rules! outln {
+    () => {
+        $crate::output::write_fmt(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_fmt(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+pub fn write_fmt(args: fmt::Arguments) {
+    match args.as_str() {
+        Some(text) => write_bytes(text.as_bytes()),
+        None => write_bytes(args.to_string().as_bytes()),
+    }
+}
+
+/// Writes raw bytes, such as file contents that need not be UTF-8.
+pub fn write_bytes(bytes: &[u8]) {
+    if record(bytes) {
+        return;
+    }
+    let mut stdout = STDOUT.lock().unwrap_or_else(PoisonError::into_inner);
+    // A closed stdout (e.g. a pipe into `head`) is not worth failing a
+    // command over.
+    let _ = stdout
+        .get_or_insert_with(|| BufWriter::new(io::stdout()))
+        .write_all(bytes);
+}
+
+/// Starts keeping a copy of everything printed, until `finish_capture`.
+pub fn start_capture() {
+    push_capture(false);
+}
+
+/// Like `start_capture`, but the output is not shown.
+pub fn start_silent_capture() {
+    push_capture(true);
+}
+
+fn push_capture(silent: bool) {
+    CAPTURES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Capture {
+            captured: Captured::default(),
+            silent,
+        });
+}
+
+/// Stops the innermost capture and returns what was printed since it
+/// started, without colors and other styles.
+pub fn finish_capture() -> Captured {
+    let mut captured = CAPTURES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .pop()
+        .map(|capture| capture.captured)
+        .unwrap_or_default();
+    captured.bytes = terminal::strip_styles(&captured.bytes);
+    captured
+}
+
+/// Adds output to the running captures, from the innermost out to the
+/// first silent one. Returns whether a silent capture took it, in which
+/// case it must not be shown. Code that writes to the terminal without
+/// going through this module, like the pager, calls it directly.
+pub fn record(bytes: &[u8]) -> bool {
+    let mut captures = CAPTURES.lock().unwrap_or_else(PoisonError::into_inner);
+    for capture in captures.iter_mut().rev() {
+        let captured = &mut capture.captured;
+        let room = CAPTURE_LIMIT - captured.bytes.len();
+        captured
+            .bytes
+            .extend_from_slice(&bytes[..bytes.len().min(room)]);
+        captured.truncated |= bytes.len() > room;
+        if capture.silent {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn flush() -> io::Result<()> {
+    match STDOUT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        Some(stdout) => stdout.flush(),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{i18n::Message, options, output, tr};
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+};
+
+const DEFAULT_TERMINAL_HEIGHT: usize = 24;
+
+/// Prints `text` one screen at a time when stdout is a terminal.
+/// Press Enter for the next page or `q` to stop. Paging is skipped when the
+/// `pager` option is off.
+pub fn page(text: &str) -> io::Result<()> {
+    // Writes go straight to stdout, after anything already buffered.
+    output::flush()?;
+    if output::record(text.as_bytes()) {
+        return Ok(());
+    }
+    let mut stdout = io::stdout();
+    if !stdout.is_terminal() || !options::get_bool(options::PAGER) {
+        write!(stdout, "{}", text)?;
+        return stdout.flush();
+    }
+
+    let page_height = terminal_height().saturating_sub(1).max(1);
+    let lines = text.lines().collect::<Vec<_>>();
+
+    for (i, chunk) in lines.chunks(page_height).enumerate() {
+        for line in chunk {
+            writeln!(stdout, "{}", line)?;
+        }
+
+        let shown = (i + 1) * page_height;
+        if shown >= lines.len() {
+            break;
+        }
+
+        write!(stdout, "{}", tr!(Message::More, shown * 100 / lines.len()))?;
+        stdout.flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if answer.trim().eq_ignore_ascii_case("q") {
+            break;
+        }
+    }
+
+    stdout.flush()
+}
+
+fn terminal_height() -> usize {
+    env::var("LINES")
+        .ok()
+        .and_then(|lines| lines.parse().ok())
+        .unwrap_or(DEFAULT_TERMINAL_HEIGHT)
+}
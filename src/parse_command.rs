@@ -1,12 +1,29 @@
+mod history_expansion;
 mod input_utils;
 
+use crate::{
+    commands, config, context::ShellContext, i18n::Message, log, options, outln, output, tr, units,
+    variables, vault,
+};
+
 use std::{
+    borrow::Cow,
     error::Error,
     fmt::Display,
     fs,
-    path::{Path, PathBuf},
+    io::{self, Read},
+    ops::Deref,
+    path::Path,
+    time::Instant,
 };
 
+/// Accepted by every command: leaves out its default flags from the config.
+pub const NO_DEFAULTS_FLAG: &str = "--no-defaults";
+/// Every word after it is an argument, even if it starts with `-`.
+pub const END_OF_FLAGS: &str = "--";
+/// Ends the command given to a flag such as `find --exec`.
+pub const NESTED_COMMAND_END: &str = ";";
+
 #[derive(Debug)]
 pub struct Command {
     pub command_type: CommandType,
@@ -20,15 +37,67 @@ impl Command {
         arguments: Vec<String>,
         flags: Vec<String>,
     ) -> Result<Self, CommandError> {
+        Self::validate(command_type, &arguments, &flags)?;
+
+        Ok(Self {
+            command_type,
+            arguments,
+            flags,
+        })
+    }
+
+    fn validate(
+        command_type: CommandType,
+        arguments: &[String],
+        flags: &[String],
+    ) -> Result<(), CommandError> {
         for flag in flags.iter() {
-            if !command_type.is_supported_flag(flag) {
+            let flag_name = flag_name(flag);
+
+            if !command_type.is_supported_flag(flag_name) {
                 return Err(CommandError::UnsupportedFlag(flag.to_owned()));
             }
+
+            if command_type.is_value_flag(flag_name) && !flag.contains('=') {
+                return Err(CommandError::MissingFlagValue(flag.to_owned()));
+            }
+
+            if let Some((_, value)) = flag.split_once('=') {
+                let expected = command_type.flag_value_type(flag_name);
+                if !expected.accepts_text(value) {
+                    return Err(CommandError::InvalidFlagValue {
+                        flag: flag.to_owned(),
+                        expected,
+                    });
+                }
+            }
+        }
+
+        let given = |name: &str| flags.iter().any(|flag| flag_name(flag) == name);
+        for constraint in command_type.get_flag_constraints() {
+            match *constraint {
+                FlagConstraint::Conflicts(first, second) if given(first) && given(second) => {
+                    return Err(CommandError::ConflictingFlags(first, second));
+                }
+                FlagConstraint::Requires(flag, required) if given(flag) && !given(required) => {
+                    return Err(CommandError::MissingRequiredFlag { flag, required });
+                }
+                _ => {}
+            }
         }
 
-        let expected_argument_count = command_type.get_expected_argument_count();
+        for (index, argument) in arguments.iter().enumerate() {
+            let expected = command_type.argument_type(index);
+            if !expected.accepts_text(argument) {
+                return Err(CommandError::InvalidArgument {
+                    position: index + 1,
+                    argument: argument.clone(),
+                    expected,
+                });
+            }
+        }
 
-        if let Some(expected_argument_count) = expected_argument_count {
+        if let Some(expected_argument_count) = command_type.get_expected_argument_count() {
             let actual_argument_count = arguments.len();
 
             if !expected_argument_count.is_valid(actual_argument_count) {
@@ -39,59 +108,416 @@ impl Command {
             }
         }
 
-        Ok(Self {
-            command_type,
-            arguments,
-            flags,
-        })
+        Ok(())
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    pub fn get_flag_value(&self, flag: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .rev()
+            .find_map(|f| f.strip_prefix(flag)?.strip_prefix('='))
+    }
+
+    /// Every value given for a flag that may be repeated, in order.
+    pub fn get_flag_values<'a>(&'a self, flag: &'a str) -> impl Iterator<Item = &'a str> {
+        self.flags
+            .iter()
+            .filter_map(move |f| f.strip_prefix(flag)?.strip_prefix('='))
+    }
+
+    /// The command with its arguments and flags, for `-vv` and the debug
+    /// log. Secrets filled in from the vault show as their `$SECRET:NAME`
+    /// references.
+    pub fn describe(&self) -> String {
+        let text = format!(
+            "{} arguments={:?} flags={:?}",
+            self.command_type.name(),
+            self.arguments,
+            self.flags
+        );
+        vault::redact(&text).into_owned()
+    }
+
+    /// Whether any argument or flag holds a secret filled in from the vault.
+    pub fn uses_secrets(&self) -> bool {
+        self.arguments
+            .iter()
+            .chain(&self.flags)
+            .any(|word| vault::contains_secret(word))
+    }
+
+    /// Binds the command to the context it will run in.
+    pub fn bind(self, context: &ShellContext) -> ExecutableCommand<'_> {
+        ExecutableCommand {
+            command: self,
+            context,
+        }
+    }
+}
+
+/// A parsed [`Command`] bound to the [`ShellContext`] it runs in.
+#[derive(Debug)]
+pub struct ExecutableCommand<'a> {
+    pub command: Command,
+    pub context: &'a ShellContext,
+}
+
+impl Deref for ExecutableCommand<'_> {
+    type Target = Command;
+
+    fn deref(&self) -> &Command {
+        &self.command
+    }
+}
+
+impl ExecutableCommand<'_> {
+    /// The text of the files given as arguments, one after another, or of
+    /// standard input up to end of file when there are none. Invalid UTF-8
+    /// is replaced, and every file ends with a newline.
+    pub fn read_input(&self) -> Result<String, Box<dyn Error>> {
+        let mut text = String::new();
+        if self.arguments.is_empty() {
+            io::stdin().read_to_string(&mut text)?;
+        }
+        for argument in &self.arguments {
+            let path = self.context.resolve(argument);
+            let contents =
+                fs::read(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+            text.push_str(&String::from_utf8_lossy(&contents));
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+        }
+        Ok(text)
     }
 
     pub fn execute(self) -> Result<(), Box<dyn Error>> {
+        let name = self.command_type.name();
+        log!(
+            Executor,
+            Debug,
+            "running {} cwd={}",
+            self.describe(),
+            self.context.cwd().display()
+        );
+        let started = Instant::now();
+        let result = self
+            .check_paths()
+            .map_err(Into::into)
+            .and_then(|()| self.run());
+        match &result {
+            Ok(()) => log!(
+                Executor,
+                Debug,
+                "{} finished in {:?}",
+                name,
+                started.elapsed()
+            ),
+            Err(error) => log!(Executor, Error, "{} failed: {}", name, error),
+        }
+        result
+    }
+
+    /// Checks the arguments declared as paths against the filesystem.
+    fn check_paths(&self) -> Result<(), CommandError> {
+        for (index, argument) in self.arguments.iter().enumerate() {
+            let expected = self.command_type.argument_type(index);
+            if !expected.accepts_path(&self.context.resolve(argument)) {
+                return Err(CommandError::InvalidArgument {
+                    position: index + 1,
+                    argument: argument.clone(),
+                    expected,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn run(self) -> Result<(), Box<dyn Error>> {
         match &self.command_type {
+            CommandType::Archive => {
+                commands::archive::execute(&self)?;
+            }
+            CommandType::Audit => {
+                commands::audit::execute(&self)?;
+            }
+            CommandType::Backup => {
+                commands::backup::execute(&self)?;
+            }
+            CommandType::Cal => {
+                commands::cal::execute(&self)?;
+            }
+            CommandType::Capture => {
+                commands::capture::execute(&self)?;
+            }
+            CommandType::Cat => {
+                commands::cat::execute(&self)?;
+            }
+            CommandType::Cd => {
+                commands::cd::execute(&self)?;
+            }
+            CommandType::Chgrp => {
+                commands::chown::chgrp(&self)?;
+            }
+            CommandType::Chmod => {
+                commands::chmod::execute(&self)?;
+            }
+            CommandType::Chown => {
+                commands::chown::chown(&self)?;
+            }
+            CommandType::Completions => {
+                commands::completions::execute(&self)?;
+            }
+            CommandType::ConvertUnit => {
+                commands::convert_unit::execute(&self)?;
+            }
+            CommandType::Countdown => {
+                commands::countdown::execute(&self)?;
+            }
+            CommandType::Cp => {
+                commands::cp::execute(&self)?;
+            }
+            CommandType::Cut => {
+                commands::cut::execute(&self)?;
+            }
+            CommandType::Date => {
+                commands::date::execute(&self)?;
+            }
+            CommandType::Debuglog => {
+                commands::debuglog::execute(&self)?;
+            }
+            CommandType::Decrypt => {
+                commands::crypt::decrypt(&self)?;
+            }
+            CommandType::Df => {
+                commands::df::execute(&self)?;
+            }
+            CommandType::Dirs => {
+                commands::dir_stack::dirs(&self)?;
+            }
+            CommandType::Docs => {
+                commands::docs::execute(&self)?;
+            }
+            CommandType::Du => {
+                commands::du::execute(&self)?;
+            }
             CommandType::Echo => {
-                println!("{}", self.arguments.join("\n"));
+                outln!("{}", self.arguments.join("\n"));
+            }
+            CommandType::Encrypt => {
+                commands::crypt::encrypt(&self)?;
+            }
+            CommandType::Examples => {
+                commands::examples::execute(&self)?;
             }
             CommandType::Exit => {
+                let _ = output::flush();
                 std::process::exit(0);
             }
+            CommandType::Explain => {
+                commands::explain::execute(&self)?;
+            }
+            CommandType::Find => {
+                commands::find::execute(&self)?;
+            }
+            CommandType::Grep => {
+                commands::grep::execute(&self)?;
+            }
+            CommandType::Hash => {
+                commands::hash::execute(&self)?;
+            }
+            CommandType::Head => {
+                commands::head::execute(&self)?;
+            }
             CommandType::Help => {
-                println!("Help is not implemented yet");
+                commands::help::execute(&self)?;
+            }
+            CommandType::Gitinfo => {
+                commands::gitinfo::execute(&self)?;
+            }
+            CommandType::History => {
+                commands::history::execute(&self)?;
+            }
+            CommandType::Ifconfig => {
+                commands::ifconfig::execute(&self)?;
+            }
+            CommandType::Imginfo => {
+                commands::imginfo::execute(&self)?;
+            }
+            CommandType::Lastout => {
+                commands::lastout::execute(&self)?;
+            }
+            CommandType::Ln => {
+                commands::ln::execute(&self)?;
+            }
+            CommandType::Man => {
+                commands::man::execute(&self)?;
+            }
+            CommandType::Mkdir => {
+                commands::mkdir::execute(&self)?;
+            }
+            CommandType::Mounts => {
+                commands::mounts::execute(&self)?;
+            }
+            CommandType::Mv => {
+                commands::mv::execute(&self)?;
+            }
+            CommandType::Myip => {
+                commands::myip::execute(&self)?;
+            }
+            CommandType::Note => {
+                commands::note::execute(&self)?;
+            }
+            CommandType::Open => {
+                commands::open::execute(&self)?;
+            }
+            CommandType::Popd => {
+                commands::dir_stack::popd(&self)?;
+            }
+            CommandType::Portscan => {
+                commands::portscan::execute(&self)?;
+            }
+            CommandType::Pushd => {
+                commands::dir_stack::pushd(&self)?;
+            }
+            CommandType::Pwd => {
+                commands::pwd::execute(&self)?;
+            }
+            CommandType::Qr => {
+                commands::qr::execute(&self)?;
+            }
+            CommandType::Remind => {
+                commands::remind::execute(&self)?;
+            }
+            CommandType::Replace => {
+                commands::replace::execute(&self)?;
+            }
+            CommandType::Rm => {
+                commands::rm::execute(&self)?;
+            }
+            CommandType::Run => {
+                commands::run::execute(&self)?;
+            }
+            CommandType::Set => {
+                commands::set::execute(&self)?;
+            }
+            CommandType::Snapshot => {
+                commands::snapshot::execute(&self)?;
+            }
+            CommandType::Sort => {
+                commands::sort::execute(&self)?;
+            }
+            CommandType::Stat => {
+                commands::stat::execute(&self)?;
+            }
+            CommandType::Stopwatch => {
+                commands::stopwatch::execute(&self)?;
+            }
+            CommandType::Tail => {
+                commands::tail::execute(&self)?;
+            }
+            CommandType::Tar => {
+                commands::tar::execute(&self)?;
+            }
+            CommandType::Todo => {
+                commands::todo::execute(&self)?;
+            }
+            CommandType::Todos => {
+                commands::todos::execute(&self)?;
+            }
+            CommandType::Tree => {
+                commands::tree::execute(&self)?;
+            }
+            CommandType::Tutorial => {
+                commands::tutorial::execute(&self)?;
+            }
+            CommandType::Umask => {
+                commands::umask::execute(&self)?;
+            }
+            CommandType::Uniq => {
+                commands::uniq::execute(&self)?;
+            }
+            CommandType::Unzip => {
+                commands::zip::unzip(&self)?;
+            }
+            CommandType::Usage => {
+                commands::usage::execute(&self)?;
+            }
+            CommandType::Vault => {
+                commands::vault::execute(&self)?;
+            }
+            CommandType::Wc => {
+                commands::wc::execute(&self)?;
+            }
+            CommandType::Wizard => {
+                commands::wizard::execute(&self)?;
+            }
+            CommandType::Zip => {
+                commands::zip::zip(&self)?;
+            }
+            CommandType::Loc => {
+                commands::loc::execute(&self)?;
             }
             CommandType::Ls => {
-                let mut dirs = self.arguments.clone();
-                if dirs.is_empty() {
-                    dirs.push(".".to_string());
-                }
+                commands::ls::execute(&self)?;
+            }
+        }
 
-                for dir in &dirs[..] {
-                    let dir = Path::new(&dir);
-                    if !dir.exists() {
-                        println!("Directory {} does not exist", dir.display());
-                        continue;
-                    }
-                    if !dir.is_dir() {
-                        println!("{} is not a directory", dir.display());
-                        continue;
-                    }
-                    let mut entries = fs::read_dir(dir)?
-                        .map(|entry| entry.unwrap().path())
-                        .collect::<Vec<PathBuf>>();
-                    entries.sort();
+        Ok(())
+    }
+}
 
-                    if dirs.len() > 1 {
-                        println!("{}:", dir.display());
-                    }
-                    for entry in entries {
-                        println!("{}", entry.display());
+impl TryFrom<&str> for Command {
+    type Error = CommandError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        log!(Parser, Trace, "input {:?}", input);
+        let tokens = tokenize_words(input)?;
+        let takes_raw_line = tokens
+            .first()
+            .and_then(|name| CommandType::lookup(&name.text).ok())
+            .is_some_and(|command_type| command_type.takes_raw_line());
+        if takes_raw_line {
+            return Self::from_tokens(input, tokens, true);
+        }
+
+        let tokens = tokens
+            .into_iter()
+            .map(|token| {
+                if !token.allows_expansion() {
+                    return Ok(token);
+                }
+                // Variables go first, so `$` in a secret is never taken for one.
+                let token = match variables::expand(&token.text) {
+                    Ok(Cow::Owned(text)) => Token {
+                        text: Cow::Owned(text),
+                        ..token
+                    },
+                    Ok(Cow::Borrowed(_)) => token,
+                    Err(error) => {
+                        return Err(CommandError::VariableExpansion(error.to_string())
+                            .at(input, token.span))
                     }
-                    if dirs.len() > 1 {
-                        println!();
+                };
+                match vault::expand_secrets(&token.text) {
+                    // Only tokens that referenced a secret get a new text.
+                    Ok(Cow::Owned(text)) => Ok(Token {
+                        text: Cow::Owned(text),
+                        ..token
+                    }),
+                    Ok(Cow::Borrowed(_)) => Ok(token),
+                    Err(error) => {
+                        Err(CommandError::SecretExpansion(error.to_string()).at(input, token.span))
                     }
                 }
-            }
-        }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(())
+        Self::from_tokens(input, tokens, true)
     }
 }
 
@@ -99,40 +525,807 @@ impl TryFrom<String> for Command {
     type Error = CommandError;
 
     fn try_from(input: String) -> Result<Self, Self::Error> {
-        let input_vec = input_utils::split_input_outside_quotes_on_whitespace(input);
+        Self::try_from(input.as_str())
+    }
+}
 
-        let command_type = CommandType::try_from(input_vec[0].to_owned())?;
+impl Command {
+    /// Parses a command line without expanding secrets or adding default
+    /// flags, so it never prompts or touches the filesystem.
+    pub fn parse(input: &str) -> Result<Self, CommandError> {
+        Self::from_tokens(input, tokenize_words(input)?, false)
+    }
+
+    /// Builds a command from words that were already split and expanded,
+    /// such as the nested command of `capture`.
+    pub fn from_words(words: &[String]) -> Result<Self, CommandError> {
+        let input = join_quoted(words);
+        let mut start = 0;
+        let tokens = words
+            .iter()
+            .map(|word| {
+                let end = start + join_quoted(std::slice::from_ref(word)).len();
+                let token = Token {
+                    text: Cow::Borrowed(word.as_str()),
+                    quoting: Quoting::Raw,
+                    span: Span { start, end },
+                };
+                start = end + 1;
+                token
+            })
+            .collect();
+        Self::from_tokens(&input, tokens, true)
+    }
+
+    /// Builds the command from its words. Each word is moved into the
+    /// command, so only words that borrow from `input` are copied. With
+    /// `with_defaults`, the flags configured for the command come first,
+    /// unless the line says `--no-defaults`.
+    pub(crate) fn from_tokens(
+        input: &str,
+        tokens: Vec<Token>,
+        with_defaults: bool,
+    ) -> Result<Self, CommandError> {
+        log!(
+            Parser,
+            Debug,
+            "words {:?}",
+            tokens.iter().map(|token| &*token.text).collect::<Vec<_>>()
+        );
+        let mut tokens = tokens.into_iter();
+        let name = tokens.next().ok_or(CommandError::EmptyInput)?;
+        let command_type =
+            CommandType::lookup(&name.text).map_err(|error| error.at(input, name.span))?;
 
         let mut arguments = Vec::new();
+        let mut argument_spans = Vec::new();
         let mut flags = Vec::new();
+        let mut flag_spans = Vec::new();
+        let mut skip_defaults = false;
+        let mut flags_ended = false;
+
+        if command_type.takes_raw_line() {
+            // Quotes and all, so `explain` can show how they are read.
+            let rest = input[name.span.end..].trim();
+            if !rest.is_empty() {
+                arguments.push(rest.to_string());
+                argument_spans.push(Span {
+                    start: input.len() - input[name.span.end..].trim_start().len(),
+                    end: name.span.end + input[name.span.end..].trim_end().len(),
+                });
+            }
+            tokens = Vec::new().into_iter();
+        }
 
-        for arg in input_vec.iter().skip(1) {
-            if arg.starts_with('-') {
-                flags.push(arg.to_owned());
+        while let Some(arg) = tokens.next() {
+            let nested = command_type
+                .nested_command_start()
+                .is_some_and(|start| arguments.len() > start);
+            let may_be_flag = !nested && !flags_ended;
+            if may_be_flag && arg.text == END_OF_FLAGS {
+                flags_ended = true;
+                continue;
+            }
+            if may_be_flag && arg.text == NO_DEFAULTS_FLAG {
+                skip_defaults = true;
+                continue;
+            }
+            let shorthand = arg
+                .text
+                .strip_prefix('-')
+                .filter(|_| may_be_flag)
+                .and_then(|number| {
+                    let shorthand = command_type.get_numeric_shorthand()?;
+                    shorthand.matches(number).then_some((shorthand, number))
+                });
+            if let Some((NumericShorthand::FlagValue(flag), number)) = shorthand {
+                flags.push(format!("{}={}", flag, number));
+                flag_spans.push(arg.span);
+                continue;
+            }
+            // A lone `-` is an argument, such as `cd -`, and so is a negative
+            // number for commands that take them.
+            if may_be_flag && shorthand.is_none() && arg.text.starts_with('-') && arg.text.len() > 1
+            {
+                for flag in command_type.expand_flag_word(&arg.text) {
+                    if command_type.nested_command_flag() == Some(&*flag) {
+                        // The command runs up to a lone `;` or the end of the line.
+                        let words = tokens
+                            .by_ref()
+                            .take_while(|word| word.text != NESTED_COMMAND_END)
+                            .collect::<Vec<_>>();
+                        let Some(last) = words.last() else {
+                            return Err(CommandError::MissingFlagValue(arg.text.into_owned())
+                                .at(input, arg.span));
+                        };
+                        let end = last.span.end;
+                        let words = words
+                            .into_iter()
+                            .map(|word| word.text.into_owned())
+                            .collect::<Vec<_>>();
+                        flags.push(format!("{}={}", flag, join_quoted(&words)));
+                        flag_spans.push(Span {
+                            start: arg.span.start,
+                            end,
+                        });
+                        continue;
+                    }
+                    if !command_type.is_value_flag(&flag) {
+                        flags.push(flag);
+                        flag_spans.push(arg.span);
+                        continue;
+                    }
+                    if command_type.needs_attached_value(&flag) {
+                        return Err(CommandError::MissingFlagValue(arg.text.into_owned())
+                            .at(input, arg.span));
+                    }
+                    match tokens.next() {
+                        Some(value) => {
+                            flags.push(format!("{}={}", flag, value.text));
+                            flag_spans.push(Span {
+                                start: arg.span.start,
+                                end: value.span.end,
+                            });
+                        }
+                        None => {
+                            return Err(CommandError::MissingFlagValue(arg.text.into_owned())
+                                .at(input, arg.span))
+                        }
+                    }
+                }
             } else {
-                arguments.push(arg.to_owned());
+                arguments.push(arg.text.into_owned());
+                argument_spans.push(arg.span);
             }
         }
 
-        Self::new(command_type, arguments, flags)
+        if with_defaults && !skip_defaults {
+            // Defaults go first, so the line's own values win. A default that
+            // conflicts with a flag on the line gives way to it.
+            let mut defaults =
+                default_flags(command_type).map_err(|error| error.at(input, name.span))?;
+            defaults.retain(|default| !command_type.conflicts_with_any(default, &flags));
+            flag_spans.splice(0..0, defaults.iter().map(|_| name.span));
+            flags.splice(0..0, defaults);
+        }
+
+        if let Err(error) = Self::validate(command_type, &arguments, &flags) {
+            let span = match &error {
+                CommandError::UnsupportedFlag(flag)
+                | CommandError::MissingFlagValue(flag)
+                | CommandError::InvalidFlagValue { flag, .. } => flags
+                    .iter()
+                    .position(|f| f == flag)
+                    .map(|index| flag_spans[index]),
+                // Point at whichever of the two conflicting flags comes last.
+                CommandError::ConflictingFlags(first, second) => flags
+                    .iter()
+                    .rposition(|f| flag_name(f) == *first || flag_name(f) == *second)
+                    .map(|index| flag_spans[index]),
+                CommandError::MissingRequiredFlag { flag, .. } => flags
+                    .iter()
+                    .rposition(|f| flag_name(f) == *flag)
+                    .map(|index| flag_spans[index]),
+                CommandError::InvalidArgument { position, .. } => {
+                    argument_spans.get(position - 1).copied()
+                }
+                CommandError::WrongArgumentsCount { expected, actual } => {
+                    // Point at the first surplus argument, or past the end when some are missing.
+                    let allowed = (0..*actual).rev().find(|count| expected.is_valid(*count));
+                    match allowed {
+                        Some(count) => argument_spans.get(count).copied(),
+                        None => Some(Span {
+                            start: input.len(),
+                            end: input.len(),
+                        }),
+                    }
+                }
+                _ => None,
+            };
+            log!(Parser, Warn, "rejected {:?}: {}", input, error);
+            return Err(match span {
+                Some(span) => error.at(input, span),
+                None => error,
+            });
+        }
+
+        Ok(Self {
+            command_type,
+            arguments,
+            flags,
+        })
+    }
+}
+
+/// The name of a flag as stored in a command, without its `=value`.
+fn flag_name(flag: &str) -> &str {
+    flag.split('=').next().unwrap_or(flag)
+}
+
+/// Flags configured for `command_type` as `defaults.<name>` in the config
+/// file. Only flags are allowed there, each checked like on the command line.
+pub(crate) fn default_flags(command_type: CommandType) -> Result<Vec<String>, CommandError> {
+    let key = format!("{}{}", config::DEFAULT_FLAGS_PREFIX, command_type.name());
+    let Some(value) = config::get(&key) else {
+        return Ok(Vec::new());
+    };
+    let invalid = |error: CommandError| CommandError::InvalidDefaults {
+        key: key.clone(),
+        error: Box::new(error),
+    };
+
+    let mut words = tokenize(&value).map_err(invalid)?.into_iter();
+    let mut flags = Vec::new();
+    while let Some(word) = words.next() {
+        if !word.starts_with('-') {
+            return Err(invalid(CommandError::UnsupportedFlag(word)));
+        }
+        for flag in command_type.expand_flag_word(&word) {
+            if command_type.needs_attached_value(&flag) {
+                return Err(invalid(CommandError::MissingFlagValue(word)));
+            } else if command_type.is_value_flag(&flag) {
+                let value = words
+                    .next()
+                    .ok_or_else(|| invalid(CommandError::MissingFlagValue(word.clone())))?;
+                flags.push(format!("{}={}", flag, value));
+            } else if command_type.is_supported_flag(flag_name(&flag)) {
+                flags.push(flag);
+            } else {
+                return Err(invalid(CommandError::UnsupportedFlag(word)));
+            }
+        }
     }
+    Ok(flags)
 }
 
+/// The result of parsing one line of input.
 #[derive(Debug)]
+pub enum ParsedLine {
+    /// The line holds nothing to run: it is empty, only whitespace or only a comment.
+    Nothing,
+    Command(Command),
+}
+
+impl ParsedLine {
+    /// Like [`Command::parse`], but blank lines yield [`ParsedLine::Nothing`]
+    /// instead of an error.
+    pub fn parse(input: &str) -> Result<Self, CommandError> {
+        Self::from_result(Command::parse(input))
+    }
+
+    fn from_result(result: Result<Command, CommandError>) -> Result<Self, CommandError> {
+        match result {
+            Ok(command) => Ok(ParsedLine::Command(command)),
+            Err(CommandError::EmptyInput) => Ok(ParsedLine::Nothing),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl TryFrom<&str> for ParsedLine {
+    type Error = CommandError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        Self::from_result(Command::try_from(input))
+    }
+}
+
+impl TryFrom<String> for ParsedLine {
+    type Error = CommandError;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        Self::try_from(input.as_str())
+    }
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<String>, CommandError> {
+    Ok(tokenize_words(input)?
+        .into_iter()
+        .map(|token| token.text.into_owned())
+        .collect())
+}
+
+/// Like [`tokenize`], but keeps how each word was quoted and where it is.
+pub fn tokenize_words(input: &str) -> Result<Vec<Token<'_>>, CommandError> {
+    input_utils::split_input_outside_quotes_on_whitespace(input)
+}
+
+pub use self::history_expansion::expand_history;
+pub use self::input_utils::{join_quoted, Quoting, Span, Token};
+
+/// Entry point for fuzzers: parses arbitrary bytes and must never panic.
+pub fn fuzz_parse(data: &[u8]) {
+    let _ = Command::parse(&String::from_utf8_lossy(data));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CommandType {
+    Archive,
+    Audit,
+    Backup,
+    Cal,
+    Capture,
+    Cat,
+    Cd,
+    Chgrp,
+    Chmod,
+    Chown,
+    Completions,
+    ConvertUnit,
+    Countdown,
+    Cp,
+    Cut,
+    Date,
+    Debuglog,
+    Decrypt,
+    Df,
+    Dirs,
+    Docs,
+    Du,
     Echo,
+    Encrypt,
+    Examples,
     Exit,
+    Explain,
+    Find,
+    Gitinfo,
+    Grep,
+    Hash,
+    Head,
     Help,
+    History,
+    Ifconfig,
+    Imginfo,
+    Lastout,
+    Ln,
+    Loc,
     Ls,
+    Man,
+    Mkdir,
+    Mounts,
+    Mv,
+    Myip,
+    Note,
+    Open,
+    Popd,
+    Portscan,
+    Pushd,
+    Pwd,
+    Qr,
+    Remind,
+    Replace,
+    Rm,
+    Run,
+    Set,
+    Snapshot,
+    Sort,
+    Stat,
+    Stopwatch,
+    Tail,
+    Tar,
+    Todo,
+    Todos,
+    Tree,
+    Tutorial,
+    Umask,
+    Uniq,
+    Unzip,
+    Usage,
+    Vault,
+    Wc,
+    Wizard,
+    Zip,
 }
 
 impl CommandType {
-    fn get_supported_flags(&self) -> Vec<&str> {
+    pub const ALL: &'static [CommandType] = &[
+        CommandType::Archive,
+        CommandType::Audit,
+        CommandType::Backup,
+        CommandType::Cal,
+        CommandType::Capture,
+        CommandType::Cat,
+        CommandType::Cd,
+        CommandType::Chgrp,
+        CommandType::Chmod,
+        CommandType::Chown,
+        CommandType::Completions,
+        CommandType::ConvertUnit,
+        CommandType::Countdown,
+        CommandType::Cp,
+        CommandType::Cut,
+        CommandType::Date,
+        CommandType::Debuglog,
+        CommandType::Decrypt,
+        CommandType::Df,
+        CommandType::Dirs,
+        CommandType::Docs,
+        CommandType::Du,
+        CommandType::Echo,
+        CommandType::Encrypt,
+        CommandType::Examples,
+        CommandType::Exit,
+        CommandType::Explain,
+        CommandType::Find,
+        CommandType::Gitinfo,
+        CommandType::Grep,
+        CommandType::Hash,
+        CommandType::Head,
+        CommandType::Help,
+        CommandType::History,
+        CommandType::Ifconfig,
+        CommandType::Imginfo,
+        CommandType::Lastout,
+        CommandType::Ln,
+        CommandType::Loc,
+        CommandType::Ls,
+        CommandType::Man,
+        CommandType::Mkdir,
+        CommandType::Mounts,
+        CommandType::Mv,
+        CommandType::Myip,
+        CommandType::Note,
+        CommandType::Open,
+        CommandType::Popd,
+        CommandType::Portscan,
+        CommandType::Pushd,
+        CommandType::Pwd,
+        CommandType::Qr,
+        CommandType::Remind,
+        CommandType::Replace,
+        CommandType::Rm,
+        CommandType::Run,
+        CommandType::Set,
+        CommandType::Snapshot,
+        CommandType::Sort,
+        CommandType::Stat,
+        CommandType::Stopwatch,
+        CommandType::Tail,
+        CommandType::Tar,
+        CommandType::Todo,
+        CommandType::Todos,
+        CommandType::Tree,
+        CommandType::Tutorial,
+        CommandType::Umask,
+        CommandType::Uniq,
+        CommandType::Unzip,
+        CommandType::Usage,
+        CommandType::Vault,
+        CommandType::Wc,
+        CommandType::Wizard,
+        CommandType::Zip,
+    ];
+
+    pub fn name(&self) -> &'static str {
         match self {
+            CommandType::Archive => "archive",
+            CommandType::Audit => "audit",
+            CommandType::Backup => "backup",
+            CommandType::Cal => "cal",
+            CommandType::Capture => "capture",
+            CommandType::Cat => "cat",
+            CommandType::Cd => "cd",
+            CommandType::Chgrp => "chgrp",
+            CommandType::Chmod => "chmod",
+            CommandType::Chown => "chown",
+            CommandType::Completions => "completions",
+            CommandType::ConvertUnit => "convert-unit",
+            CommandType::Countdown => "countdown",
+            CommandType::Cp => "cp",
+            CommandType::Cut => "cut",
+            CommandType::Date => "date",
+            CommandType::Debuglog => "debuglog",
+            CommandType::Decrypt => "decrypt",
+            CommandType::Df => "df",
+            CommandType::Dirs => "dirs",
+            CommandType::Docs => "docs",
+            CommandType::Du => "du",
+            CommandType::Echo => "echo",
+            CommandType::Encrypt => "encrypt",
+            CommandType::Examples => "examples",
+            CommandType::Exit => "exit",
+            CommandType::Explain => "explain",
+            CommandType::Find => "find",
+            CommandType::Gitinfo => "gitinfo",
+            CommandType::Grep => "grep",
+            CommandType::Hash => "hash",
+            CommandType::Head => "head",
+            CommandType::Help => "help",
+            CommandType::History => "history",
+            CommandType::Ifconfig => "ifconfig",
+            CommandType::Imginfo => "imginfo",
+            CommandType::Lastout => "lastout",
+            CommandType::Ln => "ln",
+            CommandType::Loc => "loc",
+            CommandType::Ls => "ls",
+            CommandType::Man => "man",
+            CommandType::Mkdir => "mkdir",
+            CommandType::Mounts => "mounts",
+            CommandType::Mv => "mv",
+            CommandType::Myip => "myip",
+            CommandType::Note => "note",
+            CommandType::Open => "open",
+            CommandType::Popd => "popd",
+            CommandType::Portscan => "portscan",
+            CommandType::Pushd => "pushd",
+            CommandType::Pwd => "pwd",
+            CommandType::Qr => "qr",
+            CommandType::Remind => "remind",
+            CommandType::Replace => "replace",
+            CommandType::Rm => "rm",
+            CommandType::Run => "run",
+            CommandType::Set => "set",
+            CommandType::Snapshot => "snapshot",
+            CommandType::Sort => "sort",
+            CommandType::Stat => "stat",
+            CommandType::Stopwatch => "stopwatch",
+            CommandType::Tail => "tail",
+            CommandType::Tar => "tar",
+            CommandType::Todo => "todo",
+            CommandType::Todos => "todos",
+            CommandType::Tree => "tree",
+            CommandType::Tutorial => "tutorial",
+            CommandType::Umask => "umask",
+            CommandType::Uniq => "uniq",
+            CommandType::Unzip => "unzip",
+            CommandType::Usage => "usage",
+            CommandType::Vault => "vault",
+            CommandType::Wc => "wc",
+            CommandType::Wizard => "wizard",
+            CommandType::Zip => "zip",
+        }
+    }
+
+    /// Every spelling of the flags the command accepts, aliases included.
+    pub fn get_supported_flags(&self) -> Vec<&str> {
+        let mut flags = match self {
+            CommandType::Archive => vec!["--ignore-case"],
+            CommandType::Audit => vec!["--format", "--min-severity"],
+            CommandType::Backup => vec!["--delete", "--preserve", "--reflink", "--sparse"],
+            CommandType::Cal => vec!["-3", "-y", "-m", "-s"],
+            CommandType::Capture => vec![],
+            CommandType::Cat => vec!["-n"],
+            CommandType::Cd => vec![],
+            CommandType::Chgrp => vec!["--recursive", "--reference"],
+            CommandType::Chmod => vec!["--recursive"],
+            CommandType::Chown => vec!["--recursive", "--reference"],
+            CommandType::Completions => vec![],
+            CommandType::ConvertUnit => vec!["--to"],
+            CommandType::Countdown => vec![],
+            CommandType::Cp => vec![
+                "--recursive",
+                "--force",
+                "--preserve",
+                "--reflink",
+                "--sparse",
+            ],
+            CommandType::Cut => vec!["-d", "-f"],
+            CommandType::Date => vec!["--convert", "--from", "--to"],
+            CommandType::Debuglog => vec!["--level", "--file"],
+            CommandType::Decrypt => vec!["--output"],
+            CommandType::Df => vec!["-h"],
+            CommandType::Dirs => vec!["-v"],
+            CommandType::Docs => vec!["--format", "--output"],
+            CommandType::Du => vec!["-h", "-d", "-L"],
             CommandType::Echo => vec![],
+            CommandType::Encrypt => vec!["--output", "--atomic"],
+            CommandType::Examples => vec!["--run"],
             CommandType::Exit => vec![],
+            CommandType::Explain => vec![],
+            CommandType::Find => vec![
+                "-f",
+                "-d",
+                "-L",
+                "--max-depth",
+                "--larger-than",
+                "--smaller-than",
+                "--newer-than",
+                "--older-than",
+                "--exec",
+                "--delete",
+                "--print0",
+            ],
+            CommandType::Gitinfo => vec!["--files"],
+            CommandType::Grep => vec![
+                "--ignore-case",
+                "--recursive",
+                "-e",
+                "-F",
+                "-A",
+                "-B",
+                "-C",
+                "-c",
+                "-l",
+                "--stats",
+            ],
+            CommandType::Hash => vec!["--md5", "--sha1", "--check"],
+            CommandType::Head => vec!["-n"],
             CommandType::Help => vec![],
-            CommandType::Ls => vec![],
+            CommandType::History => vec!["--older-than", "--dedupe"],
+            CommandType::Ifconfig => vec![],
+            CommandType::Imginfo => vec![],
+            CommandType::Lastout => vec!["--copy"],
+            CommandType::Ln => vec!["-s"],
+            CommandType::Loc => vec!["-L"],
+            CommandType::Ls => vec![
+                "--group-dirs-first",
+                "--pattern",
+                "--ext",
+                "--larger-than",
+                "--smaller-than",
+                "--newer-than",
+                "--older-than",
+            ],
+            CommandType::Man => vec![],
+            CommandType::Mkdir => vec!["-p"],
+            CommandType::Mounts => vec!["--type", "--format"],
+            CommandType::Mv => vec!["-i"],
+            CommandType::Myip => vec!["--public", "--endpoint"],
+            CommandType::Note => vec![],
+            CommandType::Open => vec!["--with"],
+            CommandType::Popd => vec![],
+            CommandType::Portscan => vec!["--ports", "--timeout", "--workers", "--banner"],
+            CommandType::Pushd => vec![],
+            CommandType::Pwd => vec!["-P"],
+            CommandType::Qr => vec!["--output", "--ecc", "--invert"],
+            CommandType::Remind => vec![],
+            CommandType::Replace => vec!["-g", "--in-place", "--dry-run"],
+            CommandType::Rm => vec!["--recursive", "--force", "--interactive-menu"],
+            CommandType::Run => vec!["--names"],
+            CommandType::Set => vec![],
+            CommandType::Snapshot => vec!["--output", "--atomic"],
+            CommandType::Sort => vec!["-r", "-n", "-u", "--buffer-size"],
+            CommandType::Stat => vec![],
+            CommandType::Stopwatch => vec![],
+            CommandType::Tail => vec!["-n", "-f"],
+            CommandType::Tar => vec!["-c", "-x", "-t"],
+            CommandType::Todo => vec!["--all", "--priority", "--due", "--tags", "--format"],
+            CommandType::Todos => vec!["--format", "-L"],
+            CommandType::Tree => vec!["--max-depth", "--group-dirs-first"],
+            CommandType::Tutorial => vec!["--reset"],
+            CommandType::Umask => vec!["-S"],
+            CommandType::Uniq => vec!["-c", "-d"],
+            CommandType::Unzip => vec!["-l"],
+            CommandType::Usage => vec!["--reset"],
+            CommandType::Vault => vec![],
+            CommandType::Wc => vec!["-l", "-w", "-c"],
+            CommandType::Wizard => vec![],
+            CommandType::Zip => vec![],
+        };
+        for (alias, canonical) in self.get_flag_aliases() {
+            let index = flags.iter().position(|flag| flag == canonical);
+            flags.insert(index.unwrap_or(flags.len()), alias);
+        }
+        flags
+    }
+
+    /// Short spellings of flags as `(alias, canonical)`. Parsing replaces an
+    /// alias with its canonical name, so commands only check that one.
+    fn get_flag_aliases(&self) -> &'static [(&'static str, &'static str)] {
+        const OUTPUT: (&str, &str) = ("-o", "--output");
+        const RECURSIVE: (&str, &str) = ("-r", "--recursive");
+        const FORCE: (&str, &str) = ("-f", "--force");
+        const PRESERVE: (&str, &str) = ("-p", "--preserve");
+        match self {
+            CommandType::Backup => &[PRESERVE],
+            CommandType::Chgrp | CommandType::Chmod | CommandType::Chown => {
+                &[("-R", "--recursive")]
+            }
+            CommandType::Cp => &[RECURSIVE, FORCE, PRESERVE],
+            CommandType::Rm => &[RECURSIVE, FORCE],
+            CommandType::Decrypt
+            | CommandType::Docs
+            | CommandType::Encrypt
+            | CommandType::Qr
+            | CommandType::Snapshot => &[OUTPUT],
+            CommandType::Archive => &[("-i", "--ignore-case")],
+            CommandType::Grep => &[("-i", "--ignore-case"), RECURSIVE],
+            CommandType::Hash => &[("-c", "--check")],
+            CommandType::Lastout => &[("-c", "--copy")],
+            CommandType::Replace => &[("-i", "--in-place")],
+            CommandType::Todo => &[("-p", "--priority")],
+            _ => &[],
+        }
+    }
+
+    /// The supported flags under their canonical names, without aliases.
+    pub fn canonical_flags(&self) -> Vec<&str> {
+        let aliases = self.get_flag_aliases();
+        self.get_supported_flags()
+            .into_iter()
+            .filter(|flag| !aliases.iter().any(|(alias, _)| alias == flag))
+            .collect()
+    }
+
+    /// The canonical name of a flag, keeping any `=value` after it.
+    fn canonical_flag(&self, flag: &str) -> String {
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (flag, None),
+        };
+        let name = self
+            .get_flag_aliases()
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map_or(name, |(_, canonical)| canonical);
+        match value {
+            Some(value) => format!("{}={}", name, value),
+            None => name.to_string(),
+        }
+    }
+
+    /// The canonical flags a word stands for. Bundled short flags such as
+    /// `-lw` are split when every letter is a flag of the command; only the
+    /// last of them may take a value.
+    fn expand_flag_word(&self, word: &str) -> Vec<String> {
+        let bundle = word
+            .strip_prefix('-')
+            .filter(|letters| {
+                !letters.starts_with('-')
+                    && letters.chars().nth(1).is_some()
+                    && !self.is_supported_flag(word)
+            })
+            .map(|letters| {
+                letters
+                    .chars()
+                    .map(|letter| self.canonical_flag(&format!("-{}", letter)))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|flags| {
+                flags.iter().enumerate().all(|(index, flag)| {
+                    self.is_supported_flag(flag)
+                        && (index + 1 == flags.len() || !self.is_value_flag(flag))
+                })
+            });
+        bundle.unwrap_or_else(|| vec![self.canonical_flag(word)])
+    }
+
+    fn get_value_flags(&self) -> Vec<&str> {
+        match self {
+            CommandType::Audit => vec!["--format", "--min-severity"],
+            CommandType::Backup | CommandType::Cp => vec!["--reflink", "--sparse"],
+            CommandType::Chgrp => vec!["--reference"],
+            CommandType::Chown => vec!["--reference"],
+            CommandType::ConvertUnit => vec!["--to"],
+            CommandType::Cut => vec!["-d", "-f"],
+            CommandType::Date => vec!["--convert", "--from", "--to"],
+            CommandType::Debuglog => vec!["--level", "--file"],
+            CommandType::Decrypt | CommandType::Encrypt => vec!["--output"],
+            CommandType::Docs => vec!["--format", "--output"],
+            CommandType::Du => vec!["-d"],
+            CommandType::Examples => vec!["--run"],
+            CommandType::Grep => vec!["-e", "-A", "-B", "-C"],
+            CommandType::Find => vec![
+                "--max-depth",
+                "--larger-than",
+                "--smaller-than",
+                "--newer-than",
+                "--older-than",
+                "--exec",
+            ],
+            CommandType::Hash => vec!["--check"],
+            CommandType::Head => vec!["-n"],
+            CommandType::History => vec!["--older-than"],
+            CommandType::Ls => vec![
+                "--pattern",
+                "--ext",
+                "--larger-than",
+                "--smaller-than",
+                "--newer-than",
+                "--older-than",
+            ],
+            CommandType::Mounts => vec!["--type", "--format"],
+            CommandType::Myip => vec!["--endpoint"],
+            CommandType::Open => vec!["--with"],
+            CommandType::Portscan => vec!["--ports", "--timeout", "--workers"],
+            CommandType::Qr => vec!["--output", "--ecc"],
+            CommandType::Snapshot => vec!["--output"],
+            CommandType::Sort => vec!["--buffer-size"],
+            CommandType::Tail => vec!["-n"],
+            CommandType::Todo => vec!["--priority", "--due", "--tags", "--format"],
+            CommandType::Todos => vec!["--format"],
+            CommandType::Tree => vec!["--max-depth"],
+            _ => vec![],
         }
     }
 
@@ -140,26 +1333,343 @@ impl CommandType {
         self.get_supported_flags().contains(&flag)
     }
 
-    fn get_expected_argument_count(&self) -> Option<ArgumentCount> {
+    pub fn is_value_flag(&self, flag: &str) -> bool {
+        self.get_value_flags().contains(&flag)
+    }
+
+    /// Value flags that only take their value after `=`, as in
+    /// `--reflink=auto`, because the next word is a path they would swallow.
+    fn needs_attached_value(&self, flag: &str) -> bool {
+        matches!(self, CommandType::Backup | CommandType::Cp)
+            && matches!(flag, "--reflink" | "--sparse")
+    }
+
+    /// Rules about which of the supported flags may be given together.
+    pub fn get_flag_constraints(&self) -> &'static [FlagConstraint] {
+        use FlagConstraint::*;
         match self {
+            CommandType::Cal => &[Conflicts("-m", "-s"), Conflicts("-3", "-y")],
+            CommandType::Date => &[Requires("--from", "--convert")],
+            // Deleting below a followed link would empty its target.
+            CommandType::Find => &[Conflicts("-L", "--delete")],
+            CommandType::Grep => &[Conflicts("-c", "-l")],
+            CommandType::Hash => &[Conflicts("--md5", "--sha1")],
+            CommandType::Myip => &[Requires("--endpoint", "--public")],
+            CommandType::Tar => &[
+                Conflicts("-c", "-x"),
+                Conflicts("-c", "-t"),
+                Conflicts("-x", "-t"),
+            ],
+            _ => &[],
+        }
+    }
+
+    /// Whether `flag` conflicts with any of `flags`.
+    pub fn conflicts_with_any(&self, flag: &str, flags: &[String]) -> bool {
+        self.get_flag_constraints()
+            .iter()
+            .any(|constraint| match constraint {
+                FlagConstraint::Conflicts(first, second) => {
+                    let other = if flag_name(flag) == *first {
+                        second
+                    } else if flag_name(flag) == *second {
+                        first
+                    } else {
+                        return false;
+                    };
+                    flags.iter().any(|f| flag_name(f) == *other)
+                }
+                FlagConstraint::Requires(..) => false,
+            })
+    }
+
+    /// Types of the arguments by position. The last one also applies to any
+    /// further arguments; commands that list none take any text.
+    fn get_argument_types(&self) -> &'static [ArgumentType] {
+        use ArgumentType::*;
+        match self {
+            CommandType::Backup => &[Directory, Text],
+            CommandType::Cat
+            | CommandType::Cut
+            | CommandType::Hash
+            | CommandType::Head
+            | CommandType::Imginfo
+            | CommandType::Sort
+            | CommandType::Tail
+            | CommandType::Uniq
+            | CommandType::Wc => &[ExistingFile],
+            CommandType::Chmod | CommandType::Grep => &[Text, ExistingPath],
+            CommandType::Countdown => &[Duration],
+            CommandType::Df | CommandType::Du | CommandType::Stat => &[ExistingPath],
+            CommandType::Find => &[Directory, Text],
+            CommandType::Replace => &[Text, Text, ExistingFile],
+            CommandType::Tree => &[Directory],
+            CommandType::Unzip => &[ExistingFile, Text],
+            _ => &[],
+        }
+    }
+
+    /// Types of the values of value flags; the others take any text.
+    fn get_flag_value_types(&self) -> &'static [(&'static str, ArgumentType)] {
+        use ArgumentType::*;
+        match self {
+            CommandType::Du => &[("-d", Integer)],
+            CommandType::Find | CommandType::Tree => &[("--max-depth", Integer)],
+            CommandType::Head | CommandType::Tail => &[("-n", Integer)],
+            CommandType::Myip => &[("--endpoint", Url)],
+            CommandType::Portscan => &[("--workers", Integer)],
+            _ => &[],
+        }
+    }
+
+    pub fn flag_value_type(&self, flag: &str) -> ArgumentType {
+        self.get_flag_value_types()
+            .iter()
+            .find(|(name, _)| *name == flag)
+            .map_or(ArgumentType::Text, |(_, value_type)| *value_type)
+    }
+
+    pub fn argument_type(&self, index: usize) -> ArgumentType {
+        let types = self.get_argument_types();
+        types
+            .get(index)
+            .or(types.last())
+            .copied()
+            .unwrap_or(ArgumentType::Text)
+    }
+
+    /// Commands that get the rest of the line as written, unexpanded, as
+    /// their only argument.
+    fn takes_raw_line(&self) -> bool {
+        matches!(self, CommandType::Explain)
+    }
+
+    /// Finds the command a name typed on a line stands for. Besides exact
+    /// names, the `command_matching` option can allow names in another case
+    /// and any start of a name that only one command has.
+    pub fn lookup(name: &str) -> Result<Self, CommandError> {
+        let error = match CommandType::try_from(name) {
+            Ok(command_type) => return Ok(command_type),
+            Err(error) => error,
+        };
+        let lowercase = name.to_lowercase();
+        match &*options::get(options::COMMAND_MATCHING) {
+            "ignore_case" => CommandType::try_from(lowercase.as_str()).map_err(|_| error),
+            "prefix" if !name.is_empty() => {
+                if let Ok(command_type) = CommandType::try_from(lowercase.as_str()) {
+                    return Ok(command_type);
+                }
+                let candidates = CommandType::ALL
+                    .iter()
+                    .filter(|command_type| command_type.name().starts_with(&lowercase))
+                    .collect::<Vec<_>>();
+                match candidates[..] {
+                    [] => Err(error),
+                    [command_type] => {
+                        log!(Parser, Debug, "{:?} matched {}", name, command_type.name());
+                        Ok(*command_type)
+                    }
+                    _ => Err(CommandError::AmbiguousCommand {
+                        name: name.to_owned(),
+                        candidates: candidates.iter().map(|c| c.name()).collect(),
+                    }),
+                }
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// How the command reads a word such as `-5`, which is otherwise a flag.
+    fn get_numeric_shorthand(&self) -> Option<NumericShorthand> {
+        match self {
+            CommandType::ConvertUnit => Some(NumericShorthand::Argument),
+            CommandType::Head | CommandType::Tail => Some(NumericShorthand::FlagValue("-n")),
+            _ => None,
+        }
+    }
+
+    /// For commands that run another command, how many arguments of their
+    /// own come first. Every word after the other command's name is passed
+    /// on as an argument, flags included.
+    fn nested_command_start(&self) -> Option<usize> {
+        match self {
+            CommandType::Capture => Some(1),
+            _ => None,
+        }
+    }
+
+    /// The value flag that takes a whole command, from the next word up to
+    /// a lone `;`, stored as one quoted line.
+    fn nested_command_flag(&self) -> Option<&'static str> {
+        match self {
+            CommandType::Find => Some("--exec"),
+            _ => None,
+        }
+    }
+
+    pub fn get_expected_argument_count(&self) -> Option<ArgumentCount> {
+        match self {
+            CommandType::Archive => Some(ArgumentCount::Exact(3)),
+            CommandType::Audit => Some(ArgumentCount::Exact(1)),
+            CommandType::Backup => Some(ArgumentCount::Exact(2)),
+            CommandType::Cal => Some(ArgumentCount::AtMost(2)),
+            CommandType::Capture => Some(ArgumentCount::AtLeast(2)),
+            CommandType::Cat => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Cd => Some(ArgumentCount::AtMost(1)),
+            CommandType::Chgrp => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Chmod => Some(ArgumentCount::AtLeast(2)),
+            CommandType::Chown => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Completions => Some(ArgumentCount::Exact(1)),
+            CommandType::ConvertUnit => Some(ArgumentCount::Range(1, 2)),
+            CommandType::Countdown => Some(ArgumentCount::Exact(1)),
+            CommandType::Cp => Some(ArgumentCount::AtLeast(2)),
+            CommandType::Cut => None,
+            CommandType::Date => Some(ArgumentCount::Exact(0)),
+            CommandType::Debuglog => Some(ArgumentCount::AtMost(2)),
+            CommandType::Decrypt => Some(ArgumentCount::Exact(1)),
+            CommandType::Df => None,
+            CommandType::Dirs => Some(ArgumentCount::Exact(0)),
+            CommandType::Docs => Some(ArgumentCount::Exact(1)),
+            CommandType::Du => None,
             CommandType::Echo => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Encrypt => Some(ArgumentCount::Exact(1)),
+            CommandType::Examples => Some(ArgumentCount::Exact(1)),
             CommandType::Exit => Some(ArgumentCount::Exact(0)),
-            CommandType::Help => Some(ArgumentCount::Exact(0)),
+            CommandType::Explain => Some(ArgumentCount::Exact(1)),
+            CommandType::Find => Some(ArgumentCount::Exact(2)),
+            CommandType::Gitinfo => Some(ArgumentCount::Exact(0)),
+            // With -e every argument is a path, and -r needs none.
+            CommandType::Grep => None,
+            CommandType::Hash => None,
+            CommandType::Head => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Help => Some(ArgumentCount::AtMost(1)),
+            CommandType::History => None,
+            CommandType::Ifconfig => None,
+            CommandType::Imginfo => Some(ArgumentCount::Exact(1)),
+            CommandType::Lastout => Some(ArgumentCount::Exact(0)),
+            CommandType::Ln => Some(ArgumentCount::Exact(2)),
+            CommandType::Loc => Some(ArgumentCount::AtMost(1)),
             CommandType::Ls => None,
+            CommandType::Man => Some(ArgumentCount::Exact(1)),
+            CommandType::Mkdir => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Mounts => Some(ArgumentCount::Exact(0)),
+            CommandType::Mv => Some(ArgumentCount::AtLeast(2)),
+            CommandType::Myip => Some(ArgumentCount::Exact(0)),
+            CommandType::Note => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Open => Some(ArgumentCount::Exact(1)),
+            CommandType::Popd => Some(ArgumentCount::Exact(0)),
+            CommandType::Portscan => Some(ArgumentCount::Exact(1)),
+            CommandType::Pushd => Some(ArgumentCount::AtMost(1)),
+            CommandType::Pwd => Some(ArgumentCount::Exact(0)),
+            CommandType::Qr => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Remind => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Replace => Some(ArgumentCount::AtLeast(3)),
+            CommandType::Rm => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Run => Some(ArgumentCount::AtMost(1)),
+            CommandType::Set => Some(ArgumentCount::AtMost(2)),
+            CommandType::Snapshot => Some(ArgumentCount::Range(2, 3)),
+            CommandType::Sort => None,
+            CommandType::Stat => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Stopwatch => Some(ArgumentCount::AtMost(1)),
+            CommandType::Tail => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Tar => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Todo => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Todos => Some(ArgumentCount::AtMost(1)),
+            CommandType::Tree => Some(ArgumentCount::AtMost(1)),
+            CommandType::Tutorial => Some(ArgumentCount::Exact(0)),
+            CommandType::Umask => Some(ArgumentCount::AtMost(1)),
+            CommandType::Uniq => Some(ArgumentCount::AtMost(1)),
+            CommandType::Unzip => Some(ArgumentCount::Range(1, 2)),
+            CommandType::Usage => Some(ArgumentCount::Exact(0)),
+            CommandType::Vault => Some(ArgumentCount::Range(1, 2)),
+            CommandType::Wc => Some(ArgumentCount::AtLeast(1)),
+            CommandType::Wizard => Some(ArgumentCount::Exact(1)),
+            CommandType::Zip => Some(ArgumentCount::AtLeast(2)),
         }
     }
 }
 
-impl TryFrom<String> for CommandType {
+impl TryFrom<&str> for CommandType {
     type Error = CommandError;
 
-    fn try_from(input: String) -> Result<Self, Self::Error> {
-        match input.as_str() {
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        match input {
+            "archive" => Ok(CommandType::Archive),
+            "audit" => Ok(CommandType::Audit),
+            "backup" => Ok(CommandType::Backup),
+            "cal" => Ok(CommandType::Cal),
+            "capture" => Ok(CommandType::Capture),
+            "cat" => Ok(CommandType::Cat),
+            "cd" => Ok(CommandType::Cd),
+            "chgrp" => Ok(CommandType::Chgrp),
+            "chmod" => Ok(CommandType::Chmod),
+            "chown" => Ok(CommandType::Chown),
+            "completions" => Ok(CommandType::Completions),
+            "convert-unit" => Ok(CommandType::ConvertUnit),
+            "countdown" => Ok(CommandType::Countdown),
+            "cp" => Ok(CommandType::Cp),
+            "cut" => Ok(CommandType::Cut),
+            "date" => Ok(CommandType::Date),
+            "debuglog" => Ok(CommandType::Debuglog),
+            "decrypt" => Ok(CommandType::Decrypt),
+            "df" => Ok(CommandType::Df),
+            "dirs" => Ok(CommandType::Dirs),
+            "docs" => Ok(CommandType::Docs),
+            "du" => Ok(CommandType::Du),
             "echo" => Ok(CommandType::Echo),
+            "encrypt" => Ok(CommandType::Encrypt),
+            "examples" => Ok(CommandType::Examples),
             "exit" => Ok(CommandType::Exit),
+            "explain" => Ok(CommandType::Explain),
+            "find" => Ok(CommandType::Find),
+            "gitinfo" => Ok(CommandType::Gitinfo),
+            "grep" => Ok(CommandType::Grep),
+            "hash" => Ok(CommandType::Hash),
+            "head" => Ok(CommandType::Head),
             "help" => Ok(CommandType::Help),
+            "history" => Ok(CommandType::History),
+            "ifconfig" | "ip" => Ok(CommandType::Ifconfig),
+            "imginfo" => Ok(CommandType::Imginfo),
+            "lastout" => Ok(CommandType::Lastout),
+            "ln" => Ok(CommandType::Ln),
+            "loc" => Ok(CommandType::Loc),
             "ls" => Ok(CommandType::Ls),
-            _ => Err(CommandError::UnknownCommand(input)),
+            "man" => Ok(CommandType::Man),
+            "mkdir" => Ok(CommandType::Mkdir),
+            "mounts" => Ok(CommandType::Mounts),
+            "mv" => Ok(CommandType::Mv),
+            "myip" => Ok(CommandType::Myip),
+            "note" => Ok(CommandType::Note),
+            "open" => Ok(CommandType::Open),
+            "popd" => Ok(CommandType::Popd),
+            "portscan" => Ok(CommandType::Portscan),
+            "pushd" => Ok(CommandType::Pushd),
+            "pwd" => Ok(CommandType::Pwd),
+            "qr" => Ok(CommandType::Qr),
+            "remind" => Ok(CommandType::Remind),
+            "replace" => Ok(CommandType::Replace),
+            "rm" => Ok(CommandType::Rm),
+            "run" => Ok(CommandType::Run),
+            "set" => Ok(CommandType::Set),
+            "snapshot" => Ok(CommandType::Snapshot),
+            "sort" => Ok(CommandType::Sort),
+            "stat" => Ok(CommandType::Stat),
+            "stopwatch" => Ok(CommandType::Stopwatch),
+            "tail" => Ok(CommandType::Tail),
+            "tar" => Ok(CommandType::Tar),
+            "todo" => Ok(CommandType::Todo),
+            "todos" => Ok(CommandType::Todos),
+            "tree" => Ok(CommandType::Tree),
+            "tutorial" => Ok(CommandType::Tutorial),
+            "umask" => Ok(CommandType::Umask),
+            "uniq" => Ok(CommandType::Uniq),
+            "unzip" => Ok(CommandType::Unzip),
+            "usage" => Ok(CommandType::Usage),
+            "vault" => Ok(CommandType::Vault),
+            "wc" => Ok(CommandType::Wc),
+            "wizard" => Ok(CommandType::Wizard),
+            "zip" => Ok(CommandType::Zip),
+            _ => Err(CommandError::UnknownCommand(input.to_owned())),
         }
     }
 }
@@ -186,36 +1696,255 @@ impl ArgumentCount {
 impl Display for ArgumentCount {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            ArgumentCount::Exact(count) => write!(f, "exactly {}", count),
-            ArgumentCount::AtLeast(min) => write!(f, "at least {}", min),
-            ArgumentCount::AtMost(max) => write!(f, "up to {}", max),
+            ArgumentCount::Exact(count) => write!(f, "{}", tr!(Message::ExactCount, count)),
+            ArgumentCount::AtLeast(min) => write!(f, "{}", tr!(Message::AtLeastCount, min)),
+            ArgumentCount::AtMost(max) => write!(f, "{}", tr!(Message::AtMostCount, max)),
             ArgumentCount::Range(min, max) => write!(f, "{}-{}", min, max),
         }
     }
 }
 
+/// What a word made of `-` and a number means for a command that declares
+/// one, instead of being a flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericShorthand {
+    /// A negative number argument, such as `-40C`.
+    Argument,
+    /// The value of the flag, so `head -5` is `head -n 5`.
+    FlagValue(&'static str),
+}
+
+impl NumericShorthand {
+    /// Whether the text after the `-` is a number of the kind it stands for.
+    fn matches(self, number: &str) -> bool {
+        match self {
+            NumericShorthand::Argument => number.starts_with(|c: char| c.is_ascii_digit()),
+            NumericShorthand::FlagValue(_) => {
+                !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+            }
+        }
+    }
+}
+
+/// A rule about two flags of a command, checked when the command is parsed
+/// so that commands can rely on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagConstraint {
+    /// The two flags cannot be given together.
+    Conflicts(&'static str, &'static str),
+    /// The first flag can only be given together with the second.
+    Requires(&'static str, &'static str),
+}
+
+/// What an argument must be. Commands can rely on their arguments having
+/// been checked instead of checking them again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentType {
+    Text,
+    /// A file, directory or symlink that exists.
+    ExistingPath,
+    /// An existing path that is not a directory.
+    ExistingFile,
+    Directory,
+    Integer,
+    /// A duration such as `90s` or `1h30m`.
+    Duration,
+    /// An `http` or `https` URL.
+    Url,
+}
+
+impl ArgumentType {
+    /// Checks what the text alone tells, when the line is parsed.
+    fn accepts_text(self, argument: &str) -> bool {
+        match self {
+            ArgumentType::Integer => argument.parse::<i64>().is_ok(),
+            ArgumentType::Duration => units::parse_duration(argument).is_ok(),
+            ArgumentType::Url => ["http://", "https://"].iter().any(|scheme| {
+                argument
+                    .strip_prefix(scheme)
+                    .is_some_and(|rest| !rest.is_empty() && !rest.starts_with('/'))
+            }),
+            _ => true,
+        }
+    }
+
+    /// Checks the filesystem, right before the command runs, since paths
+    /// are resolved against the working directory at that point.
+    fn accepts_path(self, path: &Path) -> bool {
+        match self {
+            ArgumentType::ExistingPath => fs::symlink_metadata(path).is_ok(),
+            ArgumentType::ExistingFile => {
+                fs::metadata(path).is_ok_and(|metadata| !metadata.is_dir())
+            }
+            ArgumentType::Directory => path.is_dir(),
+            _ => true,
+        }
+    }
+
+    /// Both checks at once, for an argument typed somewhere other than a
+    /// command line; `path` is the argument resolved as a path.
+    pub fn accepts(self, argument: &str, path: &Path) -> bool {
+        self.accepts_text(argument) && self.accepts_path(path)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ArgumentType::Text => Message::Text,
+            ArgumentType::ExistingPath => Message::ExistingPath,
+            ArgumentType::ExistingFile => Message::ExistingFile,
+            ArgumentType::Directory => Message::Directory,
+            ArgumentType::Integer => Message::Integer,
+            ArgumentType::Duration => Message::Duration,
+            ArgumentType::Url => Message::Url,
+        }
+        .text()
+    }
+}
+
 #[derive(Debug)]
 pub enum CommandError {
+    EmptyInput,
+    UnterminatedQuote,
     UnknownCommand(String),
+    /// A start of a command name that several commands share.
+    AmbiguousCommand {
+        name: String,
+        candidates: Vec<&'static str>,
+    },
     UnsupportedFlag(String),
+    MissingFlagValue(String),
+    SecretExpansion(String),
+    VariableExpansion(String),
+    /// A `!` history reference that matches no earlier line.
+    EventNotFound(String),
+    /// Default flags in the config file that the command does not accept.
+    InvalidDefaults {
+        key: String,
+        error: Box<CommandError>,
+    },
     WrongArgumentsCount {
         expected: ArgumentCount,
         actual: usize,
     },
+    /// Two flags that cannot be given together.
+    ConflictingFlags(&'static str, &'static str),
+    /// A flag given without another flag it only works with.
+    MissingRequiredFlag {
+        flag: &'static str,
+        required: &'static str,
+    },
+    /// An argument that is not of the type the command declares.
+    InvalidArgument {
+        /// Counted from 1.
+        position: usize,
+        argument: String,
+        expected: ArgumentType,
+    },
+    /// A flag value that is not of the type the command declares.
+    InvalidFlagValue {
+        /// The flag with its value, as `-n=ten`.
+        flag: String,
+        expected: ArgumentType,
+    },
+    /// Wraps another error with the part of the input it refers to.
+    Located {
+        error: Box<CommandError>,
+        input: String,
+        span: Span,
+    },
+}
+
+impl CommandError {
+    /// Attaches the location of the offending part of `input`.
+    pub fn at(self, input: &str, span: Span) -> Self {
+        match self {
+            CommandError::Located { .. } => self,
+            error => CommandError::Located {
+                error: Box::new(error),
+                input: input.to_owned(),
+                span,
+            },
+        }
+    }
+
+    /// The underlying error, without location information.
+    pub fn kind(&self) -> &CommandError {
+        match self {
+            CommandError::Located { error, .. } => error,
+            error => error,
+        }
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            CommandError::Located { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
 }
 
 impl Error for CommandError {}
 
 impl Display for CommandError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            CommandError::UnknownCommand(command) => write!(f, "Unknown command: {}", command),
-            CommandError::UnsupportedFlag(flag) => write!(f, "Unsupported flag: {}", flag),
-            CommandError::WrongArgumentsCount { expected, actual } => write!(
-                f,
-                "Wrong number of arguments: expected {}, got {}",
-                expected, actual
+        let message = match self {
+            CommandError::EmptyInput => tr!(Message::NoCommandGiven),
+            CommandError::UnterminatedQuote => tr!(Message::UnterminatedQuote),
+            CommandError::UnknownCommand(command) => tr!(Message::UnknownCommand, command),
+            CommandError::AmbiguousCommand { name, candidates } => {
+                tr!(Message::AmbiguousCommand, name, candidates.join(", "))
+            }
+            CommandError::UnsupportedFlag(flag) => tr!(Message::UnsupportedFlag, flag),
+            CommandError::MissingFlagValue(flag) => tr!(Message::MissingFlagValue, flag),
+            CommandError::SecretExpansion(error) => tr!(Message::SecretExpansion, error),
+            CommandError::VariableExpansion(error) => error.clone(),
+            CommandError::EventNotFound(event) => tr!(Message::EventNotFound, event),
+            CommandError::InvalidDefaults { key, error } => {
+                tr!(Message::InvalidDefaults, error, key)
+            }
+            CommandError::WrongArgumentsCount { expected, actual } => {
+                tr!(Message::WrongArgumentsCount, expected, actual)
+            }
+            CommandError::ConflictingFlags(first, second) => {
+                tr!(Message::ConflictingFlags, first, second)
+            }
+            CommandError::MissingRequiredFlag { flag, required } => {
+                tr!(Message::MissingRequiredFlag, flag, required)
+            }
+            CommandError::InvalidArgument {
+                position,
+                argument,
+                expected,
+            } => tr!(
+                Message::InvalidArgument,
+                position,
+                expected.description(),
+                argument
             ),
-        }
+            CommandError::InvalidFlagValue { flag, expected } => {
+                let (name, value) = flag.split_once('=').unwrap_or((flag, ""));
+                tr!(
+                    Message::InvalidFlagValue,
+                    name,
+                    expected.description(),
+                    value
+                )
+            }
+            CommandError::Located { error, input, span } => {
+                // Columns are counted in characters so the caret lines up.
+                let column = input[..span.start].chars().count();
+                let width = input[span.start..span.end].chars().count().max(1);
+                return write!(
+                    f,
+                    "{} ({})\n  {}\n  {}{}",
+                    error,
+                    tr!(Message::ErrorLocation, span.start, span.end),
+                    input,
+                    " ".repeat(column),
+                    "^".repeat(width)
+                );
+            }
+        };
+        write!(f, "{}", message)
     }
 }
@@ -0,0 +1,95 @@
+use super::{input_utils::split_input_outside_quotes_on_whitespace, CommandError};
+use super::{Quoting, Span};
+use std::borrow::Cow;
+
+const EVENT_START: char = '!';
+
+/// Expands `!!` to the previous line, `!$` to its last word and `!text` to
+/// the most recent line starting with `text`. `history` holds earlier lines,
+/// the oldest first. Only unquoted words expand, so `'!!'` stays as it is; a
+/// `!` at the end of a word or before `=` is left alone too.
+pub fn expand_history<'a>(
+    input: &'a str,
+    history: &[String],
+) -> Result<Cow<'a, str>, CommandError> {
+    if !input.contains(EVENT_START) {
+        return Ok(Cow::Borrowed(input));
+    }
+    // Unbalanced quotes are left for the parser to report.
+    let Ok(words) = split_input_outside_quotes_on_whitespace(input) else {
+        return Ok(Cow::Borrowed(input));
+    };
+
+    let mut output = String::new();
+    let mut copied = 0;
+    for word in words {
+        if word.quoting != Quoting::Unquoted || !word.text.contains(EVENT_START) {
+            continue;
+        }
+        let text = &input[word.span.start..word.span.end];
+        let mut search_from = 0;
+        while let Some(offset) = text[search_from..].find(EVENT_START) {
+            let start = search_from + offset;
+            let designator = &text[start + 1..];
+            let (replacement, length) = match designator.chars().next() {
+                None => break,
+                // `!=` is usually a comparison, as bash also assumes.
+                Some('=' | '(') => {
+                    search_from = start + 1;
+                    continue;
+                }
+                Some('!') => (previous(history)?.to_string(), 2),
+                Some('$') => (last_word(previous(history)?), 2),
+                Some(_) => {
+                    let line = history
+                        .iter()
+                        .rev()
+                        .find(|line| line.starts_with(designator))
+                        .ok_or_else(|| {
+                            CommandError::EventNotFound(format!("!{}", designator)).at(
+                                input,
+                                Span {
+                                    start: word.span.start + start,
+                                    end: word.span.end,
+                                },
+                            )
+                        })?;
+                    (line.clone(), designator.len() + 1)
+                }
+            };
+            let absolute = word.span.start + start;
+            output.push_str(&input[copied..absolute]);
+            output.push_str(&replacement);
+            copied = absolute + length;
+            search_from = start + length;
+        }
+    }
+
+    if copied == 0 {
+        return Ok(Cow::Borrowed(input));
+    }
+    output.push_str(&input[copied..]);
+    Ok(Cow::Owned(output))
+}
+
+fn previous(history: &[String]) -> Result<&str, CommandError> {
+    history
+        .last()
+        .map(String::as_str)
+        .ok_or_else(|| CommandError::EventNotFound("!!".to_string()))
+}
+
+/// The last word of `line` as it was typed, quotes included.
+fn last_word(line: &str) -> String {
+    match split_input_outside_quotes_on_whitespace(line) {
+        Ok(words) => words
+            .last()
+            .map(|word| line[word.span.start..word.span.end].to_string())
+            .unwrap_or_default(),
+        Err(_) => line
+            .split_whitespace()
+            .last()
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
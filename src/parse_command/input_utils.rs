@@ -1,31 +1,179 @@
-pub fn split_input_outside_quotes_on_whitespace(input: String) -> Vec<String> {
-    split_input_outside_quotes(input, ' ')
+use super::CommandError;
+use std::borrow::Cow;
+
+const COMMENT_START: char = '#';
+
+/// How a word was quoted, which decides the expansions later stages may apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quoting {
+    /// No quotes: every expansion applies.
+    Unquoted,
+    /// At least partly in `"..."`: secrets expand, but the word stays one argument.
+    Double,
+    /// At least partly in `'...'` or `r"..."`: the text is passed through with no expansion.
+    Raw,
+}
+
+/// Byte range of a word in the original input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A word of the input. Unquoted words borrow their text from the input;
+/// only words whose quotes had to be removed own a copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: Cow<'a, str>,
+    pub quoting: Quoting,
+    pub span: Span,
+}
+
+impl Token<'_> {
+    pub fn allows_expansion(&self) -> bool {
+        self.quoting != Quoting::Raw
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum QuoteState {
+    None,
+    Double,
+    Single,
+    RawString,
+}
+
+pub fn split_input_outside_quotes_on_whitespace(
+    input: &str,
+) -> Result<Vec<Token<'_>>, CommandError> {
+    split_input_outside_quotes(input, char::is_whitespace)
 }
 
-fn split_input_outside_quotes(input: String, delimiter: char) -> Vec<String> {
+/// Splits `input` on delimiters that are not inside quotes. Quotes are
+/// removed; a quoted empty string (`""`) produces an empty token.
+/// An unquoted `#` at the start of a word comments out the rest of the line.
+fn split_input_outside_quotes(
+    input: &str,
+    is_delimiter: impl Fn(char) -> bool,
+) -> Result<Vec<Token<'_>>, CommandError> {
     let mut output = Vec::new();
     let mut current = String::new();
-    let mut inside_quotes = false;
+    let mut quoting = Quoting::Unquoted;
+    let mut token_start = None;
+    let mut quote_start = 0;
+    let mut state = QuoteState::None;
+    let mut chars = input.char_indices().peekable();
 
-    for c in input.chars() {
-        if c == '"' {
-            inside_quotes = !inside_quotes;
-            continue;
-        }
+    while let Some((index, c)) = chars.next() {
+        let has_token = token_start.is_some();
 
-        if c == delimiter && !inside_quotes {
-            if current.is_empty() {
+        match (state, c) {
+            (QuoteState::None, '"') => {
+                if quoting == Quoting::Unquoted {
+                    begin_quoted(input, &mut current, token_start, index);
+                }
+                state = QuoteState::Double;
+                quote_start = index;
+                quoting = quoting.max(Quoting::Double);
+            }
+            (QuoteState::None, '\'') => {
+                if quoting == Quoting::Unquoted {
+                    begin_quoted(input, &mut current, token_start, index);
+                }
+                state = QuoteState::Single;
+                quote_start = index;
+                quoting = Quoting::Raw;
+            }
+            (QuoteState::None, 'r') if !has_token && chars.peek().map(|&(_, c)| c) == Some('"') => {
+                chars.next();
+                state = QuoteState::RawString;
+                quote_start = index;
+                quoting = Quoting::Raw;
+            }
+            (QuoteState::Double | QuoteState::RawString, '"') | (QuoteState::Single, '\'') => {
+                state = QuoteState::None;
+            }
+            (QuoteState::None, COMMENT_START) if !has_token => break,
+            (QuoteState::None, c) if is_delimiter(c) => {
+                if let Some(start) = token_start.take() {
+                    output.push(word(input, &mut current, quoting, start, index));
+                    quoting = Quoting::Unquoted;
+                }
                 continue;
             }
-
-            output.push(current);
-            current = String::new();
-            continue;
+            (_, c) => {
+                if quoting != Quoting::Unquoted {
+                    current.push(c);
+                }
+            }
         }
+        token_start.get_or_insert(index);
+    }
+
+    if state != QuoteState::None {
+        return Err(CommandError::UnterminatedQuote.at(
+            input,
+            Span {
+                start: quote_start,
+                end: input.len(),
+            },
+        ));
+    }
+    if let Some(start) = token_start {
+        output.push(word(input, &mut current, quoting, start, input.len()));
+    }
 
-        current.push(c);
+    Ok(output)
+}
+
+/// Unquoted words are sliced from `input` when they end. Once a word turns
+/// out to contain quotes, its text so far is copied into `current`, which
+/// then collects the rest without the quotes.
+fn begin_quoted(input: &str, current: &mut String, token_start: Option<usize>, index: usize) {
+    if let Some(start) = token_start {
+        current.push_str(&input[start..index]);
+    }
+}
+
+fn word<'a>(
+    input: &'a str,
+    current: &mut String,
+    quoting: Quoting,
+    start: usize,
+    end: usize,
+) -> Token<'a> {
+    let text = if quoting == Quoting::Unquoted {
+        Cow::Borrowed(&input[start..end])
+    } else {
+        Cow::Owned(std::mem::take(current))
+    };
+
+    Token {
+        text,
+        quoting,
+        span: Span { start, end },
     }
+}
 
-    output.push(current);
-    output
+/// Joins tokens into a line that splits back into the same tokens. Tokens
+/// that are empty or contain whitespace, quotes or characters that expand
+/// are put in single quotes, so they come back without expansion; a `'`
+/// inside ends the quotes and is written as `"'"`.
+pub fn join_quoted(tokens: &[String]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            if token.is_empty()
+                || token.starts_with(COMMENT_START)
+                || token
+                    .contains(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '$' | '!'))
+            {
+                format!("'{}'", token.replace('\'', "'\"'\"'"))
+            } else {
+                token.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
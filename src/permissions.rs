@@ -0,0 +1,135 @@
+use crate::{log, options};
+use std::{error::Error, sync::OnceLock};
+
+/// The `umask` option value that keeps the umask the shell was started with.
+pub const INHERIT: &str = "inherit";
+
+static INHERITED_UMASK: OnceLock<u32> = OnceLock::new();
+
+const CLASSES: [(char, u32); 3] = [('u', 6), ('g', 3), ('o', 0)];
+
+/// Parses an octal mode such as `022` or `0755`.
+pub fn parse_octal(text: &str) -> Result<u32, Box<dyn Error>> {
+    match u32::from_str_radix(text, 8) {
+        Ok(mode) if !text.is_empty() && mode <= 0o7777 => Ok(mode),
+        _ => Err(format!("Invalid octal mode: {}", text).into()),
+    }
+}
+
+pub fn octal(mode: u32) -> String {
+    format!("{:04o}", mode)
+}
+
+/// Renders the permission bits of `mode` as `u=rwx,g=rx,o=rx`.
+pub fn symbolic(mode: u32) -> String {
+    CLASSES
+        .iter()
+        .map(|(class, shift)| {
+            let bits = (mode >> shift) & 0o7;
+            let mut text = format!("{}=", class);
+            for (bit, letter) in [(4, 'r'), (2, 'w'), (1, 'x')] {
+                if bits & bit != 0 {
+                    text.push(letter);
+                }
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Applies symbolic clauses such as `u=rwx,g+r,o-w` to the permission bits
+/// in `mode`. Without a class (`+x`) a clause applies to all of them.
+pub fn apply_symbolic(mode: u32, clauses: &str) -> Result<u32, Box<dyn Error>> {
+    let mut mode = mode;
+    for clause in clauses.split(',') {
+        let operator_index = clause
+            .find(['=', '+', '-'])
+            .ok_or_else(|| format!("Invalid symbolic mode: {}", clause))?;
+        let (classes, rest) = clause.split_at(operator_index);
+        let (operator, letters) = rest.split_at(1);
+
+        let mut bits = 0;
+        for letter in letters.chars() {
+            bits |= match letter {
+                'r' => 4,
+                'w' => 2,
+                'x' => 1,
+                _ => return Err(format!("Invalid permission: {}", letter).into()),
+            };
+        }
+
+        let classes = if classes.is_empty() || classes == "a" {
+            "ugo"
+        } else {
+            classes
+        };
+        for class in classes.chars() {
+            let (_, shift) = CLASSES
+                .iter()
+                .find(|(name, _)| *name == class)
+                .ok_or_else(|| format!("Invalid class: {}", class))?;
+            let mask = 0o7 << shift;
+            let shifted = bits << shift;
+            mode = match operator {
+                "=" => (mode & !mask) | shifted,
+                "+" => mode | shifted,
+                _ => mode & !shifted,
+            };
+        }
+    }
+    Ok(mode)
+}
+
+/// Sets the process umask from the `umask` option, so every file the shell
+/// creates gets the configured default permissions.
+pub fn apply_configured_umask() {
+    let inherited = *INHERITED_UMASK.get_or_init(current_umask);
+    let value = options::get(options::UMASK);
+    if value == INHERIT {
+        set_umask(inherited);
+        return;
+    }
+    match parse_octal(&value) {
+        Ok(mask) => {
+            set_umask(mask);
+        }
+        Err(error) => log!(Config, Warn, "not applying umask: {}", error),
+    }
+}
+
+pub fn current_umask() -> u32 {
+    // The only way to read the umask is to set it, so put it straight back.
+    let mask = platform::set_umask(0);
+    platform::set_umask(mask);
+    mask
+}
+
+pub use self::platform::set_umask;
+
+#[cfg(unix)]
+mod platform {
+    #[cfg(target_os = "macos")]
+    type Mode = u16;
+    #[cfg(not(target_os = "macos"))]
+    type Mode = u32;
+
+    extern "C" {
+        fn umask(mask: Mode) -> Mode;
+    }
+
+    /// Sets the umask, returning the previous one.
+    pub fn set_umask(mask: u32) -> u32 {
+        // SAFETY: umask only swaps a process attribute and cannot fail.
+        unsafe { umask((mask & 0o777) as Mode) as u32 }
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    /// Windows has no umask; new files get the permissions of their
+    /// directory.
+    pub fn set_umask(_mask: u32) -> u32 {
+        0
+    }
+}
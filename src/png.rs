@@ -0,0 +1,73 @@
+//! Minimal PNG writer for black and white images. The image data is stored
+//! uncompressed, which keeps the encoder small.
+
+use crate::hash::crc32;
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const MAX_STORED_BLOCK: usize = 0xffff;
+
+/// Encodes a 1-bit grayscale image; `is_black(x, y)` gives each pixel.
+pub fn encode_bitmap(width: u32, height: u32, is_black: impl Fn(u32, u32) -> bool) -> Vec<u8> {
+    let row_bytes = (width as usize).div_ceil(8);
+    let mut pixels = Vec::with_capacity((row_bytes + 1) * height as usize);
+    for y in 0..height {
+        // Filter type: none.
+        pixels.push(0);
+        let row_start = pixels.len();
+        pixels.resize(row_start + row_bytes, 0);
+        for x in 0..width {
+            if !is_black(x, y) {
+                pixels[row_start + x as usize / 8] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // Bit depth 1, grayscale, deflate, adaptive filtering, no interlacing.
+    header.extend([1, 0, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&pixels));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        stream.push(u8::from(blocks.peek().is_none()));
+        let length = block.len() as u16;
+        stream.extend(length.to_le_bytes());
+        stream.extend((!length).to_le_bytes());
+        stream.extend(block);
+    }
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+/// The Adler-32 checksum that ends a zlib stream.
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    b << 16 | a
+}
@@ -0,0 +1,157 @@
+//! Detects the kind of project in a directory and the tasks it defines.
+
+use crate::json::{self, Value};
+use std::{error::Error, fs, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    Cargo,
+    Npm,
+    Make,
+}
+
+impl ProjectKind {
+    pub const ALL: [ProjectKind; 3] = [ProjectKind::Cargo, ProjectKind::Npm, ProjectKind::Make];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ProjectKind::Cargo => "cargo",
+            ProjectKind::Npm => "npm",
+            ProjectKind::Make => "make",
+        }
+    }
+
+    /// The file whose presence marks the project.
+    pub fn marker(self) -> &'static str {
+        match self {
+            ProjectKind::Cargo => "Cargo.toml",
+            ProjectKind::Npm => "package.json",
+            ProjectKind::Make => "Makefile",
+        }
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            ProjectKind::Cargo => "cargo",
+            // npm is a batch script on Windows, which needs its extension.
+            ProjectKind::Npm if cfg!(windows) => "npm.cmd",
+            ProjectKind::Npm => "npm",
+            ProjectKind::Make => "make",
+        }
+    }
+}
+
+const CARGO_TASKS: [&str; 8] = [
+    "build", "check", "test", "run", "clippy", "fmt", "doc", "bench",
+];
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub kind: ProjectKind,
+    pub name: String,
+    /// What the task runs, for listings: the npm script, or empty.
+    pub detail: String,
+}
+
+impl Task {
+    /// `kind:name`, which tells apart tasks of the same name.
+    pub fn qualified_name(&self) -> String {
+        format!("{}:{}", self.kind.name(), self.name)
+    }
+
+    pub fn program(&self) -> &'static str {
+        self.kind.program()
+    }
+
+    pub fn args(&self) -> Vec<String> {
+        match self.kind {
+            ProjectKind::Npm => vec!["run".to_string(), self.name.clone()],
+            ProjectKind::Cargo | ProjectKind::Make => vec![self.name.clone()],
+        }
+    }
+}
+
+/// The kinds of project `dir` is, by the files in it.
+pub fn detect(dir: &Path) -> Vec<ProjectKind> {
+    ProjectKind::ALL
+        .into_iter()
+        .filter(|kind| dir.join(kind.marker()).is_file())
+        .collect()
+}
+
+/// The tasks of every project in `dir`, in the order of [`ProjectKind::ALL`].
+pub fn tasks(dir: &Path) -> Result<Vec<Task>, Box<dyn Error>> {
+    let mut tasks = Vec::new();
+    for kind in detect(dir) {
+        let names = match kind {
+            ProjectKind::Cargo => CARGO_TASKS
+                .iter()
+                .map(|name| (name.to_string(), String::new()))
+                .collect(),
+            ProjectKind::Npm => npm_scripts(&dir.join(kind.marker()))?,
+            ProjectKind::Make => make_targets(&dir.join(kind.marker()))?
+                .into_iter()
+                .map(|target| (target, String::new()))
+                .collect(),
+        };
+        tasks.extend(
+            names
+                .into_iter()
+                .map(|(name, detail)| Task { kind, name, detail }),
+        );
+    }
+    Ok(tasks)
+}
+
+/// Finds a task by its plain or qualified name. A plain name picks the
+/// first project that has it.
+pub fn find<'a>(tasks: &'a [Task], name: &str) -> Option<&'a Task> {
+    tasks
+        .iter()
+        .find(|task| task.qualified_name() == name)
+        .or_else(|| tasks.iter().find(|task| task.name == name))
+}
+
+fn npm_scripts(path: &Path) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let package = json::parse(&fs::read_to_string(path)?)
+        .map_err(|error| format!("{}: {}", path.display(), error))?;
+    let Some(Value::Object(scripts)) = package.get("scripts") else {
+        return Ok(Vec::new());
+    };
+    Ok(scripts
+        .iter()
+        .map(|(name, script)| {
+            (
+                name.clone(),
+                script.as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect())
+}
+
+/// Explicit targets, leaving out special ones like `.PHONY`, pattern rules
+/// and variable assignments.
+fn make_targets(path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut targets = Vec::<String>::new();
+    for line in fs::read_to_string(path)?.lines() {
+        // Recipe lines start with a tab.
+        if line.starts_with('\t') {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') || names.contains('=') {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            let is_plain = name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+            if is_plain && !name.starts_with('.') && !targets.iter().any(|target| target == name) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    Ok(targets)
+}
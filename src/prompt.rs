@@ -0,0 +1,51 @@
+use crate::{context::ShellContext, gitinfo::Repository};
+use std::env;
+
+const PROMPT_ENV_VAR: &str = "MY_BASIC_CLI_PROMPT";
+const DEFAULT_PROMPT: &str = "> ";
+
+/// Renders the prompt template from `MY_BASIC_CLI_PROMPT`.
+///
+/// Supported placeholders: `{cwd}`, `{git_branch}`, `{git_dirty}` (`*` when
+/// tracked files changed), `{git}` (`(branch*) ` or nothing outside a repo),
+/// `{dir_depth}` (directories saved by `pushd`) and `{dirs}` (`[2] ` or
+/// nothing when none are saved).
+pub fn render(context: &ShellContext) -> String {
+    let template = env::var(PROMPT_ENV_VAR).unwrap_or_else(|_| DEFAULT_PROMPT.to_string());
+    if !template.contains('{') {
+        return template;
+    }
+
+    let cwd = context.cwd();
+    let repository = Repository::discover(&cwd);
+
+    let branch = repository
+        .as_ref()
+        .and_then(|repository| repository.head().ok())
+        .map(|head| head.to_string())
+        .unwrap_or_default();
+    let dirty = match &repository {
+        Some(repository) if repository.is_dirty().unwrap_or(false) => "*",
+        _ => "",
+    };
+    let git = if branch.is_empty() {
+        String::new()
+    } else {
+        format!("({}{}) ", branch, dirty)
+    };
+
+    let dir_depth = context.dir_stack().len();
+    let dirs = if dir_depth == 0 {
+        String::new()
+    } else {
+        format!("[{}] ", dir_depth)
+    };
+
+    template
+        .replace("{cwd}", &cwd.display().to_string())
+        .replace("{git_branch}", &branch)
+        .replace("{git_dirty}", dirty)
+        .replace("{git}", &git)
+        .replace("{dir_depth}", &dir_depth.to_string())
+        .replace("{dirs}", &dirs)
+}
@@ -0,0 +1,568 @@
+//! QR code encoder (ISO/IEC 18004) for byte mode data, following the
+//! structure of Project Nayuki's reference implementation.
+
+use std::error::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecc {
+    /// Recovers about 7% of the code.
+    Low,
+    /// About 15%.
+    Medium,
+    /// About 25%.
+    Quartile,
+    /// About 30%.
+    High,
+}
+
+impl Ecc {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn format_bits(self) -> u32 {
+        match self {
+            Ecc::Low => 1,
+            Ecc::Medium => 0,
+            Ecc::Quartile => 3,
+            Ecc::High => 2,
+        }
+    }
+}
+
+impl TryFrom<&str> for Ecc {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "L" | "l" => Ok(Ecc::Low),
+            "M" | "m" => Ok(Ecc::Medium),
+            "Q" | "q" => Ok(Ecc::Quartile),
+            "H" | "h" => Ok(Ecc::High),
+            _ => Err(format!(
+                "Unknown error correction level: {} (expected L, M, Q or H)",
+                value
+            )
+            .into()),
+        }
+    }
+}
+
+const MIN_VERSION: usize = 1;
+const MAX_VERSION: usize = 40;
+
+// Indexed by [Ecc][version]; version 0 does not exist.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+const NUM_ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+// Penalty weights for choosing a mask.
+const PENALTY_N1: i32 = 3;
+const PENALTY_N2: i32 = 3;
+const PENALTY_N3: i32 = 40;
+const PENALTY_N4: i32 = 10;
+
+/// A square grid of dark and light modules.
+#[derive(Debug, Clone)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encodes `data` in the smallest version that fits at the given error
+    /// correction level.
+    pub fn encode(data: &[u8], ecc: Ecc) -> Result<Self, Box<dyn Error>> {
+        let version = (MIN_VERSION..=MAX_VERSION)
+            .find(|&version| {
+                data_bit_length(data.len(), version) <= data_codewords(version, ecc) * 8
+            })
+            .ok_or("Data too long for a QR code")?;
+
+        let mut bits = BitBuffer::default();
+        bits.append(0b0100, 4);
+        bits.append(data.len() as u32, char_count_bits(version));
+        for byte in data {
+            bits.append(u32::from(*byte), 8);
+        }
+
+        let capacity = data_codewords(version, ecc) * 8;
+        bits.append(0, (capacity - bits.len()).min(4));
+        bits.append(0, (8 - bits.len() % 8) % 8);
+        for pad in [0xec, 0x11].into_iter().cycle() {
+            if bits.len() >= capacity {
+                break;
+            }
+            bits.append(pad, 8);
+        }
+
+        let codewords = add_ecc_and_interleave(&bits.to_bytes(), version, ecc);
+
+        let size = version * 4 + 17;
+        let mut qr = QrCode {
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version, ecc);
+        qr.draw_codewords(&codewords);
+
+        let best_mask = (0..8)
+            .min_by_key(|&mask| {
+                qr.apply_mask(mask);
+                qr.draw_format_bits(ecc, mask);
+                let penalty = qr.penalty_score();
+                qr.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        qr.apply_mask(best_mask);
+        qr.draw_format_bits(ecc, best_mask);
+
+        Ok(qr)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at column `x` and row `y` is dark.
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize, ecc: Ecc) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        self.draw_finder_pattern(3, 3);
+        self.draw_finder_pattern(size - 4, 3);
+        self.draw_finder_pattern(3, size - 4);
+
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // These overlap the finder patterns.
+                let is_corner = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                if !is_corner {
+                    self.draw_alignment_pattern(x, y);
+                }
+            }
+        }
+
+        // Reserves the format areas; the mask is chosen later.
+        self.draw_format_bits(ecc, 0);
+        self.draw_version(version);
+    }
+
+    fn draw_finder_pattern(&mut self, x: usize, y: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let distance = dx.abs().max(dy.abs());
+                let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                if (0..self.size as i32).contains(&xx) && (0..self.size as i32).contains(&yy) {
+                    self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment_pattern(&mut self, x: usize, y: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let distance = dx.abs().max(dy.abs());
+                self.set_function(
+                    (x as i32 + dx) as usize,
+                    (y as i32 + dy) as usize,
+                    distance != 1,
+                );
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, ecc: Ecc, mask: u32) {
+        let data = ecc.format_bits() << 3 | mask;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        // Around the top left finder pattern.
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        // Split between the other two finder patterns.
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self, version: usize) {
+        if version < 7 {
+            return;
+        }
+        let mut remainder = version as u32;
+        for _ in 0..12 {
+            remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+        }
+        let bits = (version as u32) << 12 | remainder;
+
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Places the codewords in the zigzag pattern of two-module columns,
+    /// skipping function modules.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let total_bits = codewords.len() * 8;
+        let mut i = 0;
+
+        let mut right = size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right as usize - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.is_function[y * size + x] && i < total_bits {
+                        self.modules[y * size + x] = (codewords[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    /// XORs the data modules with a mask pattern. Applying it twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if invert && !self.is_function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    fn penalty_score(&self) -> i32 {
+        let size = self.size;
+        let mut result = 0;
+
+        for horizontal in [true, false] {
+            for a in 0..size {
+                let mut run_color = false;
+                let mut run_length = 0;
+                let mut history = [0; 7];
+                for b in 0..size {
+                    let dark = if horizontal {
+                        self.is_dark(b, a)
+                    } else {
+                        self.is_dark(a, b)
+                    };
+                    if dark == run_color {
+                        run_length += 1;
+                        if run_length == 5 {
+                            result += PENALTY_N1;
+                        } else if run_length > 5 {
+                            result += 1;
+                        }
+                    } else {
+                        self.add_run_to_history(run_length, &mut history);
+                        if !run_color {
+                            result += self.count_finder_patterns(&history) * PENALTY_N3;
+                        }
+                        run_color = dark;
+                        run_length = 1;
+                    }
+                }
+                result +=
+                    self.terminate_and_count(run_color, run_length, &mut history) * PENALTY_N3;
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.is_dark(x, y);
+                if color == self.is_dark(x + 1, y)
+                    && color == self.is_dark(x, y + 1)
+                    && color == self.is_dark(x + 1, y + 1)
+                {
+                    result += PENALTY_N2;
+                }
+            }
+        }
+
+        // How far the share of dark modules is from 50%, in steps of 5%.
+        let dark = self.modules.iter().filter(|dark| **dark).count() as i32;
+        let total = (size * size) as i32;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        result + k * PENALTY_N4
+    }
+
+    fn add_run_to_history(&self, run_length: i32, history: &mut [i32; 7]) {
+        // The light border before the first run counts as part of it.
+        let run_length = if history[0] == 0 {
+            run_length + self.size as i32
+        } else {
+            run_length
+        };
+        history.copy_within(0..6, 1);
+        history[0] = run_length;
+    }
+
+    /// Counts 1:1:3:1:1 patterns with light space on either side, which
+    /// look like finder patterns to a reader.
+    fn count_finder_patterns(&self, history: &[i32; 7]) -> i32 {
+        let n = history[1];
+        let core =
+            n > 0 && history[2] == n && history[3] == n * 3 && history[4] == n && history[5] == n;
+        i32::from(core && history[0] >= n * 4 && history[6] >= n)
+            + i32::from(core && history[6] >= n * 4 && history[0] >= n)
+    }
+
+    fn terminate_and_count(&self, run_color: bool, run_length: i32, history: &mut [i32; 7]) -> i32 {
+        let mut run_length = run_length;
+        if run_color {
+            self.add_run_to_history(run_length, history);
+            run_length = 0;
+        }
+        // The light border after the last run.
+        run_length += self.size as i32;
+        self.add_run_to_history(run_length, history);
+        self.count_finder_patterns(history)
+    }
+}
+
+#[derive(Debug, Default)]
+struct BitBuffer(Vec<bool>);
+
+impl BitBuffer {
+    fn append(&mut self, value: u32, length: usize) {
+        for i in (0..length).rev() {
+            self.0.push((value >> i) & 1 != 0);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |byte, (i, bit)| byte | (u8::from(*bit) << (7 - i)))
+            })
+            .collect()
+    }
+}
+
+fn char_count_bits(version: usize) -> usize {
+    if version <= 9 {
+        8
+    } else {
+        16
+    }
+}
+
+fn data_bit_length(length: usize, version: usize) -> usize {
+    let count_bits = char_count_bits(version);
+    if length >= 1 << count_bits {
+        return usize::MAX;
+    }
+    4 + count_bits + length * 8
+}
+
+/// Modules available for data and error correction codewords, after the
+/// function patterns. Some versions leave a few remainder bits.
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignment_count = version / 7 + 2;
+        result -= (25 * alignment_count - 10) * alignment_count - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn data_codewords(version: usize, ecc: Ecc) -> usize {
+    raw_data_modules(version) / 8
+        - usize::from(ECC_CODEWORDS_PER_BLOCK[ecc.index()][version])
+            * usize::from(NUM_ERROR_CORRECTION_BLOCKS[ecc.index()][version])
+}
+
+/// Centers of the alignment patterns along each axis, in ascending order.
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let last = version * 4 + 17 - 7;
+    let mut positions = vec![6];
+    positions.extend((0..count - 1).rev().map(|i| last - i * step));
+    positions
+}
+
+/// Splits the data into blocks, appends Reed-Solomon error correction to
+/// each and interleaves the blocks.
+pub fn add_ecc_and_interleave(data: &[u8], version: usize, ecc: Ecc) -> Vec<u8> {
+    let block_count = usize::from(NUM_ERROR_CORRECTION_BLOCKS[ecc.index()][version]);
+    let block_ecc_length = usize::from(ECC_CODEWORDS_PER_BLOCK[ecc.index()][version]);
+    let raw_codewords = raw_data_modules(version) / 8;
+    let short_block_count = block_count - raw_codewords % block_count;
+    let short_block_length = raw_codewords / block_count;
+
+    let divisor = reed_solomon_divisor(block_ecc_length);
+    let mut blocks = Vec::with_capacity(block_count);
+    let mut offset = 0;
+    for i in 0..block_count {
+        let data_length =
+            short_block_length - block_ecc_length + usize::from(i >= short_block_count);
+        let mut block = data[offset..offset + data_length].to_vec();
+        offset += data_length;
+        let ecc_codewords = reed_solomon_remainder(&block, &divisor);
+        // Padding so all blocks have the same length; skipped below.
+        if i < short_block_count {
+            block.push(0);
+        }
+        block.extend(ecc_codewords);
+        blocks.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..blocks[0].len() {
+        for (j, block) in blocks.iter().enumerate() {
+            if i != short_block_length - block_ecc_length || j >= short_block_count {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0; degree];
+    result[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0; divisor.len()];
+    for byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (value, coefficient) in result.iter_mut().zip(divisor) {
+            *value ^= gf_multiply(*coefficient, factor);
+        }
+    }
+    result
+}
+
+/// Multiplies in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11d);
+        z ^= u32::from((y >> i) & 1) * u32::from(x);
+    }
+    z as u8
+}
@@ -0,0 +1,127 @@
+use crate::{atomic::AtomicFile, calendar, config, lock::FileLock, outln};
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+const REMINDERS_FILE_NAME: &str = "reminders.tsv";
+const BELL: char = '\x07';
+
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub id: u64,
+    /// Unix time the reminder is due at.
+    pub due: i64,
+    pub message: String,
+}
+
+/// Adds a reminder, returning its id.
+pub fn add(due: i64, message: &str) -> Result<u64, Box<dyn Error>> {
+    let path = reminders_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut reminders = load()?;
+    let id = reminders
+        .iter()
+        .map(|reminder| reminder.id)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    reminders.push(Reminder {
+        id,
+        due,
+        // Keeps the file one reminder per line.
+        message: message.replace(['\t', '\n', '\r'], " "),
+    });
+    save(&reminders)?;
+    Ok(id)
+}
+
+/// Removes a reminder, returning whether it existed.
+pub fn remove(id: u64) -> Result<bool, Box<dyn Error>> {
+    let path = reminders_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut reminders = load()?;
+    let count = reminders.len();
+    reminders.retain(|reminder| reminder.id != id);
+    if reminders.len() == count {
+        return Ok(false);
+    }
+    save(&reminders)?;
+    Ok(true)
+}
+
+/// Pending reminders, the soonest first.
+pub fn list() -> Result<Vec<Reminder>, Box<dyn Error>> {
+    let mut reminders = load()?;
+    reminders.sort_by_key(|reminder| (reminder.due, reminder.id));
+    Ok(reminders)
+}
+
+/// Prints the reminders that are due, with a bell, and forgets them. The
+/// shell calls this between prompts. Errors are ignored so a broken
+/// reminders file never gets in the way of the prompt.
+pub fn announce_due() {
+    let Ok(path) = reminders_path() else {
+        return;
+    };
+    if !path.exists() {
+        return;
+    }
+    let Ok(_lock) = FileLock::acquire(&path) else {
+        return;
+    };
+    let Ok(reminders) = load() else {
+        return;
+    };
+
+    let now = calendar::now();
+    let (mut due, pending): (Vec<_>, Vec<_>) = reminders
+        .into_iter()
+        .partition(|reminder| reminder.due <= now);
+    if due.is_empty() || save(&pending).is_err() {
+        return;
+    }
+    due.sort_by_key(|reminder| reminder.due);
+    for reminder in due {
+        outln!("Reminder: {}{}", reminder.message, BELL);
+    }
+}
+
+fn load() -> Result<Vec<Reminder>, Box<dyn Error>> {
+    let content = match fs::read_to_string(reminders_path()?) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut reminders = Vec::new();
+    for line in content.lines() {
+        let [id, due, message] = line.splitn(3, '\t').collect::<Vec<_>>()[..] else {
+            continue;
+        };
+        reminders.push(Reminder {
+            id: id.parse()?,
+            due: due.parse()?,
+            message: message.to_string(),
+        });
+    }
+    Ok(reminders)
+}
+
+fn save(reminders: &[Reminder]) -> Result<(), Box<dyn Error>> {
+    let content = reminders
+        .iter()
+        .map(|reminder| format!("{}\t{}\t{}\n", reminder.id, reminder.due, reminder.message))
+        .collect::<String>();
+
+    let mut file = AtomicFile::create(reminders_path()?)?;
+    file.write_all(content.as_bytes())?;
+    file.commit_unsynced()?;
+    Ok(())
+}
+
+fn reminders_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(REMINDERS_FILE_NAME))
+}
@@ -0,0 +1,71 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the shell prints besides the results commands were asked for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// `-q`: only results and errors.
+    Quiet,
+    #[default]
+    Normal,
+    /// `-v`: also per-file actions and command timings.
+    Verbose,
+    /// `-vv`: also how each input line was parsed.
+    Debug,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+impl Verbosity {
+    /// Reads `-q`/`--quiet`, `-v`/`--verbose` and `-vv` from the shell's
+    /// arguments. Repeated `-v` flags add up.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut level = Verbosity::Normal as u8;
+        for arg in args {
+            match arg.as_str() {
+                "-q" | "--quiet" => return Verbosity::Quiet,
+                "-v" | "--verbose" => level += 1,
+                "-vv" => level += 2,
+                _ => {}
+            }
+        }
+        Self::from_level(level)
+    }
+
+    fn from_level(level: u8) -> Self {
+        match level {
+            0 => Verbosity::Quiet,
+            1 => Verbosity::Normal,
+            2 => Verbosity::Verbose,
+            _ => Verbosity::Debug,
+        }
+    }
+}
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> Verbosity {
+    Verbosity::from_level(VERBOSITY.load(Ordering::Relaxed))
+}
+
+/// Prints a non-essential status line, such as a summary or confirmation,
+/// unless the shell runs with `-q`.
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::report::verbosity() >= $crate::report::Verbosity::Normal {
+            $crate::outln!($($arg)*);
+        }
+    };
+}
+
+/// Prints a detail line, such as a per-file action, only with `-v` or `-vv`.
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::report::verbosity() >= $crate::report::Verbosity::Verbose {
+            $crate::outln!($($arg)*);
+        }
+    };
+}
@@ -0,0 +1,29 @@
+use crate::{config, context::ShellContext, prompt, usage};
+use std::time::{Duration, Instant};
+
+/// Subsystems are initialized lazily on first use. This initializes each of
+/// them up front and reports how long it took, for `--profile-startup`.
+pub fn profile() -> Vec<(&'static str, Duration)> {
+    let steps: [(&str, fn()); 3] = [
+        ("config", || {
+            config::settings();
+        }),
+        ("usage", || {
+            usage::is_enabled();
+        }),
+        ("prompt", || {
+            if let Ok(context) = ShellContext::new() {
+                prompt::render(&context);
+            }
+        }),
+    ];
+
+    steps
+        .into_iter()
+        .map(|(name, init)| {
+            let started = Instant::now();
+            init();
+            (name, started.elapsed())
+        })
+        .collect()
+}
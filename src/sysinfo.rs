@@ -0,0 +1,171 @@
+use std::{
+    error::Error,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+#[derive(Debug, Default)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub is_up: bool,
+    pub mac: Option<String>,
+    pub ipv4: Vec<Ipv4Addr>,
+    pub ipv6: Vec<Ipv6Addr>,
+}
+
+pub fn network_interfaces() -> Result<Vec<NetworkInterface>, Box<dyn Error>> {
+    platform::network_interfaces()
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::NetworkInterface;
+    use std::{
+        error::Error,
+        ffi::{c_char, c_int, c_uint, c_void, CStr},
+        io,
+        net::{Ipv4Addr, Ipv6Addr},
+    };
+
+    const AF_INET: u16 = 2;
+    const AF_INET6: u16 = 10;
+    const AF_PACKET: u16 = 17;
+    const IFF_UP: c_uint = 0x1;
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct IfAddrs {
+        ifa_next: *mut IfAddrs,
+        ifa_name: *mut c_char,
+        ifa_flags: c_uint,
+        ifa_addr: *mut SockAddr,
+        ifa_netmask: *mut SockAddr,
+        ifa_ifu: *mut SockAddr,
+        ifa_data: *mut c_void,
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct SockAddr {
+        sa_family: u16,
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct SockAddrIn {
+        sin_family: u16,
+        sin_port: u16,
+        sin_addr: [u8; 4],
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct SockAddrIn6 {
+        sin6_family: u16,
+        sin6_port: u16,
+        sin6_flowinfo: u32,
+        sin6_addr: [u8; 16],
+        sin6_scope_id: u32,
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct SockAddrLl {
+        sll_family: u16,
+        sll_protocol: u16,
+        sll_ifindex: c_int,
+        sll_hatype: u16,
+        sll_pkttype: u8,
+        sll_halen: u8,
+        sll_addr: [u8; 8],
+    }
+
+    extern "C" {
+        fn getifaddrs(ifap: *mut *mut IfAddrs) -> c_int;
+        fn freeifaddrs(ifa: *mut IfAddrs);
+    }
+
+    pub fn network_interfaces() -> Result<Vec<NetworkInterface>, Box<dyn Error>> {
+        let mut head = std::ptr::null_mut();
+        if unsafe { getifaddrs(&mut head) } != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut interfaces: Vec<NetworkInterface> = Vec::new();
+        let mut current = head;
+
+        while !current.is_null() {
+            // SAFETY: `current` is a non-null node of the list returned by getifaddrs,
+            // which stays valid until freeifaddrs is called below.
+            let entry = unsafe { &*current };
+            current = entry.ifa_next;
+
+            if entry.ifa_name.is_null() {
+                continue;
+            }
+            let name = unsafe { CStr::from_ptr(entry.ifa_name) }
+                .to_string_lossy()
+                .into_owned();
+
+            let index = match interfaces.iter().position(|i| i.name == name) {
+                Some(index) => index,
+                None => {
+                    interfaces.push(NetworkInterface {
+                        name,
+                        ..Default::default()
+                    });
+                    interfaces.len() - 1
+                }
+            };
+            let interface = &mut interfaces[index];
+            interface.is_up |= entry.ifa_flags & IFF_UP != 0;
+
+            if entry.ifa_addr.is_null() {
+                continue;
+            }
+
+            // SAFETY: the family tag tells us which sockaddr variant the pointer refers to.
+            unsafe {
+                match (*entry.ifa_addr).sa_family {
+                    AF_INET => {
+                        let address = &*(entry.ifa_addr as *const SockAddrIn);
+                        interface.ipv4.push(Ipv4Addr::from(address.sin_addr));
+                    }
+                    AF_INET6 => {
+                        let address = &*(entry.ifa_addr as *const SockAddrIn6);
+                        interface.ipv6.push(Ipv6Addr::from(address.sin6_addr));
+                    }
+                    AF_PACKET => {
+                        let address = &*(entry.ifa_addr as *const SockAddrLl);
+                        let length = (address.sll_halen as usize).min(address.sll_addr.len());
+                        if length > 0 {
+                            interface.mac = Some(format_mac(&address.sll_addr[..length]));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        unsafe { freeifaddrs(head) };
+
+        Ok(interfaces)
+    }
+
+    fn format_mac(bytes: &[u8]) -> String {
+        bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::NetworkInterface;
+    use std::error::Error;
+
+    pub fn network_interfaces() -> Result<Vec<NetworkInterface>, Box<dyn Error>> {
+        Err("Listing network interfaces is not supported on this platform".into())
+    }
+}
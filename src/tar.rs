@@ -0,0 +1,361 @@
+//! Reading and writing tar archives in the POSIX ustar format. Paths too
+//! long for it are written the way GNU tar does; when reading, pax extended
+//! headers are understood as well.
+
+use std::{
+    io::{self, Read, Write},
+    str,
+};
+
+const BLOCK_SIZE: usize = 512;
+const NAME_SIZE: usize = 100;
+const PREFIX_SIZE: usize = 155;
+/// The largest size the 11 octal digits of the size field can hold.
+const MAX_SIZE: u64 = 0o77_777_777_777;
+const MAGIC: &[u8] = b"ustar\x0000";
+/// The path GNU tar gives its long name entries.
+const LONG_NAME_PATH: &str = "././@LongLink";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink(String),
+}
+
+/// The description of one archive member. Paths use `/` and directories
+/// end with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub path: String,
+    pub kind: EntryKind,
+    /// Permission bits.
+    pub mode: u32,
+    /// Only files have data.
+    pub size: u64,
+    /// Modification time in seconds since the Unix epoch.
+    pub modified: u64,
+}
+
+impl Header {
+    fn type_flag(&self) -> u8 {
+        match self.kind {
+            EntryKind::File => b'0',
+            EntryKind::Directory => b'5',
+            EntryKind::Symlink(_) => b'2',
+        }
+    }
+
+    fn to_block(&self, type_flag: u8) -> io::Result<[u8; BLOCK_SIZE]> {
+        let invalid = |message: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: {}", self.path, message),
+            )
+        };
+        if self.size > MAX_SIZE {
+            return Err(invalid("too large for a tar archive"));
+        }
+        // Longer paths go in a GNU long name entry first; see `Writer::append`.
+        let (prefix, name) = split_path(&self.path).unwrap_or(("", &self.path));
+
+        let mut block = [0; BLOCK_SIZE];
+        copy_truncated(&mut block[..NAME_SIZE], name);
+        write_octal(&mut block[100..108], u64::from(self.mode & 0o7777));
+        write_octal(&mut block[108..116], 0);
+        write_octal(&mut block[116..124], 0);
+        write_octal(&mut block[124..136], self.size);
+        write_octal(&mut block[136..148], self.modified);
+        block[156] = type_flag;
+        if let EntryKind::Symlink(target) = &self.kind {
+            copy_truncated(&mut block[157..257], target);
+        }
+        block[257..265].copy_from_slice(MAGIC);
+        copy_truncated(&mut block[345..500], prefix);
+
+        // The checksum is taken with its own field filled with spaces.
+        block[148..156].fill(b' ');
+        let checksum = checksum(&block);
+        block[148..155].copy_from_slice(format!("{:06o}\0", checksum).as_bytes());
+        Ok(block)
+    }
+}
+
+/// Splits a path into the ustar prefix and name fields, at a `/`, if it is
+/// too long for the name field alone.
+fn split_path(path: &str) -> Option<(&str, &str)> {
+    if path.len() <= NAME_SIZE {
+        return Some(("", path));
+    }
+    // A trailing `/` stays with the name.
+    let searchable = path.strip_suffix('/').unwrap_or(path);
+    searchable
+        .match_indices('/')
+        .map(|(index, _)| (&path[..index], &path[index + 1..]))
+        .find(|(prefix, name)| prefix.len() <= PREFIX_SIZE && name.len() <= NAME_SIZE)
+}
+
+fn copy_truncated(field: &mut [u8], text: &str) {
+    let length = text.len().min(field.len());
+    field[..length].copy_from_slice(&text.as_bytes()[..length]);
+}
+
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{:0width$o}\0", value, width = field.len() - 1);
+    field.copy_from_slice(digits.as_bytes());
+}
+
+fn read_octal(field: &[u8]) -> io::Result<u64> {
+    let text = str::from_utf8(field)
+        .ok()
+        .map(|text| text.trim_matches(|c: char| c == '\0' || c == ' '))
+        .ok_or_else(|| invalid_data("Invalid number in tar header"))?;
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, 8).map_err(|_| invalid_data("Invalid number in tar header"))
+}
+
+/// The text of a NUL padded field.
+fn read_text(field: &[u8]) -> String {
+    let end = field
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn checksum(block: &[u8; BLOCK_SIZE]) -> u32 {
+    block.iter().map(|&byte| u32::from(byte)).sum()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn padding(size: u64) -> u64 {
+    (BLOCK_SIZE as u64 - size % BLOCK_SIZE as u64) % BLOCK_SIZE as u64
+}
+
+/// Writes entries one after another; [`Writer::finish`] ends the archive.
+pub struct Writer<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Adds an entry; `data` must hold exactly `header.size` bytes.
+    pub fn append(&mut self, header: &Header, data: impl Read) -> io::Result<()> {
+        if split_path(&header.path).is_none() {
+            self.append_long_name(b'L', &header.path)?;
+        }
+        if let EntryKind::Symlink(target) = &header.kind {
+            if target.len() > NAME_SIZE {
+                self.append_long_name(b'K', target)?;
+            }
+        }
+        self.write_entry(header, header.type_flag(), data)
+    }
+
+    /// Writes a GNU entry of type `L` or `K`, holding the path or link
+    /// target of the next entry when it does not fit in its header.
+    fn append_long_name(&mut self, type_flag: u8, text: &str) -> io::Result<()> {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        let header = Header {
+            path: LONG_NAME_PATH.to_string(),
+            kind: EntryKind::File,
+            mode: 0,
+            size: data.len() as u64,
+            modified: 0,
+        };
+        self.write_entry(&header, type_flag, &data[..])
+    }
+
+    fn write_entry(&mut self, header: &Header, type_flag: u8, data: impl Read) -> io::Result<()> {
+        self.inner.write_all(&header.to_block(type_flag)?)?;
+        let written = io::copy(&mut data.take(header.size), &mut self.inner)?;
+        if written != header.size {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{} changed while it was archived", header.path),
+            ));
+        }
+        self.inner
+            .write_all(&[0; BLOCK_SIZE][..padding(header.size) as usize])
+    }
+
+    /// Writes the two empty blocks that end an archive.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.inner.write_all(&[0; BLOCK_SIZE * 2])?;
+        Ok(self.inner)
+    }
+}
+
+/// Reads entries in order. After [`Reader::next_entry`], the entry's data
+/// can be read with [`Reader::copy_data`]; whatever is left unread is
+/// skipped by the next call.
+pub struct Reader<R: Read> {
+    inner: R,
+    /// Bytes of the current entry's data and padding not read yet.
+    unread: u64,
+    /// Bytes of the current entry's data not read yet.
+    data_left: u64,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            unread: 0,
+            data_left: 0,
+        }
+    }
+
+    pub fn next_entry(&mut self) -> io::Result<Option<Header>> {
+        let mut long_path = None;
+        let mut long_target = None;
+        loop {
+            self.skip_unread()?;
+            let mut block = [0; BLOCK_SIZE];
+            match self.inner.read_exact(&mut block) {
+                Ok(()) => {}
+                // Some writers leave out the end of archive blocks.
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(error) => return Err(error),
+            }
+            if block.iter().all(|&byte| byte == 0) {
+                return Ok(None);
+            }
+
+            let stored = read_octal(&block[148..156])?;
+            block[148..156].fill(b' ');
+            if u64::from(checksum(&block)) != stored {
+                return Err(invalid_data("Not a tar archive, or a damaged one"));
+            }
+
+            let size = read_octal(&block[124..136])?;
+            self.unread = size + padding(size);
+            self.data_left = size;
+            let kind = block[156];
+            match kind {
+                // GNU long name or link target: the data is the text.
+                b'L' | b'K' => {
+                    let text = read_text(&self.read_data(size)?);
+                    if kind == b'L' {
+                        long_path = Some(text);
+                    } else {
+                        long_target = Some(text);
+                    }
+                    continue;
+                }
+                // Pax extended header: `<length> <key>=<value>\n` records.
+                b'x' => {
+                    let data = self.read_data(size)?;
+                    for (key, value) in pax_records(&data) {
+                        match key {
+                            "path" => long_path = Some(value.to_string()),
+                            "linkpath" => long_target = Some(value.to_string()),
+                            _ => {}
+                        }
+                    }
+                    continue;
+                }
+                // Pax global headers hold nothing this reader uses.
+                b'g' => continue,
+                _ => {}
+            }
+
+            let mut path = read_text(&block[..100]);
+            if &block[257..262] == b"ustar" {
+                let prefix = read_text(&block[345..500]);
+                if !prefix.is_empty() {
+                    path = format!("{}/{}", prefix, path);
+                }
+            }
+            let path = long_path.take().unwrap_or(path);
+            let target = long_target
+                .take()
+                .unwrap_or_else(|| read_text(&block[157..257]));
+            let kind = match kind {
+                b'5' => EntryKind::Directory,
+                b'2' => EntryKind::Symlink(target),
+                b'0' | b'\0' | b'7' => EntryKind::File,
+                // Hard links, devices and other special entries are skipped.
+                _ => continue,
+            };
+            let kind = match kind {
+                // Old archives mark directories only with the trailing `/`.
+                EntryKind::File if path.ends_with('/') => EntryKind::Directory,
+                kind => kind,
+            };
+            return Ok(Some(Header {
+                path,
+                mode: read_octal(&block[100..108])? as u32,
+                size: if kind == EntryKind::File { size } else { 0 },
+                modified: read_octal(&block[136..148])?,
+                kind,
+            }));
+        }
+    }
+
+    /// Copies the data of the current entry into `writer`.
+    pub fn copy_data(&mut self, writer: &mut impl Write) -> io::Result<u64> {
+        let copied = io::copy(&mut (&mut self.inner).take(self.data_left), writer)?;
+        if copied != self.data_left {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The archive ends in the middle of a file",
+            ));
+        }
+        self.unread -= copied;
+        self.data_left = 0;
+        Ok(copied)
+    }
+
+    fn read_data(&mut self, size: u64) -> io::Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(size.min(1 << 20) as usize);
+        self.copy_data(&mut data)?;
+        Ok(data)
+    }
+
+    fn skip_unread(&mut self) -> io::Result<()> {
+        let skipped = io::copy(&mut (&mut self.inner).take(self.unread), &mut io::sink())?;
+        if skipped != self.unread {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The archive ends in the middle of a file",
+            ));
+        }
+        self.unread = 0;
+        self.data_left = 0;
+        Ok(())
+    }
+}
+
+fn pax_records(data: &[u8]) -> Vec<(&str, &str)> {
+    let mut records = Vec::new();
+    let mut rest = data;
+    while let Some(space) = rest.iter().position(|&byte| byte == b' ') {
+        let Some(length) = str::from_utf8(&rest[..space])
+            .ok()
+            .and_then(|length| length.parse::<usize>().ok())
+            .filter(|&length| length > space && length <= rest.len())
+        else {
+            break;
+        };
+        let record = &rest[space + 1..length];
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+        if let Some((key, value)) = str::from_utf8(record)
+            .ok()
+            .and_then(|record| record.split_once('='))
+        {
+            records.push((key, value));
+        }
+        rest = &rest[length..];
+    }
+    records
+}
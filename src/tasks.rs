@@ -0,0 +1,196 @@
+use crate::{atomic::AtomicFile, calendar::DateTime, config, lock::FileLock};
+use std::{
+    error::Error,
+    fmt, fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+const TASKS_FILE_NAME: &str = "tasks.tsv";
+
+/// Ordered so that sorting puts the most important tasks first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    pub fn name(self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+}
+
+impl TryFrom<&str> for Priority {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "h" | "high" => Ok(Priority::High),
+            "m" | "medium" => Ok(Priority::Medium),
+            "l" | "low" => Ok(Priority::Low),
+            _ => Err(format!("Unknown priority: {} (expected high, medium or low)", value).into()),
+        }
+    }
+}
+
+/// A calendar date, which is all a due date needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let date_time = DateTime::parse(text)?;
+        Ok(Self::from(date_time))
+    }
+}
+
+impl From<DateTime> for Date {
+    fn from(date_time: DateTime) -> Self {
+        Self {
+            year: date_time.year,
+            month: date_time.month,
+            day: date_time.day,
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub id: u64,
+    pub text: String,
+    pub done: bool,
+    pub priority: Priority,
+    pub due: Option<Date>,
+    pub tags: Vec<String>,
+}
+
+/// Adds a task, filling in its id, and returns the id.
+pub fn add(mut task: Task) -> Result<u64, Box<dyn Error>> {
+    let path = tasks_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut tasks = load()?;
+    task.id = tasks.iter().map(|task| task.id).max().unwrap_or(0) + 1;
+    // Keeps the file one task per line.
+    task.text = task.text.replace(['\t', '\n', '\r'], " ");
+    let id = task.id;
+    tasks.push(task);
+    save(&tasks)?;
+    Ok(id)
+}
+
+/// Marks a task as done, returning whether it existed.
+pub fn complete(id: u64) -> Result<bool, Box<dyn Error>> {
+    let path = tasks_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut tasks = load()?;
+    let Some(task) = tasks.iter_mut().find(|task| task.id == id) else {
+        return Ok(false);
+    };
+    task.done = true;
+    save(&tasks)?;
+    Ok(true)
+}
+
+/// Removes a task, returning whether it existed.
+pub fn remove(id: u64) -> Result<bool, Box<dyn Error>> {
+    let path = tasks_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut tasks = load()?;
+    let count = tasks.len();
+    tasks.retain(|task| task.id != id);
+    if tasks.len() == count {
+        return Ok(false);
+    }
+    save(&tasks)?;
+    Ok(true)
+}
+
+/// All tasks: open ones first, then by priority, due date and id.
+pub fn list() -> Result<Vec<Task>, Box<dyn Error>> {
+    let mut tasks = load()?;
+    tasks.sort_by_key(|task| {
+        (
+            task.done,
+            task.priority,
+            // Tasks without a due date go after those with one.
+            task.due.is_none(),
+            task.due,
+            task.id,
+        )
+    });
+    Ok(tasks)
+}
+
+fn load() -> Result<Vec<Task>, Box<dyn Error>> {
+    let content = match fs::read_to_string(tasks_path()?) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut tasks = Vec::new();
+    for line in content.lines() {
+        let [id, done, priority, due, tags, text] = line.splitn(6, '\t').collect::<Vec<_>>()[..]
+        else {
+            continue;
+        };
+        tasks.push(Task {
+            id: id.parse()?,
+            text: text.to_string(),
+            done: done == "1",
+            priority: Priority::try_from(priority)?,
+            due: match due {
+                "" => None,
+                due => Some(Date::parse(due)?),
+            },
+            tags: tags
+                .split(',')
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+        });
+    }
+    Ok(tasks)
+}
+
+fn save(tasks: &[Task]) -> Result<(), Box<dyn Error>> {
+    let content = tasks
+        .iter()
+        .map(|task| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\n",
+                task.id,
+                u8::from(task.done),
+                task.priority.name(),
+                task.due.map(|due| due.to_string()).unwrap_or_default(),
+                task.tags.join(","),
+                task.text
+            )
+        })
+        .collect::<String>();
+
+    let mut file = AtomicFile::create(tasks_path()?)?;
+    file.write_all(content.as_bytes())?;
+    file.commit_unsynced()?;
+    Ok(())
+}
+
+fn tasks_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(TASKS_FILE_NAME))
+}
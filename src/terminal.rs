@@ -0,0 +1,274 @@
+use crate::{
+    i18n::{self, Message},
+    options, out, outln, output,
+};
+use std::{
+    env,
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+};
+
+/// Prompts for a line of input without echoing it, e.g. for passphrases.
+/// Falls back to a plain read when stdin is not a terminal. Fails without
+/// reading when a terminal's echo cannot be turned off, as without `stty`.
+pub fn read_hidden(prompt: &str) -> io::Result<String> {
+    out!("{}", prompt);
+    output::flush()?;
+
+    let is_terminal = io::stdin().is_terminal();
+    let echo_disabled = is_terminal && set_echo(false);
+    if is_terminal && !echo_disabled {
+        outln!();
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Cannot hide the input on this terminal, so it was not read",
+        ));
+    }
+    let mut input = String::new();
+    let result = io::stdin().read_line(&mut input);
+    if echo_disabled {
+        set_echo(true);
+        outln!();
+    }
+    result?;
+
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Reads one line of input after `prompt`, without the line ending. Fails
+/// when input has ended, so callers asking in a loop stop.
+pub fn ask(prompt: &str) -> io::Result<String> {
+    out!("{}", prompt);
+    output::flush()?;
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input)? == 0 {
+        outln!();
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "No more input",
+        ));
+    }
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Asks a yes or no question, defaulting to no.
+pub fn confirm(question: &str) -> io::Result<bool> {
+    out!("{} {} ", question, Message::YesNo.text());
+    output::flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(i18n::is_yes(&answer))
+}
+
+/// Lets the user pick any number of `items`, returning the indexes picked
+/// in order, or none when they cancel. Terminals get a menu with
+/// checkboxes; otherwise, and in accessible mode, the items are numbered
+/// and the numbers are asked for.
+pub fn choose_many(question: &str, items: &[String]) -> io::Result<Vec<usize>> {
+    if items.is_empty() {
+        return Ok(Vec::new());
+    }
+    if options::accessible() || !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return choose_by_number(question, items);
+    }
+    let Some(_raw) = RawMode::enable() else {
+        return choose_by_number(question, items);
+    };
+
+    // The menu is drawn straight to the terminal, as it is redrawn in place.
+    output::flush()?;
+    let mut stdout = io::stdout();
+    writeln!(stdout, "{} ({})", question, Message::ChooseKeys.text())?;
+    let mut chosen = vec![false; items.len()];
+    let mut current = 0;
+    let mut keys = io::stdin().lock().bytes();
+    loop {
+        for (index, item) in items.iter().enumerate() {
+            let check = if chosen[index] { "[x]" } else { "[ ]" };
+            let line = format!("{} {}", check, item);
+            if index == current {
+                writeln!(stdout, "\x1b[2K> {}", reverse(&line))?;
+            } else {
+                writeln!(stdout, "\x1b[2K  {}", line)?;
+            }
+        }
+        stdout.flush()?;
+
+        match keys.next().transpose()? {
+            Some(b' ') => chosen[current] = !chosen[current],
+            Some(b'a') => {
+                let all = chosen.iter().all(|&chosen| chosen);
+                chosen.fill(!all);
+            }
+            Some(b'\r' | b'\n') => break,
+            // Ctrl-C arrives as a key, as raw mode turns signals off.
+            Some(b'q' | 3) | None => {
+                chosen.fill(false);
+                break;
+            }
+            Some(b'k') => current = current.saturating_sub(1),
+            Some(b'j') => current = (current + 1).min(items.len() - 1),
+            // Arrow keys send ESC [ A and ESC [ B.
+            Some(0x1b) => match (keys.next().transpose()?, keys.next().transpose()?) {
+                (Some(b'['), Some(b'A')) => current = current.saturating_sub(1),
+                (Some(b'['), Some(b'B')) => current = (current + 1).min(items.len() - 1),
+                _ => {}
+            },
+            Some(_) => {}
+        }
+        // Back to the first item, to draw the menu over itself.
+        write!(stdout, "\x1b[{}A", items.len())?;
+    }
+    stdout.flush()?;
+    Ok((0..items.len()).filter(|&index| chosen[index]).collect())
+}
+
+/// [`choose_many`] for when there is no menu: the numbers of the items are
+/// read as a line, with ranges such as `3-5`.
+fn choose_by_number(question: &str, items: &[String]) -> io::Result<Vec<usize>> {
+    outln!("{}", question);
+    for (index, item) in items.iter().enumerate() {
+        outln!("{:>4}  {}", index + 1, item);
+    }
+    loop {
+        let Ok(answer) = ask(Message::ChooseNumbers.text()) else {
+            return Ok(Vec::new());
+        };
+        match parse_numbers(&answer, items.len()) {
+            Some(chosen) => return Ok(chosen),
+            None => outln!("Not a number from 1 to {}: {}", items.len(), answer.trim()),
+        }
+    }
+}
+
+/// The indexes of numbers such as `1 3-5` or `all`, in order and without
+/// repeats, or `None` if any is not from 1 to `count`.
+fn parse_numbers(text: &str, count: usize) -> Option<Vec<usize>> {
+    if text.trim() == "all" {
+        return Some((0..count).collect());
+    }
+    let number = |text: &str| {
+        text.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|number| (1..=count).contains(number))
+    };
+    let mut chosen = vec![false; count];
+    for part in text.split([' ', ',']).filter(|part| !part.is_empty()) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (number(first)?, number(last)?),
+            None => (number(part)?, number(part)?),
+        };
+        for index in first..=last {
+            chosen[index - 1] = true;
+        }
+    }
+    Some((0..count).filter(|&index| chosen[index]).collect())
+}
+
+/// Keys are read one at a time, without echo or signals, until dropped.
+struct RawMode {
+    /// The settings before, as `stty -g` prints them.
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> Option<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        Some(Self {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// Runs `stty` on the terminal, returning what it printed if it succeeded.
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn set_echo(enabled: bool) -> bool {
+    Command::new("stty")
+        .arg(if enabled { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+pub fn bold(text: &str) -> String {
+    style(text, "1")
+}
+
+pub fn underline(text: &str) -> String {
+    style(text, "4")
+}
+
+pub fn reverse(text: &str) -> String {
+    style(text, "7")
+}
+
+pub fn red(text: &str) -> String {
+    style(text, "31")
+}
+
+/// How `grep` marks what matched.
+pub fn bold_red(text: &str) -> String {
+    style(text, "1;31")
+}
+
+/// Whether output is styled, as the `color` option allows: by default only
+/// when stdout is a terminal and `NO_COLOR` is not set. Never in accessible
+/// mode.
+pub fn colors_enabled() -> bool {
+    match &*options::get(options::COLOR) {
+        _ if options::accessible() => false,
+        "always" => true,
+        "never" => false,
+        _ => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+    }
+}
+
+/// Wraps `text` in an ANSI escape sequence when colors are enabled.
+fn style(text: &str, code: &str) -> String {
+    if colors_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Removes the escape sequences `style` adds, leaving the plain text.
+pub fn strip_styles(bytes: &[u8]) -> Vec<u8> {
+    let mut plain = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == 0x1b && bytes.get(i + 1) == Some(&b'[') {
+            // Parameters run up to the final byte, a letter.
+            let end = bytes[i + 2..]
+                .iter()
+                .position(u8::is_ascii_alphabetic)
+                .map_or(bytes.len(), |end| i + 2 + end + 1);
+            i = end;
+        } else {
+            plain.push(bytes[i]);
+            i += 1;
+        }
+    }
+    plain
+}
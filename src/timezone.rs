@@ -0,0 +1,485 @@
+//! Time zones from the system's tz database (TZif files), plus UTC and fixed
+//! offsets such as `+05:30`.
+
+use crate::calendar::{self, DateTime, SECONDS_PER_DAY};
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+const LOCALTIME_PATH: &str = "/etc/localtime";
+
+#[derive(Debug, Clone)]
+pub struct TimeZone {
+    /// Transition times and the index of the type that starts at each.
+    transitions: Vec<(i64, usize)>,
+    types: Vec<LocalTimeType>,
+    /// Rule for times after the last transition.
+    rule: Option<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct LocalTimeType {
+    /// Seconds east of UTC.
+    offset: i64,
+    abbreviation: String,
+}
+
+/// A wall clock reading in some time zone.
+#[derive(Debug, Clone)]
+pub struct LocalTime {
+    pub date_time: DateTime,
+    pub offset: i64,
+    pub abbreviation: String,
+}
+
+impl LocalTime {
+    /// The offset from UTC as `+02:00`.
+    pub fn offset_text(&self) -> String {
+        format_offset(self.offset)
+    }
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self::fixed(0, "UTC")
+    }
+
+    fn fixed(offset: i64, abbreviation: &str) -> Self {
+        Self {
+            transitions: Vec::new(),
+            types: vec![LocalTimeType {
+                offset,
+                abbreviation: abbreviation.to_string(),
+            }],
+            rule: None,
+        }
+    }
+
+    /// The zone from `$TZ` or `/etc/localtime`, falling back to UTC.
+    pub fn local() -> Self {
+        let from_env = env::var("TZ").ok().and_then(|tz| {
+            let tz = tz.strip_prefix(':').unwrap_or(&tz);
+            Self::load(tz).ok().or_else(|| {
+                let rule = Rule::parse(tz)?;
+                Some(Self {
+                    transitions: Vec::new(),
+                    types: Vec::new(),
+                    rule: Some(rule),
+                })
+            })
+        });
+        from_env
+            .or_else(|| {
+                let data = fs::read(LOCALTIME_PATH).ok()?;
+                Self::from_tzif("local", &data).ok()
+            })
+            .unwrap_or_else(Self::utc)
+    }
+
+    /// Loads a zone by name: `UTC`, `local`, an offset such as `+02:00` or
+    /// `UTC-5`, or a tz database name such as `Europe/Warsaw`.
+    pub fn load(name: &str) -> Result<Self, Box<dyn Error>> {
+        let name = name.trim();
+        match name {
+            "UTC" | "utc" | "Z" | "GMT" => return Ok(Self::utc()),
+            "local" => return Ok(Self::local()),
+            _ => {}
+        }
+        if let Some(offset) = parse_fixed_offset(name) {
+            return Ok(Self::fixed(offset, &format_offset(offset)));
+        }
+
+        // Names must stay inside the database directory.
+        let relative = Path::new(name);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!("Unknown time zone: {}", name).into());
+        }
+        let dir = env::var_os("TZDIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(ZONEINFO_DIR));
+        let data =
+            fs::read(dir.join(relative)).map_err(|_| format!("Unknown time zone: {}", name))?;
+        Self::from_tzif(name, &data)
+    }
+
+    /// Parses a TZif file, preferring the 64-bit data of version 2 and later.
+    fn from_tzif(name: &str, data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let invalid = || format!("Invalid time zone file for {}", name);
+        let header = TzifHeader::parse(data).ok_or_else(invalid)?;
+        let (header, body, time_size) = if header.version >= b'2' {
+            let v2_start = TZIF_HEADER_LENGTH + header.block_length(4);
+            let v2_header = data
+                .get(v2_start..)
+                .and_then(TzifHeader::parse)
+                .ok_or_else(invalid)?;
+            (v2_header, &data[v2_start + TZIF_HEADER_LENGTH..], 8)
+        } else {
+            (header, &data[TZIF_HEADER_LENGTH..], 4)
+        };
+        if body.len() < header.block_length(time_size) {
+            return Err(invalid().into());
+        }
+
+        let (times, rest) = body.split_at(header.transition_count * time_size);
+        let (indices, rest) = rest.split_at(header.transition_count);
+        let (type_records, rest) = rest.split_at(header.type_count * 6);
+        let abbreviations = &rest[..header.abbreviation_length];
+
+        let transitions = times
+            .chunks(time_size)
+            .zip(indices)
+            .map(|(time, index)| {
+                let time = match time_size {
+                    8 => i64::from_be_bytes(time.try_into().unwrap_or_default()),
+                    _ => i64::from(i32::from_be_bytes(time.try_into().unwrap_or_default())),
+                };
+                (time, usize::from(*index))
+            })
+            .collect::<Vec<_>>();
+        let types = type_records
+            .chunks(6)
+            .map(|record| {
+                let offset = i32::from_be_bytes([record[0], record[1], record[2], record[3]]);
+                let abbreviation = abbreviations
+                    .get(usize::from(record[5])..)
+                    .unwrap_or_default()
+                    .split(|byte| *byte == 0)
+                    .next()
+                    .unwrap_or_default();
+                LocalTimeType {
+                    offset: i64::from(offset),
+                    abbreviation: String::from_utf8_lossy(abbreviation).into_owned(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if types.is_empty() || transitions.iter().any(|(_, index)| *index >= types.len()) {
+            return Err(invalid().into());
+        }
+
+        // Version 2 files end with a POSIX TZ string for later times.
+        let rule = if time_size == 8 {
+            let footer = &body[header.block_length(time_size)..];
+            std::str::from_utf8(footer)
+                .ok()
+                .and_then(|footer| footer.trim_matches('\n').lines().next())
+                .and_then(Rule::parse)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            transitions,
+            types,
+            rule,
+        })
+    }
+
+    /// The wall clock reading at a Unix timestamp.
+    pub fn local_time(&self, timestamp: i64) -> LocalTime {
+        let (offset, abbreviation) = self.offset_at(timestamp);
+        LocalTime {
+            date_time: DateTime::from_timestamp(timestamp + offset),
+            offset,
+            abbreviation,
+        }
+    }
+
+    /// The Unix timestamp of a wall clock reading. Readings skipped by a
+    /// clock change are taken with the offset from before it, and repeated
+    /// readings resolve to the first.
+    pub fn timestamp(&self, local: &DateTime) -> i64 {
+        let as_utc = local.timestamp();
+        // Clock changes are less than a day apart from the reading.
+        let (before, _) = self.offset_at(as_utc - SECONDS_PER_DAY);
+        let (after, _) = self.offset_at(as_utc + SECONDS_PER_DAY);
+        [before, after]
+            .into_iter()
+            .map(|offset| as_utc - offset)
+            .find(|&timestamp| self.offset_at(timestamp).0 == as_utc - timestamp)
+            .unwrap_or(as_utc - before)
+    }
+
+    fn offset_at(&self, timestamp: i64) -> (i64, String) {
+        let after_last = self
+            .transitions
+            .last()
+            .is_none_or(|(time, _)| timestamp >= *time);
+        if let (Some(rule), true) = (&self.rule, after_last) {
+            return rule.offset_at(timestamp);
+        }
+
+        let index = match self
+            .transitions
+            .partition_point(|(time, _)| *time <= timestamp)
+        {
+            // Before the first transition the first type applies.
+            0 => 0,
+            count => self.transitions[count - 1].1,
+        };
+        let local_type = &self.types[index];
+        (local_type.offset, local_type.abbreviation.clone())
+    }
+}
+
+const TZIF_HEADER_LENGTH: usize = 44;
+
+#[derive(Debug)]
+struct TzifHeader {
+    version: u8,
+    utc_indicator_count: usize,
+    standard_indicator_count: usize,
+    leap_second_count: usize,
+    transition_count: usize,
+    type_count: usize,
+    abbreviation_length: usize,
+}
+
+impl TzifHeader {
+    fn parse(data: &[u8]) -> Option<Self> {
+        if data.get(..4)? != b"TZif" {
+            return None;
+        }
+        let count = |index: usize| {
+            let start = 20 + index * 4;
+            let bytes = data.get(start..start + 4)?;
+            Some(u32::from_be_bytes(bytes.try_into().ok()?) as usize)
+        };
+        Some(Self {
+            version: *data.get(4)?,
+            utc_indicator_count: count(0)?,
+            standard_indicator_count: count(1)?,
+            leap_second_count: count(2)?,
+            transition_count: count(3)?,
+            type_count: count(4)?,
+            abbreviation_length: count(5)?,
+        })
+    }
+
+    /// Length of the data block after the header, for 4 or 8 byte times.
+    fn block_length(&self, time_size: usize) -> usize {
+        self.transition_count * (time_size + 1)
+            + self.type_count * 6
+            + self.abbreviation_length
+            + self.leap_second_count * (time_size + 4)
+            + self.standard_indicator_count
+            + self.utc_indicator_count
+    }
+}
+
+/// A POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`.
+#[derive(Debug, Clone)]
+struct Rule {
+    standard: LocalTimeType,
+    daylight: Option<(LocalTimeType, TransitionDate, TransitionDate)>,
+}
+
+/// When daylight saving time starts or ends, with the local time of day in
+/// seconds.
+#[derive(Debug, Clone, Copy)]
+enum TransitionDate {
+    /// `Jn`: day 1 to 365, never counting February 29.
+    Julian(u32, i64),
+    /// `n`: day 0 to 365, counting February 29.
+    ZeroBased(u32, i64),
+    /// `Mm.w.d`: day `d` (0 is Sunday) of week `w` of month `m`, where week
+    /// 5 means the last one.
+    MonthWeekDay(u32, u32, u32, i64),
+}
+
+impl Rule {
+    fn parse(text: &str) -> Option<Self> {
+        let mut rest = text;
+        let standard_name = take_name(&mut rest)?;
+        // POSIX offsets count hours west of UTC.
+        let standard_offset = -take_offset(&mut rest)?;
+        let standard = LocalTimeType {
+            offset: standard_offset,
+            abbreviation: standard_name,
+        };
+        if rest.is_empty() {
+            return Some(Self {
+                standard,
+                daylight: None,
+            });
+        }
+
+        let daylight_name = take_name(&mut rest)?;
+        let daylight_offset = if rest.starts_with(',') {
+            standard_offset + 3600
+        } else {
+            -take_offset(&mut rest)?
+        };
+        let mut dates = rest.strip_prefix(',')?.split(',');
+        let start = TransitionDate::parse(dates.next()?)?;
+        let end = TransitionDate::parse(dates.next()?)?;
+        Some(Self {
+            standard,
+            daylight: Some((
+                LocalTimeType {
+                    offset: daylight_offset,
+                    abbreviation: daylight_name,
+                },
+                start,
+                end,
+            )),
+        })
+    }
+
+    fn offset_at(&self, timestamp: i64) -> (i64, String) {
+        let Some((daylight, start, end)) = &self.daylight else {
+            return (self.standard.offset, self.standard.abbreviation.clone());
+        };
+        let year = DateTime::from_timestamp(timestamp + self.standard.offset).year;
+        // The start is given in standard time and the end in daylight time.
+        let starts = start.local_timestamp(year) - self.standard.offset;
+        let ends = end.local_timestamp(year) - daylight.offset;
+        let is_daylight = if starts < ends {
+            (starts..ends).contains(&timestamp)
+        } else {
+            // Southern hemisphere: daylight time spans the new year.
+            !(ends..starts).contains(&timestamp)
+        };
+        let active = if is_daylight {
+            daylight
+        } else {
+            &self.standard
+        };
+        (active.offset, active.abbreviation.clone())
+    }
+}
+
+impl TransitionDate {
+    fn parse(text: &str) -> Option<Self> {
+        let (date, time) = match text.split_once('/') {
+            Some((date, time)) => (date, parse_clock(time)?),
+            None => (text, 2 * 3600),
+        };
+        if let Some(day) = date.strip_prefix('J') {
+            return Some(TransitionDate::Julian(day.parse().ok()?, time));
+        }
+        if let Some(fields) = date.strip_prefix('M') {
+            let mut fields = fields.split('.').map(str::parse::<u32>);
+            let (Some(Ok(month)), Some(Ok(week)), Some(Ok(weekday))) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return None;
+            };
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                return None;
+            }
+            return Some(TransitionDate::MonthWeekDay(month, week, weekday, time));
+        }
+        Some(TransitionDate::ZeroBased(date.parse().ok()?, time))
+    }
+
+    /// The transition in `year` as a local timestamp.
+    fn local_timestamp(self, year: i32) -> i64 {
+        let january_first = calendar::days_from_civil(year, 1, 1);
+        let (days, time) = match self {
+            TransitionDate::Julian(day, time) => {
+                let leap_day = calendar::is_leap_year(year) && day >= 60;
+                (
+                    january_first + i64::from(day) - 1 + i64::from(leap_day),
+                    time,
+                )
+            }
+            TransitionDate::ZeroBased(day, time) => (january_first + i64::from(day), time),
+            TransitionDate::MonthWeekDay(month, week, weekday, time) => {
+                let first = calendar::days_from_civil(year, month, 1);
+                let first_weekday = calendar::weekday(first);
+                let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+                if day > calendar::days_in_month(year, month) {
+                    day -= 7;
+                }
+                (first + i64::from(day) - 1, time)
+            }
+        };
+        days * SECONDS_PER_DAY + time
+    }
+}
+
+/// Takes an abbreviation: letters, or anything between `<` and `>`.
+fn take_name(rest: &mut &str) -> Option<String> {
+    let (name, remaining) = match rest.strip_prefix('<') {
+        Some(quoted) => {
+            let (name, remaining) = quoted.split_once('>')?;
+            (name, remaining)
+        }
+        None => {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            rest.split_at(end)
+        }
+    };
+    if name.len() < 3 {
+        return None;
+    }
+    *rest = remaining;
+    Some(name.to_string())
+}
+
+/// Takes `[+-]hh[:mm[:ss]]` and returns it in seconds.
+fn take_offset(rest: &mut &str) -> Option<i64> {
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, ':' | '+' | '-')))
+        .unwrap_or(rest.len());
+    let (offset, remaining) = rest.split_at(end);
+    *rest = remaining;
+    parse_clock(offset)
+}
+
+/// Parses a signed `hh[:mm[:ss]]` in seconds.
+fn parse_clock(text: &str) -> Option<i64> {
+    let (sign, text) = match text.as_bytes().first()? {
+        b'-' => (-1, &text[1..]),
+        b'+' => (1, &text[1..]),
+        _ => (1, text),
+    };
+    let mut seconds = 0;
+    let mut parts = 0;
+    for (part, scale) in text.split(':').zip([3600, 60, 1]) {
+        seconds += part.parse::<i64>().ok()? * scale;
+        parts += 1;
+    }
+    (parts > 0 && text.split(':').count() == parts).then_some(sign * seconds)
+}
+
+/// Parses `+02:00`, `-0530`, `+2` or the same after `UTC` or `GMT`.
+fn parse_fixed_offset(name: &str) -> Option<i64> {
+    let offset = name
+        .strip_prefix("UTC")
+        .or_else(|| name.strip_prefix("GMT"))
+        .unwrap_or(name);
+    let sign = match offset.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = offset[1..].replace(':', "");
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i64>().ok()?, 0),
+        4 => (digits[..2].parse().ok()?, digits[2..].parse::<i64>().ok()?),
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+fn format_offset(offset: i64) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.abs() / 60;
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
@@ -0,0 +1,345 @@
+//! Quantities with units: sizes, durations and temperatures, and integers
+//! in other bases. Shared by the size and age flags and `convert-unit`.
+
+use std::{error::Error, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Size,
+    Duration,
+    Temperature,
+}
+
+impl Dimension {
+    pub fn name(self) -> &'static str {
+        match self {
+            Dimension::Size => "size",
+            Dimension::Duration => "duration",
+            Dimension::Temperature => "temperature",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Unit {
+    /// The first name is the one used for output.
+    pub names: &'static [&'static str],
+    pub dimension: Dimension,
+    /// Converts to bytes, seconds or kelvin: `value * scale + offset`.
+    scale: f64,
+    offset: f64,
+}
+
+impl Unit {
+    pub fn symbol(&self) -> &'static str {
+        self.names[0]
+    }
+
+    pub fn base_value(&self, value: f64) -> f64 {
+        value * self.scale + self.offset
+    }
+
+    pub fn unit_value(&self, value: f64) -> f64 {
+        (value - self.offset) / self.scale
+    }
+}
+
+const fn unit(names: &'static [&'static str], dimension: Dimension, scale: f64) -> Unit {
+    Unit {
+        names,
+        dimension,
+        scale,
+        offset: 0.0,
+    }
+}
+
+const KIB: f64 = 1024.0;
+
+pub const SECOND: Unit = unit(&["s", "sec", "second", "seconds"], Dimension::Duration, 1.0);
+
+pub const UNITS: &[Unit] = &[
+    unit(&["B", "byte", "bytes"], Dimension::Size, 1.0),
+    unit(&["KB"], Dimension::Size, 1e3),
+    unit(&["MB"], Dimension::Size, 1e6),
+    unit(&["GB"], Dimension::Size, 1e9),
+    unit(&["TB"], Dimension::Size, 1e12),
+    unit(&["PB"], Dimension::Size, 1e15),
+    unit(&["KiB"], Dimension::Size, KIB),
+    unit(&["MiB"], Dimension::Size, KIB * KIB),
+    unit(&["GiB"], Dimension::Size, KIB * KIB * KIB),
+    unit(&["TiB"], Dimension::Size, KIB * KIB * KIB * KIB),
+    unit(&["PiB"], Dimension::Size, KIB * KIB * KIB * KIB * KIB),
+    unit(&["ms", "msec"], Dimension::Duration, 0.001),
+    SECOND,
+    unit(
+        &["min", "m", "minute", "minutes"],
+        Dimension::Duration,
+        60.0,
+    ),
+    unit(&["h", "hr", "hour", "hours"], Dimension::Duration, 3600.0),
+    unit(&["d", "day", "days"], Dimension::Duration, 86400.0),
+    unit(&["w", "week", "weeks"], Dimension::Duration, 604800.0),
+    Unit {
+        names: &["°C", "C", "celsius"],
+        dimension: Dimension::Temperature,
+        scale: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        names: &["°F", "F", "fahrenheit"],
+        dimension: Dimension::Temperature,
+        scale: 5.0 / 9.0,
+        offset: 273.15 - 32.0 * 5.0 / 9.0,
+    },
+    unit(&["K", "kelvin"], Dimension::Temperature, 1.0),
+];
+
+const SIZE_SUFFIXES: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+
+/// Looks up a unit by name. An exact match wins; otherwise case is ignored
+/// as long as that leaves a single candidate.
+pub fn find_unit(name: &str) -> Option<&'static Unit> {
+    let name = name.trim();
+    if let Some(unit) = UNITS.iter().find(|unit| unit.names.contains(&name)) {
+        return Some(unit);
+    }
+    let mut matches = UNITS.iter().filter(|unit| {
+        unit.names
+            .iter()
+            .any(|candidate| candidate.eq_ignore_ascii_case(name))
+    });
+    match (matches.next(), matches.next()) {
+        (Some(unit), None) => Some(unit),
+        _ => None,
+    }
+}
+
+/// Splits `1.5GiB` or `-40 C` into the number and the unit text.
+pub fn split_quantity(text: &str) -> Result<(f64, &str), Box<dyn Error>> {
+    let text = text.trim();
+    let split = text
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && matches!(c, '-' | '+'))))
+        .map_or(text.len(), |(i, _)| i);
+    let (number, unit) = text.split_at(split);
+    let number = number
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid number: {}", text))?;
+    Ok((number, unit.trim()))
+}
+
+/// Parses a size flag such as `512`, `1K`, `10M` or `2GiB`. Flags treat every
+/// unit as a power of 1024, so `1KB` and `1KiB` are both 1024 bytes.
+pub fn parse_size(text: &str) -> Result<u64, Box<dyn Error>> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid size: {}", text))?;
+    let multiplier: u64 = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return Err(format!("Invalid size unit in {}, expected K, M, G or T", text).into()),
+    };
+
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("Size is too large: {}", text).into())
+}
+
+/// Formats a byte count with the largest binary unit that keeps it at or
+/// above 1, e.g. `1.5 KiB`.
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / KIB;
+    let mut suffix = 0;
+    while value >= KIB && suffix + 1 < SIZE_SUFFIXES.len() {
+        value /= KIB;
+        suffix += 1;
+    }
+    format!("{:.1} {}", value, SIZE_SUFFIXES[suffix])
+}
+
+/// Parses a duration made of one or more parts, such as `90s`, `1.5h` or
+/// `1h30m`. A bare number is in seconds.
+pub fn parse_duration(text: &str) -> Result<f64, Box<dyn Error>> {
+    let invalid = || {
+        format!(
+            "Invalid duration: {}, expected e.g. 30s, 15m or 1h30m",
+            text
+        )
+    };
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return Err(invalid().into());
+    }
+
+    let mut seconds = 0.0;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let unit_end = rest[number_end..]
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .map_or(rest.len(), |i| number_end + i);
+        let number = rest[..number_end].parse::<f64>().map_err(|_| invalid())?;
+        let unit_seconds = match rest[number_end..unit_end].trim() {
+            "" => 1.0,
+            name => find_unit(name)
+                .filter(|unit| unit.dimension == Dimension::Duration)
+                .ok_or_else(invalid)?
+                .base_value(1.0),
+        };
+        seconds += number * unit_seconds;
+        rest = rest[unit_end..].trim_start();
+    }
+    Ok(seconds)
+}
+
+/// Formats seconds as `1d 2h 3m 4s`, leaving out zero parts.
+pub fn format_duration(seconds: f64) -> String {
+    if seconds < 60.0 {
+        return format!("{}s", format_number(seconds));
+    }
+    let mut remaining = seconds.round() as u64;
+    let mut parts = Vec::new();
+    for (unit, size) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        if remaining >= size {
+            parts.push(format!("{}{}", remaining / size, unit));
+            remaining %= size;
+        }
+    }
+    parts.join(" ")
+}
+
+/// Formats a duration as a clock reading, `1:02:03`, optionally with
+/// milliseconds.
+pub fn format_clock(duration: Duration, with_millis: bool) -> String {
+    let seconds = duration.as_secs();
+    let clock = format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    if with_millis {
+        format!("{}.{:03}", clock, duration.subsec_millis())
+    } else {
+        clock
+    }
+}
+
+/// Formats a converted value with at most four decimals and no trailing
+/// zeros.
+pub fn format_number(value: f64) -> String {
+    let text = format!("{:.4}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base {
+    Binary,
+    Octal,
+    Decimal,
+    Hex,
+}
+
+impl Base {
+    pub fn radix(self) -> u32 {
+        match self {
+            Base::Binary => 2,
+            Base::Octal => 8,
+            Base::Decimal => 10,
+            Base::Hex => 16,
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Base::Binary => "0b",
+            Base::Octal => "0o",
+            Base::Decimal => "",
+            Base::Hex => "0x",
+        }
+    }
+}
+
+impl TryFrom<&str> for Base {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "bin" | "binary" => Ok(Base::Binary),
+            "oct" | "octal" => Ok(Base::Octal),
+            "dec" | "decimal" => Ok(Base::Decimal),
+            "hex" => Ok(Base::Hex),
+            _ => Err(format!("Unknown base: {} (expected hex, bin, oct or dec)", value).into()),
+        }
+    }
+}
+
+/// The base an integer literal is written in, judging by its `0x`, `0b` or
+/// `0o` prefix, if it is one.
+pub fn integer_base(text: &str) -> Option<Base> {
+    let digits = text.trim().trim_start_matches('-');
+    let base = match digits.get(..2)?.to_ascii_lowercase().as_str() {
+        "0x" => Base::Hex,
+        "0b" => Base::Binary,
+        "0o" => Base::Octal,
+        _ => return None,
+    };
+    Some(base)
+}
+
+/// Parses an integer in decimal or with a `0x`, `0b` or `0o` prefix.
+/// Underscores may separate digits.
+pub fn parse_integer(text: &str) -> Result<i128, Box<dyn Error>> {
+    let trimmed = text.trim();
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, trimmed),
+    };
+    let (base, digits) = match integer_base(unsigned) {
+        Some(base) => (base, &unsigned[2..]),
+        None => (Base::Decimal, unsigned),
+    };
+    let digits = digits.replace('_', "");
+    let value = i128::from_str_radix(&digits, base.radix())
+        .map_err(|_| format!("Invalid {} number: {}", base_name(base), text))?;
+    Ok(if negative { -value } else { value })
+}
+
+/// Formats an integer with the prefix of its base, e.g. `0xff`.
+pub fn format_integer(value: i128, base: Base) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let magnitude = value.unsigned_abs();
+    let digits = match base {
+        Base::Binary => format!("{:b}", magnitude),
+        Base::Octal => format!("{:o}", magnitude),
+        Base::Decimal => magnitude.to_string(),
+        Base::Hex => format!("{:x}", magnitude),
+    };
+    format!("{}{}{}", sign, base.prefix(), digits)
+}
+
+fn base_name(base: Base) -> &'static str {
+    match base {
+        Base::Binary => "binary",
+        Base::Octal => "octal",
+        Base::Decimal => "decimal",
+        Base::Hex => "hexadecimal",
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{atomic::AtomicFile, config, lock::FileLock, options, parse_command::CommandType};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+const USAGE_FILE_NAME: &str = "usage.tsv";
+
+#[derive(Debug, Default, Clone)]
+pub struct UsageRecord {
+    pub runs: u64,
+    pub failures: u64,
+    pub total_duration_ms: u64,
+}
+
+impl UsageRecord {
+    pub fn average_duration_ms(&self) -> u64 {
+        self.total_duration_ms.checked_div(self.runs).unwrap_or(0)
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.runs as f64
+        }
+    }
+}
+
+/// Whether usage tracking is on. Set `usage_tracking = false` in the config
+/// file, or run `set usage_tracking false`, to disable it.
+pub fn is_enabled() -> bool {
+    options::get_bool(options::USAGE_TRACKING)
+}
+
+/// Records one invocation of `command_type`. Errors are ignored so tracking
+/// can never get in the way of running commands.
+///
+/// The file is re-read under a lock right before writing, so invocations
+/// recorded by other running instances are merged rather than overwritten.
+pub fn record(command_type: CommandType, duration: Duration, succeeded: bool) {
+    if !is_enabled() {
+        return;
+    }
+
+    let Ok(path) = usage_path() else {
+        return;
+    };
+    let Ok(_lock) = FileLock::acquire(&path) else {
+        return;
+    };
+    let Ok(mut records) = load() else {
+        return;
+    };
+    let record = records.entry(command_type.name().to_string()).or_default();
+    record.runs += 1;
+    record.failures += !succeeded as u64;
+    record.total_duration_ms += duration.as_millis() as u64;
+
+    let _ = save(&records);
+}
+
+pub fn load() -> Result<BTreeMap<String, UsageRecord>, Box<dyn Error>> {
+    let content = match fs::read_to_string(usage_path()?) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut records = BTreeMap::new();
+    for line in content.lines() {
+        let [name, runs, failures, total_duration_ms] = line.split('\t').collect::<Vec<_>>()[..]
+        else {
+            continue;
+        };
+        records.insert(
+            name.to_string(),
+            UsageRecord {
+                runs: runs.parse()?,
+                failures: failures.parse()?,
+                total_duration_ms: total_duration_ms.parse()?,
+            },
+        );
+    }
+
+    Ok(records)
+}
+
+pub fn clear() -> Result<(), Box<dyn Error>> {
+    let path = usage_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    match fs::remove_file(&path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+fn save(records: &BTreeMap<String, UsageRecord>) -> Result<(), Box<dyn Error>> {
+    let content = records
+        .iter()
+        .map(|(name, record)| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                name, record.runs, record.failures, record.total_duration_ms
+            )
+        })
+        .collect::<String>();
+
+    let mut file = AtomicFile::create(usage_path()?)?;
+    file.write_all(content.as_bytes())?;
+    file.commit_unsynced()?;
+    Ok(())
+}
+
+fn usage_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(USAGE_FILE_NAME))
+}
@@ -0,0 +1,70 @@
+use std::{fs, io};
+
+const PASSWD_PATH: &str = "/etc/passwd";
+const GROUP_PATH: &str = "/etc/group";
+
+/// A user from the passwd database.
+#[derive(Debug, Clone)]
+pub struct User {
+    pub name: String,
+    pub uid: u32,
+    /// The user's primary group.
+    pub gid: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub name: String,
+    pub gid: u32,
+}
+
+/// Reads the local users database. Entries that only exist in a directory
+/// service (LDAP, ...) are not visible here, but numeric IDs still work for
+/// them.
+pub fn users() -> io::Result<Vec<User>> {
+    Ok(records(PASSWD_PATH)?
+        .into_iter()
+        .filter_map(|fields| {
+            Some(User {
+                name: fields.first()?.clone(),
+                uid: fields.get(2)?.parse().ok()?,
+                gid: fields.get(3)?.parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+pub fn groups() -> io::Result<Vec<Group>> {
+    Ok(records(GROUP_PATH)?
+        .into_iter()
+        .filter_map(|fields| {
+            Some(Group {
+                name: fields.first()?.clone(),
+                gid: fields.get(2)?.parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+pub fn find_user(name: &str) -> io::Result<Option<User>> {
+    Ok(users()?.into_iter().find(|user| user.name == name))
+}
+
+pub fn find_group(name: &str) -> io::Result<Option<Group>> {
+    Ok(groups()?.into_iter().find(|group| group.name == name))
+}
+
+/// Splits the `:` separated lines of a database file, skipping comments.
+fn records(path: &str) -> io::Result<Vec<Vec<String>>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split(':').map(str::to_string).collect())
+        .collect())
+}
@@ -0,0 +1,88 @@
+//! Shell variables, set by `capture` and expanded as `$NAME` or `${NAME}`
+//! in command lines. Names that are not shell variables fall back to the
+//! environment.
+
+use crate::vault::SECRET_PREFIX;
+use std::{
+    borrow::Cow,
+    env,
+    error::Error,
+    sync::{Mutex, PoisonError},
+};
+
+const VARIABLE_START: char = '$';
+
+/// Variables set during this session, in the order they were first set.
+static VARIABLES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+pub fn get(name: &str) -> Option<String> {
+    VARIABLES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(variable, _)| variable == name)
+        .map(|(_, value)| value.clone())
+        .or_else(|| env::var(name).ok())
+}
+
+pub fn set(name: &str, value: String) {
+    let mut variables = VARIABLES.lock().unwrap_or_else(PoisonError::into_inner);
+    match variables.iter_mut().find(|(variable, _)| variable == name) {
+        Some((_, old)) => *old = value,
+        None => variables.push((name.to_string(), value)),
+    }
+}
+
+/// Names start with a letter or `_`, followed by letters, digits and `_`.
+pub fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(is_name_char)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Replaces every `$NAME` and `${NAME}` in `token` with the variable's
+/// value, which stays part of the same word even if it holds spaces or
+/// newlines. A `$` not followed by a name, as in `$5` or a trailing `$`, is
+/// kept, and so are secret references, which the vault expands.
+pub fn expand(token: &str) -> Result<Cow<'_, str>, Box<dyn Error>> {
+    if !token.contains(VARIABLE_START) {
+        return Ok(Cow::Borrowed(token));
+    }
+
+    let mut output = String::new();
+    let mut copied = 0;
+    let mut search_from = 0;
+    while let Some(offset) = token[search_from..].find(VARIABLE_START) {
+        let start = search_from + offset;
+        let rest = &token[start + 1..];
+        let (name, length) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) if is_valid_name(&braced[..end]) => (&braced[..end], end + 3),
+                _ => return Err(format!("Invalid variable reference: ${{{}", braced).into()),
+            },
+            None => {
+                let end = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+                (&rest[..end], end + 1)
+            }
+        };
+        if !is_valid_name(name) || token[start..].starts_with(SECRET_PREFIX) {
+            search_from = start + 1;
+            continue;
+        }
+
+        let value = get(name).ok_or_else(|| format!("Unknown variable: {}", name))?;
+        output.push_str(&token[copied..start]);
+        output.push_str(&value);
+        copied = start + length;
+        search_from = copied;
+    }
+
+    if copied == 0 {
+        return Ok(Cow::Borrowed(token));
+    }
+    output.push_str(&token[copied..]);
+    Ok(Cow::Owned(output))
+}
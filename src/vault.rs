@@ -0,0 +1,258 @@
+use crate::{atomic, config, crypto, i18n::Message, json, lock::FileLock, log, outln, terminal};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+const VAULT_FILE_NAME: &str = "vault.bin";
+const MAGIC: &[u8; 8] = b"MBCTVLT1";
+const SALT_SIZE: usize = 16;
+const HEADER_SIZE: usize = MAGIC.len() + 4 + SALT_SIZE;
+const KDF_ITERATIONS: u32 = 200_000;
+pub const SECRET_PREFIX: &str = "$SECRET:";
+
+/// Key of the vault unlocked in this session, so the master passphrase is
+/// only asked for once.
+static UNLOCKED_KEY: Mutex<Option<([u8; SALT_SIZE], [u8; crypto::KEY_SIZE])>> = Mutex::new(None);
+
+/// Secrets filled into command lines in this session, with the reference
+/// each came from, so [`redact`] can hide them again in diagnostics.
+static EXPANDED: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+pub struct Vault {
+    header: [u8; HEADER_SIZE],
+    key: [u8; crypto::KEY_SIZE],
+    pub entries: BTreeMap<String, String>,
+}
+
+impl Vault {
+    pub fn exists() -> Result<bool, Box<dyn Error>> {
+        Ok(vault_path()?.exists())
+    }
+
+    /// Opens the vault, creating a new one protected by a freshly chosen
+    /// master passphrase if none exists yet.
+    pub fn open() -> Result<Self, Box<dyn Error>> {
+        let path = vault_path()?;
+        if !path.exists() {
+            return Self::create();
+        }
+
+        let data = fs::read(&path)?;
+        if data.len() < HEADER_SIZE + crypto::NONCE_SIZE || &data[..MAGIC.len()] != MAGIC {
+            return Err(format!("{} is not a vault file", path.display()).into());
+        }
+        let header: [u8; HEADER_SIZE] = data[..HEADER_SIZE].try_into()?;
+        let iterations = u32::from_be_bytes(header[MAGIC.len()..MAGIC.len() + 4].try_into()?);
+        let salt: [u8; SALT_SIZE] = header[MAGIC.len() + 4..].try_into()?;
+        let nonce = data[HEADER_SIZE..HEADER_SIZE + crypto::NONCE_SIZE].try_into()?;
+        let sealed = &data[HEADER_SIZE + crypto::NONCE_SIZE..];
+
+        let cached = UNLOCKED_KEY
+            .lock()
+            .unwrap()
+            .filter(|(cached_salt, _)| *cached_salt == salt)
+            .map(|(_, key)| key);
+        let key = match cached {
+            Some(key) => {
+                log!(Vault, Debug, "reusing the unlocked key");
+                key
+            }
+            None => {
+                let passphrase = terminal::read_hidden(Message::VaultPassphrase.text())?;
+                crypto::derive_key(passphrase.as_bytes(), &salt, iterations)
+            }
+        };
+
+        let plaintext = crypto::open(&key, &nonce, &header, sealed)?;
+        let entries = parse_entries(&String::from_utf8(plaintext)?)?;
+        *UNLOCKED_KEY.lock().unwrap() = Some((salt, key));
+        log!(
+            Vault,
+            Info,
+            "opened {} with {} entries",
+            path.display(),
+            entries.len()
+        );
+
+        Ok(Self {
+            header,
+            key,
+            entries,
+        })
+    }
+
+    fn create() -> Result<Self, Box<dyn Error>> {
+        outln!("Creating a new vault");
+        let passphrase = terminal::read_hidden(Message::NewVaultPassphrase.text())?;
+        if passphrase.is_empty() {
+            return Err("Passphrase must not be empty".into());
+        }
+        if terminal::read_hidden(Message::ConfirmPassphrase.text())? != passphrase {
+            return Err("Passphrases do not match".into());
+        }
+
+        let salt: [u8; SALT_SIZE] = crypto::random_bytes()?;
+        let mut header = [0; HEADER_SIZE];
+        header[..MAGIC.len()].copy_from_slice(MAGIC);
+        header[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&KDF_ITERATIONS.to_be_bytes());
+        header[MAGIC.len() + 4..].copy_from_slice(&salt);
+
+        let key = crypto::derive_key(passphrase.as_bytes(), &salt, KDF_ITERATIONS);
+        *UNLOCKED_KEY.lock().unwrap() = Some((salt, key));
+
+        let vault = Self {
+            header,
+            key,
+            entries: BTreeMap::new(),
+        };
+        vault.save()?;
+        Ok(vault)
+    }
+
+    /// Applies `change` to the current entries and saves them. The vault is
+    /// re-read under a lock, so changes made by other running instances since
+    /// it was last opened are kept.
+    pub fn update<T>(
+        change: impl FnOnce(&mut BTreeMap<String, String>) -> T,
+    ) -> Result<T, Box<dyn Error>> {
+        let _lock = FileLock::acquire(&vault_path()?)?;
+        let mut vault = Self::open()?;
+        let result = change(&mut vault.entries);
+        vault.save()?;
+        Ok(result)
+    }
+
+    fn save(&self) -> Result<(), Box<dyn Error>> {
+        let nonce: [u8; crypto::NONCE_SIZE] = crypto::random_bytes()?;
+        let plaintext = serialize_entries(&self.entries);
+
+        let mut data = self.header.to_vec();
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&crypto::seal(
+            &self.key,
+            &nonce,
+            &self.header,
+            plaintext.as_bytes(),
+        ));
+
+        let path = vault_path()?;
+        atomic::write(&path, data)?;
+        log!(
+            Vault,
+            Info,
+            "saved {} entries to {}",
+            self.entries.len(),
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Replaces every `$SECRET:NAME` in `token` with the stored secret.
+/// Tokens without secrets are returned as they are, without copying.
+pub fn expand_secrets(token: &str) -> Result<Cow<'_, str>, Box<dyn Error>> {
+    if !token.contains(SECRET_PREFIX) {
+        return Ok(Cow::Borrowed(token));
+    }
+
+    if !Vault::exists()? {
+        return Err("The vault is empty".into());
+    }
+
+    let vault = Vault::open()?;
+    let mut output = String::new();
+    let mut rest = token;
+
+    while let Some(start) = rest.find(SECRET_PREFIX) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start + SECRET_PREFIX.len()..];
+
+        let end = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        let name = &rest[..end];
+        let value = vault
+            .entries
+            .get(name)
+            .ok_or_else(|| format!("Unknown secret: {}", name))?;
+
+        output.push_str(value);
+        remember_expansion(name, value);
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+
+    Ok(Cow::Owned(output))
+}
+
+fn remember_expansion(name: &str, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    let mut expanded = EXPANDED.lock().unwrap_or_else(PoisonError::into_inner);
+    if !expanded.iter().any(|(known, _)| known == value) {
+        expanded.push((value.to_string(), format!("{}{}", SECRET_PREFIX, name)));
+        // Longer values first, so one containing another is hidden whole.
+        expanded.sort_by_key(|(value, _)| std::cmp::Reverse(value.len()));
+    }
+}
+
+/// Whether `text` holds a secret expanded in this session.
+pub fn contains_secret(text: &str) -> bool {
+    matches!(redact(text), Cow::Owned(_))
+}
+
+/// Replaces the secrets expanded so far in `text` with their `$SECRET:NAME`
+/// references, for output meant for diagnostics. Values are also found as
+/// `{:?}` prints them, escaped.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let expanded = EXPANDED.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut text = Cow::Borrowed(text);
+    for (value, reference) in expanded.iter() {
+        let debug = format!("{:?}", value);
+        let escaped = &debug[1..debug.len() - 1];
+        for form in [value.as_str(), escaped] {
+            if text.contains(form) {
+                text = Cow::Owned(text.replace(form, reference));
+            }
+        }
+    }
+    text
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_name_char)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+fn vault_path() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(config::config_dir()?.join(VAULT_FILE_NAME))
+}
+
+fn serialize_entries(entries: &BTreeMap<String, String>) -> String {
+    let fields = entries
+        .iter()
+        .map(|(name, value)| format!("{}:{}", json::quote(name), json::quote(value)))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn parse_entries(content: &str) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let json::Value::Object(fields) = json::parse(content)? else {
+        return Err("Vault contents are malformed".into());
+    };
+
+    fields
+        .into_iter()
+        .map(|(name, value)| match value {
+            json::Value::String(value) => Ok((name, value)),
+            _ => Err("Vault contents are malformed".into()),
+        })
+        .collect()
+}
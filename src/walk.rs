@@ -0,0 +1,320 @@
+use crate::{ignore::IgnoreRules, log, options, outln};
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const GIT_DIR_NAME: &str = ".git";
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Skip anything excluded by `.gitignore` files.
+    pub respect_gitignore: bool,
+    /// Descend into symlinked directories. Each directory is visited once,
+    /// so symlink cycles terminate.
+    pub follow_links: bool,
+    /// Report a file with several hard links only once, for size accounting.
+    pub dedupe_hardlinks: bool,
+    /// How deep to go; entries directly in the root are at depth 1.
+    pub max_depth: Option<usize>,
+    /// Look inside `.git` directories, which are skipped otherwise.
+    pub include_git_dir: bool,
+}
+
+/// An entry that could not be read, and why.
+pub type Error = (PathBuf, io::Error);
+
+/// Prints the entries that were skipped, or fails on the first one when the
+/// `strict` option is on.
+pub fn report_errors(errors: &[Error]) -> io::Result<()> {
+    if let Some((path, error)) = errors.first() {
+        if options::get_bool(options::STRICT) {
+            return Err(io::Error::new(
+                error.kind(),
+                format!("{}: {}", path.display(), error),
+            ));
+        }
+    }
+    for (path, error) in errors {
+        outln!("Skipping {}: {}", path.display(), error);
+    }
+    Ok(())
+}
+
+/// An entry found by [`visit`].
+#[derive(Debug)]
+pub struct Entry {
+    pub path: PathBuf,
+    /// 0 for the root, 1 for the entries directly in it.
+    pub depth: usize,
+    /// Of the link's target for symlinks that are followed, otherwise of
+    /// the entry itself.
+    pub metadata: fs::Metadata,
+    /// Whether no entries of the same directory come after it.
+    pub is_last: bool,
+    /// A hard link to a file reported already. Only roots are reported
+    /// again, as they were asked for; other repeats are skipped.
+    pub repeated: bool,
+}
+
+impl Entry {
+    /// Whether the walk descends into it: directories, and symlinks to them
+    /// when links are followed.
+    pub fn is_dir(&self) -> bool {
+        self.metadata.is_dir()
+    }
+}
+
+/// What a walk calls for each entry. Directories are entered in order,
+/// before the walk goes on with their siblings.
+pub trait Visitor {
+    fn entry(&mut self, entry: &Entry);
+
+    /// Called for every directory after [`Visitor::entry`] and everything
+    /// below it, also when the walk did not go inside.
+    fn leave(&mut self, _dir: &Entry) {}
+
+    /// Called when the entries of `dir` cannot be read, before it is left.
+    /// The error is also kept for [`report_errors`].
+    fn unreadable(&mut self, _dir: &Entry, _error: &io::Error) {}
+
+    /// The order of the entries of a directory.
+    fn order(&self, a: &Path, b: &Path) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Walks `root` and everything below it, handing each entry to `visitor`,
+/// the root first. Only a failure to read `root` itself is an error;
+/// anything below it that cannot be read is returned instead of aborting the
+/// walk, for [`report_errors`].
+pub fn visit(root: &Path, options: Options, visitor: &mut impl Visitor) -> io::Result<Vec<Error>> {
+    let mut walker = Walker::new(options);
+    walker.visit(root, visitor)?;
+    Ok(walker.errors)
+}
+
+/// The files found by [`walk`], plus the entries that could not be read.
+#[derive(Debug, Default)]
+pub struct Walk {
+    pub files: Vec<PathBuf>,
+    pub errors: Vec<Error>,
+}
+
+/// Collects the files of a walk, including symlinks to files.
+struct Files(Vec<PathBuf>);
+
+impl Visitor for Files {
+    fn entry(&mut self, entry: &Entry) {
+        if !entry.is_dir() && entry.path.is_file() {
+            self.0.push(entry.path.clone());
+        }
+    }
+}
+
+/// Recursively lists the files below `root` in sorted order, skipping `.git`
+/// and, when `respect_gitignore` is set, anything excluded by `.gitignore`.
+/// Unreadable subdirectories are reported and skipped.
+pub fn files(root: &Path, respect_gitignore: bool) -> io::Result<Vec<PathBuf>> {
+    let walk = walk(
+        root,
+        Options {
+            respect_gitignore,
+            ..Options::default()
+        },
+    )?;
+    report_errors(&walk.errors)?;
+    Ok(walk.files)
+}
+
+/// Lists the files below `root` with the given options.
+pub fn walk(root: &Path, options: Options) -> io::Result<Walk> {
+    let mut files = Files(Vec::new());
+    let errors = visit(root, options, &mut files)?;
+    Ok(Walk {
+        files: files.0,
+        errors,
+    })
+}
+
+/// Walks one root after another, remembering what earlier walks saw, so a
+/// file hard linked under two roots is still reported once.
+pub struct Walker {
+    options: Options,
+    visited_dirs: HashSet<PathBuf>,
+    seen_inodes: HashSet<(u64, u64)>,
+    /// Entries that could not be read, for [`report_errors`].
+    pub errors: Vec<Error>,
+}
+
+impl Walker {
+    pub fn new(options: Options) -> Self {
+        Self {
+            options,
+            visited_dirs: HashSet::new(),
+            seen_inodes: HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Walks `root` like [`visit`], adding what cannot be read to
+    /// [`Walker::errors`].
+    pub fn visit(&mut self, root: &Path, visitor: &mut impl Visitor) -> io::Result<()> {
+        log!(
+            Walk,
+            Debug,
+            "walking {} with {:?}",
+            root.display(),
+            self.options
+        );
+        // The root is always followed: it is what was asked for.
+        let mut root = self.entry(root.to_path_buf(), 0, true, true)?;
+        root.repeated = self.seen_before(&root);
+        visitor.entry(&root);
+        if root.is_dir() {
+            self.mark_visited(&root.path);
+            if self.options.max_depth != Some(0) {
+                let rules = if self.options.respect_gitignore {
+                    IgnoreRules::default().for_directory(&root.path)
+                } else {
+                    IgnoreRules::default()
+                };
+                let entries = read_sorted(&root.path, visitor)?;
+                self.visit_entries(entries, 1, &rules, visitor);
+            }
+            visitor.leave(&root);
+        }
+        Ok(())
+    }
+
+    fn entry(&self, path: PathBuf, depth: usize, is_last: bool, follow: bool) -> io::Result<Entry> {
+        let link_metadata = fs::symlink_metadata(&path)?;
+        // A dangling link is shown as the link it is.
+        let metadata = if link_metadata.file_type().is_symlink() && follow {
+            fs::metadata(&path).unwrap_or(link_metadata)
+        } else {
+            link_metadata
+        };
+        Ok(Entry {
+            path,
+            depth,
+            metadata,
+            is_last,
+            repeated: false,
+        })
+    }
+
+    fn visit_entries(
+        &mut self,
+        entries: Vec<PathBuf>,
+        depth: usize,
+        rules: &IgnoreRules,
+        visitor: &mut impl Visitor,
+    ) {
+        let entries = entries
+            .into_iter()
+            .filter(|path| {
+                self.options.include_git_dir
+                    || path.file_name().is_none_or(|name| name != GIT_DIR_NAME)
+            })
+            .filter(|path| !rules.is_ignored(path, path.is_dir()))
+            .collect::<Vec<_>>();
+        let count = entries.len();
+
+        for (index, path) in entries.into_iter().enumerate() {
+            let is_last = index + 1 == count;
+            let entry = match self.entry(path.clone(), depth, is_last, self.options.follow_links) {
+                Ok(entry) => entry,
+                Err(error) => {
+                    self.errors.push((path, error));
+                    continue;
+                }
+            };
+            if self.seen_before(&entry) {
+                continue;
+            }
+            visitor.entry(&entry);
+            if !entry.is_dir() {
+                continue;
+            }
+
+            let descend = self.options.max_depth.is_none_or(|max| depth < max)
+                && self.mark_visited(&entry.path);
+            if descend {
+                let rules = if self.options.respect_gitignore {
+                    rules.for_directory(&entry.path)
+                } else {
+                    rules.clone()
+                };
+                match read_sorted(&entry.path, visitor) {
+                    Ok(entries) => self.visit_entries(entries, depth + 1, &rules, visitor),
+                    Err(error) => {
+                        log!(
+                            Walk,
+                            Warn,
+                            "cannot read {}: {}",
+                            entry.path.display(),
+                            error
+                        );
+                        visitor.unreadable(&entry, &error);
+                        self.errors.push((entry.path.clone(), error));
+                    }
+                }
+            }
+            visitor.leave(&entry);
+        }
+    }
+
+    /// Whether `entry` is a hard link to a file reported already, when hard
+    /// links are deduplicated.
+    fn seen_before(&mut self, entry: &Entry) -> bool {
+        if entry.is_dir() || !self.options.dedupe_hardlinks {
+            return false;
+        }
+        let Some(id) = hardlink_id(&entry.metadata) else {
+            return false;
+        };
+        let seen = !self.seen_inodes.insert(id);
+        if seen {
+            log!(Walk, Debug, "skipping hard link {}", entry.path.display());
+        }
+        seen
+    }
+
+    /// Records `dir` as visited, returning false if it already was.
+    fn mark_visited(&mut self, dir: &Path) -> bool {
+        if !self.options.follow_links {
+            return true;
+        }
+        match fs::canonicalize(dir) {
+            Ok(canonical) => self.visited_dirs.insert(canonical),
+            Err(error) => {
+                self.errors.push((dir.to_path_buf(), error));
+                false
+            }
+        }
+    }
+}
+
+fn read_sorted(dir: &Path, visitor: &impl Visitor) -> io::Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    entries.sort_by(|a, b| visitor.order(a, b));
+    Ok(entries)
+}
+
+/// Device and inode of a file that has more than one hard link.
+#[cfg(unix)]
+pub fn hardlink_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+pub fn hardlink_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
@@ -0,0 +1,459 @@
+//! Reading and writing zip archives. Entries are written uncompressed;
+//! reading also understands deflate, which is what other zip programs use.
+//! Archives over 4 GiB or with more than 65535 entries (zip64) are not
+//! supported.
+
+use crate::{
+    calendar::DateTime,
+    hash::Crc32,
+    inflate,
+    tar::{EntryKind, Header},
+    timezone::TimeZone,
+};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_SIGNATURE: u32 = 0x0605_4b50;
+const LOCAL_HEADER_SIZE: usize = 30;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const END_SIZE: usize = 22;
+const MAX_COMMENT_SIZE: usize = 0xffff;
+
+/// Version 2.0, which has directories and deflate; made on Unix, so that
+/// readers take the permissions from the external attributes.
+const VERSION_NEEDED: u16 = 20;
+const VERSION_MADE_BY: u16 = 3 << 8 | VERSION_NEEDED;
+const FLAG_ENCRYPTED: u16 = 1;
+const FLAG_UTF8: u16 = 1 << 11;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+/// The extended timestamp extra field, holding the Unix modification time.
+const EXTENDED_TIMESTAMP: u16 = 0x5455;
+
+const FILE_TYPE_MASK: u32 = 0o170000;
+const REGULAR_FILE: u32 = 0o100000;
+const DIRECTORY: u32 = 0o040000;
+const SYMLINK: u32 = 0o120000;
+/// The MS-DOS directory attribute.
+const DOS_DIRECTORY: u32 = 0x10;
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn too_large(what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{} too large for a zip archive", what),
+    )
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap_or_default())
+}
+
+/// The MS-DOS time and date fields for a Unix time, on the local clock.
+/// They cannot hold times before 1980.
+fn dos_time(zone: &TimeZone, modified: u64) -> (u16, u16) {
+    let local = zone.local_time(modified as i64).date_time;
+    if local.year < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let year = ((local.year - 1980) as u32).min(127);
+    let time = (local.hour << 11) | (local.minute << 5) | (local.second / 2);
+    let date = (year << 9) | (local.month << 5) | local.day;
+    (time as u16, date as u16)
+}
+
+fn from_dos_time(zone: &TimeZone, time: u16, date: u16) -> u64 {
+    let local = DateTime {
+        year: 1980 + i32::from(date >> 9),
+        month: u32::from(date >> 5 & 0xf).clamp(1, 12),
+        day: u32::from(date & 0x1f).max(1),
+        hour: u32::from(time >> 11),
+        minute: u32::from(time >> 5 & 0x3f),
+        second: u32::from(time & 0x1f) * 2,
+    };
+    zone.timestamp(&local).max(0) as u64
+}
+
+fn changed(path: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!("{} changed while it was archived", path),
+    )
+}
+
+/// Copies exactly `size` bytes into `writer`, returning their checksum.
+fn copy_checked(
+    data: &mut impl Read,
+    size: u32,
+    writer: &mut impl Write,
+    path: &str,
+) -> io::Result<u32> {
+    let mut crc = Crc32::new();
+    let mut data = data.take(u64::from(size));
+    let mut buffer = [0; 64 * 1024];
+    let mut copied = 0;
+    loop {
+        let read = data.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        crc.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        copied += read as u64;
+    }
+    if copied != u64::from(size) {
+        return Err(changed(path));
+    }
+    Ok(crc.finalize())
+}
+
+/// Writes entries one after another; [`Writer::finish`] adds the central
+/// directory that ends the archive.
+pub struct Writer<W: Write> {
+    inner: W,
+    /// Bytes written so far, where the next local header goes.
+    offset: u64,
+    central_directory: Vec<u8>,
+    count: usize,
+    /// MS-DOS times are on the local clock.
+    zone: TimeZone,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            central_directory: Vec::new(),
+            count: 0,
+            zone: TimeZone::local(),
+        }
+    }
+
+    /// Adds an entry; `data` must hold exactly `header.size` bytes. It is
+    /// read twice, first for its checksum, which goes before it. Symbolic
+    /// links store their target as their data.
+    pub fn append(&mut self, header: &Header, data: impl Read + Seek) -> io::Result<()> {
+        match &header.kind {
+            EntryKind::File => {
+                self.write_entry(header, &header.path, REGULAR_FILE, header.size, data)
+            }
+            EntryKind::Directory => {
+                let mut path = header.path.clone();
+                if !path.ends_with('/') {
+                    path.push('/');
+                }
+                self.write_entry(header, &path, DIRECTORY, 0, io::empty())
+            }
+            EntryKind::Symlink(target) => self.write_entry(
+                header,
+                &header.path,
+                SYMLINK,
+                target.len() as u64,
+                io::Cursor::new(target.as_bytes()),
+            ),
+        }
+    }
+
+    fn write_entry(
+        &mut self,
+        header: &Header,
+        path: &str,
+        file_type: u32,
+        size: u64,
+        mut data: impl Read + Seek,
+    ) -> io::Result<()> {
+        let size = u32::try_from(size).map_err(|_| too_large(path))?;
+        let name_length = u16::try_from(path.len()).map_err(|_| too_large(path))?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large("The archive is"))?;
+
+        let start = data.stream_position()?;
+        let checksum = copy_checked(&mut data, size, &mut io::sink(), &header.path)?;
+        data.seek(SeekFrom::Start(start))?;
+
+        let (time, date) = dos_time(&self.zone, header.modified);
+        let mut extra = Vec::with_capacity(9);
+        extra.extend(EXTENDED_TIMESTAMP.to_le_bytes());
+        extra.extend(5u16.to_le_bytes());
+        // Only the modification time is present.
+        extra.push(1);
+        extra.extend(
+            u32::try_from(header.modified)
+                .unwrap_or(u32::MAX)
+                .to_le_bytes(),
+        );
+
+        let mut local = Vec::with_capacity(LOCAL_HEADER_SIZE + path.len() + extra.len());
+        local.extend(LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        local.extend(VERSION_NEEDED.to_le_bytes());
+        local.extend(FLAG_UTF8.to_le_bytes());
+        local.extend(METHOD_STORED.to_le_bytes());
+        local.extend(time.to_le_bytes());
+        local.extend(date.to_le_bytes());
+        local.extend(checksum.to_le_bytes());
+        // Compressed and uncompressed sizes, the same when stored.
+        local.extend(size.to_le_bytes());
+        local.extend(size.to_le_bytes());
+        local.extend(name_length.to_le_bytes());
+        local.extend((extra.len() as u16).to_le_bytes());
+        local.extend(path.as_bytes());
+        local.extend(&extra);
+        self.inner.write_all(&local)?;
+        if copy_checked(&mut data, size, &mut self.inner, &header.path)? != checksum {
+            return Err(changed(&header.path));
+        }
+
+        let mut external = (file_type | header.mode & 0o7777) << 16;
+        if file_type == DIRECTORY {
+            external |= DOS_DIRECTORY;
+        }
+        let central = &mut self.central_directory;
+        central.extend(CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+        central.extend(VERSION_MADE_BY.to_le_bytes());
+        // From the version needed to the extra field length, as above.
+        central.extend_from_slice(&local[4..LOCAL_HEADER_SIZE]);
+        // No comment, on disk 0, no internal attributes.
+        central.extend([0; 6]);
+        central.extend(external.to_le_bytes());
+        central.extend(offset.to_le_bytes());
+        central.extend(path.as_bytes());
+        central.extend(&extra);
+
+        self.offset += local.len() as u64 + u64::from(size);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Writes the central directory and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let count = u16::try_from(self.count).map_err(|_| too_large("The number of entries is"))?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large("The archive is"))?;
+        let size =
+            u32::try_from(self.central_directory.len()).map_err(|_| too_large("The archive is"))?;
+        self.inner.write_all(&self.central_directory)?;
+
+        let mut end = Vec::with_capacity(END_SIZE);
+        end.extend(END_SIGNATURE.to_le_bytes());
+        // On disk 0, whose central directory starts on disk 0.
+        end.extend([0; 4]);
+        end.extend(count.to_le_bytes());
+        end.extend(count.to_le_bytes());
+        end.extend(size.to_le_bytes());
+        end.extend(offset.to_le_bytes());
+        // No comment.
+        end.extend([0; 2]);
+        self.inner.write_all(&end)?;
+        Ok(self.inner)
+    }
+}
+
+/// An entry listed in the central directory.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub header: Header,
+    method: u16,
+    flags: u16,
+    checksum: u32,
+    compressed_size: u64,
+    /// The uncompressed size of the data, which for links is the target.
+    data_size: u64,
+    /// Where the entry's local header starts.
+    offset: u64,
+}
+
+/// Reads the central directory when opened; the data of each entry is read
+/// on request with [`Reader::copy_data`].
+pub struct Reader<R: Read + Seek> {
+    inner: R,
+    entries: Vec<Entry>,
+}
+
+impl<R: Read + Seek> Reader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let (count, directory_offset) = find_central_directory(&mut inner)?;
+        inner.seek(SeekFrom::Start(directory_offset))?;
+        let mut directory = io::BufReader::new(&mut inner);
+        let zone = TimeZone::local();
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            entries.push(read_central_header(&mut directory, &zone)?);
+        }
+        drop(directory);
+
+        let mut reader = Self { inner, entries };
+        // Link targets are stored as the data of their entries.
+        for index in 0..reader.entries.len() {
+            if let EntryKind::Symlink(_) = reader.entries[index].header.kind {
+                let entry = reader.entries[index].clone();
+                let mut target = Vec::new();
+                reader.copy_data(&entry, &mut target)?;
+                let entry = &mut reader.entries[index];
+                entry.header.kind =
+                    EntryKind::Symlink(String::from_utf8_lossy(&target).into_owned());
+            }
+        }
+        Ok(reader)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Copies the uncompressed data of `entry` into `writer`, checking it
+    /// against the checksum in the archive.
+    pub fn copy_data(&mut self, entry: &Entry, writer: &mut impl Write) -> io::Result<u64> {
+        if entry.flags & FLAG_ENCRYPTED != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "encrypted entries are not supported",
+            ));
+        }
+        self.inner.seek(SeekFrom::Start(entry.offset))?;
+        let mut local = [0; LOCAL_HEADER_SIZE];
+        self.inner.read_exact(&mut local)?;
+        if u32_at(&local, 0) != LOCAL_HEADER_SIGNATURE {
+            return Err(invalid_data("Not a zip archive, or a damaged one"));
+        }
+        let skipped = i64::from(u16_at(&local, 26)) + i64::from(u16_at(&local, 28));
+        self.inner.seek(SeekFrom::Current(skipped))?;
+
+        let mut compressed = (&mut self.inner).take(entry.compressed_size);
+        let (checksum, size) = match entry.method {
+            METHOD_STORED => {
+                let mut crc = Crc32::new();
+                let mut buffer = [0; 64 * 1024];
+                let mut size = 0;
+                loop {
+                    let read = compressed.read(&mut buffer)?;
+                    if read == 0 {
+                        break;
+                    }
+                    crc.update(&buffer[..read]);
+                    writer.write_all(&buffer[..read])?;
+                    size += read as u64;
+                }
+                (crc.finalize(), size)
+            }
+            METHOD_DEFLATED => {
+                let mut data = Vec::new();
+                compressed.read_to_end(&mut data)?;
+                let data = inflate::inflate(&data, entry.data_size)?;
+                writer.write_all(&data)?;
+                (crate::hash::crc32(&data), data.len() as u64)
+            }
+            method => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("compression method {} is not supported", method),
+                ))
+            }
+        };
+        if size != entry.data_size || checksum != entry.checksum {
+            return Err(invalid_data("damaged data: the checksum does not match"));
+        }
+        Ok(size)
+    }
+}
+
+/// Finds the end of central directory record, which may be followed by a
+/// comment, and returns the number of entries and where they start.
+fn find_central_directory(inner: &mut (impl Read + Seek)) -> io::Result<(usize, u64)> {
+    let length = inner.seek(SeekFrom::End(0))?;
+    let tail_length = length.min((END_SIZE + MAX_COMMENT_SIZE) as u64);
+    inner.seek(SeekFrom::Start(length - tail_length))?;
+    let mut tail = vec![0; tail_length as usize];
+    inner.read_exact(&mut tail)?;
+
+    let end = (0..tail.len().saturating_sub(END_SIZE - 1))
+        .rev()
+        .find(|&start| u32_at(&tail, start) == END_SIGNATURE)
+        .ok_or_else(|| invalid_data("Not a zip archive, or a damaged one"))?;
+    let record = &tail[end..end + END_SIZE];
+    let count = u16_at(record, 10);
+    let offset = u32_at(record, 16);
+    if count == u16::MAX || offset == u32::MAX {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "zip64 archives are not supported",
+        ));
+    }
+    Ok((usize::from(count), u64::from(offset)))
+}
+
+fn read_central_header(directory: &mut impl Read, zone: &TimeZone) -> io::Result<Entry> {
+    let mut fixed = [0; CENTRAL_HEADER_SIZE];
+    directory.read_exact(&mut fixed)?;
+    if u32_at(&fixed, 0) != CENTRAL_HEADER_SIGNATURE {
+        return Err(invalid_data("Not a zip archive, or a damaged one"));
+    }
+    let mut variable = vec![
+        0;
+        usize::from(u16_at(&fixed, 28))
+            + usize::from(u16_at(&fixed, 30))
+            + usize::from(u16_at(&fixed, 32))
+    ];
+    directory.read_exact(&mut variable)?;
+    let (name, rest) = variable.split_at(usize::from(u16_at(&fixed, 28)));
+    let extra = &rest[..usize::from(u16_at(&fixed, 30))];
+
+    let path = String::from_utf8_lossy(name).into_owned();
+    let made_on_unix = u16_at(&fixed, 4) >> 8 == 3;
+    let external = u32_at(&fixed, 38);
+    let unix_mode = if made_on_unix { external >> 16 } else { 0 };
+    let kind = match unix_mode & FILE_TYPE_MASK {
+        SYMLINK => EntryKind::Symlink(String::new()),
+        DIRECTORY => EntryKind::Directory,
+        _ if path.ends_with('/') || external & DOS_DIRECTORY != 0 => EntryKind::Directory,
+        _ => EntryKind::File,
+    };
+    let mode = match (unix_mode & 0o7777, &kind) {
+        (0, EntryKind::Directory) => 0o755,
+        (0, _) => 0o644,
+        (mode, _) => mode,
+    };
+    let modified = extended_timestamp(extra)
+        .unwrap_or_else(|| from_dos_time(zone, u16_at(&fixed, 12), u16_at(&fixed, 14)));
+
+    let data_size = u64::from(u32_at(&fixed, 24));
+    Ok(Entry {
+        header: Header {
+            path,
+            size: if kind == EntryKind::File {
+                data_size
+            } else {
+                0
+            },
+            kind,
+            mode,
+            modified,
+        },
+        method: u16_at(&fixed, 10),
+        flags: u16_at(&fixed, 8),
+        checksum: u32_at(&fixed, 16),
+        compressed_size: u64::from(u32_at(&fixed, 20)),
+        data_size,
+        offset: u64::from(u32_at(&fixed, 42)),
+    })
+}
+
+/// The modification time from an extended timestamp extra field.
+fn extended_timestamp(mut extra: &[u8]) -> Option<u64> {
+    while extra.len() >= 4 {
+        let id = u16_at(extra, 0);
+        let length = usize::from(u16_at(extra, 2));
+        let data = extra.get(4..4 + length)?;
+        if id == EXTENDED_TIMESTAMP && data.len() >= 5 && data[0] & 1 != 0 {
+            return Some(u64::from(u32_at(data, 1)));
+        }
+        extra = &extra[4 + length..];
+    }
+    None
+}
@@ -0,0 +1,88 @@
+use my_basic_cli_tools::aho_corasick::Patterns;
+
+/// The ranges found, as pairs for comparing.
+fn found(patterns: &Patterns, text: &str) -> Vec<(usize, usize)> {
+    patterns
+        .find_ranges(text.as_bytes())
+        .into_iter()
+        .map(|range| (range.start, range.end))
+        .collect()
+}
+
+#[test]
+fn finds_any_of_several_patterns() {
+    let patterns = Patterns::new(&["he", "she", "his", "hers"]);
+
+    assert!(patterns.is_match(b"ushers"));
+    assert!(patterns.is_match(b"this"));
+    assert!(!patterns.is_match(b"ell"));
+    assert!(!patterns.is_match(b""));
+}
+
+#[test]
+fn joins_overlapping_occurrences() {
+    let patterns = Patterns::new(&["he", "she", "his", "hers"]);
+
+    // "she", "he" and "hers" overlap in "ushers".
+    assert_eq!(found(&patterns, "ushers"), [(1, 6)]);
+    assert_eq!(found(&patterns, "he said his"), [(0, 2), (8, 11)]);
+}
+
+#[test]
+fn a_long_match_swallows_earlier_short_ones() {
+    let patterns = Patterns::new(&["a", "b", "abcd"]);
+
+    assert_eq!(found(&patterns, "xabcdx"), [(1, 5)]);
+    assert_eq!(found(&patterns, "a-b"), [(0, 1), (2, 3)]);
+    // Adjacent occurrences become one range.
+    assert_eq!(found(&patterns, "ab"), [(0, 2)]);
+}
+
+#[test]
+fn falls_back_after_a_partial_match() {
+    let patterns = Patterns::new(&["abcx", "bcd"]);
+
+    assert_eq!(found(&patterns, "abcd"), [(1, 4)]);
+    assert!(!patterns.is_match(b"abc"));
+}
+
+#[test]
+fn an_empty_pattern_matches_everything_but_marks_nothing() {
+    let patterns = Patterns::new(&["", "zz"]);
+
+    assert!(patterns.is_match(b"abc"));
+    assert!(found(&patterns, "abc").is_empty());
+}
+
+#[test]
+fn agrees_with_a_plain_search() {
+    let words = ["ab", "ba", "aab", "bbb", "a"];
+    let patterns = Patterns::new(&words);
+    // Every text of up to 6 letters from {a, b, c}.
+    for length in 0..=6u32 {
+        for number in 0..3usize.pow(length) {
+            let text = (0..length)
+                .map(|position| b"abc"[number / 3usize.pow(position) % 3])
+                .collect::<Vec<u8>>();
+            let expected = words.iter().any(|word| {
+                text.windows(word.len())
+                    .any(|window| window == word.as_bytes())
+            });
+            assert_eq!(patterns.is_match(&text), expected, "{:?}", text);
+
+            let mut covered = vec![false; text.len()];
+            for word in words {
+                for start in 0..text.len().saturating_sub(word.len() - 1) {
+                    if text[start..].starts_with(word.as_bytes()) {
+                        covered[start..start + word.len()].fill(true);
+                    }
+                }
+            }
+            let mut marked = vec![false; text.len()];
+            for range in patterns.find_ranges(&text) {
+                marked[range].fill(true);
+            }
+            assert_eq!(marked, covered, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use my_basic_cli_tools::atomic::{self, AtomicFile};
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// A fresh directory per test, removed when the test ends.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("atomic-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn replaces_content_and_leaves_no_temp_file() {
+    let dir = TempDir::new("replace");
+    let path = dir.path().join("file.txt");
+    fs::write(&path, "old").unwrap();
+
+    atomic::write(&path, "new").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn dropping_without_commit_keeps_the_old_content() {
+    let dir = TempDir::new("drop");
+    let path = dir.path().join("file.txt");
+    fs::write(&path, "old").unwrap();
+
+    let mut file = AtomicFile::create(&path).unwrap();
+    file.write_all(b"new").unwrap();
+    drop(file);
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn permissions_of_the_target_are_kept() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new("mode");
+    let script = dir.path().join("script.sh");
+    let secret = dir.path().join("secret");
+    fs::write(&script, "#!/bin/sh\n").unwrap();
+    fs::write(&secret, "old").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::set_permissions(&secret, fs::Permissions::from_mode(0o600)).unwrap();
+
+    atomic::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+    atomic::write(&secret, "new").unwrap();
+
+    let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+    assert_eq!(mode(&script), 0o755);
+    assert_eq!(mode(&secret), 0o600);
+}
+
+#[cfg(unix)]
+#[test]
+fn symbolic_links_are_followed_and_kept() {
+    use std::os::unix::fs::symlink;
+
+    let dir = TempDir::new("link");
+    fs::create_dir(dir.path().join("real")).unwrap();
+    let target = dir.path().join("real/file.txt");
+    let link = dir.path().join("link.txt");
+    let chained = dir.path().join("chained.txt");
+    fs::write(&target, "old").unwrap();
+    symlink("real/file.txt", &link).unwrap();
+    symlink("link.txt", &chained).unwrap();
+
+    atomic::write(&chained, "new").unwrap();
+
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert!(fs::symlink_metadata(&chained)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+    // The temporary file was made next to the real file, not the link.
+    assert_eq!(fs::read_dir(dir.path().join("real")).unwrap().count(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn dangling_links_create_their_target() {
+    use std::os::unix::fs::symlink;
+
+    let dir = TempDir::new("dangling");
+    let link = dir.path().join("link.txt");
+    symlink("missing.txt", &link).unwrap();
+
+    atomic::write(&link, "new").unwrap();
+
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("missing.txt")).unwrap(),
+        "new"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn link_loops_are_an_error() {
+    use std::os::unix::fs::symlink;
+
+    let dir = TempDir::new("loop");
+    let a = dir.path().join("a");
+    let b = dir.path().join("b");
+    symlink("b", &a).unwrap();
+    symlink("a", &b).unwrap();
+
+    assert!(atomic::write(&a, "new").is_err());
+}
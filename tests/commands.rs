@@ -0,0 +1,765 @@
+//! Runs the shell binary on single lines, in a scratch directory with its
+//! own config directory.
+
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{self, Command, Output, Stdio},
+};
+
+struct Sandbox(PathBuf);
+
+impl Sandbox {
+    fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("commands-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(path.join("work")).unwrap();
+        Self(path)
+    }
+
+    /// The directory commands run in.
+    fn dir(&self) -> PathBuf {
+        self.0.join("work")
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir().join(name)
+    }
+
+    fn run(&self, line: &str) -> Output {
+        self.run_with_input(line, "")
+    }
+
+    fn run_with_input(&self, line: &str, input: &str) -> Output {
+        self.run_shell(&["-c", line], input)
+    }
+
+    /// Runs the shell with `args`, which end with `-c <line>`.
+    fn run_shell(&self, args: &[&str], input: &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_my_basic_cli_tools"))
+            .args(args)
+            .current_dir(self.dir())
+            .env("XDG_CONFIG_HOME", self.0.join("config"))
+            .env("LANG", "C")
+            .env_remove("LC_ALL")
+            .env_remove("LC_MESSAGES")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    /// Starts the shell on `line` and returns once it printed something.
+    #[cfg(unix)]
+    fn spawn(&self, line: &str) -> process::Child {
+        use std::io::Read;
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_my_basic_cli_tools"))
+            .args(["-c", line])
+            .current_dir(self.dir())
+            .env("XDG_CONFIG_HOME", self.0.join("config"))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let mut first = [0];
+        child
+            .stdout
+            .as_mut()
+            .unwrap()
+            .read_exact(&mut first)
+            .unwrap();
+        child
+    }
+}
+
+/// Sends SIGINT, as Ctrl-C does, and waits for the process to end.
+#[cfg(unix)]
+fn interrupt(child: &mut process::Child) -> process::ExitStatus {
+    use std::{thread, time::Duration};
+
+    let sent = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(sent.success());
+    for _ in 0..100 {
+        if let Some(status) = child.try_wait().unwrap() {
+            return status;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    child.kill().unwrap();
+    panic!("still running 5 seconds after SIGINT");
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn names(dir: &Path) -> Vec<String> {
+    let mut names = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn decrypt_with_a_wrong_passphrase_keeps_an_existing_file() {
+    let sandbox = Sandbox::new("decrypt");
+    fs::write(sandbox.path("notes.txt"), "plain").unwrap();
+    assert!(sandbox
+        .run_with_input("encrypt notes.txt", "right\nright\n")
+        .status
+        .success());
+    fs::write(sandbox.path("notes.txt"), "newer").unwrap();
+
+    let output = sandbox.run_with_input("decrypt notes.txt.enc", "wrong\n");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(sandbox.path("notes.txt")).unwrap(),
+        "newer"
+    );
+    assert_eq!(names(&sandbox.dir()), ["notes.txt", "notes.txt.enc"]);
+
+    let output = sandbox.run_with_input("decrypt notes.txt.enc", "right\n");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(
+        fs::read_to_string(sandbox.path("notes.txt")).unwrap(),
+        "plain"
+    );
+}
+
+#[test]
+fn decrypt_rejects_a_changed_or_truncated_file() {
+    let sandbox = Sandbox::new("tamper");
+    // Three chunks of 64 KiB, the last one short.
+    let plain = (0..150_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    fs::write(sandbox.path("data.bin"), &plain).unwrap();
+    let encrypted = sandbox.run_with_input("encrypt data.bin", "right\nright\n");
+    assert!(encrypted.status.success(), "{}", stdout(&encrypted));
+    fs::remove_file(sandbox.path("data.bin")).unwrap();
+    let sealed = fs::read(sandbox.path("data.bin.enc")).unwrap();
+    // Each chunk has a 16 byte tag.
+    let header = sealed.len() - (plain.len() + 3 * 16);
+    let sealed_chunk = 64 * 1024 + 16;
+
+    let mut flipped_data = sealed.clone();
+    flipped_data[header + sealed_chunk + 100] ^= 1;
+    let mut flipped_header = sealed.clone();
+    flipped_header[header - 1] ^= 1;
+    let damaged = [
+        flipped_data,
+        flipped_header,
+        // Whole chunks dropped from the end.
+        sealed[..header + 2 * sealed_chunk].to_vec(),
+        sealed[..header + sealed_chunk].to_vec(),
+    ];
+    for (index, data) in damaged.iter().enumerate() {
+        fs::write(sandbox.path("data.bin.enc"), data).unwrap();
+        let output = sandbox.run_with_input("decrypt data.bin.enc", "right\n");
+        assert!(!output.status.success(), "case {}", index);
+        assert_eq!(names(&sandbox.dir()), ["data.bin.enc"], "case {}", index);
+    }
+
+    // The iteration count follows the 8 byte magic.
+    let mut slow = sealed.clone();
+    slow[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+    fs::write(sandbox.path("data.bin.enc"), &slow).unwrap();
+    let output = sandbox.run_with_input("decrypt data.bin.enc", "right\n");
+    assert!(
+        stdout(&output).contains("Invalid iteration count in header"),
+        "{}",
+        stdout(&output)
+    );
+
+    fs::write(sandbox.path("data.bin.enc"), &sealed).unwrap();
+    let output = sandbox.run_with_input("decrypt data.bin.enc", "right\n");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(fs::read(sandbox.path("data.bin")).unwrap(), plain);
+}
+
+#[cfg(unix)]
+#[test]
+fn replace_in_place_keeps_the_mode_and_links() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let sandbox = Sandbox::new("replace");
+    let script = sandbox.path("script.sh");
+    fs::write(&script, "echo old\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    symlink("script.sh", sandbox.path("link.sh")).unwrap();
+
+    let output = sandbox.run("replace -i old new link.sh");
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(stdout(&output), "Replaced 1 occurrence in 1 of 1 file\n");
+    assert_eq!(fs::read_to_string(&script).unwrap(), "echo new\n");
+    let mode = fs::metadata(&script).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o755);
+    let link = fs::symlink_metadata(sandbox.path("link.sh")).unwrap();
+    assert!(link.file_type().is_symlink());
+    assert_eq!(names(&sandbox.dir()), ["link.sh", "script.sh"]);
+}
+
+#[test]
+fn cp_preserve_sparse_and_reflink_map_onto_copy_options() {
+    use std::time::{Duration, SystemTime};
+
+    let sandbox = Sandbox::new("cp");
+    let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    fs::create_dir(sandbox.path("dir")).unwrap();
+    fs::write(sandbox.path("dir/file"), "data").unwrap();
+    let set_modified = |path: PathBuf| {
+        fs::File::open(path).unwrap().set_modified(old).unwrap();
+    };
+    set_modified(sandbox.path("dir/file"));
+    set_modified(sandbox.path("dir"));
+    fs::write(sandbox.path("zeros"), vec![0; 1 << 20]).unwrap();
+
+    let output = sandbox.run("cp -rp dir kept");
+    assert!(output.status.success(), "{}", stdout(&output));
+    let output = sandbox.run("cp -r dir fresh");
+    assert!(output.status.success(), "{}", stdout(&output));
+    let modified = |name: &str| {
+        fs::metadata(sandbox.path(name))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    assert_eq!(modified("kept/file"), old);
+    assert_eq!(modified("kept"), old);
+    assert_ne!(modified("fresh/file"), old);
+
+    let output = sandbox.run("cp --sparse=always --reflink=auto zeros holes");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(fs::read(sandbox.path("holes")).unwrap(), vec![0; 1 << 20]);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let holes = fs::metadata(sandbox.path("holes")).unwrap();
+        assert!(
+            holes.blocks() * 512 < holes.len(),
+            "{} blocks",
+            holes.blocks()
+        );
+    }
+
+    let output = sandbox.run("cp --sparse=sometimes zeros other");
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("Unknown sparse mode: sometimes"));
+}
+
+#[cfg(unix)]
+#[test]
+fn cp_sparse_auto_keeps_zero_blocks_that_hold_data() {
+    use std::os::unix::fs::MetadataExt;
+
+    let sandbox = Sandbox::new("cp_sparse");
+    let mut data = vec![b'a'; 4096];
+    data.extend(vec![0; 3 * 4096]);
+    data.extend(vec![b'b'; 4096]);
+    let mut file = fs::File::create(sandbox.path("sparse")).unwrap();
+    file.write_all(&data).unwrap();
+    // A hole after the data makes the file sparse.
+    file.set_len(1 << 20).unwrap();
+    drop(file);
+
+    let output = sandbox.run("cp sparse copy");
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    let source = fs::metadata(sandbox.path("sparse")).unwrap();
+    let copy = fs::metadata(sandbox.path("copy")).unwrap();
+    assert_eq!(copy.len(), source.len());
+    assert_eq!(copy.blocks(), source.blocks());
+    assert_eq!(
+        fs::read(sandbox.path("copy")).unwrap(),
+        fs::read(sandbox.path("sparse")).unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn cp_keeps_links_to_directories_and_setuid_bits() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let sandbox = Sandbox::new("cp_links");
+    fs::create_dir(sandbox.path("dir")).unwrap();
+    fs::write(sandbox.path("dir/tool"), "#!/bin/sh\n").unwrap();
+    fs::set_permissions(sandbox.path("dir/tool"), fs::Permissions::from_mode(0o4755)).unwrap();
+    symlink("dir", sandbox.path("link")).unwrap();
+
+    let output = sandbox.run("cp link copy");
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("use -r"), "{}", stdout(&output));
+    let output = sandbox.run("cp -r link copy");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(
+        fs::read_link(sandbox.path("copy")).unwrap(),
+        Path::new("dir")
+    );
+
+    let output = sandbox.run("cp -p dir/tool tool");
+    assert!(output.status.success(), "{}", stdout(&output));
+    let mode = fs::metadata(sandbox.path("tool"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o7777, 0o4755);
+
+    // The next word is the source, not the reflink mode.
+    let output = sandbox.run("cp --reflink tool other");
+    assert!(!output.status.success());
+    assert!(
+        stdout(&output).contains("Missing value for flag: --reflink"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn find_filters_and_runs_actions_on_matches() {
+    let sandbox = Sandbox::new("find");
+    fs::create_dir_all(sandbox.path("logs/old")).unwrap();
+    fs::write(sandbox.path("logs/big.log"), "x".repeat(2000)).unwrap();
+    fs::write(sandbox.path("logs/old/small.log"), "xy").unwrap();
+    fs::write(sandbox.path("logs/notes.txt"), "").unwrap();
+
+    let output = sandbox.run("find logs \"*.log\" --smaller-than 1K");
+    assert_eq!(stdout(&output), "logs/old/small.log\n");
+
+    let output = sandbox.run("find logs \"*.log\" --print0");
+    assert_eq!(stdout(&output), "logs/big.log\0logs/old/small.log\0");
+
+    let output = sandbox.run("find logs \"*.log\" --exec wc -c {} ; -f");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(stdout(&output), "2000 logs/big.log\n2 logs/old/small.log\n");
+
+    let output = sandbox.run("find logs \"*\" --max-depth 1 -d --exec echo \"dir {}\"");
+    assert_eq!(stdout(&output), "dir logs/old\n");
+
+    let output = sandbox.run("find logs \"*.log\" --delete");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(names(&sandbox.path("logs")), ["notes.txt", "old"]);
+    assert!(names(&sandbox.path("logs/old")).is_empty());
+}
+
+#[test]
+fn grep_shows_context_counts_and_several_patterns() {
+    let sandbox = Sandbox::new("grep");
+    let lines = [
+        "one", "two foo", "three", "four", "five", "six Bar", "seven",
+    ];
+    fs::write(sandbox.path("a.txt"), lines.join("\n")).unwrap();
+    fs::create_dir(sandbox.path("sub")).unwrap();
+    fs::write(sandbox.path("sub/data.bin"), b"foo\0").unwrap();
+    fs::write(sandbox.path("sub/b.txt"), "food\n").unwrap();
+
+    let output = sandbox.run("grep -i -C 1 -e foo -e bar a.txt");
+    assert_eq!(
+        stdout(&output),
+        "a.txt-1-one\na.txt:2:two foo\na.txt-3-three\n--\n\
+         a.txt-5-five\na.txt:6:six Bar\na.txt-7-seven\n"
+    );
+
+    // Without context, lines far apart are not separated.
+    let output = sandbox.run("grep -i -e foo -e bar a.txt");
+    assert_eq!(stdout(&output), "a.txt:2:two foo\na.txt:6:six Bar\n");
+
+    let output = sandbox.run("grep -B 1 -A 2 five a.txt");
+    assert_eq!(
+        stdout(&output),
+        "a.txt-4-four\na.txt:5:five\na.txt-6-six Bar\na.txt-7-seven\n"
+    );
+
+    // Binary files are skipped, as are their matches.
+    let output = sandbox.run("grep -r -c o");
+    assert_eq!(stdout(&output), "a.txt:3\nsub/b.txt:1\n");
+
+    let output = sandbox.run("grep -r -l -F foo");
+    assert_eq!(stdout(&output), "a.txt\nsub/b.txt\n");
+
+    let output = sandbox.run("grep -r --stats seven");
+    let text = stdout(&output);
+    assert!(text.starts_with("a.txt:7:seven\n"), "{}", text);
+    assert!(
+        text.contains("3 files searched, 1 with a match, 1 binary skipped"),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn sort_merges_runs_spilled_past_the_buffer_size() {
+    let sandbox = Sandbox::new("sort");
+    // Numbers in a scrambled order, some repeated, with different padding.
+    let lines = (0..2000)
+        .map(|index| format!("{:>width$} n", index * 7919 % 500, width = index % 4))
+        .collect::<Vec<_>>();
+    fs::write(sandbox.path("numbers.txt"), lines.join("\n")).unwrap();
+
+    for flags in ["", "-r", "-n -u", "-r -n -u"] {
+        let in_memory = sandbox.run(&format!("sort {} numbers.txt", flags));
+        let spilled = sandbox.run(&format!("sort {} --buffer-size 1K numbers.txt", flags));
+        assert!(spilled.status.success(), "{}", stdout(&spilled));
+        assert_eq!(stdout(&spilled), stdout(&in_memory), "sort {}", flags);
+    }
+
+    let output = sandbox.run("sort -n -u --buffer-size 1K numbers.txt");
+    let text = stdout(&output);
+    assert_eq!(text.lines().count(), 500);
+    assert!(text.starts_with("0 n\n"), "{}", &text[..20]);
+}
+
+#[test]
+fn secrets_are_hidden_in_debug_output_and_errors() {
+    let sandbox = Sandbox::new("redact");
+    let created = sandbox.run_with_input("vault set TOKEN", "master\nmaster\nhunter2\n");
+    assert!(created.status.success(), "{}", stdout(&created));
+
+    let output = sandbox.run_shell(
+        &["-vv", "-c", "wc --no-defaults \"x$SECRET:TOKEN\""],
+        "master\n",
+    );
+
+    let text = stdout(&output);
+    assert!(!output.status.success());
+    assert!(!text.contains("hunter2"), "{}", text);
+    assert!(text.contains(r#"arguments=["x$SECRET:TOKEN"]"#), "{}", text);
+}
+
+#[test]
+fn secrets_are_hidden_in_the_debug_log() {
+    let sandbox = Sandbox::new("debuglog");
+    let created = sandbox.run_with_input("vault set TOKEN", "master\nmaster\nhunter2\n");
+    assert!(created.status.success(), "{}", stdout(&created));
+
+    // Lines of an interactive session, the last answering the passphrase prompt.
+    sandbox.run_shell(
+        &[],
+        "debuglog on all --level trace --file debug.log\n\
+         wc --no-defaults \"x$SECRET:TOKEN\"\n\
+         master\n",
+    );
+
+    let log = fs::read_to_string(sandbox.path("debug.log")).unwrap();
+    assert!(!log.contains("hunter2"), "{}", log);
+    assert!(
+        log.contains("running wc arguments=[\"x$SECRET:TOKEN\"]"),
+        "{}",
+        log
+    );
+}
+
+#[test]
+fn rm_interactive_menu_removes_only_the_picked_paths() {
+    let sandbox = Sandbox::new("rmmenu");
+    for name in ["a.log", "b.log", "c.log"] {
+        fs::write(sandbox.path(name), "").unwrap();
+    }
+
+    // Without a terminal the paths are picked by number.
+    let output = sandbox.run_with_input("rm --interactive-menu a.log b.log c.log", "2-3\n");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(names(&sandbox.dir()), ["a.log"]);
+
+    let cancelled = sandbox.run_with_input("rm --interactive-menu a.log", "\n");
+    assert!(cancelled.status.success(), "{}", stdout(&cancelled));
+    assert_eq!(names(&sandbox.dir()), ["a.log"]);
+}
+
+#[test]
+fn archive_grep_and_cat_read_members_without_extracting() {
+    let sandbox = Sandbox::new("archive");
+    fs::create_dir(sandbox.path("notes")).unwrap();
+    fs::write(sandbox.path("notes/a.txt"), "one\nTODO two\n").unwrap();
+    fs::write(sandbox.path("notes/b.txt"), "TODO three").unwrap();
+    for create in ["tar -c notes.tar notes", "zip notes.zip notes"] {
+        let created = sandbox.run(create);
+        assert!(created.status.success(), "{}", stdout(&created));
+    }
+    fs::remove_dir_all(sandbox.path("notes")).unwrap();
+
+    for archive in ["notes.tar", "notes.zip"] {
+        let found = sandbox.run(&format!("archive grep TODO {}", archive));
+        assert!(found.status.success(), "{}", stdout(&found));
+        assert_eq!(
+            stdout(&found),
+            "notes/a.txt:2:TODO two\nnotes/b.txt:1:TODO three\n"
+        );
+
+        let printed = sandbox.run(&format!("archive cat {} notes/a.txt", archive));
+        assert_eq!(stdout(&printed), "one\nTODO two\n");
+        let missing = sandbox.run(&format!("archive cat {} notes/c.txt", archive));
+        assert!(!missing.status.success());
+    }
+    // Nothing was extracted.
+    assert_eq!(names(&sandbox.dir()), ["notes.tar", "notes.zip"]);
+}
+
+#[test]
+fn gitinfo_compares_content_when_only_the_time_changed() {
+    use std::time::{Duration, SystemTime};
+
+    let sandbox = Sandbox::new("gitinfo");
+    fs::write(sandbox.path("notes.txt"), "hello\n").unwrap();
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(sandbox.dir())
+            .output()
+    };
+    // Without git there is no index to compare with.
+    if !git(&["init", "-q"]).is_ok_and(|output| output.status.success()) {
+        return;
+    }
+    assert!(git(&["add", "notes.txt"]).unwrap().status.success());
+    assert!(git(&["commit", "-q", "-m", "notes"])
+        .unwrap()
+        .status
+        .success());
+    let status = || {
+        let output = sandbox.run("gitinfo --files");
+        assert!(output.status.success(), "{}", stdout(&output));
+        stdout(&output)
+    };
+    let set_time = |seconds| {
+        let file = fs::File::options()
+            .write(true)
+            .open(sandbox.path("notes.txt"))
+            .unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+            .unwrap();
+    };
+
+    set_time(1_000_000_000);
+    assert!(status().contains("Status: clean"));
+
+    // The same size, so only the content shows the change.
+    fs::write(sandbox.path("notes.txt"), "jello\n").unwrap();
+    set_time(1_000_000_000);
+    assert!(status().contains("modified: notes.txt"));
+}
+
+#[test]
+fn help_lines_up_the_summaries_after_the_longest_name() {
+    let sandbox = Sandbox::new("help");
+
+    let output = sandbox.run("help");
+
+    let listing = stdout(&output);
+    // Each command line as its name and the padding after it.
+    let names = listing
+        .lines()
+        .filter_map(|line| line.strip_prefix("  "))
+        .map(|line| {
+            let summary = line.split_once(' ').unwrap().1.trim_start();
+            &line[..line.len() - summary.len()]
+        })
+        .collect::<Vec<_>>();
+    assert!(names.len() > 50, "{}", listing);
+    let width = names[0].len();
+    assert!(names.iter().all(|name| name.len() == width), "{}", listing);
+    // The longest name is followed by a single space.
+    assert!(
+        names.iter().any(|name| !name.ends_with("  ")),
+        "{}",
+        listing
+    );
+}
+
+#[test]
+fn output_of_vault_get_is_not_kept() {
+    let sandbox = Sandbox::new("lastout");
+    let created = sandbox.run_with_input("vault set TOKEN", "master\nmaster\nhunter2\n");
+    assert!(created.status.success(), "{}", stdout(&created));
+
+    let output = sandbox.run_shell(&[], "vault get TOKEN\nmaster\nlastout\n");
+
+    // Once from vault get, not again from lastout.
+    assert_eq!(stdout(&output).matches("hunter2").count(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn ctrl_c_cancels_the_running_command_not_the_shell() {
+    let sandbox = Sandbox::new("interrupt");
+    let mut child = sandbox.spawn("countdown 1h");
+
+    let status = interrupt(&mut child);
+
+    // The command failed as cancelled; a killed process has no exit code.
+    assert_eq!(status.code(), Some(1));
+}
+
+#[cfg(unix)]
+#[test]
+fn tail_follow_prints_appended_lines_until_ctrl_c() {
+    use std::{fs::OpenOptions, io::Read, thread, time::Duration};
+
+    let sandbox = Sandbox::new("tail");
+    let log = sandbox.path("app.log");
+    fs::write(&log, "first\n").unwrap();
+    let mut child = sandbox.spawn("tail -f app.log");
+
+    let mut file = OpenOptions::new().append(true).open(&log).unwrap();
+    file.write_all(b"second\n").unwrap();
+    thread::sleep(Duration::from_millis(1000));
+    let status = interrupt(&mut child);
+
+    assert!(status.success(), "{}", status);
+    let mut printed = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut printed)
+        .unwrap();
+    // The first byte was read by spawn.
+    assert_eq!(printed, "irst\nsecond\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn audit_fails_on_findings_at_the_minimum_severity() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = Sandbox::new("audit");
+    fs::write(sandbox.path("notes.txt"), "notes\n").unwrap();
+    let output = sandbox.run("audit .");
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert!(stdout(&output).contains("No issues found"));
+
+    // An executable document is a low finding only.
+    fs::set_permissions(sandbox.path("notes.txt"), fs::Permissions::from_mode(0o755)).unwrap();
+    assert!(!sandbox.run("audit .").status.success());
+    assert!(sandbox.run("audit . --min-severity high").status.success());
+
+    fs::write(sandbox.path("shared"), "").unwrap();
+    fs::set_permissions(sandbox.path("shared"), fs::Permissions::from_mode(0o666)).unwrap();
+    let output = sandbox.run("audit . --min-severity high --format json");
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("\"path\""), "{}", stdout(&output));
+}
+
+#[test]
+fn examples_run_with_their_own_config_dir() {
+    let sandbox = Sandbox::new("examples");
+    let config = sandbox.0.join("config/my_basic_cli_tools");
+
+    let output = sandbox.run("examples note --run 1");
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert!(
+        stdout(&output).starts_with("$ note add"),
+        "{}",
+        stdout(&output)
+    );
+    assert!(!config.join("notes.tsv").exists());
+    assert!(names(&sandbox.dir()).is_empty());
+}
+
+#[test]
+fn dry_run_leaves_files_and_saved_data_alone() {
+    let sandbox = Sandbox::new("dry_run");
+    let config = sandbox.0.join("config/my_basic_cli_tools");
+    fs::create_dir_all(&config).unwrap();
+    fs::write(config.join("config"), "dry_run = true\n").unwrap();
+    fs::write(sandbox.path("notes.txt"), "secret\n").unwrap();
+
+    for line in [
+        "encrypt notes.txt",
+        "snapshot create . -o snapshot.json",
+        "docs generate -o docs",
+        "todo add buy milk",
+        "note add call back",
+        "vault set token",
+        "remind me in 10m stand up",
+    ] {
+        let output = sandbox.run(line);
+        assert!(output.status.success(), "{}: {}", line, stdout(&output));
+        assert!(
+            stdout(&output).contains("would "),
+            "{}: {}",
+            line,
+            stdout(&output)
+        );
+    }
+
+    assert_eq!(names(&sandbox.dir()), ["notes.txt"]);
+    for file in ["tasks.tsv", "notes.tsv", "vault.bin", "reminders.tsv"] {
+        assert!(!config.join(file).exists(), "{}", file);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn backup_into_its_own_source_replaces_read_only_copies() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = Sandbox::new("backup");
+    fs::create_dir(sandbox.path("src")).unwrap();
+    fs::write(sandbox.path("src/a.txt"), "one").unwrap();
+
+    // Paths are resolved against the shell's directory, not the process's.
+    let first = sandbox.run_shell(&[], "cd src\nbackup . copy -p\n");
+    assert!(stdout(&first).contains("1 copied"), "{}", stdout(&first));
+    let copy = sandbox.path("src/copy/a.txt");
+    assert_eq!(fs::read_to_string(&copy).unwrap(), "one");
+
+    fs::set_permissions(&copy, fs::Permissions::from_mode(0o444)).unwrap();
+    fs::write(sandbox.path("src/a.txt"), "two").unwrap();
+    let second = sandbox.run("backup src src/copy");
+    assert!(second.status.success(), "{}", stdout(&second));
+    assert!(stdout(&second).contains("1 copied"), "{}", stdout(&second));
+    assert_eq!(fs::read_to_string(&copy).unwrap(), "two");
+    assert!(!sandbox.path("src/copy/copy").exists());
+
+    let same = sandbox.run("backup src src");
+    assert!(!same.status.success(), "{}", stdout(&same));
+}
+
+#[test]
+fn backup_delete_keeps_to_the_destination() {
+    let sandbox = Sandbox::new("backup_state");
+    fs::create_dir(sandbox.path("src")).unwrap();
+    fs::create_dir(sandbox.path("dest")).unwrap();
+    fs::write(sandbox.path("keep.txt"), "mine").unwrap();
+    fs::write(
+        sandbox.path("dest/.backup_state.json"),
+        r#"{"files": [{"path": "../keep.txt", "size": 4, "mtime_secs": 0, "mtime_nanos": 0, "sha256": ""}]}"#,
+    )
+    .unwrap();
+
+    let output = sandbox.run("backup src dest --delete");
+
+    assert!(!output.status.success());
+    assert!(
+        stdout(&output).contains("unsafe path: ../keep.txt"),
+        "{}",
+        stdout(&output)
+    );
+    assert!(sandbox.path("keep.txt").exists());
+}
@@ -0,0 +1,156 @@
+use my_basic_cli_tools::crypto::{self, AuthenticationError};
+
+fn hex(text: &str) -> Vec<u8> {
+    let digits = text.split_whitespace().collect::<String>();
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&digits[index..index + 2], 16).unwrap())
+        .collect()
+}
+
+const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only \
+one tip for the future, sunscreen would be it.";
+
+/// RFC 8439, section 2.8.2.
+fn aead_vector() -> ([u8; 32], [u8; 12], Vec<u8>, Vec<u8>) {
+    let key = hex("808182838485868788898a8b8c8d8e8f 909192939495969798999a9b9c9d9e9f");
+    let nonce = hex("07000000 4041424344454647");
+    let aad = hex("50515253c0c1c2c3c4c5c6c7");
+    let sealed = hex(
+        "d31a8d34648e60db7b86afbc53ef7ec2 a4aded51296e08fea9e2b5a736ee62d6
+         3dbea45e8ca9671282fafb69da92728b 1a71de0a9e060b2905d6a5b67ecd3b36
+         92ddbd7f2d778b8c9803aee328091b58 fab324e4fad675945585808b4831d7bc
+         3ff4def08e4b7a9de576d26586cec64b 6116
+         1ae10b594f09e26a7e902ecbd0600691",
+    );
+    (
+        key.try_into().unwrap(),
+        nonce.try_into().unwrap(),
+        aad,
+        sealed,
+    )
+}
+
+#[test]
+fn chacha20_encrypts_the_rfc_8439_example() {
+    // Section 2.4.2; sealing starts at block counter 1, as it does.
+    let key = hex("000102030405060708090a0b0c0d0e0f 101112131415161718191a1b1c1d1e1f");
+    let nonce = hex("000000000000004a00000000");
+    let expected = hex(
+        "6e2e359a2568f98041ba0728dd0d6981 e97e7aec1d4360c20a27afccfd9fae0b
+         f91b65c5524733ab8f593dabcd62b357 1639d624e65152ab8f530c359f0861d8
+         07ca0dbf500d6a6156a38e088a22b65e 52bc514d16ccf806818ce91ab7793736
+         5af90bbf74a35be6b40b8eedf2785e42 874d",
+    );
+
+    let sealed = crypto::seal(
+        &key.try_into().unwrap(),
+        &nonce.try_into().unwrap(),
+        b"",
+        SUNSCREEN,
+    );
+
+    assert_eq!(sealed[..SUNSCREEN.len()], expected);
+}
+
+#[test]
+fn poly1305_matches_the_rfc_8439_example() {
+    // Section 2.5.2.
+    let key = hex("85d6be7857556d337f4452fe42d506a8 0103808afb0db2fd4abff6af4149f51b");
+
+    let tag = crypto::poly1305(
+        &key.try_into().unwrap(),
+        b"Cryptographic Forum Research Group",
+    );
+
+    assert_eq!(tag[..], hex("a8061dc1305136c6c22b8baf0c0127a9"));
+}
+
+#[test]
+fn seal_and_open_match_the_rfc_8439_aead_example() {
+    let (key, nonce, aad, expected) = aead_vector();
+
+    assert_eq!(crypto::seal(&key, &nonce, &aad, SUNSCREEN), expected);
+    assert_eq!(
+        crypto::open(&key, &nonce, &aad, &expected).unwrap(),
+        SUNSCREEN
+    );
+}
+
+#[test]
+fn open_rejects_any_change() {
+    let (key, nonce, aad, sealed) = aead_vector();
+
+    // Every byte of the ciphertext and the tag is covered.
+    for index in 0..sealed.len() {
+        let mut tampered = sealed.clone();
+        tampered[index] ^= 1;
+        assert!(matches!(
+            crypto::open(&key, &nonce, &aad, &tampered),
+            Err(AuthenticationError)
+        ));
+    }
+    let mut other_aad = aad.clone();
+    other_aad[0] ^= 1;
+    assert!(crypto::open(&key, &nonce, &other_aad, &sealed).is_err());
+    let mut other_nonce = nonce;
+    other_nonce[11] ^= 1;
+    assert!(crypto::open(&key, &other_nonce, &aad, &sealed).is_err());
+    assert!(crypto::open(&key, &nonce, &aad, &sealed[..sealed.len() - 1]).is_err());
+    assert!(crypto::open(&key, &nonce, &aad, &sealed[..crypto::TAG_SIZE - 1]).is_err());
+}
+
+#[test]
+fn derive_key_matches_pbkdf2_hmac_sha256_vectors() {
+    // The inputs of RFC 6070, with SHA-256 in place of SHA-1.
+    let cases = [
+        (
+            &b"password"[..],
+            &b"salt"[..],
+            1,
+            "120fb6cffcf8b32c43e7225256c4f837 a86548c92ccc35480805987cb70be17b",
+        ),
+        (
+            b"password",
+            b"salt",
+            2,
+            "ae4d0c95af6b46d32d0adff928f06dd0 2a303f8ef3c251dfd6e2d85a95474c43",
+        ),
+        (
+            b"password",
+            b"salt",
+            4096,
+            "c5e478d59288c841aa530db6845c4c8d 962893a001ce4e11a4963873aa98134a",
+        ),
+        (
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            "348c89dbcbd32b2f32d814b8116e84cf 2b17347ebc1800181c4e2a1fb8dd53e1",
+        ),
+    ];
+    for (passphrase, salt, iterations, expected) in cases {
+        assert_eq!(
+            crypto::derive_key(passphrase, salt, iterations)[..],
+            hex(expected),
+            "{} iterations",
+            iterations
+        );
+    }
+}
+
+#[test]
+fn hmac_sha256_matches_rfc_4231() {
+    // Test cases 1 and 6; the second key is longer than a block.
+    assert_eq!(
+        crypto::hmac_sha256(&[0x0b; 20], b"Hi There")[..],
+        hex("b0344c61d8db38535ca8afceaf0bf12b 881dc200c9833da726e9376c2e32cff7")
+    );
+    assert_eq!(
+        crypto::hmac_sha256(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First"
+        )[..],
+        hex("60e431591ee0b67f0d8a26aacbf5b77f 8e0bc6213728c5140546040f0ee37f54")
+    );
+}
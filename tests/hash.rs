@@ -0,0 +1,98 @@
+use my_basic_cli_tools::hash::{to_hex, Md5, Sha1, Sha256};
+
+/// The three digests of `data`, hashed in one go.
+fn digests(data: &[u8]) -> [String; 3] {
+    let mut sha256 = Sha256::new();
+    let mut sha1 = Sha1::new();
+    let mut md5 = Md5::new();
+    sha256.update(data);
+    sha1.update(data);
+    md5.update(data);
+    [
+        to_hex(&sha256.finalize()),
+        to_hex(&sha1.finalize()),
+        to_hex(&md5.finalize()),
+    ]
+}
+
+#[test]
+fn digests_match_the_fips_180_and_rfc_1321_examples() {
+    let cases: [(&[u8], [&str; 3]); 3] = [
+        (
+            b"",
+            [
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+                "d41d8cd98f00b204e9800998ecf8427e",
+            ],
+        ),
+        (
+            b"abc",
+            [
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                "a9993e364706816aba3e25717850c26c9cd0d89d",
+                "900150983cd24fb0d6963f7d28e17f72",
+            ],
+        ),
+        // 448 bits, so the padding needs a block of its own.
+        (
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            [
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+                "84983e441c3bd26ebaae4aa1f95129e5e54670f1",
+                "8215ef0796a20bcaaae116d3876c664a",
+            ],
+        ),
+    ];
+    for (data, expected) in cases {
+        assert_eq!(
+            digests(data),
+            expected,
+            "{:?}",
+            String::from_utf8_lossy(data)
+        );
+    }
+}
+
+#[test]
+fn md5_matches_the_rest_of_rfc_1321() {
+    let cases: [(&[u8], &str); 2] = [
+        (b"message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+        (
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "57edf4a22be3c955ac49da2e2107b67a",
+        ),
+    ];
+    for (data, expected) in cases {
+        assert_eq!(digests(data)[2], expected);
+    }
+}
+
+#[test]
+fn a_million_a_hashes_the_same_in_uneven_pieces() {
+    let data = vec![b'a'; 1_000_000];
+    let expected = [
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+        "34aa973cd4c4daa4f61eeb2bdbad27316534016f",
+        "7707d6ae4e027c70eea2a935c2296f21",
+    ];
+    assert_eq!(digests(&data), expected);
+
+    // Pieces that straddle block boundaries.
+    let mut sha256 = Sha256::new();
+    let mut sha1 = Sha1::new();
+    let mut md5 = Md5::new();
+    for piece in data.chunks(63).chain([&[][..]]) {
+        sha256.update(piece);
+        sha1.update(piece);
+        md5.update(piece);
+    }
+    assert_eq!(
+        [
+            to_hex(&sha256.finalize()),
+            to_hex(&sha1.finalize()),
+            to_hex(&md5.finalize()),
+        ],
+        expected
+    );
+}
@@ -0,0 +1,59 @@
+use my_basic_cli_tools::inflate::inflate;
+
+fn hex(text: &str) -> Vec<u8> {
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).unwrap())
+        .collect()
+}
+
+/// The lines compressed below with dynamic codes.
+fn lines() -> Vec<u8> {
+    (1..=40)
+        .flat_map(|n| format!("line {}\n", n).into_bytes())
+        .collect()
+}
+
+// Streams made with zlib, without its header and trailer.
+const STORED: &str = "010b00f4ff73746f7265642074657874";
+const FIXED: &str = "f348cdc9c9d751f040a21401";
+const DYNAMIC: &str = "35cfbb0d80301443d13e533002b6c36f200aa488fd4b84de4d75bb237b3cefbda88d3fae\
+a4d22b5b65af1c95b37255b4521c01094950c21298d004273ce379eec2339ef18c673ce319cf78c10b5ee651bce005\
+2f78c10b5ef0fada3e";
+
+#[test]
+fn inflates_each_kind_of_block() {
+    assert_eq!(inflate(&hex(STORED), 100).unwrap(), b"stored text");
+    // Fixed codes, with a match reaching back over the repeats.
+    assert_eq!(inflate(&hex(FIXED), 100).unwrap(), b"Hello, Hello, Hello!");
+    assert_eq!(inflate(&hex(DYNAMIC), 1000).unwrap(), lines());
+}
+
+#[test]
+fn inflates_several_blocks_in_a_row() {
+    // A stored block that is not the last, then a fixed one that is.
+    let stream = hex(&format!("000300fcff616263{}", FIXED));
+
+    assert_eq!(inflate(&stream, 100).unwrap(), b"abcHello, Hello, Hello!");
+}
+
+#[test]
+fn stops_at_the_limit() {
+    assert!(inflate(&hex(FIXED), 19).is_err());
+    assert!(inflate(&hex(FIXED), 20).is_ok());
+    assert!(inflate(&hex(DYNAMIC), 100).is_err());
+}
+
+#[test]
+fn rejects_damaged_streams() {
+    // Block type 3 does not exist.
+    assert!(inflate(&[0x07], 100).is_err());
+    // The length of a stored block must match its complement.
+    assert!(inflate(&hex("010b00f4fe73746f7265642074657874"), 100).is_err());
+    // Streams cut short.
+    for stream in [STORED, FIXED, DYNAMIC] {
+        let stream = hex(stream);
+        assert!(inflate(&stream[..stream.len() - 2], 1000).is_err());
+    }
+    assert!(inflate(&[], 100).is_err());
+}
@@ -0,0 +1,421 @@
+use std::borrow::Cow;
+
+use my_basic_cli_tools::{
+    parse_command::{
+        expand_history, fuzz_parse, join_quoted, tokenize, tokenize_words, CommandError, Quoting,
+        Span,
+    },
+    Command, ParsedLine,
+};
+
+const ITERATIONS: usize = 20_000;
+const INTERESTING_CHARS: &[char] = &[
+    ' ', ' ', '\t', '"', '"', '\'', 'r', '-', '-', '=', 'a', 'b', 'é', '€', '\0', '\n', '$',
+];
+const INTERESTING_WORDS: &[&str] = &[
+    "echo", "ls", "help", "portscan", "--ports", "--format", "json", "-o", "=", "--", "\"",
+];
+
+/// Small deterministic xorshift generator, so failures are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn bytes(&mut self) -> Vec<u8> {
+        let length = self.below(64);
+        (0..length).map(|_| self.next() as u8).collect()
+    }
+
+    fn shell_like_line(&mut self) -> String {
+        let mut line = String::new();
+        for _ in 0..self.below(12) {
+            if self.below(2) == 0 {
+                line.push_str(INTERESTING_WORDS[self.below(INTERESTING_WORDS.len())]);
+            } else {
+                line.push(INTERESTING_CHARS[self.below(INTERESTING_CHARS.len())]);
+            }
+        }
+        line
+    }
+
+    fn token(&mut self) -> String {
+        let alphabet = [' ', '\t', 'x', 'y', '-', '=', 'é', '$', '\'', '"', '!', '#'];
+        (0..self.below(6))
+            .map(|_| alphabet[self.below(alphabet.len())])
+            .collect()
+    }
+}
+
+#[test]
+fn arbitrary_bytes_never_panic() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for _ in 0..ITERATIONS {
+        fuzz_parse(&rng.bytes());
+    }
+}
+
+#[test]
+fn shell_like_lines_never_panic() {
+    let mut rng = Rng(0xdeadbeefcafebabe);
+    for _ in 0..ITERATIONS {
+        let line = rng.shell_like_line();
+        let _ = tokenize(&line);
+        let _ = Command::parse(&line);
+    }
+}
+
+#[test]
+fn quoting_round_trips() {
+    let mut rng = Rng(0x0123456789abcdef);
+    for _ in 0..ITERATIONS {
+        let tokens = (0..rng.below(6)).map(|_| rng.token()).collect::<Vec<_>>();
+        let line = join_quoted(&tokens);
+
+        assert_eq!(tokenize(&line).unwrap(), tokens, "line: {:?}", line);
+    }
+}
+
+#[test]
+fn requoted_words_are_not_expanded_again() {
+    let tokens = ["it's".to_string(), "$HOME".to_string(), "plain".to_string()];
+    let line = join_quoted(&tokens);
+
+    assert_eq!(line, r#"'it'"'"'s' '$HOME' plain"#);
+    let quoting = tokenize_words(&line)
+        .unwrap()
+        .iter()
+        .map(|word| word.quoting)
+        .collect::<Vec<_>>();
+    assert_eq!(quoting, [Quoting::Raw, Quoting::Raw, Quoting::Unquoted]);
+}
+
+#[test]
+fn tokenizing_is_stable_after_requoting() {
+    let mut rng = Rng(0x5555aaaa5555aaaa);
+    for _ in 0..ITERATIONS {
+        let line = rng.shell_like_line();
+        let Ok(tokens) = tokenize(&line) else {
+            continue;
+        };
+
+        assert_eq!(tokenize(&join_quoted(&tokens)).unwrap(), tokens);
+    }
+}
+
+#[test]
+fn empty_and_unbalanced_input_return_errors() {
+    assert!(matches!(
+        Command::parse("").unwrap_err().kind(),
+        CommandError::EmptyInput
+    ));
+    assert!(matches!(
+        Command::parse("   \t ").unwrap_err().kind(),
+        CommandError::EmptyInput
+    ));
+    assert!(matches!(
+        Command::parse("echo \"unterminated").unwrap_err().kind(),
+        CommandError::UnterminatedQuote
+    ));
+    assert!(matches!(
+        Command::parse("portscan localhost --ports")
+            .unwrap_err()
+            .kind(),
+        CommandError::MissingFlagValue(_)
+    ));
+}
+
+#[test]
+fn quoted_empty_argument_is_kept() {
+    assert_eq!(tokenize("echo \"\" x").unwrap(), vec!["echo", "", "x"]);
+    assert_eq!(tokenize("echo a   ").unwrap(), vec!["echo", "a"]);
+}
+
+#[test]
+fn blank_lines_parse_to_nothing() {
+    for line in ["", " ", "\t  \t", "\n", "# a comment", "   #echo hi"] {
+        assert!(matches!(ParsedLine::parse(line), Ok(ParsedLine::Nothing)));
+    }
+    assert!(matches!(
+        ParsedLine::parse("  echo hi "),
+        Ok(ParsedLine::Command(_))
+    ));
+    assert!(matches!(
+        ParsedLine::parse("nope").unwrap_err().kind(),
+        CommandError::UnknownCommand(_)
+    ));
+}
+
+#[test]
+fn unquoted_hash_starts_a_comment() {
+    assert_eq!(tokenize("echo a # b c").unwrap(), vec!["echo", "a"]);
+    assert_eq!(tokenize("echo a #b").unwrap(), vec!["echo", "a"]);
+    assert_eq!(tokenize("echo \"a # b\"").unwrap(), vec!["echo", "a # b"]);
+    assert_eq!(tokenize("echo \"#\"x").unwrap(), vec!["echo", "#x"]);
+    assert_eq!(tokenize("echo issue#12").unwrap(), vec!["echo", "issue#12"]);
+}
+
+#[test]
+fn single_quotes_and_raw_strings_are_passed_through() {
+    assert_eq!(
+        tokenize("echo 'a \"b\" # c' r\"d 'e'\"").unwrap(),
+        vec!["echo", "a \"b\" # c", "d 'e'"]
+    );
+    assert!(matches!(
+        Command::parse("echo 'open").unwrap_err().kind(),
+        CommandError::UnterminatedQuote
+    ));
+    assert_eq!(tokenize("echo rust").unwrap(), vec!["echo", "rust"]);
+}
+
+#[test]
+fn words_record_their_quoting() {
+    let words = tokenize_words("echo plain \"double\" 'single' r\"raw\" \"mixed\"'raw'").unwrap();
+    let quoting = words.iter().map(|word| word.quoting).collect::<Vec<_>>();
+
+    assert_eq!(
+        quoting,
+        vec![
+            Quoting::Unquoted,
+            Quoting::Unquoted,
+            Quoting::Double,
+            Quoting::Raw,
+            Quoting::Raw,
+            Quoting::Raw,
+        ]
+    );
+    assert!(!words[3].allows_expansion());
+    assert!(words[2].allows_expansion());
+}
+
+#[test]
+fn raw_words_are_not_expanded() {
+    let command = Command::try_from("echo '$SECRET:NAME' r\"$SECRET:OTHER\"".to_string()).unwrap();
+    assert_eq!(command.arguments, vec!["$SECRET:NAME", "$SECRET:OTHER"]);
+}
+
+#[test]
+fn errors_point_at_the_offending_input() {
+    let error = Command::parse("ls --bogus").unwrap_err();
+    assert_eq!(error.span(), Some(Span { start: 3, end: 10 }));
+    assert_eq!(
+        error.to_string(),
+        "Unsupported flag: --bogus (at bytes 3..10)\n  ls --bogus\n     ^^^^^^^"
+    );
+
+    let error = Command::parse("  nope x").unwrap_err();
+    assert_eq!(error.span(), Some(Span { start: 2, end: 6 }));
+
+    let error = Command::parse("portscan a b").unwrap_err();
+    assert_eq!(error.span(), Some(Span { start: 11, end: 12 }));
+
+    let error = Command::parse("man").unwrap_err();
+    assert_eq!(error.span(), Some(Span { start: 3, end: 3 }));
+
+    let error = Command::parse("echo 'é \"x").unwrap_err();
+    assert_eq!(error.span(), Some(Span { start: 5, end: 11 }));
+}
+
+#[test]
+fn token_spans_cover_quotes() {
+    let words = tokenize_words("echo \"a b\" r\"c\"").unwrap();
+    let spans = words.iter().map(|word| word.span).collect::<Vec<_>>();
+    assert_eq!(
+        spans,
+        vec![
+            Span { start: 0, end: 4 },
+            Span { start: 5, end: 10 },
+            Span { start: 11, end: 15 },
+        ]
+    );
+}
+
+#[test]
+fn only_quoted_words_are_copied() {
+    let words = tokenize_words("ls src \"a b\" c'd'").unwrap();
+    let borrowed = words
+        .iter()
+        .map(|word| matches!(word.text, Cow::Borrowed(_)))
+        .collect::<Vec<_>>();
+
+    assert_eq!(borrowed, vec![true, true, false, false]);
+    assert_eq!(words[3].text, "cd");
+}
+
+#[test]
+fn history_references_expand_outside_quotes() {
+    let history = vec!["ls src".to_string(), "cat \"my notes.txt\"".to_string()];
+
+    assert_eq!(
+        expand_history("!!", &history).unwrap(),
+        "cat \"my notes.txt\""
+    );
+    assert_eq!(
+        expand_history("head !$", &history).unwrap(),
+        "head \"my notes.txt\""
+    );
+    assert_eq!(expand_history("!ls -a", &history).unwrap(), "ls src -a");
+    assert_eq!(
+        expand_history("echo '!!' !", &history).unwrap(),
+        "echo '!!' !"
+    );
+    assert!(matches!(
+        expand_history("echo !!", &history).unwrap(),
+        Cow::Owned(_)
+    ));
+
+    let error = expand_history("!nope", &history).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Event not found: !nope (at bytes 0..5)\n  !nope\n  ^^^^^"
+    );
+    assert!(expand_history("!!", &[]).is_err());
+}
+
+#[test]
+fn nested_commands_keep_their_flags() {
+    let command = Command::parse("capture lines wc -l \"my notes.txt\"").unwrap();
+    assert_eq!(command.arguments, vec!["lines", "wc", "-l", "my notes.txt"]);
+    assert!(command.flags.is_empty());
+
+    let nested = Command::from_words(&command.arguments[1..]).unwrap();
+    assert_eq!(nested.arguments, vec!["my notes.txt"]);
+    assert_eq!(nested.flags, vec!["-l"]);
+
+    let error = Command::from_words(&["wc".to_string(), "--bogus".to_string()]).unwrap_err();
+    assert_eq!(error.span(), Some(Span { start: 3, end: 10 }));
+}
+
+#[test]
+fn no_defaults_flag_is_accepted_everywhere() {
+    let command = Command::parse("head --no-defaults -n 3 notes.txt").unwrap();
+    assert_eq!(command.flags, vec!["-n=3"]);
+    assert_eq!(command.arguments, vec!["notes.txt"]);
+
+    let command = Command::parse("capture top head --no-defaults notes.txt").unwrap();
+    assert_eq!(
+        command.arguments,
+        vec!["top", "head", "--no-defaults", "notes.txt"]
+    );
+}
+
+#[test]
+fn typed_arguments_are_checked_when_parsing() {
+    assert!(Command::parse("countdown 1h30m").is_ok());
+
+    let error = Command::parse("countdown soon").unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        CommandError::InvalidArgument { position: 1, .. }
+    ));
+    assert_eq!(error.span(), Some(Span { start: 10, end: 14 }));
+    assert!(error
+        .to_string()
+        .starts_with("Argument 1 must be a duration such as 30s or 1h30m: soon"));
+
+    // Paths are only checked when the command runs.
+    assert!(Command::parse("cat missing.txt").is_ok());
+}
+
+#[test]
+fn typed_flag_values_are_checked_when_parsing() {
+    assert!(Command::parse("head -n 3 notes.txt").is_ok());
+    assert!(Command::parse("myip --public --endpoint https://example.com/").is_ok());
+
+    let error = Command::parse("head -n ten notes.txt").unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        CommandError::InvalidFlagValue { flag, .. } if flag == "-n=ten"
+    ));
+    assert_eq!(error.span(), Some(Span { start: 5, end: 11 }));
+    assert!(error
+        .to_string()
+        .starts_with("The value of -n must be an integer: ten"));
+
+    let error = Command::parse("myip --public --endpoint example.com").unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("The value of --endpoint must be an http or https URL: example.com"));
+}
+
+#[test]
+fn flag_constraints_are_checked_when_parsing() {
+    assert!(Command::parse("cal -m 2024").is_ok());
+    assert!(Command::parse("date --convert 2024-01-01 --from UTC").is_ok());
+
+    let error = Command::parse("cal -s -m").unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        CommandError::ConflictingFlags("-m", "-s")
+    ));
+    assert_eq!(error.span(), Some(Span { start: 7, end: 9 }));
+
+    let error = Command::parse("date --from UTC").unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        CommandError::MissingRequiredFlag {
+            flag: "--from",
+            required: "--convert"
+        }
+    ));
+    assert!(error
+        .to_string()
+        .starts_with("--from can only be used together with --convert"));
+}
+
+#[test]
+fn short_flags_are_bundled_and_aliases_normalized() {
+    let command = Command::parse("wc -lw notes.txt").unwrap();
+    assert_eq!(command.flags, vec!["-l", "-w"]);
+
+    let command = Command::parse("rm -rf build").unwrap();
+    assert_eq!(command.flags, vec!["--recursive", "--force"]);
+    let command = Command::parse("rm --recursive build").unwrap();
+    assert_eq!(command.flags, vec!["--recursive"]);
+
+    // Only the last flag of a bundle takes a value.
+    let command = Command::parse("qr hello --invert -o code.png").unwrap();
+    assert_eq!(command.flags, vec!["--invert", "--output=code.png"]);
+
+    let error = Command::parse("wc -lx notes.txt").unwrap_err();
+    assert!(matches!(error.kind(), CommandError::UnsupportedFlag(flag) if flag == "-lx"));
+}
+
+#[test]
+fn numeric_shorthand_and_end_of_flags() {
+    let command = Command::parse("head -5 notes.txt").unwrap();
+    assert_eq!(command.flags, vec!["-n=5"]);
+    assert_eq!(command.arguments, vec!["notes.txt"]);
+
+    let command = Command::parse("convert-unit -40C F").unwrap();
+    assert!(command.flags.is_empty());
+    assert_eq!(command.arguments, vec!["-40C", "F"]);
+
+    // Other commands still read `-5` as a flag.
+    assert!(matches!(
+        Command::parse("cat -5 notes.txt").unwrap_err().kind(),
+        CommandError::UnsupportedFlag(_)
+    ));
+
+    let command = Command::parse("rm -r -- -notes.txt --no-defaults").unwrap();
+    assert_eq!(command.flags, vec!["--recursive"]);
+    assert_eq!(command.arguments, vec!["-notes.txt", "--no-defaults"]);
+}
+
+#[test]
+fn command_names_match_exactly_by_default() {
+    assert!(matches!(
+        Command::parse("hist").unwrap_err().kind(),
+        CommandError::UnknownCommand(name) if name == "hist"
+    ));
+    assert!(Command::parse("LS").is_err());
+}
@@ -0,0 +1,85 @@
+use my_basic_cli_tools::{
+    hash::crc32,
+    inflate::inflate,
+    png::{adler32, encode_bitmap},
+};
+
+fn hex(text: &str) -> Vec<u8> {
+    let digits = text.split_whitespace().collect::<String>();
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&digits[index..index + 2], 16).unwrap())
+        .collect()
+}
+
+/// The chunks of a PNG file as (type, data, stored CRC).
+fn chunks(png: &[u8]) -> Vec<([u8; 4], &[u8], u32)> {
+    let mut chunks = Vec::new();
+    let mut rest = &png[8..];
+    while !rest.is_empty() {
+        let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+        let kind = rest[4..8].try_into().unwrap();
+        let data = &rest[8..8 + length];
+        let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+        chunks.push((kind, data, crc));
+        rest = &rest[12 + length..];
+    }
+    chunks
+}
+
+#[test]
+fn encodes_a_small_image_like_zlib_does() {
+    // Built with Python's struct and zlib at level 0, which stores too.
+    let expected = hex(
+        "89504e470d0a1a0a 0000000d49484452 0000000a00000003 0100000000 8246a3d8
+         0000001449444154 7801010900f6ff00 6d8000db4000b6c0 0e64037f e24c9236
+         0000000049454e44 ae426082",
+    );
+
+    let png = encode_bitmap(10, 3, |x, y| (x + y).is_multiple_of(3));
+
+    assert_eq!(png, expected);
+}
+
+#[test]
+fn splits_large_images_into_stored_blocks() {
+    // Rows of 151 bytes, 75500 in all: more than one stored block holds.
+    let (width, height) = (1200, 500);
+    let is_black = |x: u32, y: u32| (x * 7 + y * 3).is_multiple_of(5);
+
+    let png = encode_bitmap(width, height, is_black);
+
+    let chunks = chunks(&png);
+    let kinds = chunks.iter().map(|(kind, _, _)| kind).collect::<Vec<_>>();
+    assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+    for (kind, data, crc) in &chunks {
+        assert_eq!(crc32(&[&kind[..], data].concat()), *crc);
+    }
+    // The CRC of an empty IEND chunk is the same in every PNG.
+    assert_eq!(chunks[2].2, 0xae42_6082);
+
+    let stream = chunks[1].1;
+    assert_eq!(stream[..2], [0x78, 0x01]);
+    let pixels = inflate(&stream[2..stream.len() - 4], 1 << 20).unwrap();
+    let mut expected = Vec::new();
+    for y in 0..height {
+        expected.push(0);
+        for byte in 0..width / 8 {
+            let bits = (0..8).fold(0, |bits, bit| {
+                bits << 1 | u8::from(!is_black(byte * 8 + bit, y))
+            });
+            expected.push(bits);
+        }
+    }
+    assert_eq!(pixels, expected);
+    let trailer = u32::from_be_bytes(stream[stream.len() - 4..].try_into().unwrap());
+    assert_eq!(trailer, adler32(&expected));
+}
+
+#[test]
+fn adler32_matches_known_checksums() {
+    assert_eq!(adler32(b""), 1);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    // Large enough for both sums to wrap around the modulus.
+    assert_eq!(adler32(&[0xff; 100_000]), 0x149a_302c);
+}
@@ -0,0 +1,112 @@
+use my_basic_cli_tools::qr::{add_ecc_and_interleave, Ecc, QrCode};
+
+fn rows(qr: &QrCode) -> Vec<String> {
+    (0..qr.size())
+        .map(|y| {
+            (0..qr.size())
+                .map(|x| if qr.is_dark(x, y) { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+/// "hello" at level M: version 1 with mask 0, checked with a separate
+/// decoder.
+const HELLO: [&str; 21] = [
+    "#######..##...#######",
+    "#.....#.##....#.....#",
+    "#.###.#..#.##.#.###.#",
+    "#.###.#...##..#.###.#",
+    "#.###.#.##..#.#.###.#",
+    "#.....#.....#.#.....#",
+    "#######.#.#.#.#######",
+    "..........###........",
+    "#.#.#.#..#.#....#..#.",
+    "..#.##....#...#....##",
+    ".#.#..#.###.#...#####",
+    "##..#.........#....#.",
+    ".##.#.##..#.#.#.#....",
+    "........####.#.#..###",
+    "#######...##.###..###",
+    "#.....#...####.##....",
+    "#.###.#.#.##.###...##",
+    "#.###.#..#....##..##.",
+    "#.###.#.###.#...#.#.#",
+    "#.....#..#....#.#..#.",
+    "#######.###.#.##...##",
+];
+
+#[test]
+fn error_correction_matches_the_iso_18004_examples() {
+    // "01234567" in numeric mode, the standard's worked example, and
+    // "HELLO WORLD" in alphanumeric mode; both version 1-M.
+    let cases = [
+        (
+            [
+                0x10, 0x20, 0x0c, 0x56, 0x61, 0x80, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11,
+                0xec, 0x11,
+            ],
+            [0xa5, 0x24, 0xd4, 0xc1, 0xed, 0x36, 0xc7, 0x87, 0x2c, 0x55],
+        ),
+        (
+            [
+                0x20, 0x5b, 0x0b, 0x78, 0xd1, 0x72, 0xdc, 0x4d, 0x43, 0x40, 0xec, 0x11, 0xec, 0x11,
+                0xec, 0x11,
+            ],
+            [0xc4, 0x23, 0x27, 0x77, 0xeb, 0xd7, 0xe7, 0xe2, 0x5d, 0x17],
+        ),
+    ];
+    for (data, ecc) in cases {
+        let codewords = add_ecc_and_interleave(&data, 1, Ecc::Medium);
+        assert_eq!(codewords[..16], data);
+        assert_eq!(codewords[16..], ecc);
+    }
+}
+
+#[test]
+fn blocks_are_interleaved_column_by_column() {
+    // Version 5-Q has two blocks of 15 data codewords, then two of 16,
+    // each with 18 error correction codewords.
+    let data = (0..62).collect::<Vec<u8>>();
+
+    let codewords = add_ecc_and_interleave(&data, 5, Ecc::Quartile);
+
+    assert_eq!(codewords.len(), 62 + 4 * 18);
+    let mut expected = Vec::new();
+    for column in 0..15 {
+        expected.extend([column, 15 + column, 30 + column, 46 + column]);
+    }
+    // Only the longer blocks have a 16th codeword.
+    expected.extend([45, 61]);
+    assert_eq!(codewords[..62], expected);
+}
+
+#[test]
+fn encodes_a_whole_symbol() {
+    let qr = QrCode::encode(b"hello", Ecc::Medium).unwrap();
+
+    assert_eq!(rows(&qr), HELLO);
+}
+
+#[test]
+fn version_7_and_up_carry_their_version() {
+    // 140 bytes need version 7 at level L.
+    let qr = QrCode::encode(&[b'x'; 140], Ecc::Low).unwrap();
+    assert_eq!(qr.size(), 7 * 4 + 17);
+
+    // The block above the bottom left finder pattern, least significant
+    // bit first; the other copy mirrors it.
+    let mut bits = 0;
+    for i in 0..18 {
+        let (a, b) = (qr.size() - 11 + i % 3, i / 3);
+        assert_eq!(qr.is_dark(a, b), qr.is_dark(b, a));
+        bits |= u32::from(qr.is_dark(b, a)) << i;
+    }
+    assert_eq!(bits, 0x07c94);
+}
+
+#[test]
+fn refuses_data_too_long_for_version_40() {
+    assert!(QrCode::encode(&[0; 2953], Ecc::Low).is_ok());
+    assert!(QrCode::encode(&[0; 2954], Ecc::Low).is_err());
+}
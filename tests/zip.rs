@@ -0,0 +1,205 @@
+use my_basic_cli_tools::{
+    tar::{EntryKind, Header},
+    zip::{Reader, Writer},
+};
+use std::{
+    env, fs,
+    io::{self, Cursor},
+    path::Path,
+    process::{self, Command},
+};
+
+fn hex(text: &str) -> Vec<u8> {
+    let digits = text.split_whitespace().collect::<String>();
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&digits[index..index + 2], 16).unwrap())
+        .collect()
+}
+
+fn header(path: &str, kind: EntryKind, mode: u32, size: u64) -> Header {
+    Header {
+        path: path.to_string(),
+        kind,
+        mode,
+        size,
+        modified: 1_700_000_000,
+    }
+}
+
+/// Each entry's header and data.
+fn read_all(archive: Vec<u8>) -> io::Result<Vec<(Header, Vec<u8>)>> {
+    let mut reader = Reader::new(Cursor::new(archive))?;
+    let mut read = Vec::new();
+    for entry in reader.entries().to_vec() {
+        let mut data = Vec::new();
+        if entry.header.kind == EntryKind::File {
+            reader.copy_data(&entry, &mut data)?;
+        }
+        read.push((entry.header, data));
+    }
+    Ok(read)
+}
+
+/// Made by Python's zipfile: `hello.txt` stored, the directory `docs/` and
+/// `docs/lines.txt` deflated.
+const OTHER_PROGRAM: &str = "
+    504b030414000000000083182250d69b38d60c0000000c000000090000006865
+    6c6c6f2e74787448656c6c6f2c207a6970210a504b0304140000000000831822
+    5000000000000000000000000005000000646f63732f504b0304140000000800
+    83182250b5c3fb205c000000370100000e000000646f63732f6c696e65732e74
+    787435cfbb0d80301443d13e533002b6c36f200aa488fd4b84de4d75bb237b3c
+    efbda88d3faea4d22b5b65af1c95b37255b4521c01094950c21298d004273ce3
+    79eec2339ef18c673ce319cf78c10b5ee651bce0052f78c10b5ef0fada3e504b
+    0102140314000000000083182250d69b38d60c0000000c000000090000000000
+    000000000000a4010000000068656c6c6f2e747874504b010214031400000000
+    0083182250000000000000000000000000050000000000000000001000ed4133
+    000000646f63732f504b0102140314000000080083182250b5c3fb205c000000
+    370100000e0000000000000000000000800156000000646f63732f6c696e6573
+    2e747874504b05060000000003000300a6000000de0000000000";
+
+#[test]
+fn reads_back_what_it_wrote() {
+    let data = (0..=255u8).cycle().take(70_000).collect::<Vec<_>>();
+    let entries = [
+        (
+            header("bin.dat", EntryKind::File, 0o600, 70_000),
+            data.clone(),
+        ),
+        (header("empty", EntryKind::File, 0o644, 0), Vec::new()),
+        (header("dir", EntryKind::Directory, 0o750, 0), Vec::new()),
+        (
+            header(
+                "dir/link",
+                EntryKind::Symlink("../bin.dat".into()),
+                0o777,
+                0,
+            ),
+            Vec::new(),
+        ),
+    ];
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    for (header, data) in &entries {
+        writer.append(header, Cursor::new(data)).unwrap();
+    }
+    let archive = writer.finish().unwrap().into_inner();
+
+    let read = read_all(archive).unwrap();
+
+    assert_eq!(read.len(), entries.len());
+    for ((read, read_data), (written, data)) in read.iter().zip(&entries) {
+        // Directories are stored with a trailing `/`.
+        let path = match written.kind {
+            EntryKind::Directory => format!("{}/", written.path),
+            _ => written.path.clone(),
+        };
+        assert_eq!(read.path, path);
+        assert_eq!(read.kind, written.kind);
+        assert_eq!(read.mode, written.mode);
+        assert_eq!(read.size, written.size);
+        assert_eq!(read.modified, written.modified);
+        assert_eq!(read_data, data);
+    }
+}
+
+#[test]
+fn an_empty_archive_has_no_entries() {
+    let archive = Writer::new(Cursor::new(Vec::new()))
+        .finish()
+        .unwrap()
+        .into_inner();
+
+    assert!(read_all(archive).unwrap().is_empty());
+}
+
+#[test]
+fn refuses_data_shorter_than_its_header_says() {
+    let mut writer = Writer::new(io::sink());
+
+    let result = writer.append(
+        &header("short", EntryKind::File, 0o644, 10),
+        Cursor::new(b"12345"),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn reads_stored_and_deflated_entries_of_another_program() {
+    let read = read_all(hex(OTHER_PROGRAM)).unwrap();
+
+    let summary = read
+        .iter()
+        .map(|(header, _)| (header.path.as_str(), header.kind.clone(), header.mode))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            ("hello.txt", EntryKind::File, 0o644),
+            ("docs/", EntryKind::Directory, 0o755),
+            ("docs/lines.txt", EntryKind::File, 0o600),
+        ]
+    );
+    assert_eq!(read[0].1, b"Hello, zip!\n");
+    let lines = (1..=40)
+        .flat_map(|n| format!("line {}\n", n).into_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(read[2].1, lines);
+}
+
+#[test]
+fn detects_damaged_data() {
+    let mut archive = hex(OTHER_PROGRAM);
+    // The `H` of "Hello, zip!", after the 30 byte header and the name.
+    let start = 30 + "hello.txt".len();
+    assert_eq!(archive[start], b'H');
+    archive[start] = b'J';
+
+    assert!(read_all(archive).is_err());
+    assert!(Reader::new(Cursor::new(b"not a zip archive".to_vec())).is_err());
+}
+
+#[test]
+fn unzip_skips_entries_outside_the_target() {
+    let dir = env::temp_dir().join(format!("zip-escape-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("work/out")).unwrap();
+    let mut writer = Writer::new(Vec::new());
+    for path in [
+        "../escaped.txt",
+        "/absolute.txt",
+        "kept/../../up.txt",
+        "inside.txt",
+    ] {
+        let header = header(path, EntryKind::File, 0o644, 2);
+        writer.append(&header, Cursor::new(b"hi")).unwrap();
+    }
+    fs::write(dir.join("work/evil.zip"), writer.finish().unwrap()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_my_basic_cli_tools"))
+        .args(["-c", "unzip evil.zip out"])
+        .current_dir(dir.join("work"))
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .env("LANG", "C")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let names = |dir: &Path| {
+        let mut names = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    // The safe entry is extracted, the others skipped and reported.
+    assert!(output.status.success(), "{}", stdout);
+    assert_eq!(stdout.matches("Skipping").count(), 3, "{}", stdout);
+    assert_eq!(names(&dir.join("work/out")), ["inside.txt"]);
+    assert_eq!(names(&dir.join("work")), ["evil.zip", "out"]);
+    assert!(!Path::new("/absolute.txt").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}